@@ -20,8 +20,10 @@ mod joint_fixed3;
 mod joint_prismatic3;
 mod joint_revolute3;
 mod keva3;
+mod many_grains3;
 mod many_pyramids3;
 mod many_sleep3;
+mod many_small_islands3;
 mod many_static3;
 mod pyramid3;
 mod stacks3;
@@ -59,6 +61,29 @@ pub fn main() {
         ("Convex polyhedron", convex_polyhedron3::init_world),
         ("Many static", many_static3::init_world),
         ("Many sleep", many_sleep3::init_world),
+        ("Many grains", many_grains3::init_world),
+        // Islands have between 1 and 8 dynamic bodies, so a threshold of 16 solves all of them
+        // sequentially.
+        (
+            "Many small islands (parallel from 0)",
+            many_small_islands3::init_world::<0>,
+        ),
+        (
+            "Many small islands (parallel from 2)",
+            many_small_islands3::init_world::<2>,
+        ),
+        (
+            "Many small islands (parallel from 4)",
+            many_small_islands3::init_world::<4>,
+        ),
+        (
+            "Many small islands (parallel from 8)",
+            many_small_islands3::init_world::<8>,
+        ),
+        (
+            "Many small islands (parallel from 16)",
+            many_small_islands3::init_world::<16>,
+        ),
         ("Heightfield", heightfield3::init_world),
         ("Stacks", stacks3::init_world),
         ("Pyramid", pyramid3::init_world),
@@ -0,0 +1,81 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+
+/// Thousands of small grains piled in a container, all touching each other so they form a
+/// single island from the first timestep.
+///
+/// Island-based parallelism doesn’t help here, so this compares the sequential contact solver
+/// with the graph-colored one enabled by the `graph-coloring` feature.
+pub fn init_world(testbed: &mut Testbed) {
+    /*
+     * World
+     */
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let impulse_joints = ImpulseJointSet::new();
+    let multibody_joints = MultibodyJointSet::new();
+
+    /*
+     * Container
+     */
+    let num = 20;
+    let rad = 0.1;
+    let half_width = rad * num as f32;
+    let half_height = rad * num as f32 * 2.0;
+    let thickness = 0.1;
+
+    let container = bodies.insert(RigidBodyBuilder::fixed());
+    let walls = [
+        (
+            vector![half_width, thickness, half_width],
+            vector![0.0, -thickness, 0.0],
+        ),
+        (
+            vector![thickness, half_height, half_width],
+            vector![-half_width - thickness, half_height, 0.0],
+        ),
+        (
+            vector![thickness, half_height, half_width],
+            vector![half_width + thickness, half_height, 0.0],
+        ),
+        (
+            vector![half_width, half_height, thickness],
+            vector![0.0, half_height, -half_width - thickness],
+        ),
+        (
+            vector![half_width, half_height, thickness],
+            vector![0.0, half_height, half_width + thickness],
+        ),
+    ];
+    for (half_extents, translation) in walls {
+        let collider = ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            .translation(translation);
+        colliders.insert_with_parent(collider, container, &mut bodies);
+    }
+
+    /*
+     * Grains, stacked so that each one touches its neighbors.
+     */
+    let shift = rad * 2.0;
+
+    for i in 0..num {
+        for j in 0..num {
+            for k in 0..num {
+                let x = (i as f32 + 0.5) * shift - half_width;
+                let y = (j as f32 + 0.5) * shift;
+                let z = (k as f32 + 0.5) * shift - half_width;
+
+                let rigid_body = RigidBodyBuilder::dynamic().translation(vector![x, y, z]);
+                let handle = bodies.insert(rigid_body);
+                let collider = ColliderBuilder::ball(rad);
+                colliders.insert_with_parent(collider, handle, &mut bodies);
+            }
+        }
+    }
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_world(bodies, colliders, impulse_joints, multibody_joints);
+    testbed.look_at(point![10.0, 10.0, 10.0], Point::origin());
+}
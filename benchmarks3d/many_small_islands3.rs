@@ -0,0 +1,63 @@
+use rapier3d::prelude::*;
+use rapier_testbed3d::Testbed;
+
+/// Thousands of small islands, solved sequentially when they contain fewer than
+/// `MIN_PARALLEL_ISLAND_SIZE` dynamic bodies.
+///
+/// This is registered with several thresholds to find the crossover point between the
+/// sequential and parallel solvers on the target platform, with the `parallel` feature enabled.
+pub fn init_world<const MIN_PARALLEL_ISLAND_SIZE: usize>(testbed: &mut Testbed) {
+    /*
+     * World
+     */
+    let mut bodies = RigidBodySet::new();
+    let mut colliders = ColliderSet::new();
+    let impulse_joints = ImpulseJointSet::new();
+    let multibody_joints = MultibodyJointSet::new();
+
+    /*
+     * Create thousands of islands: stacks of one to eight cubes,
+     * each stack sitting on its own fixed pad.
+     */
+    let num = 40;
+    let max_stack_height = 8;
+    let rad = 0.5;
+
+    let shift = rad * 2.0 + 2.0;
+    let centerx = shift * (num as f32) / 2.0;
+    let centerz = shift * (num as f32) / 2.0;
+
+    for i in 0..num {
+        for k in 0..num {
+            let x = i as f32 * shift - centerx;
+            let z = k as f32 * shift - centerz;
+
+            let pad = bodies.insert(RigidBodyBuilder::fixed().translation(vector![x, -rad, z]));
+            let collider = ColliderBuilder::cuboid(rad * 2.0, rad, rad * 2.0);
+            colliders.insert_with_parent(collider, pad, &mut bodies);
+
+            let stack_height = 1 + (i + k) % max_stack_height;
+            for j in 0..stack_height {
+                let y = rad + j as f32 * rad * 2.0;
+                let cube = bodies.insert(RigidBodyBuilder::dynamic().translation(vector![x, y, z]));
+                colliders.insert_with_parent(
+                    ColliderBuilder::cuboid(rad, rad, rad),
+                    cube,
+                    &mut bodies,
+                );
+            }
+        }
+    }
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_world(bodies, colliders, impulse_joints, multibody_joints);
+
+    // Prevent the islands from being merged.
+    let params = testbed.integration_parameters_mut();
+    params.min_island_size = 1;
+    params.min_parallel_island_size = MIN_PARALLEL_ISLAND_SIZE;
+
+    testbed.look_at(point![100.0, 100.0, 100.0], Point::origin());
+}
@@ -6,7 +6,9 @@ pub use self::character_controller::{
 };
 
 #[cfg(feature = "dim3")]
-pub use self::ray_cast_vehicle_controller::{DynamicRayCastVehicleController, Wheel, WheelTuning};
+pub use self::ray_cast_vehicle_controller::{
+    DynamicRayCastVehicleController, VehicleInputs, Wheel, WheelTuning,
+};
 
 mod character_controller;
 
@@ -159,6 +159,11 @@ pub struct Wheel {
     /// The maximum amount of braking impulse applied to slow down the vehicle.
     pub brake: Real,
 
+    /// Is this wheel driven by the engine force given to [`DynamicRayCastVehicleController::update`]?
+    pub is_driven: bool,
+    /// Is this wheel steered by the steering angle given to [`DynamicRayCastVehicleController::update`]?
+    pub is_steered: bool,
+
     clipped_inv_contact_dot_suspension: Real,
     suspension_relative_velocity: Real,
     /// The force applied by the suspension.
@@ -188,12 +193,14 @@ impl Wheel {
             rotation: 0.0,
             delta_rotation: 0.0,
             brake: 0.0,
+            is_driven: false,
+            is_steered: false,
             roll_influence: 0.1,
             clipped_inv_contact_dot_suspension: 0.0,
             suspension_relative_velocity: 0.0,
             wheel_suspension_force: 0.0,
             max_suspension_force: info.max_suspension_force,
-            skid_info: 0.0,
+            skid_info: 1.0,
             side_impulse: 0.0,
             forward_impulse: 0.0,
             side_friction_stiffness: info.side_friction_stiffness,
@@ -220,6 +227,39 @@ impl Wheel {
     pub fn axle(&self) -> Vector<Real> {
         self.wheel_axle_ws
     }
+
+    /// How much the wheel’s suspension is compressed relative to its rest length.
+    ///
+    /// This is zero if the wheel isn’t in contact with the ground.
+    pub fn suspension_compression(&self) -> Real {
+        if self.raycast_info.is_in_contact {
+            self.suspension_rest_length - self.raycast_info.suspension_length
+        } else {
+            0.0
+        }
+    }
+
+    /// How much the tire slipped during the last update.
+    ///
+    /// This is `0.0` if the tire has full traction, and gets closer to `1.0` as the impulses
+    /// required to keep the tire from sliding exceed its friction limit. This can be used to
+    /// trigger skidding effects.
+    pub fn slip(&self) -> Real {
+        1.0 - self.skid_info
+    }
+}
+
+/// The driver inputs given to [`DynamicRayCastVehicleController::update`].
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct VehicleInputs {
+    /// The forward force applied by each wheel marked as [`Wheel::is_driven`].
+    ///
+    /// A negative value makes the vehicle drive backward.
+    pub engine_force: Real,
+    /// The braking impulse applied by every wheel.
+    pub brake: Real,
+    /// The steering angle of each wheel marked as [`Wheel::is_steered`].
+    pub steering: Real,
 }
 
 /// Information about suspension and the ground obtained from the ray-casting
@@ -402,6 +442,37 @@ impl DynamicRayCastVehicleController {
         }
     }
 
+    /// Applies the driver `inputs` to the wheels, then updates the vehicle’s velocity based on
+    /// its suspension, engine force, and brake.
+    ///
+    /// The engine force is only applied by the wheels marked as [`Wheel::is_driven`], and the
+    /// steering angle is only applied to the wheels marked as [`Wheel::is_steered`]. The brake
+    /// is applied by all the wheels.
+    ///
+    /// This is equivalent to setting [`Wheel::engine_force`], [`Wheel::steering`], and
+    /// [`Wheel::brake`] manually before calling [`Self::update_vehicle`].
+    pub fn update(
+        &mut self,
+        dt: Real,
+        inputs: &VehicleInputs,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        queries: &QueryPipeline,
+        filter: QueryFilter,
+    ) {
+        for wheel in &mut self.wheels {
+            if wheel.is_driven {
+                wheel.engine_force = inputs.engine_force;
+            }
+            if wheel.is_steered {
+                wheel.steering = inputs.steering;
+            }
+            wheel.brake = inputs.brake;
+        }
+
+        self.update_vehicle(dt, bodies, colliders, queries, filter);
+    }
+
     /// Updates the vehicle’s velocity based on its suspension, engine force, and brake.
     pub fn update_vehicle(
         &mut self,
@@ -824,3 +895,104 @@ fn resolve_single_unilateral(body1: &RigidBody, pt1: &Point<Real>, normal: &Vect
     let contact_damping = 0.2;
     -contact_damping * rel_vel * jac_diag_ab_inv
 }
+
+#[cfg(test)]
+#[cfg(feature = "dim3")]
+mod test {
+    use super::{DynamicRayCastVehicleController, VehicleInputs, WheelTuning};
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+    use crate::pipeline::{QueryFilter, QueryPipeline};
+
+    fn drive(
+        world: &mut TestWorld,
+        vehicle: &mut DynamicRayCastVehicleController,
+        inputs: VehicleInputs,
+        num_steps: usize,
+    ) {
+        let mut queries = QueryPipeline::new();
+        let filter = QueryFilter::exclude_dynamic().exclude_rigid_body(vehicle.chassis);
+
+        for _ in 0..num_steps {
+            queries.update(&world.colliders);
+            vehicle.update(
+                world.params.dt,
+                &inputs,
+                &mut world.bodies,
+                &world.colliders,
+                &queries,
+                filter,
+            );
+            world.step();
+        }
+    }
+
+    #[test]
+    fn drive_and_steer() {
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_body(
+            RigidBodyBuilder::fixed().translation(Vector::y() * -0.1),
+            ColliderBuilder::cuboid(100.0, 0.1, 100.0),
+        );
+
+        let (hw, hh) = (0.3, 0.15);
+        let (chassis, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5),
+            ColliderBuilder::cuboid(hw * 2.0, hh, hw).density(100.0),
+        );
+
+        let tuning = WheelTuning {
+            suspension_stiffness: 100.0,
+            suspension_damping: 10.0,
+            ..WheelTuning::default()
+        };
+        let mut vehicle = DynamicRayCastVehicleController::new(chassis);
+        for (x, z) in [(1.5, 1.0), (1.5, -1.0), (-1.5, 1.0), (-1.5, -1.0)] {
+            let wheel = vehicle.add_wheel(
+                Point::new(hw * x, -hh, hw * z),
+                -Vector::y(),
+                Vector::z(),
+                hh,
+                hh / 4.0,
+                &tuning,
+            );
+            // Front-wheel drive and steering.
+            wheel.is_driven = x > 0.0;
+            wheel.is_steered = x > 0.0;
+        }
+
+        // Let the suspension settle.
+        drive(&mut world, &mut vehicle, VehicleInputs::default(), 60);
+        let start = *world.bodies[chassis].translation();
+
+        // Driving straight moves the vehicle forward, along the local `x` axis.
+        let straight = VehicleInputs {
+            engine_force: 30.0,
+            ..VehicleInputs::default()
+        };
+        drive(&mut world, &mut vehicle, straight, 60);
+        let rb = &world.bodies[chassis];
+        let moved = rb.translation() - start;
+        assert!(moved.x > 0.1, "{moved:?}");
+        assert!(moved.z.abs() < 1.0e-2 * moved.x, "{moved:?}");
+        assert!(rb.linvel().x > 0.0);
+
+        // Steering to the left turns the vehicle around the `y` axis.
+        let left = VehicleInputs {
+            engine_force: 30.0,
+            steering: 0.5,
+            ..VehicleInputs::default()
+        };
+        drive(&mut world, &mut vehicle, left, 60);
+        let rb = &world.bodies[chassis];
+        let heading: Vector<Real> = rb.rotation() * Vector::x();
+        assert!(heading.z < -0.1, "{heading:?}");
+        assert!(rb.angvel().y > 0.0, "{:?}", rb.angvel());
+        for wheel in vehicle.wheels() {
+            assert!((0.0..=1.0).contains(&wheel.slip()));
+        }
+    }
+}
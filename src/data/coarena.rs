@@ -1,5 +1,17 @@
 use crate::data::arena::Index;
 
+/// A change of an element between two coarenas, as computed by [`Coarena::diff`].
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CoarenaDelta<T> {
+    /// The element only exists in the second coarena, with the given value.
+    Added(T),
+    /// The element only exists in the first coarena.
+    Removed,
+    /// The element exists in both coarenas, and its value changed to the given value.
+    Changed(T),
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, Default)]
 /// A container for data associated to item existing into another Arena.
@@ -13,6 +25,34 @@ impl<T> Coarena<T> {
         Self { data: Vec::new() }
     }
 
+    /// A coarena with no element, but with `capacity` deleted elements set to `removed_value`,
+    /// so elements with indices up to `capacity - 1` can be inserted without reallocation.
+    pub fn with_capacity(capacity: usize, removed_value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            data: vec![(u32::MAX, removed_value); capacity],
+        }
+    }
+
+    /// Appends `additional` deleted elements set to `removed_value`, so elements with the
+    /// `additional` indices following the current ones can be inserted without reallocation.
+    ///
+    /// This is useful to avoid incremental reallocations when inserting many elements at once.
+    pub fn reserve(&mut self, additional: usize, removed_value: T)
+    where
+        T: Clone,
+    {
+        self.data
+            .resize(self.data.len() + additional, (u32::MAX, removed_value));
+    }
+
+    /// The number of indices that can be used by this coarena without reallocation.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
     /// Iterates through all the elements of this coarena.
     pub fn iter(&self) -> impl Iterator<Item = (Index, &T)> {
         self.data
@@ -22,6 +62,26 @@ impl<T> Coarena<T> {
             .map(|(i, elt)| (Index::from_raw_parts(i as u32, elt.0), &elt.1))
     }
 
+    /// Iterates through all the elements of this coarena, with their generation number.
+    ///
+    /// The generation number is the one of the returned index. It is given explicitly for
+    /// convenience, e.g., to track how often the slots are reused.
+    pub fn iter_with_gen(&self) -> impl Iterator<Item = (Index, u32, &T)> {
+        self.iter().map(|(index, value)| {
+            let (_, gen) = index.into_raw_parts();
+            (index, gen, value)
+        })
+    }
+
+    /// Iterates mutably through all the elements of this coarena.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut T)> {
+        self.data
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, elt)| elt.0 != u32::MAX)
+            .map(|(i, elt)| (Index::from_raw_parts(i as u32, elt.0), &mut elt.1))
+    }
+
     /// Gets a specific element from the coarena without specifying its generation number.
     ///
     /// It is strongly encouraged to use `Coarena::get` instead of this method because this method
@@ -48,6 +108,98 @@ impl<T> Coarena<T> {
         }
     }
 
+    /// Deletes all the elements for which `f` returns `false`.
+    ///
+    /// The deleted values are reset to `removed_value`. The indices of the retained elements
+    /// remain valid.
+    pub fn retain<F: FnMut(Index, &mut T) -> bool>(&mut self, removed_value: T, mut f: F)
+    where
+        T: Clone,
+    {
+        for (i, (gen, value)) in self.data.iter_mut().enumerate() {
+            if *gen != u32::MAX && !f(Index::from_raw_parts(i as u32, *gen), value) {
+                *gen = u32::MAX; // invalidate the generation number.
+                *value = removed_value.clone();
+            }
+        }
+    }
+
+    /// Creates a new coarena with the same elements as `self`, with values transformed by `f`.
+    ///
+    /// The indices (including generation numbers) of the elements of `self` remain valid for
+    /// the returned coarena. `f` is only called on live elements, the deleted ones are set to
+    /// `U::default()`.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, mut f: F) -> Coarena<U>
+    where
+        U: Default,
+    {
+        let data = self
+            .data
+            .iter()
+            .map(|(gen, value)| {
+                if *gen != u32::MAX {
+                    (*gen, f(value))
+                } else {
+                    (u32::MAX, U::default())
+                }
+            })
+            .collect();
+        Coarena { data }
+    }
+
+    /// Releases the memory used by the deleted elements at the end of this coarena.
+    ///
+    /// This only reclaims the trailing run of deleted elements, i.e., the ones with an index
+    /// larger than the largest index of a live element. Deleted elements before that aren’t
+    /// reclaimed so that the indices of the live elements remain valid.
+    pub fn shrink_to_fit(&mut self) {
+        let len = self
+            .data
+            .iter()
+            .rposition(|(gen, _)| *gen != u32::MAX)
+            .map_or(0, |i| i + 1);
+        self.data.truncate(len);
+        self.data.shrink_to_fit();
+    }
+
+    /// Computes the changes needed to turn `self` into `other`.
+    ///
+    /// The changes are sorted by index. An element whose index has a different generation number
+    /// in `other` is reported as [`CoarenaDelta::Removed`] followed by [`CoarenaDelta::Added`],
+    /// since it is a different element.
+    pub fn diff(&self, other: &Coarena<T>) -> Vec<(Index, CoarenaDelta<T>)>
+    where
+        T: PartialEq + Clone,
+    {
+        let mut result = vec![];
+
+        for i in 0..self.data.len().max(other.data.len()) {
+            let old = self.data.get(i).filter(|(gen, _)| *gen != u32::MAX);
+            let new = other.data.get(i).filter(|(gen, _)| *gen != u32::MAX);
+
+            match (old, new) {
+                (Some((old_gen, old_value)), Some((new_gen, new_value))) if old_gen == new_gen => {
+                    if old_value != new_value {
+                        let index = Index::from_raw_parts(i as u32, *new_gen);
+                        result.push((index, CoarenaDelta::Changed(new_value.clone())));
+                    }
+                }
+                _ => {
+                    if let Some((old_gen, _)) = old {
+                        let index = Index::from_raw_parts(i as u32, *old_gen);
+                        result.push((index, CoarenaDelta::Removed));
+                    }
+                    if let Some((new_gen, new_value)) = new {
+                        let index = Index::from_raw_parts(i as u32, *new_gen);
+                        result.push((index, CoarenaDelta::Added(new_value.clone())));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     /// Gets a specific element from the coarena, if it exists.
     pub fn get(&self, index: Index) -> Option<&T> {
         let (i, g) = index.into_raw_parts();
@@ -64,6 +216,45 @@ impl<T> Coarena<T> {
             .and_then(|(gg, t)| if g == *gg { Some(t) } else { None })
     }
 
+    /// Gets mutable references to several elements of the coarena at once.
+    ///
+    /// The i-th returned reference is `None` if the element at `indices[i]` doesn’t exist. No
+    /// element is created by this method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two of the given indices refer to the same slot, even with different generation
+    /// numbers.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [Index; N]) -> [Option<&mut T>; N] {
+        for (k, a) in indices.iter().enumerate() {
+            for b in &indices[..k] {
+                assert_ne!(
+                    a.into_raw_parts().0,
+                    b.into_raw_parts().0,
+                    "Cannot index the same object twice."
+                );
+            }
+        }
+
+        let len = self.data.len();
+        let data = self.data.as_mut_ptr();
+        indices.map(|index| {
+            let (i, g) = index.into_raw_parts();
+            if i as usize >= len {
+                return None;
+            }
+
+            // SAFETY: `i` is in bounds, and all the indices point to different slots so the
+            //         returned references don’t alias.
+            let elt = unsafe { &mut *data.add(i as usize) };
+            if elt.0 == g {
+                Some(&mut elt.1)
+            } else {
+                None
+            }
+        })
+    }
+
     /// Inserts an element into this coarena.
     pub fn insert(&mut self, a: Index, value: T)
     where
@@ -78,6 +269,29 @@ impl<T> Coarena<T> {
         self.data[i1 as usize] = (g1, value);
     }
 
+    /// Gets the entry of the given index for in-place manipulation.
+    ///
+    /// The coarena is grown if needed, with `T::default()` for the new deleted elements. The
+    /// entry is vacant if the element at `index` doesn’t exist, or exists with a different
+    /// generation number. In the latter case, inserting through the entry overwrites the
+    /// existing element.
+    pub fn entry(&mut self, index: Index) -> CoarenaEntry<'_, T>
+    where
+        T: Default,
+    {
+        let (i, gen) = index.into_raw_parts();
+
+        if self.data.len() <= i as usize {
+            self.data
+                .resize_with(i as usize + 1, || (u32::MAX, T::default()));
+        }
+
+        CoarenaEntry {
+            slot: &mut self.data[i as usize],
+            gen,
+        }
+    }
+
     /// Ensure that the given element exists in this coarena, and return its mutable reference.
     pub fn ensure_element_exist(&mut self, a: Index, default: T) -> &mut T
     where
@@ -141,3 +355,419 @@ impl<T> Coarena<T> {
         (&mut elt1.1, &mut elt2.1)
     }
 }
+
+/// A view into a single element of a coarena, which may or may not exist.
+///
+/// This is returned by [`Coarena::entry`].
+pub struct CoarenaEntry<'a, T> {
+    slot: &'a mut (u32, T),
+    gen: u32,
+}
+
+impl<'a, T> CoarenaEntry<'a, T> {
+    /// Is the element of this entry missing from the coarena?
+    pub fn is_vacant(&self) -> bool {
+        self.slot.0 != self.gen
+    }
+
+    /// Inserts `value` if the entry is vacant, and returns a mutable reference to the element.
+    pub fn or_insert(self, value: T) -> &'a mut T {
+        self.or_insert_with(|| value)
+    }
+
+    /// Inserts the result of `f` if the entry is vacant, and returns a mutable reference to the
+    /// element.
+    ///
+    /// `f` isn’t called if the element already exists.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, f: F) -> &'a mut T {
+        if self.is_vacant() {
+            *self.slot = (self.gen, f());
+        }
+
+        &mut self.slot.1
+    }
+
+    /// Inserts `T::default()` if the entry is vacant, and returns a mutable reference to the
+    /// element.
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Calls `f` on the element if it exists, and returns the entry for further manipulation.
+    pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        if !self.is_vacant() {
+            f(&mut self.slot.1);
+        }
+
+        self
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl<T> Coarena<T> {
+    /// Serializes only the live elements of this coarena, as `(index, generation, value)`
+    /// triples, along with its length.
+    ///
+    /// This is much more compact than the default serialization for sparse coarenas. Use it
+    /// with `#[serde(serialize_with = "Coarena::serialize_compact")]` on a coarena field, along
+    /// with [`Coarena::deserialize_compact`]. The values of the deleted elements aren’t
+    /// preserved: they are reset to `T::default()` by the deserialization.
+    pub fn serialize_compact<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+    {
+        let live: Vec<_> = self
+            .iter_with_gen()
+            .map(|(index, gen, value)| (index.into_raw_parts().0, gen, value))
+            .collect();
+        serde::Serialize::serialize(&(self.data.len() as u32, live), serializer)
+    }
+
+    /// Deserializes a coarena serialized with [`Coarena::serialize_compact`].
+    ///
+    /// Use it with `#[serde(deserialize_with = "Coarena::deserialize_compact")]`. The deleted
+    /// elements are set to `T::default()`.
+    pub fn deserialize_compact<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        T: serde::Deserialize<'de> + Default,
+    {
+        let (len, live): (u32, Vec<(u32, u32, T)>) = serde::Deserialize::deserialize(deserializer)?;
+        let mut data = Vec::with_capacity(len as usize);
+        data.resize_with(len as usize, || (u32::MAX, T::default()));
+
+        for (i, gen, value) in live {
+            let elt = data.get_mut(i as usize).ok_or_else(|| {
+                <D::Error as serde::de::Error>::custom("coarena element index out of bounds")
+            })?;
+            *elt = (gen, value);
+        }
+
+        Ok(Self { data })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Coarena, CoarenaDelta};
+    use crate::data::arena::Index;
+
+    #[test]
+    fn retain_interleaved() {
+        let mut coarena = Coarena::new();
+        let indices: Vec<_> = (0..10).map(|i| Index::from_raw_parts(i, i + 1)).collect();
+        for (i, index) in indices.iter().enumerate() {
+            coarena.insert(*index, i);
+        }
+        // Leave a hole that must not be visited by the predicate.
+        assert_eq!(coarena.remove(indices[9], 0), Some(9));
+
+        let mut visited = vec![];
+        coarena.retain(42, |index, value| {
+            visited.push(index);
+            *value *= 10;
+            *value % 20 == 0
+        });
+
+        assert_eq!(visited, indices[..9]);
+        assert_eq!(coarena.data.len(), 10);
+
+        let survivors: Vec<_> = coarena
+            .iter()
+            .map(|(index, value)| (index, *value))
+            .collect();
+        let expected: Vec<_> = [0, 2, 4, 6, 8]
+            .into_iter()
+            .map(|i| (indices[i], i * 10))
+            .collect();
+        assert_eq!(survivors, expected);
+
+        assert_eq!(coarena.get(indices[1]), None);
+        assert_eq!(coarena.get_unknown_gen(1), Some(&42));
+        assert_eq!(coarena.get(indices[2]), Some(&20));
+        // The element deleted before the call is left unchanged.
+        assert_eq!(coarena.get_unknown_gen(9), Some(&0));
+    }
+
+    #[test]
+    fn shrink_to_fit_reclaims_trailing_holes() {
+        let mut coarena = Coarena::new();
+        let indices: Vec<_> = (0..1000).map(|i| Index::from_raw_parts(i, 0)).collect();
+        for index in &indices {
+            coarena.insert(*index, 1.0);
+        }
+
+        // Interior holes must be kept.
+        let _ = coarena.remove(indices[5], 0.0);
+        for index in &indices[10..] {
+            let _ = coarena.remove(*index, 0.0);
+        }
+
+        let capacity = coarena.data.capacity();
+        coarena.shrink_to_fit();
+        assert!(coarena.data.capacity() < capacity);
+        assert_eq!(coarena.data.len(), 10);
+        assert_eq!(coarena.iter().count(), 9);
+        assert_eq!(coarena.get(indices[9]), Some(&1.0));
+        assert_eq!(coarena.get(indices[5]), None);
+
+        for index in &indices[..10] {
+            let _ = coarena.remove(*index, 0.0);
+        }
+        coarena.shrink_to_fit();
+        assert_eq!(coarena.data.capacity(), 0);
+    }
+
+    #[test]
+    fn diff() {
+        let index = |i, gen| Index::from_raw_parts(i, gen);
+        let mut old = Coarena::new();
+        old.insert(index(0, 1), 10);
+        old.insert(index(1, 1), 20);
+        old.insert(index(2, 1), 30);
+        old.insert(index(3, 1), 40);
+
+        let mut new = old.clone();
+        assert!(old.diff(&new).is_empty());
+
+        // Value mutation.
+        *new.get_mut(index(0, 1)).unwrap() = 11;
+        // Removal.
+        let _ = new.remove(index(1, 1), 0);
+        // Overwrite with a new generation.
+        new.insert(index(2, 2), 30);
+        // Insertion, past the end of `old`.
+        new.insert(index(5, 1), 60);
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                (index(0, 1), CoarenaDelta::Changed(11)),
+                (index(1, 1), CoarenaDelta::Removed),
+                (index(2, 1), CoarenaDelta::Removed),
+                (index(2, 2), CoarenaDelta::Added(30)),
+                (index(5, 1), CoarenaDelta::Added(60)),
+            ]
+        );
+
+        // The reverse diff removes what was added, and vice-versa.
+        assert_eq!(
+            new.diff(&old),
+            vec![
+                (index(0, 1), CoarenaDelta::Changed(10)),
+                (index(1, 1), CoarenaDelta::Added(20)),
+                (index(2, 2), CoarenaDelta::Removed),
+                (index(2, 1), CoarenaDelta::Added(30)),
+                (index(5, 1), CoarenaDelta::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_disjoint_mut() {
+        let index = |i, gen| Index::from_raw_parts(i, gen);
+        let mut coarena = Coarena::new();
+        coarena.insert(index(0, 1), 10);
+        coarena.insert(index(2, 1), 30);
+        coarena.insert(index(3, 1), 40);
+
+        // Present, stale generation, and past the end.
+        let [a, b, c] = coarena.get_disjoint_mut([index(2, 1), index(3, 2), index(7, 1)]);
+        assert_eq!(a, Some(&mut 30));
+        assert_eq!(b, None);
+        assert_eq!(c, None);
+
+        // Present, never inserted, and present.
+        let [a, b, c] = coarena.get_disjoint_mut([index(3, 1), index(1, 1), index(0, 1)]);
+        *a.unwrap() += 1;
+        assert_eq!(b, None);
+        *c.unwrap() += 1;
+
+        assert_eq!(coarena.get(index(0, 1)), Some(&11));
+        assert_eq!(coarena.get(index(3, 1)), Some(&41));
+        // Nothing was inserted.
+        assert_eq!(coarena.get(index(1, 1)), None);
+        assert_eq!(coarena.get(index(7, 1)), None);
+        assert_eq!(coarena.iter().count(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot index the same object twice.")]
+    fn get_disjoint_mut_aliasing() {
+        let mut coarena = Coarena::new();
+        coarena.insert(Index::from_raw_parts(0, 1), 10);
+        coarena.insert(Index::from_raw_parts(1, 1), 20);
+        let _ = coarena.get_disjoint_mut([
+            Index::from_raw_parts(0, 1),
+            Index::from_raw_parts(1, 1),
+            Index::from_raw_parts(0, 2),
+        ]);
+    }
+
+    #[test]
+    fn reserve_avoids_reallocations() {
+        let mut coarena = Coarena::new();
+        coarena.insert(Index::from_raw_parts(0, 0), 0);
+        coarena.reserve(10_000, 0);
+        assert!(coarena.capacity() >= 10_001);
+        // The reserved slots are deleted elements.
+        assert_eq!(coarena.iter().count(), 1);
+        let ptr = coarena.data.as_ptr();
+
+        for i in 1..10_001 {
+            coarena.insert(Index::from_raw_parts(i, 0), i);
+            assert_eq!(coarena.data.as_ptr(), ptr);
+        }
+
+        let mut coarena = Coarena::with_capacity(10_000, 0);
+        assert!(coarena.capacity() >= 10_000);
+        assert_eq!(coarena.iter().count(), 0);
+        let ptr = coarena.data.as_ptr();
+
+        // Insertion order doesn’t matter.
+        for i in (0..10_000).rev() {
+            coarena.insert(Index::from_raw_parts(i, 0), i);
+            assert_eq!(coarena.data.as_ptr(), ptr);
+        }
+        assert_eq!(coarena.iter().count(), 10_000);
+    }
+
+    #[test]
+    fn map() {
+        let index = |i, gen| Index::from_raw_parts(i, gen);
+        let mut coarena = Coarena::new();
+        coarena.insert(index(0, 1), 10);
+        coarena.insert(index(1, 3), 20);
+        coarena.insert(index(3, 2), 40);
+        let _ = coarena.remove(index(1, 3), 0);
+
+        let mut num_calls = 0;
+        let mapped = coarena.map(|value| {
+            num_calls += 1;
+            format!("#{value}")
+        });
+
+        // Deleted elements and holes aren’t transformed.
+        assert_eq!(num_calls, 2);
+        assert_eq!(mapped.get(index(0, 1)).map(String::as_str), Some("#10"));
+        assert_eq!(mapped.get(index(3, 2)).map(String::as_str), Some("#40"));
+        assert_eq!(mapped.get(index(1, 3)), None);
+        assert_eq!(mapped.get(index(2, 0)), None);
+        // Stale generations are still rejected.
+        assert_eq!(mapped.get(index(3, 1)), None);
+        assert_eq!(
+            mapped.iter().map(|(index, _)| index).collect::<Vec<_>>(),
+            vec![index(0, 1), index(3, 2)]
+        );
+    }
+
+    #[test]
+    fn iter_interleaved() {
+        let index = |i, gen| Index::from_raw_parts(i, gen);
+        let mut coarena = Coarena::new();
+        coarena.insert(index(0, 1), 10);
+        coarena.insert(index(1, 4), 20);
+        coarena.insert(index(2, 2), 30);
+        coarena.insert(index(4, 7), 50);
+        coarena.insert(index(5, 3), 60);
+        // Dead slots: 1 and 5 were removed, 3 was never inserted.
+        let _ = coarena.remove(index(1, 4), 0);
+        let _ = coarena.remove(index(5, 3), 0);
+
+        for (_, value) in coarena.iter_mut() {
+            *value += 1;
+        }
+
+        assert_eq!(
+            coarena.iter_with_gen().collect::<Vec<_>>(),
+            vec![
+                (index(0, 1), 1, &11),
+                (index(2, 2), 2, &31),
+                (index(4, 7), 7, &51),
+            ]
+        );
+        assert_eq!(
+            coarena
+                .iter_mut()
+                .map(|(index, value)| (index, *value))
+                .collect::<Vec<_>>(),
+            vec![(index(0, 1), 11), (index(2, 2), 31), (index(4, 7), 51)]
+        );
+        // The dead slots weren’t modified.
+        assert_eq!(coarena.get_unknown_gen(1), Some(&0));
+        assert_eq!(coarena.get_unknown_gen(5), Some(&0));
+    }
+
+    #[test]
+    fn entry() {
+        let index = |i, gen| Index::from_raw_parts(i, gen);
+        let mut coarena = Coarena::new();
+
+        // Insert on absent, past the end of the coarena.
+        assert!(coarena.entry(index(3, 1)).is_vacant());
+        *coarena.entry(index(3, 1)).or_insert(40) += 1;
+        assert_eq!(coarena.get(index(3, 1)), Some(&41));
+        assert_eq!(coarena.iter().count(), 1);
+
+        // Modify on present, without calling the default constructor.
+        let value = coarena
+            .entry(index(3, 1))
+            .and_modify(|value| *value *= 2)
+            .or_insert_with(|| unreachable!());
+        assert_eq!(*value, 82);
+
+        // `and_modify` does nothing on absent elements.
+        let _ = coarena
+            .entry(index(1, 1))
+            .and_modify(|_| unreachable!())
+            .or_default();
+        assert_eq!(coarena.get(index(1, 1)), Some(&0));
+
+        // A stale generation is overwritten.
+        let entry = coarena.entry(index(3, 2));
+        assert!(entry.is_vacant());
+        assert_eq!(*entry.and_modify(|_| unreachable!()).or_insert(7), 7);
+        assert_eq!(coarena.get(index(3, 2)), Some(&7));
+        assert_eq!(coarena.get(index(3, 1)), None);
+        assert_eq!(
+            coarena.iter().collect::<Vec<_>>(),
+            vec![(index(1, 1), &0), (index(3, 2), &7)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde-serialize")]
+    fn serde_compact_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Compact {
+            #[serde(
+                serialize_with = "Coarena::serialize_compact",
+                deserialize_with = "Coarena::deserialize_compact"
+            )]
+            coarena: Coarena<u64>,
+        }
+
+        // A coarena with only one live element out of ten, and trailing deleted elements.
+        let mut coarena = Coarena::new();
+        for i in 0..1000 {
+            coarena.insert(Index::from_raw_parts(i, 3), i as u64);
+        }
+        coarena.retain(0, |index, _| {
+            index.into_raw_parts().0 % 10 == 0 && index.into_raw_parts().0 < 900
+        });
+
+        let full = bincode::serialize(&coarena).unwrap();
+        let compact = bincode::serialize(&Compact {
+            coarena: coarena.clone(),
+        })
+        .unwrap();
+        assert!(compact.len() * 5 < full.len());
+
+        let deserialized: Compact = bincode::deserialize(&compact).unwrap();
+        assert_eq!(deserialized.coarena.data, coarena.data);
+    }
+}
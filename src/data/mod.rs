@@ -1,7 +1,7 @@
 //! Data structures modified with guaranteed deterministic behavior after deserialization.
 
 pub use self::arena::{Arena, Index};
-pub use self::coarena::Coarena;
+pub use self::coarena::{Coarena, CoarenaDelta, CoarenaEntry};
 
 pub mod arena;
 mod coarena;
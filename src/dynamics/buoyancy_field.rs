@@ -0,0 +1,146 @@
+use crate::dynamics::RigidBody;
+use crate::geometry::{Collider, ColliderSet};
+use crate::math::{AngVector, Point, Real, UnitVector, Vector};
+use crate::utils::SimdCross;
+
+#[cfg(doc)]
+use crate::pipeline::PhysicsPipeline;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// A fluid filling the half-space below a plane, applying buoyancy forces to the rigid-bodies
+/// whose colliders are submerged.
+///
+/// This is set with [`PhysicsPipeline::set_buoyancy_field`]. At each timestep, each collider
+/// attached to a dynamic rigid-body receives a force opposite to the gravity, equal to the
+/// weight of the fluid it displaces (Archimedes’ principle), applied at the center of its
+/// submerged part. Sensor colliders aren’t affected.
+///
+/// The submerged part of a collider is estimated from its world-space [`Aabb`](crate::geometry::Aabb):
+/// the volume of the shape is multiplied by the fraction of the extent of the `Aabb` along the
+/// plane normal that is below the plane. This is exact for boxes aligned with the plane, and a
+/// coarse approximation for other shapes.
+pub struct BuoyancyField {
+    /// A point on the surface of the fluid.
+    pub plane_point: Point<Real>,
+    /// The normal of the surface of the fluid, pointing outside of the fluid.
+    pub plane_normal: UnitVector<Real>,
+    /// The density of the fluid, i.e., its mass per unit volume (per unit area in 2D).
+    pub fluid_density: Real,
+}
+
+impl BuoyancyField {
+    /// Creates a fluid filling the half-space below the plane with the given point and normal.
+    pub fn new(
+        plane_point: Point<Real>,
+        plane_normal: UnitVector<Real>,
+        fluid_density: Real,
+    ) -> Self {
+        Self {
+            plane_point,
+            plane_normal,
+            fluid_density,
+        }
+    }
+
+    /// The estimated submerged volume (area in 2D) of the collider, and the world-space center
+    /// of its submerged part.
+    ///
+    /// Returns `None` if the collider is completely outside of the fluid.
+    pub fn submerged_volume(&self, collider: &Collider) -> Option<(Real, Point<Real>)> {
+        let aabb = collider.compute_aabb();
+        let center = aabb.center();
+        let half_extents = aabb.half_extents();
+        let normal = *self.plane_normal;
+
+        // The signed distance of the `Aabb` center to the plane, and its half extent along the
+        // plane normal.
+        let center_dist = (center - self.plane_point).dot(&normal);
+        let radius = normal.abs().dot(&half_extents);
+
+        let submerged_fraction = if radius > 0.0 {
+            ((radius - center_dist) / (2.0 * radius)).clamp(0.0, 1.0)
+        } else if center_dist <= 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        if submerged_fraction == 0.0 {
+            return None;
+        }
+
+        let volume = collider.shape.mass_properties(1.0).mass() * submerged_fraction;
+        let submerged_depth = 2.0 * radius * submerged_fraction;
+        let submerged_center = center - normal * (radius - submerged_depth / 2.0);
+        Some((volume, submerged_center))
+    }
+
+    /// Adds the buoyancy forces applied to the colliders of `rb` to its forces.
+    ///
+    /// This must be called after the effective forces of `rb` were computed.
+    pub(crate) fn apply(
+        &self,
+        rb: &mut RigidBody,
+        colliders: &ColliderSet,
+        gravity: &Vector<Real>,
+    ) {
+        let mut force = Vector::zeros();
+        let mut torque: AngVector<Real> = na::zero();
+
+        for handle in rb.colliders() {
+            let Some(co) = colliders.get(*handle) else {
+                continue;
+            };
+
+            if !co.is_enabled() || co.is_sensor() {
+                continue;
+            }
+
+            if let Some((volume, point)) = self.submerged_volume(co) {
+                let co_force = -gravity * (self.fluid_density * volume);
+                force += co_force;
+                torque += (point - rb.mprops.world_com).gcross(co_force);
+            }
+        }
+
+        rb.forces.force += force;
+        rb.forces.torque += torque;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+
+    #[test]
+    fn buoyancy_field_floats_light_box() {
+        use crate::dynamics::BuoyancyField;
+
+        let mut world = TestWorld::with_gravity();
+
+        // A box half as dense as the fluid, dropped from above the surface.
+        let rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 2.0)
+            .linear_damping(5.0)
+            .can_sleep(false);
+        let (handle, _) = world.insert_body(rb, cuboid(0.5, 0.5).density(500.0));
+
+        let field = BuoyancyField::new(Point::origin(), Vector::y_axis(), 1000.0);
+        assert!(world.pipeline.set_buoyancy_field(Some(field)).is_none());
+
+        world.steps(600);
+
+        // The box floats half-submerged, i.e., with its center on the surface.
+        let rb = &world.bodies[handle];
+        assert!(rb.translation().y.abs() < 1.0e-3);
+        assert!(rb.linvel().norm() < 1.0e-3);
+
+        // Without the fluid, the box falls.
+        assert_eq!(world.pipeline.set_buoyancy_field(None), Some(field));
+        world.steps(10);
+        assert!(world.bodies[handle].linvel().y < -0.5);
+    }
+}
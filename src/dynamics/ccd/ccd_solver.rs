@@ -21,6 +21,10 @@ pub enum PredictedImpacts {
 pub struct CCDSolver {
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     query_pipeline: QueryPipeline,
+    // The kinematic body hit first by each body frozen by the last call to
+    // `predict_impacts_at_next_positions`.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    kinematic_impacts: HashMap<RigidBodyHandle, RigidBodyHandle>,
 }
 
 impl Default for CCDSolver {
@@ -45,15 +49,27 @@ impl CCDSolver {
     {
         CCDSolver {
             query_pipeline: QueryPipeline::with_query_dispatcher(d),
+            kinematic_impacts: HashMap::default(),
         }
     }
 
     /// Apply motion-clamping to the bodies affected by the given `impacts`.
     ///
     /// The `impacts` should be the result of a previous call to `self.predict_next_impacts`.
+    ///
+    /// A body hitting a kinematic body is carried along by the kinematic body after the
+    /// time of impact, so that it doesn’t get traversed by it.
     pub fn clamp_motions(&self, dt: Real, bodies: &mut RigidBodySet, impacts: &PredictedImpacts) {
         if let PredictedImpacts::Impacts(tois) = impacts {
             for (handle, toi) in tois {
+                let carrier_motion = self.kinematic_impacts.get(handle).map(|kinematic| {
+                    let kinematic = &bodies[*kinematic];
+                    (
+                        kinematic.vels,
+                        kinematic.pos.next_position,
+                        kinematic.mprops.local_mprops.local_com,
+                    )
+                });
                 let rb = bodies.index_mut_internal(*handle);
                 let local_com = &rb.mprops.local_mprops.local_com;
 
@@ -68,9 +84,18 @@ impl CCDSolver {
                 //     rb.ccd.ccd_thickness,
                 //     rb.ccd.max_point_velocity(&rb.integrated_vels)
                 // );
-                let new_pos =
-                    rb.integrated_vels
-                        .integrate(toi.max(min_toi), &rb.pos.position, local_com);
+                let toi = toi.max(min_toi);
+                let mut new_pos = rb
+                    .integrated_vels
+                    .integrate(toi, &rb.pos.position, local_com);
+
+                if let Some((vels, end_pos, local_com)) = carrier_motion {
+                    // Apply the motion of the kinematic body between the time of impact
+                    // and the end of the timestep.
+                    let pos_at_toi = vels.integrate(toi - dt, &end_pos, &local_com);
+                    new_pos = end_pos * pos_at_toi.inverse() * new_pos;
+                }
+
                 rb.pos.next_position = new_pos;
             }
         }
@@ -205,6 +230,7 @@ impl CCDSolver {
                                     None,
                                     0.0,
                                     min_toi,
+                                    dt,
                                     smallest_dist,
                                 ) {
                                     min_toi = min_toi.min(toi.toi);
@@ -238,6 +264,7 @@ impl CCDSolver {
         let mut all_toi = BinaryHeap::new();
         let mut pairs_seen = HashMap::default();
         let mut min_overstep = dt;
+        self.kinematic_impacts.clear();
 
         // Update the query pipeline.
         self.query_pipeline.update_with_generator(
@@ -323,6 +350,7 @@ impl CCDSolver {
                                     // NOTE: we use dt here only once we know that
                                     // there is at least one TOI before dt.
                                     min_overstep,
+                                    dt,
                                     smallest_dist,
                                 ) {
                                     if toi.toi > dt {
@@ -389,11 +417,23 @@ impl CCDSolver {
             if should_freeze1 {
                 let _ = frozen.insert(toi.b1.unwrap(), toi.toi);
                 colliders_to_check.extend_from_slice(&rb1.unwrap().colliders.0);
+
+                if rb2.is_some_and(|rb| rb.is_kinematic()) {
+                    let _ = self
+                        .kinematic_impacts
+                        .insert(toi.b1.unwrap(), toi.b2.unwrap());
+                }
             }
 
             if should_freeze2 {
                 let _ = frozen.insert(toi.b2.unwrap(), toi.toi);
                 colliders_to_check.extend_from_slice(&rb2.unwrap().colliders.0);
+
+                if rb1.is_some_and(|rb| rb.is_kinematic()) {
+                    let _ = self
+                        .kinematic_impacts
+                        .insert(toi.b2.unwrap(), toi.b1.unwrap());
+                }
             }
 
             let start_time = toi.toi;
@@ -454,6 +494,7 @@ impl CCDSolver {
                             frozen2.copied(),
                             start_time,
                             dt,
+                            dt,
                             smallest_dist,
                         ) {
                             all_toi.push(toi);
@@ -554,3 +595,68 @@ impl CCDSolver {
         PredictedImpacts::Impacts(frozen)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn ccd_against_fast_kinematic_wall() {
+        const WALL_HALF_THICKNESS: Real = 0.05;
+        const BULLET_RADIUS: Real = 0.1;
+
+        // Returns the x coordinates of the bullet and the wall after each timestep.
+        let run = |ccd_enabled: bool| -> Vec<(Real, Real)> {
+            let mut world = TestWorld::new();
+
+            // The bullet and the wall close on each other by more than 3m at each timestep,
+            // and the wall ends up behind the bullet’s starting point after the first one.
+            let wall = world.bodies.insert(
+                RigidBodyBuilder::kinematic_velocity_based()
+                    .translation(Vector::x() * 1.0)
+                    .linvel(Vector::x() * -100.0),
+            );
+            #[cfg(feature = "dim2")]
+            let co = ColliderBuilder::cuboid(WALL_HALF_THICKNESS, 5.0);
+            #[cfg(feature = "dim3")]
+            let co = ColliderBuilder::cuboid(WALL_HALF_THICKNESS, 5.0, 5.0);
+            world
+                .colliders
+                .insert_with_parent(co, wall, &mut world.bodies);
+
+            let (bullet, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .linvel(Vector::x() * 100.0)
+                    .ccd_enabled(ccd_enabled),
+                ColliderBuilder::ball(BULLET_RADIUS),
+            );
+
+            let mut positions = vec![];
+
+            for _ in 0..5 {
+                world.step();
+                positions.push((
+                    world.bodies[bullet].translation().x,
+                    world.bodies[wall].translation().x,
+                ));
+            }
+
+            positions
+        };
+
+        // Without CCD, the bullet tunnels through the wall.
+        let (bullet_x, wall_x) = *run(false).last().unwrap();
+        assert!(bullet_x > wall_x);
+
+        // With CCD, the bullet stays in front of the wall, which then pushes it.
+        let positions = run(true);
+        let (bullet_x, wall_x) = positions[0];
+        assert!(bullet_x + BULLET_RADIUS <= wall_x - WALL_HALF_THICKNESS + 1.0e-3);
+        for (bullet_x, wall_x) in positions {
+            assert!(bullet_x < wall_x - WALL_HALF_THICKNESS);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use crate::dynamics::{RigidBody, RigidBodyHandle};
+use crate::dynamics::{
+    RigidBody, RigidBodyHandle, RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+};
 use crate::geometry::{Collider, ColliderHandle};
-use crate::math::Real;
+use crate::math::{Isometry, Real};
 use parry::query::{NonlinearRigidMotion, QueryDispatcher};
 
 #[derive(Copy, Clone, Debug)]
@@ -46,6 +48,7 @@ impl TOIEntry {
         frozen2: Option<Real>,
         start_time: Real,
         end_time: Real,
+        dt: Real,
         smallest_contact_dist: Real,
     ) -> Option<Self> {
         assert!(start_time <= end_time);
@@ -53,14 +56,16 @@ impl TOIEntry {
             return None;
         }
 
-        let linvel1 = frozen1.is_none() as u32 as Real
-            * rb1.map(|b| b.integrated_vels.linvel).unwrap_or(na::zero());
-        let linvel2 = frozen2.is_none() as u32 as Real
-            * rb2.map(|b| b.integrated_vels.linvel).unwrap_or(na::zero());
-        let angvel1 = frozen1.is_none() as u32 as Real
-            * rb1.map(|b| b.integrated_vels.angvel).unwrap_or(na::zero());
-        let angvel2 = frozen2.is_none() as u32 as Real
-            * rb2.map(|b| b.integrated_vels.angvel).unwrap_or(na::zero());
+        let vels1 = rb1.map(|b| Self::body_velocity(b, co1, dt));
+        let vels2 = rb2.map(|b| Self::body_velocity(b, co2, dt));
+        let linvel1 =
+            frozen1.is_none() as u32 as Real * vels1.map(|v| v.linvel).unwrap_or(na::zero());
+        let linvel2 =
+            frozen2.is_none() as u32 as Real * vels2.map(|v| v.linvel).unwrap_or(na::zero());
+        let angvel1 =
+            frozen1.is_none() as u32 as Real * vels1.map(|v| v.angvel).unwrap_or(na::zero());
+        let angvel2 =
+            frozen2.is_none() as u32 as Real * vels2.map(|v| v.angvel).unwrap_or(na::zero());
 
         #[cfg(feature = "dim2")]
         let vel12 = (linvel2 - linvel1).norm()
@@ -86,8 +91,12 @@ impl TOIEntry {
 
         // Compute the TOI.
         let identity = NonlinearRigidMotion::identity();
-        let mut motion1 = rb1.map(Self::body_motion).unwrap_or(identity);
-        let mut motion2 = rb2.map(Self::body_motion).unwrap_or(identity);
+        let mut motion1 = rb1
+            .map(|b| Self::body_motion(b, co1, dt))
+            .unwrap_or(identity);
+        let mut motion2 = rb2
+            .map(|b| Self::body_motion(b, co2, dt))
+            .unwrap_or(identity);
 
         if let Some(t) = frozen1 {
             motion1.freeze(t);
@@ -149,7 +158,32 @@ impl TOIEntry {
         ))
     }
 
-    fn body_motion(rb: &RigidBody) -> NonlinearRigidMotion {
+    /// The position of the rigid-body `rb` at the beginning of the timestep.
+    ///
+    /// This is deduced from the position of its collider `co` since the position of
+    /// velocity-based kinematic bodies is updated before the CCD is run.
+    fn body_start_position(rb: &RigidBody, co: &Collider) -> Isometry<Real> {
+        co.parent
+            .map(|p| co.pos.0 * p.pos_wrt_parent.inverse())
+            .unwrap_or(rb.pos.position)
+    }
+
+    /// The velocity of the rigid-body `rb` during a timestep of length `dt`.
+    fn body_velocity(rb: &RigidBody, co: &Collider, dt: Real) -> RigidBodyVelocity {
+        match rb.body_type {
+            // Follow the prescribed motion of kinematic bodies so that fast-moving kinematic
+            // bodies are swept too. Dynamic bodies can’t tunnel through them otherwise.
+            RigidBodyType::KinematicPositionBased => RigidBodyPosition {
+                position: Self::body_start_position(rb, co),
+                next_position: rb.pos.next_position,
+            }
+            .interpolate_velocity(crate::utils::inv(dt), &rb.mprops.local_mprops.local_com),
+            RigidBodyType::KinematicVelocityBased => rb.vels,
+            RigidBodyType::Dynamic | RigidBodyType::Fixed => rb.integrated_vels,
+        }
+    }
+
+    fn body_motion(rb: &RigidBody, co: &Collider, dt: Real) -> NonlinearRigidMotion {
         if rb.ccd.ccd_active {
             NonlinearRigidMotion::new(
                 rb.pos.position,
@@ -157,6 +191,14 @@ impl TOIEntry {
                 rb.integrated_vels.linvel,
                 rb.integrated_vels.angvel,
             )
+        } else if rb.is_kinematic() {
+            let vels = Self::body_velocity(rb, co, dt);
+            NonlinearRigidMotion::new(
+                Self::body_start_position(rb, co),
+                rb.mprops.local_mprops.local_com,
+                vels.linvel,
+                vels.angvel,
+            )
         } else {
             NonlinearRigidMotion::constant_position(rb.pos.next_position)
         }
@@ -33,3 +33,57 @@ impl CoefficientCombineRule {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::CoefficientCombineRule;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn restitution_combine_rule_priority() {
+        let effective_restitution = |rule1, rule2| {
+            let mut world = TestWorld::new();
+
+            let co1 = ColliderBuilder::ball(1.0)
+                .restitution(0.2)
+                .restitution_combine_rule(rule1);
+            let (_, h1) = world.insert_body(RigidBodyBuilder::fixed(), co1);
+            let co2 = ColliderBuilder::ball(1.0)
+                .restitution(0.8)
+                .restitution_combine_rule(rule2);
+            let (_, h2) = world.insert_body(
+                RigidBodyBuilder::dynamic().translation(Vector::y() * 1.9),
+                co2,
+            );
+
+            world.step();
+
+            let pair = world.narrow_phase.contact_pair(h1, h2).unwrap();
+            pair.manifolds[0].data.solver_contacts[0].restitution
+        };
+
+        assert_eq!(
+            effective_restitution(
+                CoefficientCombineRule::Average,
+                CoefficientCombineRule::Average
+            ),
+            0.5
+        );
+        // `Max` has a higher priority than `Min`, whichever collider it is set on.
+        assert_eq!(
+            effective_restitution(CoefficientCombineRule::Max, CoefficientCombineRule::Min),
+            0.8
+        );
+        assert_eq!(
+            effective_restitution(CoefficientCombineRule::Min, CoefficientCombineRule::Max),
+            0.8
+        );
+        assert_eq!(
+            effective_restitution(CoefficientCombineRule::Min, CoefficientCombineRule::Average),
+            0.2
+        );
+    }
+}
@@ -4,6 +4,8 @@ use std::num::NonZeroUsize;
 
 #[cfg(doc)]
 use super::RigidBodyActivation;
+#[cfg(doc)]
+use crate::pipeline::StepStats;
 
 // TODO: enabling the block solver in 3d introduces a lot of jitters in
 //       the 3D domino demo. So for now we dont enable it in 3D.
@@ -34,6 +36,24 @@ pub struct IntegrationParameters {
     /// (default `5.0`).
     pub contact_damping_ratio: Real,
 
+    /// >= 0: the local damping applied along the normal of each contact (default: `0.0`).
+    ///
+    /// The penetration correction of each contact is reduced by this fraction of the normal
+    /// relative velocity at the contact at the beginning of the timestep, so contacts already
+    /// separating (resp. approaching) the bodies push them less (resp. more). This damps the
+    /// overshoot of the penetration correction, which makes tall stacks jitter when
+    /// [`Self::contact_damping_ratio`] is small. Values between `0.0` and `1.0` are recommended.
+    pub contact_normal_damping: Real,
+
+    /// >= 0: the local damping applied along the tangent directions of each contact (default: `0.0`).
+    ///
+    /// The friction of each contact is given a bias proportional to this fraction of the tangent
+    /// relative velocity at the contact at the beginning of the timestep. This damps the
+    /// tangential jitter and creep of resting contacts, the same way
+    /// [`Self::contact_normal_damping`] does along the normal. Values between `0.0` and `1.0`
+    /// are recommended.
+    pub contact_tangent_damping: Real,
+
     /// > 0: the natural frequency used by the springs for contact constraint regularization.
     ///
     /// Increasing this value will make it so that penetrations get fixed more quickly at the
@@ -87,22 +107,205 @@ pub struct IntegrationParameters {
     ///
     /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
     pub normalized_max_corrective_velocity: Real,
+    /// The number of timesteps over which the penetration correction of new contacts is ramped
+    /// up (default: `0`).
+    ///
+    /// When set, a contact only gets a fraction `age / penetration_warmup_steps` of the velocity
+    /// pushing the bodies out of penetration, where `age` is the number of previous timesteps
+    /// it was solved for (see [`ContactData::age`](crate::geometry::ContactData::age)). This
+    /// lets bodies spawned deeply overlapping separate gently instead of being ejected at high
+    /// velocities. Since this also applies to contacts created during normal motion, e.g., when
+    /// a body lands on the ground, these can penetrate slightly deeper during the first
+    /// timesteps. Setting this to `0` applies the full correction to all the contacts.
+    pub penetration_warmup_steps: u32,
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002m`).
     ///
     /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
     pub normalized_prediction_distance: Real,
+    /// The maximal distance a contact point can move between two timesteps while keeping its
+    /// warmstarting impulses, even if its feature ids changed (default: `0.005m`).
+    ///
+    /// The contacts of a contact manifold are normally matched between timesteps based on the
+    /// features of the shapes they lie on. These features can flicker when, e.g., two faces are
+    /// nearly parallel, which resets the warmstarting of the contact and makes resting bodies
+    /// jitter. A new contact that doesn’t match any previous contact by features is matched
+    /// instead with the closest lost contact, if it is closer than this distance. Set this to
+    /// zero to only match contacts based on their features.
+    ///
+    /// This value is implicitly scaled by [`IntegrationParameters::length_unit`].
+    pub normalized_contact_matching_distance: Real,
     /// The number of solver iterations run by the constraints solver for calculating forces (default: `4`).
     pub num_solver_iterations: NonZeroUsize,
     /// Number of addition friction resolution iteration run during the last solver sub-step (default: `0`).
     pub num_additional_friction_iterations: usize,
     /// Number of internal Project Gauss Seidel (PGS) iterations run at each solver iteration (default: `1`).
     pub num_internal_pgs_iterations: usize,
+    /// Can the solver iterations of each island stop early once they converged
+    /// (default: `false`)?
+    ///
+    /// If `true`, the PGS iterations of an island stop as soon as one of them changes the
+    /// velocity of every body of the island by less than [`Self::adaptive_iterations_tolerance`].
+    /// The remaining [`Self::num_internal_pgs_iterations`] of the current solver iteration are
+    /// skipped, and so are the contact resolutions of the remaining
+    /// [`Self::num_solver_iterations`] of the timestep, which only apply the contact impulses
+    /// of the converged iteration. This avoids wasting iterations on islands that are easy to
+    /// solve, e.g., a few resting bodies, while islands that are harder to solve, e.g., large
+    /// stacks, still use all their iterations. The number of iterations actually run is
+    /// reported by [`StepStats::num_pgs_iterations`].
+    ///
+    /// The contact resolutions are only skipped if [`Self::warmstart_coefficient`] is `1.0`.
+    /// Contacts starting during the skipped iterations are only handled at the next timestep,
+    /// so bodies can penetrate a bit more than without this option.
+    pub adaptive_iterations: bool,
+    /// The largest change of linear (in `m/s`) or angular (in `rad/s`) velocity of a body during
+    /// a PGS iteration below which the iterations of its island are considered converged
+    /// (default: `1.0e-4`).
+    ///
+    /// This is only used if [`Self::adaptive_iterations`] is `true`.
+    pub adaptive_iterations_tolerance: Real,
+    /// Should the constraints solver measure its residuals (default: `false`)?
+    ///
+    /// If `true`, the velocity changes caused by the last PGS iteration are reported by
+    /// [`StepStats::max_normal_residual`] and [`StepStats::max_friction_residual`]. This
+    /// copies the velocities of every body twice per PGS iteration of the last substep, so it
+    /// is disabled by default, and these residuals are zero.
+    pub measure_solver_residuals: bool,
     /// The number of stabilization iterations run at each solver iterations (default: `2`).
     pub num_internal_stabilization_iterations: usize,
     /// Minimum number of dynamic bodies in each active island (default: `128`).
     pub min_island_size: usize,
+    /// Minimum number of dynamic bodies an active island must contain to be solved in parallel
+    /// with the other islands (default: `0`).
+    ///
+    /// Smaller islands are solved sequentially on the thread calling `PhysicsPipeline::step`,
+    /// which avoids having the cost of spawning a parallel task dominate the cost of solving the
+    /// island itself. This is only used when the `parallel` feature is enabled.
+    pub min_parallel_island_size: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
+    ///
+    /// When CCD is enabled, the timestep is split at the time of the first impact involving a
+    /// CCD-enabled rigid-body, until this number of substeps is reached. After the last
+    /// substep, the motion of the rigid-bodies still about to tunnel is clamped at their time
+    /// of impact instead. The number of substeps actually performed is reported by
+    /// [`StepStats::ccd_substeps_used`](crate::pipeline::StepStats::ccd_substeps_used). Setting
+    /// this to `0` disables CCD.
     pub max_ccd_substeps: usize,
+    /// Should the contact manifolds of the same contact pair sharing the same normal and contact
+    /// plane be merged into a single manifold before being solved (default: `false`)?
+    ///
+    /// This typically happens when several sub-shapes of a compound shape touch the same face of
+    /// another collider. Merging these manifolds avoids over-constraining the contact, which can
+    /// cause jitter, by keeping at most `MAX_MANIFOLD_POINTS` contacts. The contacts moved to
+    /// another manifold are warm-started from their original contact, and the impulses computed
+    /// by the solver are written back to that original contact. Contacts discarded by the merge
+    /// get zero impulses.
+    pub merge_coincident_manifolds: bool,
+    /// The maximum number of solver contacts kept for each contact pair, across all its contact
+    /// manifolds (default: `None`, i.e., no limit).
+    ///
+    /// Contacts with complex meshes produce one contact manifold per triangle (or segment in
+    /// 2D), and contact manifolds with more than `MAX_MANIFOLD_POINTS` contacts (e.g. after
+    /// [`Self::merge_coincident_manifolds`]) are solved as several constraints, which increases
+    /// the cost of the constraints resolution. When set, the excess contacts of each pair are
+    /// discarded before the constraints generation, keeping the deepest contact and the ones
+    /// spanning the largest area of the pair so the contact remains stable. Discarded contacts
+    /// get zero impulses.
+    pub max_contacts_per_manifold: Option<NonZeroUsize>,
+    /// Should all the constraints be solved one at a time, even if SIMD is enabled
+    /// (default: `false`)?
+    ///
+    /// The SIMD solver groups constraints into batches of `SIMD_WIDTH` constraints, so the order
+    /// in which the constraints are solved depends on the SIMD width the library was compiled
+    /// with. Enabling this makes the simulation results independent of the SIMD width, e.g.,
+    /// for lockstep networking between platforms with different SIMD widths. This has no effect
+    /// if SIMD isn’t enabled. Otherwise, it gives up the speedup of SIMD, so the constraints
+    /// resolution can be several times slower.
+    pub force_scalar_solver: bool,
+    /// Should the contact manifolds of each island be sorted before the constraints
+    /// generation (default: `false`)?
+    ///
+    /// The constraints solver resolves the contacts one after the other, so the result depends
+    /// on the order in which the contact manifolds are handed to it. By default, this is the
+    /// order of the contact pairs in the narrow-phase, which depends on the history of the
+    /// simulation (e.g. the order in which the pairs were found, or in which colliders were
+    /// added and removed). When enabled, the manifolds are sorted by the handles of their
+    /// rigid-bodies, then of their colliders, then by the index of their sub-shapes, before
+    /// being grouped into SIMD batches. This makes the simulation results independent of that
+    /// history, at the cost of a sort at each step.
+    pub deterministic_contact_order: bool,
+    /// Should the gyroscopic torque be applied to the rigid-bodies (default: `false`)?
+    ///
+    /// The gyroscopic torque makes bodies with non-uniform angular inertia precess and tumble
+    /// when spinning fast, e.g., the Dzhanibekov effect of a body spinning about its
+    /// intermediate principal axis. It is integrated implicitly to remain stable at large
+    /// angular velocities. This has no effect in 2D, and multibodies always take it into
+    /// account.
+    pub enable_gyroscopic_forces: bool,
+    /// The model used for resolving friction (default: [`FrictionModel::Cone`]).
+    pub friction_model: FrictionModel,
+    /// The scheme used for integrating the positions of the rigid-bodies from their velocities
+    /// (default: [`IntegrationScheme::SemiImplicitEuler`]).
+    pub integration_scheme: IntegrationScheme,
+    /// How rigid-bodies with a non-finite (NaN or infinite) position, velocity, or force are
+    /// handled at the beginning of each step (default: [`NonFiniteBodyHandling::ZeroComponents`]).
+    #[cfg(feature = "sanitize")]
+    pub non_finite_body_handling: NonFiniteBodyHandling,
+}
+
+/// The model used by the constraints solver for resolving friction.
+///
+/// In 2D, there is a single friction direction so both models are equivalent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum FrictionModel {
+    /// The friction along the two tangent directions of a contact is solved independently, and
+    /// each tangent impulse is clamped independently.
+    ///
+    /// This approximates the friction cone with a pyramid, i.e., friction is stronger along the
+    /// diagonals of the tangent directions.
+    PyramidDecoupled,
+    /// The friction along the two tangent directions of a contact is solved simultaneously, and
+    /// the tangent impulse is clamped to the friction cone.
+    #[default]
+    Cone,
+}
+
+/// The scheme used for integrating the positions of the rigid-bodies from their velocities.
+///
+/// This only affects rigid-bodies that aren’t part of a multibody.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum IntegrationScheme {
+    /// The positions are integrated with the velocities at the end of each substep, i.e.,
+    /// after the forces and constraints impulses were applied.
+    ///
+    /// This is first-order accurate and slowly loses energy on conservative systems like
+    /// pendulums or orbits.
+    #[default]
+    SemiImplicitEuler,
+    /// The positions are integrated with the velocities at the middle of each substep, i.e.,
+    /// with only half of the velocity change due to the external forces, in the spirit of the
+    /// velocity Verlet (or leapfrog) scheme.
+    ///
+    /// This is second-order accurate for the external forces and better conserves the energy
+    /// of conservative systems over long simulations, for the same cost.
+    SymplecticRK2,
+}
+
+/// How the physics pipeline handles rigid-bodies with a non-finite (NaN or infinite) state.
+///
+/// Without this, a single non-finite rigid-body state can spread to every other body
+/// solved alongside it.
+#[cfg(feature = "sanitize")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum NonFiniteBodyHandling {
+    /// Sets the non-finite components of the rigid-body’s position, velocity, and user-applied
+    /// forces to zero. A non-finite rotation is reset to the identity.
+    #[default]
+    ZeroComponents,
+    /// Disables the rigid-body, removing it from the simulation until it is re-enabled.
+    Disable,
 }
 
 impl IntegrationParameters {
@@ -265,6 +468,15 @@ impl IntegrationParameters {
         self.normalized_prediction_distance * self.length_unit
     }
 
+    /// The maximal distance a contact point can move between two timesteps while keeping its
+    /// warmstarting impulses (default: `0.005m` multiplied by [`Self::length_unit`]).
+    ///
+    /// This is equal to [`Self::normalized_contact_matching_distance`] multiplied by
+    /// [`Self::length_unit`].
+    pub fn contact_matching_distance(&self) -> Real {
+        self.normalized_contact_matching_distance * self.length_unit
+    }
+
     /// Initialize the simulation parameters with settings matching the TGS-soft solver
     /// with warmstarting.
     ///
@@ -275,10 +487,15 @@ impl IntegrationParameters {
             min_ccd_dt: 1.0 / 60.0 / 100.0,
             contact_natural_frequency: 30.0,
             contact_damping_ratio: 5.0,
+            contact_normal_damping: 0.0,
+            contact_tangent_damping: 0.0,
             joint_natural_frequency: 1.0e6,
             joint_damping_ratio: 1.0,
             warmstart_coefficient: 1.0,
             num_internal_pgs_iterations: 1,
+            adaptive_iterations: false,
+            adaptive_iterations_tolerance: 1.0e-4,
+            measure_solver_residuals: false,
             num_internal_stabilization_iterations: 2,
             num_additional_friction_iterations: 0,
             num_solver_iterations: NonZeroUsize::new(4).unwrap(),
@@ -288,11 +505,23 @@ impl IntegrationParameters {
             // However we don't want it to be too small and end up with
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
+            min_parallel_island_size: 0,
             normalized_allowed_linear_error: 0.001,
             normalized_max_corrective_velocity: 10.0,
+            penetration_warmup_steps: 0,
             normalized_prediction_distance: 0.002,
+            normalized_contact_matching_distance: 0.005,
             max_ccd_substeps: 1,
+            merge_coincident_manifolds: false,
+            max_contacts_per_manifold: None,
+            force_scalar_solver: false,
+            deterministic_contact_order: false,
+            enable_gyroscopic_forces: false,
+            friction_model: FrictionModel::Cone,
+            integration_scheme: IntegrationScheme::SemiImplicitEuler,
             length_unit: 1.0,
+            #[cfg(feature = "sanitize")]
+            non_finite_body_handling: NonFiniteBodyHandling::ZeroComponents,
         }
     }
 
@@ -327,3 +556,620 @@ impl Default for IntegrationParameters {
         Self::tgs_soft()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        IntegrationParameters, RevoluteJointBuilder, RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::{cuboid, floor, TestWorld};
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn gyroscopic_forces_flip_intermediate_axis() {
+        // Returns the smallest y component of the body’s intermediate axis during the simulation.
+        let run = |enable_gyroscopic_forces: bool| {
+            let mut world = TestWorld::new();
+
+            // The y axis is the intermediate principal axis of this box. It spins about it,
+            // with a slight perturbation along the x axis.
+            let (handle, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .angvel(Vector::new(0.01, 10.0, 0.0))
+                    .can_sleep(false),
+                ColliderBuilder::cuboid(1.0, 0.5, 0.25),
+            );
+
+            world.params = IntegrationParameters {
+                enable_gyroscopic_forces,
+                ..IntegrationParameters::default()
+            };
+            let mut min_axis_y = Real::MAX;
+            for _ in 0..600 {
+                world.step();
+
+                let axis = world.bodies[handle].rotation() * Vector::y();
+                min_axis_y = min_axis_y.min(axis.y);
+            }
+            min_axis_y
+        };
+
+        // Without gyroscopic forces, the body keeps spinning about its initial axis.
+        assert!(run(false) > 0.99);
+        // Otherwise the perturbation grows until the body flips over.
+        assert!(run(true) < -0.9);
+    }
+
+    #[test]
+    fn symplectic_rk2_reduces_pendulum_energy_drift() {
+        use crate::dynamics::IntegrationScheme;
+
+        const MASS: Real = 1.0;
+        const GRAVITY: Real = 9.81;
+
+        fn energy_drift(scheme: IntegrationScheme) -> Real {
+            let mut world = TestWorld::new();
+
+            // A frictionless pendulum, released horizontally.
+            let h = world.bodies.insert(RigidBodyBuilder::fixed());
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x())
+                .can_sleep(false);
+            let (h_dynamic, _) = world.insert_body(rb, ColliderBuilder::ball(0.1).mass(MASS));
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new().local_anchor2(-Point::from(Vector::x()));
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis())
+                .local_anchor2(-Point::from(Vector::x()));
+            world.impulse_joints.insert(h, h_dynamic, joint, true);
+
+            let energy = |bodies: &RigidBodySet| {
+                let rb = &bodies[h_dynamic];
+                rb.kinetic_energy() + MASS * GRAVITY * rb.translation().y
+            };
+
+            world.params.integration_scheme = scheme;
+            world.gravity = Vector::y() * -GRAVITY;
+            let initial_energy = energy(&world.bodies);
+            let mut max_drift: Real = 0.0;
+
+            for _ in 0..10_000 {
+                world.step();
+                max_drift = max_drift.max((energy(&world.bodies) - initial_energy).abs());
+            }
+
+            max_drift
+        }
+
+        let euler_drift = energy_drift(IntegrationScheme::SemiImplicitEuler);
+        let rk2_drift = energy_drift(IntegrationScheme::SymplecticRK2);
+        assert!(rk2_drift < euler_drift);
+    }
+
+    #[test]
+    fn deterministic_contact_order() {
+        let run = |deterministic_contact_order: bool, remove_ball: bool| {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_floor();
+
+            // A ball resting far from the other bodies. Removing it changes the order of the
+            // remaining contact pairs in the narrow-phase, but not their dynamics.
+            let (ball, _) = world.insert_ball(Vector::x() * 50.0 + Vector::y() * 0.5, 0.5);
+
+            // A pyramid of boxes resting on the floor.
+            let cube = cuboid(0.5, 0.5);
+            let mut handles = vec![];
+            for row in 0..4 {
+                for i in 0..4 - row {
+                    let x = i as Real * 1.1 + row as Real * 0.55;
+                    let y = row as Real + 0.5;
+                    let (handle, _) = world.insert_body(
+                        RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * y),
+                        cube.clone(),
+                    );
+                    handles.push(handle);
+                }
+            }
+
+            world.params = IntegrationParameters {
+                deterministic_contact_order,
+                ..IntegrationParameters::default()
+            };
+            for i in 0..60 {
+                if remove_ball && i == 1 {
+                    world.bodies.remove(
+                        ball,
+                        &mut world.islands,
+                        &mut world.colliders,
+                        &mut world.impulse_joints,
+                        &mut world.multibody_joints,
+                        true,
+                    );
+                }
+
+                world.step();
+            }
+
+            handles
+                .iter()
+                .map(|h| *world.bodies[*h].position())
+                .collect::<Vec<_>>()
+        };
+
+        // NOTE: without sorting, the results may or may not differ depending on where the
+        //       contact pairs moved, so only the sorted case is checked.
+        assert_eq!(run(true, false), run(true, true));
+    }
+
+    #[test]
+    fn penetration_warmup_bounds_spawn_velocities() {
+        let run = |penetration_warmup_steps: u32| {
+            let mut world = TestWorld::new();
+
+            // A cluster of deeply overlapping balls.
+            let mut handles = vec![];
+            for i in 0..3 {
+                for j in 0..3 {
+                    let position = Vector::x() * i as Real * 0.2 + Vector::y() * j as Real * 0.2;
+                    let (handle, _) = world.insert_ball(position, 0.5);
+                    handles.push(handle);
+                }
+            }
+
+            world.params = IntegrationParameters {
+                penetration_warmup_steps,
+                ..IntegrationParameters::default()
+            };
+            let mut peak_speeds = vec![];
+
+            for _ in 0..60 {
+                world.step();
+                let peak = handles
+                    .iter()
+                    .map(|h| world.bodies[*h].linvel().norm())
+                    .fold(0.0, Real::max);
+                peak_speeds.push(peak);
+            }
+
+            peak_speeds
+        };
+
+        let peak = |speeds: &[Real]| speeds.iter().copied().fold(0.0, Real::max);
+        let without_warmup = run(0);
+        let with_warmup = run(30);
+
+        // The new contacts don’t push the bodies apart at the first step.
+        assert!(with_warmup[0] < 1.0e-3);
+        // The velocities ramp up progressively, and remain much smaller than without warmup.
+        // NOTE: the velocities of the cluster depend on the order its contacts are solved in,
+        //       which differs between the scalar and SIMD solvers, so the peaks are compared
+        //       over the whole simulation rather than at a given step.
+        assert!(with_warmup[1] < with_warmup[5]);
+        assert!(peak(&with_warmup) < peak(&without_warmup) * 0.5);
+        // The cluster still separates.
+        assert!(*with_warmup.last().unwrap() > 0.1);
+    }
+
+    #[test]
+    fn penetration_warmup_is_the_same_for_all_solvers() {
+        let run = |force_scalar_solver: bool| {
+            let mut world = TestWorld::new();
+
+            // Independent pairs of overlapping balls, with different penetration depths, which
+            // are solved by SIMD constraints unless `force_scalar_solver` is set.
+            let mut handles = vec![];
+            for i in 0..8 {
+                for j in 0..2 {
+                    let position = Vector::x() * i as Real * 10.0
+                        + Vector::y() * j as Real * (0.2 + 0.05 * i as Real);
+                    let (handle, _) = world.insert_ball(position, 0.5);
+                    handles.push(handle);
+                }
+            }
+
+            world.params = IntegrationParameters {
+                penetration_warmup_steps: 30,
+                force_scalar_solver,
+                ..IntegrationParameters::default()
+            };
+            let mut positions = vec![];
+
+            for _ in 0..60 {
+                world.step();
+                positions.extend(handles.iter().map(|h| world.bodies[*h].translation().y));
+            }
+
+            positions
+        };
+
+        for (simd, scalar) in run(false).into_iter().zip(run(true)) {
+            approx::assert_relative_eq!(simd, scalar, epsilon = 1.0e-5);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sanitize")]
+    fn non_finite_velocity_is_sanitized() {
+        use crate::dynamics::NonFiniteBodyHandling;
+
+        let mut world = TestWorld::with_gravity();
+
+        let (h1, _) = world.insert_ball(Vector::zeros(), 1.0);
+        let (h2, _) = world.insert_ball(Vector::zeros(), 1.0);
+
+        for mode in [
+            NonFiniteBodyHandling::ZeroComponents,
+            NonFiniteBodyHandling::Disable,
+        ] {
+            world.bodies[h1].set_linvel(Vector::repeat(Real::NAN), true);
+            world.params = IntegrationParameters {
+                non_finite_body_handling: mode,
+                ..Default::default()
+            };
+            world.step();
+
+            match mode {
+                NonFiniteBodyHandling::ZeroComponents => {
+                    assert!(world.bodies[h1].linvel().iter().all(|e| e.is_finite()))
+                }
+                NonFiniteBodyHandling::Disable => assert!(!world.bodies[h1].is_enabled()),
+            }
+            assert!(world.bodies[h2].translation().iter().all(|e| e.is_finite()));
+        }
+    }
+
+    #[test]
+    fn warmstart_coefficient_reduces_overshoot_after_perturbation() {
+        let run = |warmstart_coefficient: Real| {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_body(RigidBodyBuilder::fixed(), cuboid(10.0, 0.5));
+
+            // A stack of light boxes, with a heavy box at the top.
+            let stack: Vec<_> = (0..6)
+                .map(|i| {
+                    let rb = RigidBodyBuilder::dynamic()
+                        .translation(Vector::y() * (1.0 + i as Real))
+                        .can_sleep(false);
+                    let density = if i == 5 { 100.0 } else { 1.0 };
+                    world.insert_body(rb, cuboid(0.5, 0.5).density(density)).0
+                })
+                .collect();
+            world.steps(100);
+
+            // Removing the heavy box makes the warmstart impulses of the contacts below it
+            // much larger than needed.
+            world.bodies.remove(
+                stack[5],
+                &mut world.islands,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                true,
+            );
+            world.params.warmstart_coefficient = warmstart_coefficient;
+            world.step();
+
+            // The largest upward velocity of the remaining boxes.
+            stack[..5]
+                .iter()
+                .map(|h| world.bodies[*h].linvel().y)
+                .fold(0.0, Real::max)
+        };
+
+        let overshoots: Vec<_> = [1.0, 0.5, 0.0].into_iter().map(run).collect();
+        assert!(overshoots[0] > 0.0);
+        assert!(overshoots[1] < overshoots[0]);
+        assert!(overshoots[2] < overshoots[1]);
+    }
+
+    #[test]
+    fn force_scalar_solver() {
+        use crate::dynamics::FixedJointBuilder;
+        use crate::math::SPATIAL_DIM;
+        use crate::pipeline::StepStats;
+
+        const NUM_BALLS: usize = 8;
+
+        let run = |force_scalar_solver: bool| -> (StepStats, Vec<Vector<Real>>) {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_floor();
+
+            // A row of balls resting on the floor, each attached to the next one.
+            let mut handles = vec![];
+            for i in 0..NUM_BALLS {
+                let pos = Vector::x() * (i as Real * 2.0) + Vector::y() * 0.5;
+                let linvel =
+                    Vector::x() * (i as Real * 0.3) - Vector::y() * (1.0 + i as Real * 0.2);
+                let (ball, _) = world.insert_body(
+                    RigidBodyBuilder::dynamic().translation(pos).linvel(linvel),
+                    ColliderBuilder::ball(0.5),
+                );
+
+                if let Some(prev) = handles.last() {
+                    let joint =
+                        FixedJointBuilder::new().local_anchor2(Point::from(Vector::x() * -2.0));
+                    world.impulse_joints.insert(*prev, ball, joint, true);
+                }
+                handles.push(ball);
+            }
+
+            world.params = IntegrationParameters {
+                force_scalar_solver,
+                ..IntegrationParameters::default()
+            };
+            world.steps(20);
+
+            let positions = handles
+                .iter()
+                .map(|h| *world.bodies[*h].translation())
+                .collect();
+            (world.pipeline.step_stats, positions)
+        };
+
+        // With the scalar solver, every contact manifold and joint gets its own constraint,
+        // exactly like when SIMD is disabled.
+        let (scalar_stats, scalar_positions) = run(true);
+        assert_eq!(scalar_stats.num_contact_constraints, NUM_BALLS);
+        // Each locked axis of a fixed joint is a separate constraint.
+        assert_eq!(
+            scalar_stats.num_joint_constraints,
+            (NUM_BALLS - 1) * SPATIAL_DIM
+        );
+
+        // The scalar solver gives the same results whatever the SIMD width (1 without SIMD,
+        // 4 with `simd-stable`, 8 with `simd-nightly`). These are the ball positions computed by
+        // a build without SIMD.
+        #[cfg(feature = "dim2")]
+        let expected = [
+            [0.025363, 0.498788],
+            [2.025358, 0.498729],
+            [4.025363, 0.498716],
+            [6.025315, 0.498769],
+            [8.02535, 0.498688],
+            [10.025308, 0.498765],
+            [12.025366, 0.498691],
+            [14.025368, 0.49874],
+        ];
+        #[cfg(feature = "dim3")]
+        let expected = [
+            [0.032514, 0.498786, 0.0],
+            [2.032563, 0.498735, 0.0],
+            [4.03256, 0.498729, 0.0],
+            [6.032543, 0.498731, 0.0],
+            [8.032526, 0.498729, 0.0],
+            [10.032519, 0.498726, 0.0],
+            [12.032517, 0.498725, 0.0],
+            [14.032519, 0.498725, 0.0],
+        ];
+        for (position, expected) in scalar_positions.iter().zip(expected) {
+            approx::assert_relative_eq!(*position, Vector::from(expected), epsilon = 1.0e-4);
+        }
+
+        let (stats, positions) = run(false);
+        #[cfg(not(feature = "simd-is-enabled"))]
+        {
+            assert_eq!(stats, scalar_stats);
+            assert_eq!(positions, scalar_positions);
+        }
+        #[cfg(feature = "simd-is-enabled")]
+        {
+            assert!(stats.num_contact_constraints < scalar_stats.num_contact_constraints);
+            // The SIMD solver solves the constraints in a different order, so its results are
+            // only close to the ones of the scalar solver.
+            for (position, scalar_position) in positions.iter().zip(&scalar_positions) {
+                approx::assert_relative_eq!(*position, *scalar_position, epsilon = 1.0e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn friction_model() {
+        use crate::dynamics::FrictionModel;
+        use crate::pipeline::ActiveHooks;
+        #[cfg(feature = "dim3")]
+        use crate::pipeline::{ContactModificationContext, PhysicsHooks};
+
+        // NOTE: in 3D, the first friction direction is aligned with the sliding velocity by
+        //       default, in which case both models are equivalent. So fix it along the x axis.
+        #[cfg(feature = "dim3")]
+        struct FixedFrictionDirection;
+        #[cfg(feature = "dim3")]
+        impl PhysicsHooks for FixedFrictionDirection {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.friction_direction = Vector::x();
+                }
+            }
+        }
+        #[cfg(feature = "dim2")]
+        let hooks = ();
+        #[cfg(feature = "dim3")]
+        let hooks = FixedFrictionDirection;
+
+        let run = |friction_model: FrictionModel| -> (Vector<Real>, Vector<Real>) {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_body(
+                RigidBodyBuilder::fixed(),
+                floor().active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS),
+            );
+
+            // A box sliding on the floor, diagonally to the tangent directions in 3D.
+            #[cfg(feature = "dim2")]
+            let linvel = Vector::x() * 4.0;
+            #[cfg(feature = "dim3")]
+            let linvel = (Vector::x() + Vector::z()).normalize() * 4.0;
+            let (handle, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 0.5)
+                    .linvel(linvel)
+                    .lock_rotations(),
+                cuboid(0.5, 0.5),
+            );
+
+            world.params = IntegrationParameters {
+                friction_model,
+                ..IntegrationParameters::default()
+            };
+            for _ in 0..120 {
+                world.step_with(&hooks, &());
+            }
+
+            (
+                *world.bodies[handle].translation(),
+                *world.bodies[handle].linvel(),
+            )
+        };
+
+        let (cone_pos, cone_vel) = run(FrictionModel::Cone);
+        let (pyramid_pos, pyramid_vel) = run(FrictionModel::PyramidDecoupled);
+
+        // The box stopped sliding with both models.
+        assert!(cone_vel.norm() < 1.0e-2, "{cone_vel:?}");
+        assert!(pyramid_vel.norm() < 1.0e-2, "{pyramid_vel:?}");
+        assert!(cone_pos.x > 1.0);
+
+        // There is a single tangent direction in 2D so both models are equivalent.
+        #[cfg(feature = "dim2")]
+        assert_eq!(cone_pos, pyramid_pos);
+        // The pyramid is wider than the cone along its diagonals, so the box stops sooner.
+        #[cfg(feature = "dim3")]
+        {
+            let cone_dist = cone_pos.xz().norm();
+            let pyramid_dist = pyramid_pos.xz().norm();
+            assert!(pyramid_dist < cone_dist * 0.8, "{pyramid_dist} {cone_dist}");
+        }
+    }
+
+    #[test]
+    fn adaptive_iterations_skip_converged_islands() {
+        let run = |adaptive_iterations: bool| {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_body(RigidBodyBuilder::fixed(), cuboid(50.0, 0.5));
+
+            let mut insert_box = |x: Real, y: Real| {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * x + Vector::y() * y)
+                    .can_sleep(false);
+                world.insert_body(rb, cuboid(0.5, 0.5)).0
+            };
+
+            // One tall stack, and many boxes resting alone, each in its own island.
+            let stack: Vec<_> = (0..10)
+                .map(|i| insert_box(-20.0, 1.0 + i as Real))
+                .collect();
+            for i in 0..10 {
+                let _ = insert_box(i as Real * 2.0, 1.0);
+            }
+
+            world.params = IntegrationParameters {
+                min_island_size: 1,
+                adaptive_iterations,
+                ..IntegrationParameters::default()
+            };
+            let mut num_pgs_iterations = 0;
+
+            for _ in 0..200 {
+                world.step();
+                num_pgs_iterations += world.pipeline.step_stats.num_pgs_iterations;
+            }
+
+            assert_eq!(world.islands.num_islands(), 11);
+            (num_pgs_iterations, world.bodies[stack[9]].translation().y)
+        };
+
+        let (num_iterations, stack_height) = run(false);
+        let (adaptive_num_iterations, adaptive_stack_height) = run(true);
+        assert_eq!(num_iterations, 200 * 11 * 4);
+        // The isolated boxes converge after a couple of solver iterations and skip the others.
+        assert!(adaptive_num_iterations < num_iterations * 3 / 5);
+        // The stack is still standing.
+        assert!((adaptive_stack_height - stack_height).abs() < 0.05);
+        assert!(adaptive_stack_height > 9.5);
+    }
+
+    // NOTE: in 3D with `f32`, such a tall tower drifts because of rounding errors, whatever
+    //       the damping.
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn contact_normal_damping_reduces_tower_jitter() {
+        let run = |contact_normal_damping: Real| {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_body(RigidBodyBuilder::fixed(), cuboid(10.0, 0.5));
+
+            let tower: Vec<_> = (0..20)
+                .map(|i| {
+                    let rb = RigidBodyBuilder::dynamic()
+                        .translation(Vector::y() * (1.0 + i as Real))
+                        .can_sleep(false);
+                    world.insert_body(rb, cuboid(0.5, 0.5)).0
+                })
+                .collect();
+
+            // A small damping ratio makes the penetration correction overshoot, so the tower
+            // bounces on itself for a while before settling.
+            world.params = IntegrationParameters {
+                contact_damping_ratio: 0.25,
+                contact_normal_damping,
+                ..IntegrationParameters::default()
+            };
+            world.steps(100);
+
+            // The summed velocities of the settling tower, over the next 100 steps.
+            let mut jitter = 0.0;
+            for _ in 0..100 {
+                world.step();
+                jitter += tower
+                    .iter()
+                    .map(|h| world.bodies[*h].linvel().norm())
+                    .sum::<Real>();
+            }
+            jitter
+        };
+
+        let jitter: Vec<_> = [0.0, 0.5, 0.9].into_iter().map(run).collect();
+        assert!(jitter[1] < jitter[0]);
+        assert!(jitter[2] < jitter[1]);
+        assert!(jitter[2] < jitter[0] * 0.6);
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn contact_tangent_damping_reduces_creep_on_slope() {
+        let run = |contact_tangent_damping: Real| {
+            let mut world = TestWorld::with_gravity();
+
+            // A slope gentle enough for friction to hold the box once it landed.
+            let slope = 0.4;
+            world.insert_body(RigidBodyBuilder::fixed().rotation(slope), cuboid(10.0, 0.5));
+
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.5)
+                .rotation(slope)
+                .can_sleep(false);
+            let (handle, _) = world.insert_body(rb, cuboid(0.5, 0.5));
+
+            world.params = IntegrationParameters {
+                contact_tangent_damping,
+                ..IntegrationParameters::default()
+            };
+            world.steps(200);
+
+            // How far the box slid down the slope while landing and settling.
+            -world.bodies[handle].translation().x
+        };
+
+        let creep: Vec<_> = [0.0, 0.5, 0.9].into_iter().map(run).collect();
+        assert!(creep[0] > 0.0);
+        assert!(creep[1] < creep[0]);
+        assert!(creep[2] < creep[1]);
+        assert!(creep[2] < creep[0] * 0.5);
+    }
+}
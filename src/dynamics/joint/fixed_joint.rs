@@ -108,6 +108,20 @@ impl FixedJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.data.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.data.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
@@ -107,6 +107,23 @@ pub enum JointAxis {
     AngZ,
 }
 
+impl JointAxis {
+    /// All the degrees of freedom of a joint, ordered by their index.
+    #[cfg(feature = "dim2")]
+    pub(crate) const ALL: [JointAxis; SPATIAL_DIM] =
+        [JointAxis::LinX, JointAxis::LinY, JointAxis::AngX];
+    /// All the degrees of freedom of a joint, ordered by their index.
+    #[cfg(feature = "dim3")]
+    pub(crate) const ALL: [JointAxis; SPATIAL_DIM] = [
+        JointAxis::LinX,
+        JointAxis::LinY,
+        JointAxis::LinZ,
+        JointAxis::AngX,
+        JointAxis::AngY,
+        JointAxis::AngZ,
+    ];
+}
+
 impl From<JointAxis> for JointAxesMask {
     fn from(axis: JointAxis) -> Self {
         JointAxesMask::from_bits(1 << axis as usize).unwrap()
@@ -123,6 +140,18 @@ pub struct JointLimits<N> {
     pub max: N,
     /// The impulse applied to enforce the joint’s limit.
     pub impulse: N,
+    /// The stiffness of the spring pushing the joint back within its limits (default: `0.0`).
+    ///
+    /// If both this and [`Self::damping`] are zero, the limit is a hard stop. Otherwise, the
+    /// joint can exceed its limit and is pushed back by a force equal to `stiffness` times the
+    /// distance past the limit, plus `damping` times the velocity moving away from it. This is
+    /// in `N/m` for linear limits and `N.m/rad` for angular limits. This is ignored for the
+    /// limits of coupled axes.
+    pub stiffness: N,
+    /// The damping of the spring pushing the joint back within its limits (default: `0.0`).
+    ///
+    /// See [`Self::stiffness`].
+    pub damping: N,
 }
 
 impl<N: SimdRealCopy> Default for JointLimits<N> {
@@ -131,6 +160,8 @@ impl<N: SimdRealCopy> Default for JointLimits<N> {
             min: -N::splat(Real::MAX),
             max: N::splat(Real::MAX),
             impulse: N::splat(0.0),
+            stiffness: N::splat(0.0),
+            damping: N::splat(0.0),
         }
     }
 }
@@ -141,7 +172,54 @@ impl<N: SimdRealCopy> From<[N; 2]> for JointLimits<N> {
             min: value[0],
             max: value[1],
             impulse: N::splat(0.0),
+            stiffness: N::splat(0.0),
+            damping: N::splat(0.0),
+        }
+    }
+}
+
+impl JointLimits<Real> {
+    /// Makes these limits soft, with the given spring stiffness and damping.
+    ///
+    /// See [`Self::stiffness`] for details. Setting both to zero makes the limits hard again.
+    #[must_use]
+    pub fn with_spring(mut self, stiffness: Real, damping: Real) -> Self {
+        self.set_spring(stiffness, damping);
+        self
+    }
+
+    /// Sets the stiffness and damping of the spring pushing the joint back within these limits.
+    ///
+    /// See [`Self::stiffness`] for details. Setting both to zero makes the limits hard again.
+    pub fn set_spring(&mut self, stiffness: Real, damping: Real) -> &mut Self {
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+
+    /// Is this a soft limit, i.e., with a non-zero stiffness or damping?
+    pub fn is_soft(&self) -> bool {
+        self.stiffness != 0.0 || self.damping != 0.0
+    }
+
+    /// The coefficients of the spring pushing the joint back within a soft limit, or `None`
+    /// if this is a hard limit.
+    ///
+    /// Only the `erp_inv_dt`, `cfm_coeff` and `cfm_gain` of the returned parameters are
+    /// relevant.
+    pub(crate) fn spring_params(&self, dt: Real) -> Option<MotorParameters<Real>> {
+        if !self.is_soft() {
+            return None;
         }
+
+        let (erp_inv_dt, cfm_coeff, cfm_gain) =
+            MotorModel::ForceBased.combine_coefficients(dt, self.stiffness, self.damping);
+        Some(MotorParameters {
+            erp_inv_dt,
+            cfm_coeff,
+            cfm_gain,
+            ..MotorParameters::default()
+        })
     }
 }
 
@@ -159,6 +237,14 @@ pub struct JointMotor {
     pub damping: Real,
     /// The maximum force this motor can deliver.
     pub max_force: Real,
+    /// The maximum rate of change of the velocity targeted by this motor (default: infinite).
+    ///
+    /// When [`Self::target_vel`] changes, the velocity given to the constraints solver moves
+    /// toward it by at most `max_accel * dt` at each timestep instead of switching to it
+    /// instantly. This is in `m/s²` for linear motors and `rad/s²` for angular motors.
+    pub max_accel: Real,
+    /// The velocity targeted by the constraints solver at the last timestep.
+    pub(crate) ramped_target_vel: Real,
     /// The impulse applied by this motor.
     pub impulse: Real,
     /// The spring-like model used for simulating this motor.
@@ -173,6 +259,8 @@ impl Default for JointMotor {
             stiffness: 0.0,
             damping: 0.0,
             max_force: Real::MAX,
+            max_accel: Real::INFINITY,
+            ramped_target_vel: 0.0,
             impulse: 0.0,
             model: MotorModel::AccelerationBased,
         }
@@ -180,6 +268,14 @@ impl Default for JointMotor {
 }
 
 impl JointMotor {
+    /// The velocity targeted by the constraints solver at the last timestep.
+    ///
+    /// This lags behind [`Self::target_vel`] if [`Self::max_accel`] is finite, and is updated
+    /// automatically at each timestep.
+    pub fn ramped_target_vel(&self) -> Real {
+        self.ramped_target_vel
+    }
+
     pub(crate) fn motor_params(&self, dt: Real) -> MotorParameters<Real> {
         let (erp_inv_dt, cfm_coeff, cfm_gain) =
             self.model
@@ -190,10 +286,23 @@ impl JointMotor {
             cfm_gain,
             // keep_lhs,
             target_pos: self.target_pos,
-            target_vel: self.target_vel,
+            target_vel: self.ramped_target_vel,
             max_impulse: self.max_force * dt,
         }
     }
+
+    /// Moves [`Self::ramped_target_vel`] toward [`Self::target_vel`], changing it by at most
+    /// `max_accel * dt`.
+    pub(crate) fn ramp_target_vel(&mut self, dt: Real) {
+        let max_delta = self.max_accel * dt;
+        let delta = self.target_vel - self.ramped_target_vel;
+
+        if delta.abs() <= max_delta {
+            self.ramped_target_vel = self.target_vel;
+        } else {
+            self.ramped_target_vel += max_delta.copysign(delta);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -242,10 +351,28 @@ pub struct GenericJoint {
     /// For coupled degrees of freedoms (DoF), only the first linear (resp. angular) coupled DoF motor and `motor_axes`
     /// bitmask is applied to the coupled linear (resp. angular) axes.
     pub motors: [JointMotor; SPATIAL_DIM],
+    /// The orientation of the joint’s frame on the second rigid-body, relative to its frame on the
+    /// first rigid-body, targeted by the angular motors (default: `None`).
+    ///
+    /// If set, the target angles of the angular motors are measured relative to the first
+    /// frame rotated by this orientation. This is ignored by multibody joints.
+    pub motor_target_orientation: Option<Rotation<Real>>,
     /// Are contacts between the attached rigid-bodies enabled?
     pub contacts_enabled: bool,
     /// Whether or not the joint is enabled.
     pub enabled: JointEnabled,
+    /// The force (in `N`) above which this joint breaks (default: `Real::INFINITY`).
+    ///
+    /// This is compared to the norm of the linear force applied by the joint (including its
+    /// limits and motors) during the last solver substep. A broken impulse joint is removed from
+    /// its [`ImpulseJointSet`](crate::dynamics::ImpulseJointSet) and a
+    /// [`JointBreakEvent`](crate::dynamics::JointBreakEvent) is emitted. This is ignored by
+    /// multibody joints.
+    pub breaking_force: Real,
+    /// The torque (in `N.m`) above which this joint breaks (default: `Real::INFINITY`).
+    ///
+    /// See [`Self::breaking_force`] for details.
+    pub breaking_torque: Real,
     /// User-defined data associated to this joint.
     pub user_data: u128,
 }
@@ -261,8 +388,11 @@ impl Default for GenericJoint {
             coupled_axes: JointAxesMask::empty(),
             limits: [JointLimits::default(); SPATIAL_DIM],
             motors: [JointMotor::default(); SPATIAL_DIM],
+            motor_target_orientation: None,
             contacts_enabled: true,
             enabled: JointEnabled::Enabled,
+            breaking_force: Real::INFINITY,
+            breaking_torque: Real::INFINITY,
             user_data: 0,
         }
     }
@@ -400,6 +530,28 @@ impl GenericJoint {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    ///
+    /// See [`Self::breaking_force`] for details.
+    pub fn set_breaking_force(&mut self, force: Real) -> &mut Self {
+        self.breaking_force = force;
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    ///
+    /// See [`Self::breaking_force`] for details.
+    pub fn set_breaking_torque(&mut self, torque: Real) -> &mut Self {
+        self.breaking_torque = torque;
+        self
+    }
+
+    /// Can this joint break?
+    #[must_use]
+    pub fn is_breakable(&self) -> bool {
+        self.breaking_force < Real::INFINITY || self.breaking_torque < Real::INFINITY
+    }
+
     /// The joint limits along the specified axis.
     #[must_use]
     pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
@@ -420,6 +572,21 @@ impl GenericJoint {
         self
     }
 
+    /// Makes the joint limits along the specified axis soft, with the given spring stiffness
+    /// and damping.
+    ///
+    /// See [`JointLimits::stiffness`] for details. Setting both to zero makes the limits
+    /// hard again.
+    pub fn set_limit_spring(
+        &mut self,
+        axis: JointAxis,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.limits[axis as usize].set_spring(stiffness, damping);
+        self
+    }
+
     /// The spring-like motor model along the specified axis of this joint.
     #[must_use]
     pub fn motor_model(&self, axis: JointAxis) -> Option<MotorModel> {
@@ -464,12 +631,48 @@ impl GenericJoint {
         self.set_motor(axis, target_pos, 0.0, stiffness, damping)
     }
 
+    /// Configures the angular motors to reach the given orientation of the second frame relative
+    /// to the first frame.
+    ///
+    /// This sets [`Self::motor_target_orientation`] and sets the target angles of the motors of
+    /// all the angular axes to zero, each with the given `stiffness` and `damping`. Because the
+    /// error is measured from the target orientation itself, the motors follow the shortest
+    /// rotation path to the target, independently from the sign of the quaternion `target`.
+    pub fn set_motor_target_orientation(
+        &mut self,
+        target: Rotation<Real>,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.motor_target_orientation = Some(target);
+
+        #[cfg(feature = "dim2")]
+        let axes = [JointAxis::AngX];
+        #[cfg(feature = "dim3")]
+        let axes = [JointAxis::AngX, JointAxis::AngY, JointAxis::AngZ];
+
+        for axis in axes {
+            self.set_motor_position(axis, 0.0, stiffness, damping);
+        }
+
+        self
+    }
+
     /// Sets the maximum force the motor can deliver along the specified axis.
     pub fn set_motor_max_force(&mut self, axis: JointAxis, max_force: Real) -> &mut Self {
         self.motors[axis as usize].max_force = max_force;
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor along the specified
+    /// axis.
+    ///
+    /// See [`JointMotor::max_accel`] for details.
+    pub fn set_motor_max_accel(&mut self, axis: JointAxis, max_accel: Real) -> &mut Self {
+        self.motors[axis as usize].max_accel = max_accel;
+        self
+    }
+
     /// The motor affecting the joint’s degree of freedom along the specified axis.
     #[must_use]
     pub fn motor(&self, axis: JointAxis) -> Option<&JointMotor> {
@@ -499,6 +702,13 @@ impl GenericJoint {
         self
     }
 
+    /// Updates the velocities targeted by the motors, at the beginning of a timestep.
+    pub(crate) fn ramp_motors(&mut self, dt: Real) {
+        for motor in &mut self.motors {
+            motor.ramp_target_vel(dt);
+        }
+    }
+
     /// Flips the orientation of the joint, including limits and motors.
     pub fn flip(&mut self) {
         std::mem::swap(&mut self.local_frame1, &mut self.local_frame2);
@@ -513,6 +723,7 @@ impl GenericJoint {
             }
 
             self.motors[dim].target_vel = -self.motors[dim].target_vel;
+            self.motors[dim].ramped_target_vel = -self.motors[dim].ramped_target_vel;
             self.motors[dim].target_pos = -self.motors[dim].target_pos;
         }
     }
@@ -607,6 +818,20 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
@@ -656,6 +881,14 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Makes the joint limits along the specified axis soft, with the given spring stiffness
+    /// and damping.
+    #[must_use]
+    pub fn limit_spring(mut self, axis: JointAxis, stiffness: Real, damping: Real) -> Self {
+        self.0.set_limit_spring(axis, stiffness, damping);
+        self
+    }
+
     /// Sets the coupled degrees of freedom for this joint’s limits and motor.
     #[must_use]
     pub fn coupled_axes(mut self, axes: JointAxesMask) -> Self {
@@ -713,6 +946,14 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor along the specified
+    /// axis.
+    #[must_use]
+    pub fn motor_max_accel(mut self, axis: JointAxis, max_accel: Real) -> Self {
+        self.0.set_motor_max_accel(axis, max_accel);
+        self
+    }
+
     /// An arbitrary user-defined 128-bit integer associated to the joints built by this builder.
     pub fn user_data(mut self, data: u128) -> Self {
         self.0.user_data = data;
@@ -731,3 +972,141 @@ impl From<GenericJointBuilder> for GenericJoint {
         val.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::JointAxis;
+    use crate::dynamics::{IntegrationParameters, RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn joint_limit_spring_setters() {
+        use crate::dynamics::{GenericJointBuilder, JointAxesMask, JointAxis, JointLimits};
+
+        let mut limits = JointLimits::from([-1.0, 1.0]).with_spring(100.0, 10.0);
+        assert_eq!((limits.stiffness, limits.damping), (100.0, 10.0));
+        assert!(limits.is_soft());
+        limits.set_spring(0.0, 0.0);
+        assert!(!limits.is_soft());
+
+        let mut joint = GenericJointBuilder::new(JointAxesMask::empty())
+            .limits(JointAxis::LinX, [-1.0, 1.0])
+            .limit_spring(JointAxis::LinX, 100.0, 10.0)
+            .build();
+        let limits = joint.limits(JointAxis::LinX).unwrap();
+        assert_eq!((limits.stiffness, limits.damping), (100.0, 10.0));
+        joint.set_limit_spring(JointAxis::LinX, 0.0, 0.0);
+        assert!(!joint.limits(JointAxis::LinX).unwrap().is_soft());
+    }
+
+    #[test]
+    fn soft_joint_limit_follows_spring_law() {
+        use crate::dynamics::{JointAxis, PrismaticJointBuilder};
+
+        const MASS: Real = 10.0;
+        const STIFFNESS: Real = 500.0;
+        const GRAVITY: Real = 9.81;
+
+        let mut world = TestWorld::new();
+
+        let h = world.bodies.insert(RigidBodyBuilder::fixed());
+        let rb_dynamic = RigidBodyBuilder::dynamic().additional_mass(MASS);
+        let h_dynamic = world.bodies.insert(rb_dynamic);
+
+        // The dynamic body falls along the prismatic axis, past its soft lower limit.
+        let joint = PrismaticJointBuilder::new(Vector::y_axis())
+            .limits([-0.5, 0.5])
+            .limit_spring(STIFFNESS, 100.0);
+        let joint_handle = world.impulse_joints.insert(h, h_dynamic, joint, true);
+
+        world.gravity = Vector::y() * -GRAVITY;
+        world.steps(600);
+
+        // At rest, the spring force balances the weight.
+        let penetration = -0.5 - world.bodies[h_dynamic].translation().y;
+        approx::assert_relative_eq!(penetration, MASS * GRAVITY / STIFFNESS, epsilon = 1.0e-3);
+
+        let substep_dt = world.params.dt / world.params.num_solver_iterations.get() as Real;
+        let joint = world.impulse_joints.get(joint_handle).unwrap();
+        let force = -joint.data.limits(JointAxis::LinX).unwrap().impulse / substep_dt;
+        approx::assert_relative_eq!(force, STIFFNESS * penetration, max_relative = 1.0e-2);
+    }
+
+    #[test]
+    fn joint_motor_max_accel() {
+        const MAX_ACCEL: Real = 20.0;
+        const TARGET_VEL: Real = 10.0;
+
+        // Returns the angular velocity of the motorized body after each step.
+        let run = |max_accel: Real, multibody: bool| -> Vec<Real> {
+            let mut world = TestWorld::new();
+
+            let ground = world.bodies.insert(RigidBodyBuilder::fixed());
+            let (wheel, _) = world.insert_ball(Vector::zeros(), 0.5);
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let joint = joint
+                .motor_velocity(TARGET_VEL, 1.0e4)
+                .motor_max_accel(max_accel);
+            let impulse_joint = if multibody {
+                world
+                    .multibody_joints
+                    .insert(ground, wheel, joint, true)
+                    .unwrap();
+                None
+            } else {
+                Some(world.impulse_joints.insert(ground, wheel, joint, true))
+            };
+
+            let mut angvels = vec![];
+            for i in 0..90 {
+                world.step();
+
+                #[cfg(feature = "dim2")]
+                angvels.push(world.bodies[wheel].angvel());
+                #[cfg(feature = "dim3")]
+                angvels.push(world.bodies[wheel].angvel().z);
+
+                if let Some(handle) = impulse_joint {
+                    let motor = world.impulse_joints.get(handle).unwrap().data.motors
+                        [JointAxis::AngX as usize];
+                    let dt = world.params.dt;
+                    approx::assert_relative_eq!(
+                        motor.ramped_target_vel(),
+                        (max_accel * dt * (i + 1) as Real).min(TARGET_VEL),
+                        epsilon = 1.0e-4
+                    );
+                }
+            }
+
+            angvels
+        };
+
+        let dt = IntegrationParameters::default().dt;
+
+        // Without acceleration bound, the target velocity is reached immediately.
+        for multibody in [false, true] {
+            let angvels = run(Real::INFINITY, multibody);
+            approx::assert_relative_eq!(angvels[0].abs(), TARGET_VEL, epsilon = 1.0e-2);
+
+            let angvels = run(MAX_ACCEL, multibody);
+            let mut prev_angvel = 0.0;
+            for (i, angvel) in angvels.iter().enumerate() {
+                let expected = (MAX_ACCEL * dt * (i + 1) as Real).min(TARGET_VEL);
+                assert!(
+                    (angvel.abs() - prev_angvel).abs() <= MAX_ACCEL * dt * 1.01,
+                    "step {i}: {prev_angvel} -> {angvel}"
+                );
+                approx::assert_relative_eq!(angvel.abs(), expected, epsilon = 1.0e-2);
+                prev_angvel = angvel.abs();
+            }
+
+            // The target velocity is reached after `TARGET_VEL / MAX_ACCEL` seconds.
+            approx::assert_relative_eq!(prev_angvel, TARGET_VEL, epsilon = 1.0e-2);
+        }
+    }
+}
@@ -1,5 +1,22 @@
-use crate::dynamics::{GenericJoint, ImpulseJointHandle, RigidBodyHandle};
-use crate::math::{Real, SpacialVector};
+use crate::dynamics::{
+    GenericJoint, ImpulseJointHandle, JointAxesMask, JointAxis, RigidBodyHandle,
+};
+use crate::math::{Real, SpacialVector, ANG_DIM, DIM, SPATIAL_DIM};
+
+/// The minimum limit impulse magnitude for a joint limit to be considered as hit.
+const LIMIT_EVENT_IMPULSE_THRESHOLD: Real = 1.0e-6;
+
+/// The impulse applied by an impulse joint to the bodies it is attached to.
+///
+/// The first `DIM` components are the linear impulse (in `N.s`, i.e., mass times velocity)
+/// and the remaining components are the angular impulse (in `N.m.s`, i.e., angular inertia
+/// times angular velocity). Each component is expressed along the corresponding axis of the
+/// joint’s first local frame ([`GenericJoint::local_frame1`]) in world-space, with
+/// [`JointAxis`] ordering. This is the impulse applied to the first body; the second body
+/// receives the opposite impulse.
+///
+/// This is the impulse of the last solver substep, not the sum over the whole timestep.
+pub type JointImpulse = SpacialVector<Real>;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq)]
@@ -13,9 +30,291 @@ pub struct ImpulseJoint {
     /// The joint’s description.
     pub data: GenericJoint,
 
-    /// The impulses applied by this joint.
-    pub impulses: SpacialVector<Real>,
+    /// The impulses applied by this joint during the last solver substep.
+    ///
+    /// See [`JointImpulse`] for details on the coordinate frame and units.
+    pub impulses: JointImpulse,
 
     // A joint needs to know its handle to simplify its removal.
     pub(crate) handle: ImpulseJointHandle,
+    // The axes with a min (resp. max) limit enforced during the last solve.
+    // This is used to only emit limit events when a limit starts being hit.
+    pub(crate) min_limits_hit: JointAxesMask,
+    pub(crate) max_limits_hit: JointAxesMask,
+    // The impulse to seed the solver with at the next timestep, if any.
+    pub(crate) warmstart_impulse: Option<JointImpulse>,
+}
+
+impl ImpulseJoint {
+    /// The impulse the solver will be seeded with at the next timestep, if any.
+    ///
+    /// This is `None` unless [`Self::set_warmstart_impulse`] was called since the last
+    /// timestep involving this joint.
+    pub fn warmstart_impulse(&self) -> Option<JointImpulse> {
+        self.warmstart_impulse
+    }
+
+    /// Sets the impulse the solver will be seeded with at the next timestep, instead of zero.
+    ///
+    /// This is useful to carry the [`Self::impulses`] of a joint over to another joint
+    /// replacing it (e.g. to change the joint type) without a one-frame jolt. The impulse
+    /// uses the same frame and units as [`Self::impulses`] (see [`JointImpulse`]), so it can
+    /// be transferred as-is between joints with the same local frames. Only the components
+    /// along the locked axes of this joint are used, the others are ignored.
+    ///
+    /// The solver starts from this impulse instead of zero at the first substep of the next
+    /// timestep where this joint is active, and then discards it.
+    pub fn set_warmstart_impulse(&mut self, impulse: JointImpulse) {
+        self.warmstart_impulse = Some(impulse);
+    }
+
+    /// Updates the set of limits being hit based on the limit impulses computed by
+    /// the last solve, and calls `f` for each limit that started being hit.
+    pub(crate) fn update_limits_hit(&mut self, mut f: impl FnMut(JointLimitEvent)) {
+        let mut min_limits_hit = JointAxesMask::empty();
+        let mut max_limits_hit = JointAxesMask::empty();
+
+        for (i, axis) in JointAxis::ALL.into_iter().enumerate() {
+            let mask = JointAxesMask::from(axis);
+            if !self.data.limit_axes.contains(mask) {
+                continue;
+            }
+
+            let impulse = self.data.limits[i].impulse;
+
+            // NOTE: the limit impulse is negative when the lower bound is enforced,
+            //       and positive when the upper bound is enforced.
+            if impulse < -LIMIT_EVENT_IMPULSE_THRESHOLD {
+                min_limits_hit |= mask;
+
+                if !self.min_limits_hit.contains(mask) {
+                    f(JointLimitEvent {
+                        joint: self.handle,
+                        axis,
+                        hit_min: true,
+                    });
+                }
+            } else if impulse > LIMIT_EVENT_IMPULSE_THRESHOLD {
+                max_limits_hit |= mask;
+
+                if !self.max_limits_hit.contains(mask) {
+                    f(JointLimitEvent {
+                        joint: self.handle,
+                        axis,
+                        hit_min: false,
+                    });
+                }
+            }
+        }
+
+        self.min_limits_hit = min_limits_hit;
+        self.max_limits_hit = max_limits_hit;
+    }
+
+    /// Checks if the force or torque applied by this joint during the last solver substep,
+    /// of length `1.0 / inv_dt`, exceeds its breaking force or torque.
+    ///
+    /// Returns the event to emit if the joint breaks.
+    pub(crate) fn check_breaking(&self, inv_dt: Real) -> Option<JointBreakEvent> {
+        if !self.data.is_breakable() {
+            return None;
+        }
+
+        // Include the impulses of the limits and motors since they are applied along the
+        // free axes, which don’t have any impulse in `self.impulses`.
+        let mut impulse = self.impulses;
+        for i in 0..SPATIAL_DIM {
+            impulse[i] += self.data.limits[i].impulse + self.data.motors[i].impulse;
+        }
+
+        let force = impulse.fixed_rows::<DIM>(0).norm() * inv_dt;
+        let torque = impulse.fixed_rows::<ANG_DIM>(DIM).norm() * inv_dt;
+
+        if force > self.data.breaking_force || torque > self.data.breaking_torque {
+            Some(JointBreakEvent {
+                joint: self.handle,
+                body1: self.body1,
+                body2: self.body2,
+                force,
+                torque,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Event emitted when an impulse joint starts hitting one of its limits.
+///
+/// This is only emitted once when the limit starts being enforced by the solver, not at
+/// every step while the joint stays against its limit.
+pub struct JointLimitEvent {
+    /// The joint that hit one of its limits.
+    pub joint: ImpulseJointHandle,
+    /// The degree of freedom along which the limit was hit.
+    pub axis: JointAxis,
+    /// `true` if the minimum bound of the limit was hit, `false` if the maximum bound was hit.
+    pub hit_min: bool,
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Event emitted when an impulse joint breaks because the force or torque it applied exceeded
+/// its [`GenericJoint::breaking_force`] or [`GenericJoint::breaking_torque`].
+///
+/// The joint has already been removed from its
+/// [`ImpulseJointSet`](crate::dynamics::ImpulseJointSet) when this is emitted, so `joint` is no
+/// longer a valid handle.
+pub struct JointBreakEvent {
+    /// The joint that broke.
+    pub joint: ImpulseJointHandle,
+    /// The first rigid-body that was attached to the joint.
+    pub body1: RigidBodyHandle,
+    /// The second rigid-body that was attached to the joint.
+    pub body2: RigidBodyHandle,
+    /// The norm of the linear force applied by the joint when it broke.
+    pub force: Real,
+    /// The norm of the torque applied by the joint when it broke.
+    pub torque: Real,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{FixedJointBuilder, RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn joint_breaks_past_breaking_torque() {
+        use crate::pipeline::ChannelEventCollector;
+
+        let mut world = TestWorld::new();
+
+        let (collision_send, _) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let (joint_break_send, joint_break_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send)
+            .with_joint_break_event_sender(joint_break_send);
+
+        let h_fixed = world.bodies.insert(RigidBodyBuilder::fixed());
+        let (h_dynamic, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().can_sleep(false),
+            ColliderBuilder::ball(0.5),
+        );
+
+        // The limits prevent the joint from rotating, so it has to resist the torque applied
+        // to the dynamic body.
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint.limits([0.0, 0.0]).breaking_torque(10.0);
+        let joint_handle = world.impulse_joints.insert(h_fixed, h_dynamic, joint, true);
+
+        for (torque, breaks) in [(5.0, false), (20.0, true)] {
+            let body = world.bodies.get_mut(h_dynamic).unwrap();
+            body.reset_torques(false);
+            #[cfg(feature = "dim2")]
+            body.add_torque(torque, false);
+            #[cfg(feature = "dim3")]
+            body.add_torque(Vector::z() * torque, false);
+
+            for _ in 0..60 {
+                world.step_with(&(), &events);
+            }
+
+            // The joint only breaks past its breaking torque.
+            assert_eq!(world.impulse_joints.contains(joint_handle), !breaks);
+        }
+
+        // The event is emitted once, when the joint breaks.
+        let break_events: Vec<_> = joint_break_recv.try_iter().collect();
+        assert_eq!(break_events.len(), 1);
+        assert_eq!(break_events[0].joint, joint_handle);
+        assert_eq!(break_events[0].body1, h_fixed);
+        assert_eq!(break_events[0].body2, h_dynamic);
+        assert!(break_events[0].torque > 10.0);
+    }
+
+    #[test]
+    fn joint_warmstart_impulse_is_applied() {
+        // Lets a hanging chain settle with enough solver iterations for its joint impulses to
+        // converge, then replaces all its joints, seeding the new ones with the impulses of the
+        // old ones scaled by `seed_scale`, and steps once with a single solver iteration.
+        fn run(seed_scale: Option<Real>) -> (Real, Real) {
+            let mut world = TestWorld::with_gravity();
+            world.params.num_solver_iterations = std::num::NonZeroUsize::new(1).unwrap();
+            world.params.num_internal_pgs_iterations = 50;
+
+            let mut links = vec![world.bodies.insert(RigidBodyBuilder::fixed())];
+            for i in 1..=10 {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(-Vector::y() * i as Real)
+                    .can_sleep(false);
+                let (handle, _) = world.insert_body(rb, ColliderBuilder::ball(0.1));
+                links.push(handle);
+            }
+
+            let joint = FixedJointBuilder::new().local_anchor2(Point::from(Vector::y()));
+            let mut joint_handles: Vec<_> = links
+                .windows(2)
+                .map(|w| world.impulse_joints.insert(w[0], w[1], joint, true))
+                .collect();
+
+            world.steps(100);
+
+            let top_impulse = world
+                .impulse_joints
+                .get(joint_handles[0])
+                .unwrap()
+                .impulses
+                .y;
+
+            for (handle, w) in joint_handles.iter_mut().zip(links.windows(2)) {
+                let old = world.impulse_joints.remove(*handle, false).unwrap();
+                *handle = world.impulse_joints.insert(w[0], w[1], joint, true);
+
+                if let Some(scale) = seed_scale {
+                    let new = world.impulse_joints.get_mut(*handle, false).unwrap();
+                    new.set_warmstart_impulse(old.impulses * scale);
+                    assert!(new.warmstart_impulse().is_some());
+                }
+            }
+
+            world.params.num_internal_pgs_iterations = 1;
+            world.step();
+
+            for handle in &joint_handles {
+                assert!(world
+                    .impulse_joints
+                    .get(*handle)
+                    .unwrap()
+                    .warmstart_impulse()
+                    .is_none());
+            }
+
+            let max_speed = links[1..]
+                .iter()
+                .map(|h| world.bodies[*h].linvel().norm())
+                .fold(0.0, Real::max);
+            (top_impulse, max_speed)
+        }
+
+        let (top_impulse, unseeded) = run(None);
+        let (_, seeded) = run(Some(1.0));
+        let (_, wrong_seed) = run(Some(-1.0));
+
+        // The chain hangs from the fixed body, which is the first body of the top joint, so
+        // the impulse applied to the first body points downward.
+        assert!(top_impulse < 0.0);
+
+        // A single solver iteration can’t carry the weight of the chain up to its top, so the
+        // chain falls unless the solver starts from the impulses it converged to.
+        assert!(unseeded > 0.1, "{unseeded}");
+        assert!(seeded < 0.1 * unseeded, "{seeded} {unseeded}");
+        assert!(wrong_seed > unseeded, "{wrong_seed} {unseeded}");
+    }
 }
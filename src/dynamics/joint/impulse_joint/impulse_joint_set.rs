@@ -5,7 +5,8 @@ use crate::geometry::{InteractionGraph, RigidBodyGraphIndex, TemporaryInteractio
 
 use crate::data::arena::Arena;
 use crate::data::Coarena;
-use crate::dynamics::{GenericJoint, IslandManager, RigidBodyHandle, RigidBodySet};
+use crate::dynamics::{GenericJoint, IslandManager, JointAxesMask, RigidBodyHandle, RigidBodySet};
+use crate::math::{Isometry, Real};
 
 /// The unique identifier of a joint added to the joint set.
 /// The unique identifier of a collider added to a collider set.
@@ -90,6 +91,11 @@ impl ImpulseJointSet {
     }
 
     /// Iterates through all the impulse joints attached to the given rigid-body.
+    ///
+    /// Each item is the pair of rigid-bodies attached to the joint, the joint handle, and the
+    /// joint itself. This only reads the adjacency of `body` in the joint graph so its cost is
+    /// proportional to the number of joints attached to `body` rather than to the total number
+    /// of joints in this set.
     pub fn attached_joints(
         &self,
         body: RigidBodyHandle,
@@ -259,6 +265,9 @@ impl ImpulseJointSet {
             data,
             impulses: na::zero(),
             handle: ImpulseJointHandle(handle),
+            min_limits_hit: JointAxesMask::empty(),
+            max_limits_hit: JointAxesMask::empty(),
+            warmstart_impulse: None,
         };
 
         let default_id = InteractionGraph::<(), ()>::invalid_graph_index();
@@ -291,6 +300,32 @@ impl ImpulseJointSet {
         ImpulseJointHandle(handle)
     }
 
+    /// Inserts a new joint into this set, anchored at the given world-space frame.
+    ///
+    /// The local frames of `data` are replaced by the frames matching `anchor_world` expressed
+    /// in the local-space of each body, based on their current positions in `bodies`. The
+    /// joint is thus created without any initial error, whatever the poses of both bodies.
+    ///
+    /// If `wake_up` is set to `true`, then the bodies attached to this joint will be
+    /// automatically woken up during the next timestep.
+    ///
+    /// # Panics
+    /// Panics if `body1` or `body2` isn’t part of `bodies`.
+    pub fn insert_from_world(
+        &mut self,
+        bodies: &RigidBodySet,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        data: impl Into<GenericJoint>,
+        anchor_world: Isometry<Real>,
+        wake_up: bool,
+    ) -> ImpulseJointHandle {
+        let mut data = data.into();
+        data.set_local_frame1(bodies[body1].position().inv_mul(&anchor_world))
+            .set_local_frame2(bodies[body2].position().inv_mul(&anchor_world));
+        self.insert(body1, body2, data, wake_up)
+    }
+
     /// Retrieve all the enabled impulse joints happening between two active bodies.
     // NOTE: this is very similar to the code from NarrowPhase::select_active_interactions.
     pub(crate) fn select_active_interactions(
@@ -402,3 +437,109 @@ impl ImpulseJointSet {
         deleted
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn fixed_joint_inserted_from_world_anchor_has_no_initial_error() {
+        use crate::dynamics::FixedJointBuilder;
+        use crate::math::Isometry;
+
+        let mut world = TestWorld::new();
+
+        #[cfg(feature = "dim2")]
+        let (pos1, pos2, anchor) = (
+            Isometry::new(Vector::new(1.0, 2.0), 0.3),
+            Isometry::new(Vector::new(-2.0, 0.5), -1.2),
+            Isometry::new(Vector::new(0.5, -1.0), 2.0),
+        );
+        #[cfg(feature = "dim3")]
+        let (pos1, pos2, anchor) = (
+            Isometry::new(Vector::new(1.0, 2.0, 3.0), Vector::new(0.3, -0.2, 0.5)),
+            Isometry::new(Vector::new(-2.0, 0.5, 1.0), Vector::new(-1.2, 0.4, 0.1)),
+            Isometry::new(Vector::new(0.5, -1.0, 0.0), Vector::new(0.0, 2.0, -0.7)),
+        );
+
+        let h1 = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().position(pos1));
+        let h2 = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().position(pos2));
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), h1, &mut world.bodies);
+        world
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), h2, &mut world.bodies);
+
+        let joint_handle = world.impulse_joints.insert_from_world(
+            &world.bodies,
+            h1,
+            h2,
+            FixedJointBuilder::new(),
+            anchor,
+            true,
+        );
+
+        // Both local frames match the world-space anchor.
+        let joint = &world.impulse_joints.get(joint_handle).unwrap().data;
+        approx::assert_relative_eq!(pos1 * joint.local_frame1, anchor, epsilon = 1.0e-5);
+        approx::assert_relative_eq!(pos2 * joint.local_frame2, anchor, epsilon = 1.0e-5);
+
+        world.step();
+
+        // The joint didn’t have any error to correct, so the bodies didn’t move.
+        for (handle, pos) in [(h1, pos1), (h2, pos2)] {
+            assert!(world.bodies[handle].linvel().norm() < 1.0e-5);
+            approx::assert_relative_eq!(*world.bodies[handle].position(), pos, epsilon = 1.0e-5);
+        }
+        assert!(
+            world
+                .impulse_joints
+                .get(joint_handle)
+                .unwrap()
+                .impulses
+                .norm()
+                < 1.0e-5
+        );
+    }
+
+    #[test]
+    fn joint_limit_event_on_entry_only() {
+        use crate::dynamics::{JointAxis, PrismaticJointBuilder};
+        use crate::pipeline::ChannelEventCollector;
+
+        let mut world = TestWorld::with_gravity();
+
+        let (collision_send, _) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let (joint_limit_send, joint_limit_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send)
+            .with_joint_limit_event_sender(joint_limit_send);
+
+        let rb = RigidBodyBuilder::fixed().build();
+        let h = world.bodies.insert(rb);
+        let rb_dynamic = RigidBodyBuilder::dynamic().additional_mass(1.0).build();
+        let h_dynamic = world.bodies.insert(rb_dynamic);
+
+        // The dynamic body falls along the prismatic axis until it rests on its lower limit.
+        let joint = PrismaticJointBuilder::new(Vector::y_axis()).limits([-1.0, 1.0]);
+        let joint_handle = world.impulse_joints.insert(h, h_dynamic, joint, true);
+
+        for _ in 0..200 {
+            world.step_with(&(), &events);
+        }
+
+        let limit_events: Vec<_> = joint_limit_recv.try_iter().collect();
+        assert_eq!(limit_events.len(), 1);
+        assert_eq!(limit_events[0].joint, joint_handle);
+        assert_eq!(limit_events[0].axis, JointAxis::LinX);
+        assert!(limit_events[0].hit_min);
+    }
+}
@@ -14,7 +14,8 @@ use na::{
 };
 
 #[cfg(doc)]
-use crate::prelude::{GenericJoint, RigidBody};
+use crate::prelude::GenericJoint;
+use crate::prelude::RigidBody;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default)]
@@ -33,6 +34,37 @@ impl Force {
     }
 }
 
+/// The net force applied to the rigid-body `rb` of a multibody link with the acceleration `acc`,
+/// given the external `force` and `torque` applied to it.
+///
+/// This is the external force minus the inertial and gyroscopic forces.
+fn link_net_forces(
+    rb: &RigidBody,
+    force: &Vector<Real>,
+    torque: &AngVector<Real>,
+    acc: &RigidBodyVelocity,
+) -> Force {
+    // TODO: should gyroscopic forces already be computed by the rigid-body itself
+    //       (at the same time that we add the gravity force)?
+    let gyroscopic;
+    let rb_inertia = rb.mprops.effective_angular_inertia();
+    let rb_mass = rb.mprops.effective_mass();
+
+    #[cfg(feature = "dim3")]
+    {
+        gyroscopic = rb.vels.angvel.cross(&(rb_inertia * rb.vels.angvel));
+    }
+    #[cfg(feature = "dim2")]
+    {
+        gyroscopic = 0.0;
+    }
+
+    Force::new(
+        force - rb_mass.component_mul(&acc.linvel),
+        torque - gyroscopic - rb_inertia * acc.angvel,
+    )
+}
+
 #[cfg(feature = "dim2")]
 fn concat_rb_mass_matrix(
     mass: Vector<Real>,
@@ -423,54 +455,12 @@ impl Multibody {
         for i in 0..self.links.len() {
             let link = &self.links[i];
             let rb = &bodies[link.rigid_body];
-
-            let mut acc = RigidBodyVelocity::zero();
-
-            if i != 0 {
-                let parent_id = link.parent_internal_id;
-                let parent_link = &self.links[parent_id];
-                let parent_rb = &bodies[parent_link.rigid_body];
-
-                acc += self.workspace.accs[parent_id];
-                // The 2.0 originates from the two identical terms of Jdot (the terms become
-                // identical once they are multiplied by the generalized velocities).
-                acc.linvel += 2.0 * parent_rb.vels.angvel.gcross(link.joint_velocity.linvel);
-                #[cfg(feature = "dim3")]
-                {
-                    acc.angvel += parent_rb.vels.angvel.cross(&link.joint_velocity.angvel);
-                }
-
-                acc.linvel += parent_rb
-                    .vels
-                    .angvel
-                    .gcross(parent_rb.vels.angvel.gcross(link.shift02));
-                acc.linvel += self.workspace.accs[parent_id].angvel.gcross(link.shift02);
-            }
-
-            acc.linvel += rb.vels.angvel.gcross(rb.vels.angvel.gcross(link.shift23));
-            acc.linvel += acc.angvel.gcross(link.shift23);
+            let parent_acc = (i != 0).then(|| self.workspace.accs[link.parent_internal_id]);
+            let acc = self.link_velocity_dependent_acceleration(bodies, i, parent_acc);
 
             self.workspace.accs[i] = acc;
 
-            // TODO: should gyroscopic forces already be computed by the rigid-body itself
-            //       (at the same time that we add the gravity force)?
-            let gyroscopic;
-            let rb_inertia = rb.mprops.effective_angular_inertia();
-            let rb_mass = rb.mprops.effective_mass();
-
-            #[cfg(feature = "dim3")]
-            {
-                gyroscopic = rb.vels.angvel.cross(&(rb_inertia * rb.vels.angvel));
-            }
-            #[cfg(feature = "dim2")]
-            {
-                gyroscopic = 0.0;
-            }
-
-            let external_forces = Force::new(
-                rb.forces.force - rb_mass.component_mul(&acc.linvel),
-                rb.forces.torque - gyroscopic - rb_inertia * acc.angvel,
-            );
+            let external_forces = link_net_forces(rb, &rb.forces.force, &rb.forces.torque, &acc);
             self.accelerations.gemv_tr(
                 1.0,
                 &self.body_jacobians[i],
@@ -486,6 +476,57 @@ impl Multibody {
             .with_rearranged_rows_mut(&mut self.accelerations, |accs| {
                 self.acc_inv_augmented_mass.solve_mut(accs);
             });
+
+        // Add the contribution of the generalized accelerations to get the full link
+        // accelerations, as returned by `Self::link_acceleration`.
+        let accelerations = self.accelerations.rows(0, self.ndofs);
+        for (acc, jacobian) in self.workspace.accs[..self.links.len()]
+            .iter_mut()
+            .zip(self.body_jacobians.iter())
+        {
+            *acc.as_vector_mut() += jacobian * accelerations;
+        }
+    }
+
+    /// The acceleration of the link `i` resulting from the velocities of this multibody alone,
+    /// i.e., with zero generalized accelerations.
+    ///
+    /// The `parent_acc` is the same acceleration computed for the parent link, or `None` for
+    /// the root.
+    fn link_velocity_dependent_acceleration(
+        &self,
+        bodies: &RigidBodySet,
+        i: usize,
+        parent_acc: Option<RigidBodyVelocity>,
+    ) -> RigidBodyVelocity {
+        let link = &self.links[i];
+        let rb = &bodies[link.rigid_body];
+
+        let mut acc = RigidBodyVelocity::zero();
+
+        if let Some(parent_acc) = parent_acc {
+            let parent_link = &self.links[link.parent_internal_id];
+            let parent_rb = &bodies[parent_link.rigid_body];
+
+            acc += parent_acc;
+            // The 2.0 originates from the two identical terms of Jdot (the terms become
+            // identical once they are multiplied by the generalized velocities).
+            acc.linvel += 2.0 * parent_rb.vels.angvel.gcross(link.joint_velocity.linvel);
+            #[cfg(feature = "dim3")]
+            {
+                acc.angvel += parent_rb.vels.angvel.cross(&link.joint_velocity.angvel);
+            }
+
+            acc.linvel += parent_rb
+                .vels
+                .angvel
+                .gcross(parent_rb.vels.angvel.gcross(link.shift02));
+            acc.linvel += parent_acc.angvel.gcross(link.shift02);
+        }
+
+        acc.linvel += rb.vels.angvel.gcross(rb.vels.angvel.gcross(link.shift23));
+        acc.linvel += acc.angvel.gcross(link.shift23);
+        acc
     }
 
     /// Computes the constant terms of the dynamics.
@@ -847,6 +888,95 @@ impl Multibody {
         &self.body_jacobians[link_id]
     }
 
+    /// The acceleration of the link `link_id`, computed by the forward dynamics at the beginning
+    /// of the last solver substep of the last timestep.
+    ///
+    /// This is the acceleration of the center-of-mass of the link, in world-space, resulting from
+    /// the forces applied to this multibody (gravity, user forces, Coriolis and centrifugal
+    /// forces, joint damping). It doesn’t take contacts or the constraints resolved by the solver
+    /// (joint limits and motors) into account. Returns `None` if `link_id` doesn’t identify a
+    /// multibody link part of `self`.
+    pub fn link_acceleration(&self, link_id: usize) -> Option<RigidBodyVelocity> {
+        if link_id < self.links.len() {
+            Some(self.workspace.accs[link_id])
+        } else {
+            None
+        }
+    }
+
+    /// Did the last time links were added to this multibody have to grow its internal
+    /// workspace buffers?
+    ///
+    /// See [`Self::workspace_resize_count`].
+    pub fn workspace_last_resize_grew(&self) -> bool {
+        self.workspace.last_resize_grew
+    }
+
+    /// The number of times the internal workspace buffers of this multibody had to grow
+    /// because links were added to it.
+    ///
+    /// The buffers are reused when links are removed, so a count increasing at each
+    /// timestep hints at links being frequently added and removed.
+    pub fn workspace_resize_count(&self) -> u64 {
+        self.workspace.resize_count
+    }
+
+    /// Computes the generalized forces needed to give this multibody the generalized
+    /// accelerations `desired_acceleration`, with the recursive Newton-Euler algorithm.
+    ///
+    /// The resulting generalized forces compensate the gravity, the user forces applied to the
+    /// links, the Coriolis and centrifugal forces, and the joint damping. They are expressed
+    /// along the degrees of freedom of the multibody, like [`Self::generalized_acceleration`],
+    /// e.g., as a torque along the axis of a revolute joint. Contacts and joint limits and motors
+    /// are not taken into account.
+    ///
+    /// This uses the link velocities and the jacobians computed by the last timestep (or by the
+    /// last call to [`Self::forward_kinematics`]).
+    ///
+    /// # Panics
+    /// Panics if the length of `desired_acceleration` isn’t [`Self::ndofs`].
+    pub fn inverse_dynamics(
+        &self,
+        bodies: &RigidBodySet,
+        gravity: &Vector<Real>,
+        desired_acceleration: &DVector<Real>,
+    ) -> DVector<Real> {
+        assert_eq!(
+            desired_acceleration.len(),
+            self.ndofs,
+            "The desired acceleration must have one component per degree of freedom."
+        );
+
+        let mut generalized_forces = DVector::zeros(self.ndofs);
+        let mut velocity_dependent_accs = Vec::with_capacity(self.links.len());
+
+        for i in 0..self.links.len() {
+            let link = &self.links[i];
+            let rb = &bodies[link.rigid_body];
+            let parent_acc = (i != 0).then(|| velocity_dependent_accs[link.parent_internal_id]);
+            let velocity_dependent_acc =
+                self.link_velocity_dependent_acceleration(bodies, i, parent_acc);
+            velocity_dependent_accs.push(velocity_dependent_acc);
+
+            let mut acc = velocity_dependent_acc;
+            *acc.as_vector_mut() += &self.body_jacobians[i] * desired_acceleration;
+
+            let effective_mass = rb.mprops.effective_mass();
+            let force = rb.forces.user_force
+                + gravity.component_mul(&effective_mass) * rb.forces.gravity_scale;
+            let net_forces = link_net_forces(rb, &force, &rb.forces.user_torque, &acc);
+            generalized_forces.gemv_tr(-1.0, &self.body_jacobians[i], net_forces.as_vector(), 1.0);
+        }
+
+        generalized_forces.cmpy(
+            1.0,
+            &self.damping.rows(0, self.ndofs),
+            &self.velocities.rows(0, self.ndofs),
+            1.0,
+        );
+        generalized_forces
+    }
+
     /// The mutable generalized velocities of this multibodies.
     #[inline]
     pub fn generalized_velocity_mut(&mut self) -> DVectorViewMut<Real> {
@@ -1373,8 +1503,12 @@ impl IndexSequence {
 #[cfg(test)]
 mod test {
     use super::IndexSequence;
+    use crate::dynamics::RevoluteJointBuilder;
     use crate::dynamics::{ImpulseJointSet, IslandManager};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
     use crate::math::{Real, SPATIAL_DIM};
+    use crate::pipeline::test_world::TestWorld;
     use crate::prelude::{
         ColliderSet, MultibodyJointHandle, MultibodyJointSet, RevoluteJoint, RigidBodyBuilder,
         RigidBodySet,
@@ -1403,6 +1537,48 @@ mod test {
         assert_eq!(joints.get(mb_handle).unwrap().0.ndofs, SPATIAL_DIM + 3);
     }
 
+    #[test]
+    fn multibody_workspace_resize_stats() {
+        let mut world = TestWorld::with_gravity();
+
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let rb = RigidBodyBuilder::dynamic().translation(Vector::x() * i as Real);
+                let (handle, _) = world.insert_body(rb, ColliderBuilder::ball(0.1));
+                handle
+            })
+            .collect();
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new().local_anchor2(Point::from(-Vector::x()));
+        #[cfg(feature = "dim3")]
+        let joint =
+            RevoluteJointBuilder::new(Vector::z_axis()).local_anchor2(Point::from(-Vector::x()));
+        let mb_handle = world
+            .multibody_joints
+            .insert(handles[0], handles[1], joint, true)
+            .unwrap();
+        let multibody = world.multibody_joints.get(mb_handle).unwrap().0;
+        assert!(multibody.workspace_last_resize_grew());
+        let resize_count = multibody.workspace_resize_count();
+        assert!(resize_count > 0);
+
+        // Adding a link grows the buffers.
+        world
+            .multibody_joints
+            .insert(handles[1], handles[2], joint, true)
+            .unwrap();
+        let multibody = world.multibody_joints.get(mb_handle).unwrap().0;
+        assert!(multibody.workspace_last_resize_grew());
+        assert!(multibody.workspace_resize_count() > resize_count);
+        let resize_count = multibody.workspace_resize_count();
+
+        // Simulating the multibody reuses the buffers.
+        world.steps(10);
+        let multibody = world.multibody_joints.get(mb_handle).unwrap().0;
+        assert_eq!(multibody.workspace_resize_count(), resize_count);
+    }
+
     #[test]
     fn test_multibody_insert() {
         let mut rnd = oorandom::Rand32::new(1234);
@@ -1564,4 +1740,163 @@ mod test {
             DVector::from(vec![0.0, 10.0, 0.0, 0.0, 0.0, 50.0, 60.0, 0.0, 80.0, 0.0])
         );
     }
+
+    #[test]
+    fn multibody_link_acceleration_at_release() {
+        use std::num::NonZeroUsize;
+
+        const LENGTH: Real = 1.0;
+        const HALF_WIDTH: Real = 0.001;
+        const GRAVITY: Real = 9.81;
+
+        let mut world = TestWorld::new();
+
+        // A double pendulum made of two identical thin rods, released horizontally.
+        let root = world.bodies.insert(RigidBodyBuilder::fixed());
+        let mut parent = root;
+        let mut handles = vec![];
+
+        for i in 0..2 {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i as Real + 0.5) * LENGTH)
+                .can_sleep(false);
+            let handle = world.bodies.insert(rb);
+            #[cfg(feature = "dim2")]
+            let co = ColliderBuilder::cuboid(LENGTH / 2.0, HALF_WIDTH);
+            #[cfg(feature = "dim3")]
+            let co = ColliderBuilder::cuboid(LENGTH / 2.0, HALF_WIDTH, HALF_WIDTH);
+            world
+                .colliders
+                .insert_with_parent(co, handle, &mut world.bodies);
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let anchor1 = if parent == root { 0.0 } else { LENGTH / 2.0 };
+            let joint = joint
+                .local_anchor1(Point::from(Vector::x() * anchor1))
+                .local_anchor2(Point::from(Vector::x() * -LENGTH / 2.0))
+                .contacts_enabled(false);
+            handles.push(
+                world
+                    .multibody_joints
+                    .insert(parent, handle, joint, true)
+                    .unwrap(),
+            );
+            parent = handle;
+        }
+
+        world.gravity = Vector::y() * -GRAVITY;
+        // The accelerations are computed at the beginning of the last substep, so use a
+        // single one to read them at release.
+        world.params.num_solver_iterations = NonZeroUsize::new(1).unwrap();
+        world.step();
+
+        // With the absolute angles of the rods as generalized coordinates, the equations of
+        // motion at release give the angular accelerations -9/7 g/L and 3/7 g/L. So the center
+        // of mass of the tip rod falls faster than gravity: its acceleration is -15/14 g.
+        let (multibody, tip_id) = world.multibody_joints.get(handles[1]).unwrap();
+        let acc = multibody.link_acceleration(tip_id).unwrap();
+        approx::assert_relative_eq!(acc.linvel.x, 0.0, epsilon = 1.0e-3);
+        approx::assert_relative_eq!(acc.linvel.y, -15.0 / 14.0 * GRAVITY, epsilon = 1.0e-3);
+        #[cfg(feature = "dim2")]
+        approx::assert_relative_eq!(acc.angvel, 3.0 / 7.0 * GRAVITY / LENGTH, epsilon = 1.0e-3);
+        #[cfg(feature = "dim3")]
+        approx::assert_relative_eq!(
+            acc.angvel,
+            Vector::z() * 3.0 / 7.0 * GRAVITY / LENGTH,
+            epsilon = 1.0e-3
+        );
+        assert!(multibody.link_acceleration(2).is_some());
+        assert!(multibody.link_acceleration(3).is_none());
+    }
+
+    #[test]
+    fn multibody_inverse_dynamics_reproduces_desired_accelerations() {
+        use na::DVector;
+        use std::num::NonZeroUsize;
+
+        const LENGTH: Real = 1.0;
+        const HALF_WIDTH: Real = 0.1;
+        let gravity = Vector::y() * -9.81;
+
+        let mut world = TestWorld::new();
+
+        // A double pendulum made of two rods, initially horizontal.
+        let root = world.bodies.insert(RigidBodyBuilder::fixed());
+        let mut parent = root;
+        let mut links = vec![];
+
+        for i in 0..2 {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i as Real + 0.5) * LENGTH)
+                .can_sleep(false);
+            let handle = world.bodies.insert(rb);
+            #[cfg(feature = "dim2")]
+            let co = ColliderBuilder::cuboid(LENGTH / 2.0, HALF_WIDTH);
+            #[cfg(feature = "dim3")]
+            let co = ColliderBuilder::cuboid(LENGTH / 2.0, HALF_WIDTH, HALF_WIDTH);
+            world
+                .colliders
+                .insert_with_parent(co, handle, &mut world.bodies);
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let anchor1 = if parent == root { 0.0 } else { LENGTH / 2.0 };
+            let joint = joint
+                .local_anchor1(Point::from(Vector::x() * anchor1))
+                .local_anchor2(Point::from(Vector::x() * -LENGTH / 2.0))
+                .contacts_enabled(false);
+            links.push(
+                world
+                    .multibody_joints
+                    .insert(parent, handle, joint, true)
+                    .unwrap(),
+            );
+            parent = handle;
+        }
+
+        // The accelerations are computed at the beginning of the last substep, so use a single
+        // one to read them at the beginning of the timestep.
+        world.params.num_solver_iterations = NonZeroUsize::new(1).unwrap();
+
+        // Step without gravity so the pendulum stays at rest with up-to-date jacobians.
+        world.step();
+
+        let desired_acceleration = DVector::from_vec(vec![1.5, -2.0]);
+        let (multibody, _) = world.multibody_joints.get(links[0]).unwrap();
+        let generalized_forces =
+            multibody.inverse_dynamics(&world.bodies, &gravity, &desired_acceleration);
+
+        // Holding the pendulum horizontal requires a torque at both joints.
+        let hold = multibody.inverse_dynamics(&world.bodies, &gravity, &DVector::zeros(2));
+        assert!(hold[0] > 0.0 && hold[1] > 0.0);
+
+        // Apply each generalized force as a torque between the two bodies of its joint.
+        let handles: Vec<_> = multibody
+            .links()
+            .map(|link| link.rigid_body_handle())
+            .collect();
+        for (k, torque) in generalized_forces.iter().enumerate() {
+            #[cfg(feature = "dim2")]
+            let torque = *torque;
+            #[cfg(feature = "dim3")]
+            let torque = Vector::z() * *torque;
+            world.bodies[handles[k + 1]].add_torque(torque, true);
+            world.bodies[handles[k]].add_torque(-torque, true);
+        }
+
+        world.gravity = gravity;
+        world.step();
+
+        let (multibody, _) = world.multibody_joints.get(links[0]).unwrap();
+        approx::assert_relative_eq!(
+            multibody.generalized_acceleration().into_owned(),
+            desired_acceleration,
+            epsilon = 1.0e-3
+        );
+    }
 }
@@ -22,6 +22,13 @@ pub struct MultibodyJoint {
     /// Kinematic joint velocities are never changed by the physics engine. This gives the user
     /// total control over the values of their degrees of freedoms.
     pub kinematic: bool,
+    /// The maximum force (along the free linear axes) or torque (along the free angular axes)
+    /// resisting the motion of each degree of freedom of this joint (default: `0.0`).
+    ///
+    /// This models dry (Coulomb) friction in the joint: it opposes the joint velocities, and
+    /// holds the joint still as long as the other forces don’t exceed it. It never makes the
+    /// joint move by itself.
+    pub friction: Real,
     pub(crate) coords: SpacialVector<Real>,
     pub(crate) joint_rot: Rotation<Real>,
 }
@@ -32,6 +39,7 @@ impl MultibodyJoint {
         Self {
             data,
             kinematic,
+            friction: 0.0,
             coords: na::zero(),
             joint_rot: Rotation::identity(),
         }
@@ -254,6 +262,9 @@ impl MultibodyJoint {
                 if (motor_bits & (1 << i)) != 0 {
                     num_constraints += 1;
                 }
+                if self.friction > 0.0 {
+                    num_constraints += 1;
+                }
             }
         }
 
@@ -307,6 +318,7 @@ impl MultibodyJoint {
                         multibody,
                         link,
                         [self.data.limits[i].min, self.data.limits[i].max],
+                        self.data.limits[i].spring_params(params.dt),
                         self.coords[i],
                         curr_free_dof,
                         j_id,
@@ -315,6 +327,20 @@ impl MultibodyJoint {
                         &mut num_constraints,
                     );
                 }
+
+                if self.friction > 0.0 {
+                    joint::unit_joint_friction_constraint(
+                        params,
+                        multibody,
+                        link,
+                        self.friction,
+                        curr_free_dof,
+                        j_id,
+                        jacobians,
+                        constraints,
+                        &mut num_constraints,
+                    );
+                }
                 curr_free_dof += 1;
             }
         }
@@ -342,6 +368,7 @@ impl MultibodyJoint {
                         multibody,
                         link,
                         limits,
+                        self.data.limits[i].spring_params(params.dt),
                         self.coords[i],
                         curr_free_dof,
                         j_id,
@@ -369,6 +396,20 @@ impl MultibodyJoint {
                         &mut num_constraints,
                     );
                 }
+
+                if self.friction > 0.0 {
+                    joint::unit_joint_friction_constraint(
+                        params,
+                        multibody,
+                        link,
+                        self.friction,
+                        curr_free_dof,
+                        j_id,
+                        jacobians,
+                        constraints,
+                        &mut num_constraints,
+                    );
+                }
                 curr_free_dof += 1;
             }
         }
@@ -376,3 +417,82 @@ impl MultibodyJoint {
         num_constraints
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn multibody_joint_friction_damps_pendulum() {
+        const LENGTH: Real = 1.0;
+        const GRAVITY: Real = 9.81;
+        const INIT_ANGLE: Real = 0.5;
+
+        // Returns the smallest angle of the pendulum (relative to its rest angle), and the
+        // index of the step after which it stopped moving, if any.
+        let run = |friction_ratio: Real| -> (Real, Option<usize>) {
+            let mut world = TestWorld::new();
+            world.gravity = Vector::y() * -GRAVITY;
+
+            let root = world.bodies.insert(RigidBodyBuilder::fixed());
+            let bob_pos =
+                Vector::x() * INIT_ANGLE.sin() * LENGTH - Vector::y() * INIT_ANGLE.cos() * LENGTH;
+            let (bob, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(bob_pos)
+                    .can_sleep(false),
+                ColliderBuilder::ball(0.1),
+            );
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let joint = joint
+                .local_anchor2(Point::from(-bob_pos))
+                .contacts_enabled(false);
+            let handle = world
+                .multibody_joints
+                .insert(root, bob, joint, true)
+                .unwrap();
+
+            // The friction torque is a fraction of the torque of the weight of the bob when the
+            // pendulum is horizontal.
+            let friction = friction_ratio * world.bodies[bob].mass() * GRAVITY * LENGTH;
+            let (multibody, link_id) = world.multibody_joints.get_mut(handle).unwrap();
+            multibody.link_mut(link_id).unwrap().joint.friction = friction;
+
+            let mut min_angle = INIT_ANGLE;
+            let mut stopped_at = None;
+            for i in 0..300 {
+                world.step();
+
+                let rb = &world.bodies[bob];
+                let angle = rb.translation().x.atan2(-rb.translation().y);
+                min_angle = min_angle.min(angle);
+
+                if rb.linvel().norm() > 1.0e-3 {
+                    stopped_at = None;
+                } else if stopped_at.is_none() {
+                    stopped_at = Some(i);
+                }
+            }
+
+            (min_angle, stopped_at)
+        };
+
+        // Without friction, the pendulum swings to the other side, and keeps swinging.
+        let (min_angle, stopped_at) = run(0.0);
+        assert!(min_angle < -INIT_ANGLE * 0.5);
+        assert_eq!(stopped_at, None);
+
+        // With enough friction, it stops before reaching its rest angle, and the friction then
+        // holds it still.
+        let (min_angle, stopped_at) = run(0.3);
+        assert!((0.0..INIT_ANGLE * 0.5).contains(&min_angle));
+        assert!(stopped_at.unwrap() < 120);
+    }
+}
@@ -412,6 +412,10 @@ impl MultibodyJointSet {
     }
 
     /// Iterates through all the joints attached to the given rigid-body.
+    ///
+    /// Each item is the pair of rigid-bodies attached to the joint (parent first), and the joint
+    /// handle. This only reads the adjacency of `rb` in the connectivity graph so its cost is
+    /// proportional to the number of joints attached to `rb`.
     pub fn attached_joints(
         &self,
         rb: RigidBodyHandle,
@@ -6,8 +6,14 @@ use na::DVector;
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug)]
 pub(crate) struct MultibodyWorkspace {
+    /// The acceleration of each link computed by the last call to
+    /// [`Multibody::update_acceleration`](super::Multibody::update_acceleration).
     pub accs: Vec<RigidBodyVelocity>,
     pub ndofs_vec: DVector<Real>,
+    /// Did the last call to [`Self::resize`] have to grow the buffers?
+    pub last_resize_grew: bool,
+    /// The number of calls to [`Self::resize`] that had to grow the buffers.
+    pub resize_count: u64,
 }
 
 impl MultibodyWorkspace {
@@ -16,12 +22,58 @@ impl MultibodyWorkspace {
         MultibodyWorkspace {
             accs: Vec::new(),
             ndofs_vec: DVector::zeros(0),
+            last_resize_grew: false,
+            resize_count: 0,
         }
     }
 
     /// Resize the workspace so it is enough for `nlinks` links.
+    ///
+    /// This does nothing if the workspace is already large enough.
     pub fn resize(&mut self, nlinks: usize, ndofs: usize) {
-        self.accs.resize(nlinks, RigidBodyVelocity::zero());
-        self.ndofs_vec = DVector::zeros(ndofs)
+        self.last_resize_grew = self.accs.len() < nlinks || self.ndofs_vec.len() < ndofs;
+
+        if !self.last_resize_grew {
+            return;
+        }
+
+        self.resize_count += 1;
+
+        if self.accs.len() < nlinks {
+            self.accs.resize(nlinks, RigidBodyVelocity::zero());
+        }
+
+        if self.ndofs_vec.len() < ndofs {
+            self.ndofs_vec = DVector::zeros(ndofs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MultibodyWorkspace;
+
+    #[test]
+    fn resize_only_grows() {
+        let mut workspace = MultibodyWorkspace::new();
+        workspace.resize(4, 10);
+        assert!(workspace.last_resize_grew);
+        assert_eq!(workspace.resize_count, 1);
+
+        let accs = workspace.accs.as_ptr();
+        let ndofs_vec = workspace.ndofs_vec.as_ptr();
+
+        for (nlinks, ndofs) in [(4, 10), (3, 7), (1, 0)] {
+            workspace.resize(nlinks, ndofs);
+            assert!(!workspace.last_resize_grew);
+            assert_eq!(workspace.resize_count, 1);
+            assert_eq!(workspace.accs.as_ptr(), accs);
+            assert_eq!(workspace.ndofs_vec.as_ptr(), ndofs_vec);
+        }
+
+        workspace.resize(5, 10);
+        assert!(workspace.last_resize_grew);
+        assert_eq!(workspace.resize_count, 2);
+        assert_eq!(workspace.accs.len(), 5);
     }
 }
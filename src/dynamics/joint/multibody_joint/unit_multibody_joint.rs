@@ -1,7 +1,7 @@
 #![allow(missing_docs)] // For downcast.
 
 use crate::dynamics::joint::MultibodyLink;
-use crate::dynamics::solver::{JointGenericOneBodyConstraint, WritebackId};
+use crate::dynamics::solver::{JointGenericOneBodyConstraint, MotorParameters, WritebackId};
 use crate::dynamics::{IntegrationParameters, JointMotor, Multibody};
 use crate::math::Real;
 use na::DVector;
@@ -13,6 +13,7 @@ pub fn unit_joint_limit_constraint(
     multibody: &Multibody,
     link: &MultibodyLink,
     limits: [Real; 2],
+    spring: Option<MotorParameters<Real>>,
     curr_pos: Real,
     dof_id: usize,
     j_id: &mut usize,
@@ -28,6 +29,13 @@ pub fn unit_joint_limit_constraint(
     let rhs_bias = ((curr_pos - limits[1]).max(0.0) - (limits[0] - curr_pos).max(0.0)) * erp_inv_dt;
     let rhs_wo_bias = 0.0;
 
+    let (rhs, rhs_wo_bias, cfm_coeff, cfm_gain) = if let Some(spring) = spring {
+        let rhs = rhs_wo_bias + spring.soft_limit_rhs(curr_pos, limits, min_enabled);
+        (rhs, rhs, spring.cfm_coeff, spring.cfm_gain)
+    } else {
+        (rhs_wo_bias + rhs_bias, rhs_wo_bias, cfm_coeff, 0.0)
+    };
+
     let dof_j_id = *j_id + dof_id + link.assembly_id;
     jacobians.rows_mut(*j_id, ndofs * 2).fill(0.0);
     jacobians[dof_j_id] = 1.0;
@@ -50,10 +58,10 @@ pub fn unit_joint_limit_constraint(
         impulse: 0.0,
         impulse_bounds,
         inv_lhs: crate::utils::inv(lhs),
-        rhs: rhs_wo_bias + rhs_bias,
+        rhs,
         rhs_wo_bias,
         cfm_coeff,
-        cfm_gain: 0.0,
+        cfm_gain,
         writeback_id: WritebackId::Limit(dof_id),
     };
 
@@ -128,3 +136,53 @@ pub fn unit_joint_motor_constraint(
 
     *j_id += 2 * ndofs;
 }
+
+/// Initializes and generate the velocity constraint resisting the motion of one degree of
+/// freedom of this multibody_joint with dry friction.
+///
+/// The constraint drives the velocity of the degree of freedom toward zero, with an impulse
+/// bounded by `friction * dt`, so it can only dissipate energy.
+pub fn unit_joint_friction_constraint(
+    params: &IntegrationParameters,
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    friction: Real,
+    dof_id: usize,
+    j_id: &mut usize,
+    jacobians: &mut DVector<Real>,
+    constraints: &mut [JointGenericOneBodyConstraint],
+    insert_at: &mut usize,
+) {
+    let ndofs = multibody.ndofs();
+    let max_impulse = friction * params.dt;
+
+    let dof_j_id = *j_id + dof_id + link.assembly_id;
+    jacobians.rows_mut(*j_id, ndofs * 2).fill(0.0);
+    jacobians[dof_j_id] = 1.0;
+    jacobians[dof_j_id + ndofs] = 1.0;
+    multibody
+        .inv_augmented_mass()
+        .solve_mut(&mut jacobians.rows_mut(*j_id + ndofs, ndofs));
+
+    let lhs = jacobians[dof_j_id + ndofs]; // = J^t * M^-1 J
+
+    let constraint = JointGenericOneBodyConstraint {
+        solver_vel2: multibody.solver_id,
+        ndofs2: ndofs,
+        j_id2: *j_id,
+        joint_id: usize::MAX,
+        impulse: 0.0,
+        impulse_bounds: [-max_impulse, max_impulse],
+        cfm_coeff: 0.0,
+        cfm_gain: 0.0,
+        inv_lhs: crate::utils::inv(lhs),
+        rhs: 0.0,
+        rhs_wo_bias: 0.0,
+        writeback_id: WritebackId::Dof(dof_id),
+    };
+
+    constraints[*insert_at] = constraint;
+    *insert_at += 1;
+
+    *j_id += 2 * ndofs;
+}
@@ -139,6 +139,12 @@ impl PrismaticJoint {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    pub fn set_motor_max_accel(&mut self, max_accel: Real) -> &mut Self {
+        self.data.set_motor_max_accel(JointAxis::LinX, max_accel);
+        self
+    }
+
     /// The limit distance attached bodies can translate along the joint’s principal axis.
     #[must_use]
     pub fn limits(&self) -> Option<&JointLimits<Real>> {
@@ -150,6 +156,15 @@ impl PrismaticJoint {
         self.data.set_limits(JointAxis::LinX, limits);
         self
     }
+
+    /// Makes the limits soft, with the given spring stiffness and damping.
+    ///
+    /// See [`JointLimits::stiffness`] for details.
+    pub fn set_limit_spring(&mut self, stiffness: Real, damping: Real) -> &mut Self {
+        self.data
+            .set_limit_spring(JointAxis::LinX, stiffness, damping);
+        self
+    }
 }
 
 impl From<PrismaticJoint> for GenericJoint {
@@ -180,6 +195,20 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.data.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.data.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -249,6 +278,13 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    #[must_use]
+    pub fn motor_max_accel(mut self, max_accel: Real) -> Self {
+        self.0.set_motor_max_accel(max_accel);
+        self
+    }
+
     /// Sets the `[min,max]` limit distances attached bodies can translate along the joint’s principal axis.
     #[must_use]
     pub fn limits(mut self, limits: [Real; 2]) -> Self {
@@ -256,6 +292,13 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Makes the limits soft, with the given spring stiffness and damping.
+    #[must_use]
+    pub fn limit_spring(mut self, stiffness: Real, damping: Real) -> Self {
+        self.0.set_limit_spring(stiffness, damping);
+        self
+    }
+
     /// Builds the prismatic joint.
     #[must_use]
     pub fn build(self) -> PrismaticJoint {
@@ -148,6 +148,12 @@ impl RevoluteJoint {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    pub fn set_motor_max_accel(&mut self, max_accel: Real) -> &mut Self {
+        self.data.set_motor_max_accel(JointAxis::AngX, max_accel);
+        self
+    }
+
     /// The limit angle attached bodies can translate along the joint’s principal axis.
     #[must_use]
     pub fn limits(&self) -> Option<&JointLimits<Real>> {
@@ -159,6 +165,15 @@ impl RevoluteJoint {
         self.data.set_limits(JointAxis::AngX, limits);
         self
     }
+
+    /// Makes the limits soft, with the given spring stiffness and damping.
+    ///
+    /// See [`JointLimits::stiffness`] for details.
+    pub fn set_limit_spring(&mut self, stiffness: Real, damping: Real) -> &mut Self {
+        self.data
+            .set_limit_spring(JointAxis::AngX, stiffness, damping);
+        self
+    }
 }
 
 impl From<RevoluteJoint> for GenericJoint {
@@ -197,6 +212,20 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.data.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.data.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -252,6 +281,13 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    #[must_use]
+    pub fn motor_max_accel(mut self, max_accel: Real) -> Self {
+        self.0.set_motor_max_accel(max_accel);
+        self
+    }
+
     /// Sets the `[min,max]` limit angles attached bodies can rotate along the joint’s principal axis.
     #[must_use]
     pub fn limits(mut self, limits: [Real; 2]) -> Self {
@@ -259,6 +295,13 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Makes the limits soft, with the given spring stiffness and damping.
+    #[must_use]
+    pub fn limit_spring(mut self, stiffness: Real, damping: Real) -> Self {
+        self.0.set_limit_spring(stiffness, damping);
+        self
+    }
+
     /// Builds the revolute joint.
     #[must_use]
     pub fn build(self) -> RevoluteJoint {
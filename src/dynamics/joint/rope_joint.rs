@@ -116,6 +116,12 @@ impl RopeJoint {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    pub fn set_motor_max_accel(&mut self, max_accel: Real) -> &mut Self {
+        self.data.set_motor_max_accel(JointAxis::LinX, max_accel);
+        self
+    }
+
     /// The maximum distance allowed between the attached objects.
     #[must_use]
     pub fn max_distance(&self) -> Real {
@@ -160,6 +166,20 @@ impl RopeJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.data.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.data.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -215,6 +235,13 @@ impl RopeJointBuilder {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor.
+    #[must_use]
+    pub fn motor_max_accel(mut self, max_accel: Real) -> Self {
+        self.0.set_motor_max_accel(max_accel);
+        self
+    }
+
     /// Sets the maximum allowed distance between the attached bodies.
     ///
     /// The `max_dist` must be strictly greater than 0.0.
@@ -1,6 +1,6 @@
 use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
 use crate::dynamics::{JointAxis, JointMotor, MotorModel};
-use crate::math::{Isometry, Point, Real};
+use crate::math::{Isometry, Point, Real, Rotation};
 
 use super::JointLimits;
 
@@ -144,12 +144,34 @@ impl SphericalJoint {
         self
     }
 
+    /// Configures the angular motors to reach the given orientation of the second body’s frame
+    /// relative to the first body’s frame.
+    ///
+    /// See [`GenericJoint::set_motor_target_orientation`] for details.
+    pub fn set_motor_target_orientation(
+        &mut self,
+        target: Rotation<Real>,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_target_orientation(target, stiffness, damping);
+        self
+    }
+
     /// Sets the maximum force the motor can deliver along the specified axis.
     pub fn set_motor_max_force(&mut self, axis: JointAxis, max_force: Real) -> &mut Self {
         self.data.set_motor_max_force(axis, max_force);
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor along the specified
+    /// axis.
+    pub fn set_motor_max_accel(&mut self, axis: JointAxis, max_accel: Real) -> &mut Self {
+        self.data.set_motor_max_accel(axis, max_accel);
+        self
+    }
+
     /// The limit distance attached bodies can translate along the specified axis.
     #[must_use]
     pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
@@ -162,6 +184,20 @@ impl SphericalJoint {
         self.data.set_limits(axis, limits);
         self
     }
+
+    /// Makes the limits along the specified axis soft, with the given spring stiffness and
+    /// damping.
+    ///
+    /// See [`JointLimits::stiffness`] for details.
+    pub fn set_limit_spring(
+        &mut self,
+        axis: JointAxis,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.data.set_limit_spring(axis, stiffness, damping);
+        self
+    }
 }
 
 impl From<SphericalJoint> for GenericJoint {
@@ -194,6 +230,20 @@ impl SphericalJointBuilder {
         self
     }
 
+    /// Sets the force above which this joint breaks.
+    #[must_use]
+    pub fn breaking_force(mut self, force: Real) -> Self {
+        self.0.data.set_breaking_force(force);
+        self
+    }
+
+    /// Sets the torque above which this joint breaks.
+    #[must_use]
+    pub fn breaking_torque(mut self, torque: Real) -> Self {
+        self.0.data.set_breaking_torque(torque);
+        self
+    }
+
     /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
     #[must_use]
     pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
@@ -267,6 +317,22 @@ impl SphericalJointBuilder {
         self
     }
 
+    /// Configures the angular motors to reach the given orientation of the second body’s frame
+    /// relative to the first body’s frame.
+    ///
+    /// See [`GenericJoint::set_motor_target_orientation`] for details.
+    #[must_use]
+    pub fn motor_target_orientation(
+        mut self,
+        target: Rotation<Real>,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        self.0
+            .set_motor_target_orientation(target, stiffness, damping);
+        self
+    }
+
     /// Sets the maximum force the motor can deliver along the specified axis.
     #[must_use]
     pub fn motor_max_force(mut self, axis: JointAxis, max_force: Real) -> Self {
@@ -274,6 +340,14 @@ impl SphericalJointBuilder {
         self
     }
 
+    /// Sets the maximum rate of change of the velocity targeted by the motor along the specified
+    /// axis.
+    #[must_use]
+    pub fn motor_max_accel(mut self, axis: JointAxis, max_accel: Real) -> Self {
+        self.0.set_motor_max_accel(axis, max_accel);
+        self
+    }
+
     /// Sets the `[min,max]` limit distances attached bodies can rotate along the specified axis.
     #[must_use]
     pub fn limits(mut self, axis: JointAxis, limits: [Real; 2]) -> Self {
@@ -281,6 +355,14 @@ impl SphericalJointBuilder {
         self
     }
 
+    /// Makes the limits along the specified axis soft, with the given spring stiffness and
+    /// damping.
+    #[must_use]
+    pub fn limit_spring(mut self, axis: JointAxis, stiffness: Real, damping: Real) -> Self {
+        self.0.set_limit_spring(axis, stiffness, damping);
+        self
+    }
+
     /// Builds the spherical joint.
     #[must_use]
     pub fn build(self) -> SphericalJoint {
@@ -293,3 +375,41 @@ impl From<SphericalJointBuilder> for GenericJoint {
         val.0.into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn spherical_joint_servos_to_target_orientation() {
+        use crate::dynamics::SphericalJointBuilder;
+        use crate::math::Rotation;
+
+        let target = Rotation::from_scaled_axis(Vector::new(1.5, -1.0, 2.0));
+
+        // The sign of the quaternion must not affect the reached orientation.
+        for motor_target in [target, Rotation::new_unchecked(-target.into_inner())] {
+            let mut world = TestWorld::new();
+
+            let h_fixed = world.bodies.insert(RigidBodyBuilder::fixed());
+            let (h_dynamic, _) = world.insert_body(
+                RigidBodyBuilder::dynamic().can_sleep(false),
+                ColliderBuilder::ball(0.5),
+            );
+
+            let joint =
+                SphericalJointBuilder::new().motor_target_orientation(motor_target, 100.0, 20.0);
+            world.impulse_joints.insert(h_fixed, h_dynamic, joint, true);
+
+            world.steps(300);
+
+            let rb = &world.bodies[h_dynamic];
+            assert!(rb.rotation().angle_to(&target) < 1.0e-3);
+            assert!(rb.angvel().norm() < 1.0e-3);
+        }
+    }
+}
@@ -1,15 +1,18 @@
 //! Structures related to dynamics: bodies, impulse_joints, etc.
 
+pub use self::buoyancy_field::BuoyancyField;
 pub use self::ccd::CCDSolver;
 pub use self::coefficient_combine_rule::CoefficientCombineRule;
-pub use self::integration_parameters::IntegrationParameters;
+#[cfg(feature = "sanitize")]
+pub use self::integration_parameters::NonFiniteBodyHandling;
+pub use self::integration_parameters::{FrictionModel, IntegrationParameters, IntegrationScheme};
 pub use self::island_manager::IslandManager;
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
 pub use self::rigid_body_components::*;
 // #[cfg(not(feature = "parallel"))]
-pub(crate) use self::solver::IslandSolver;
+pub(crate) use self::solver::{solve_inserted_body_contacts, IslandSolver};
 // #[cfg(feature = "parallel")]
 // pub(crate) use self::solver::ParallelIslandSolver;
 pub use parry::mass_properties::MassProperties;
@@ -17,6 +20,7 @@ pub use parry::mass_properties::MassProperties;
 pub use self::rigid_body::{RigidBody, RigidBodyBuilder};
 pub use self::rigid_body_set::{BodyPair, RigidBodySet};
 
+mod buoyancy_field;
 mod ccd;
 mod coefficient_combine_rule;
 mod integration_parameters;
@@ -7,7 +7,7 @@ use crate::geometry::{
     ColliderHandle, ColliderMassProps, ColliderParent, ColliderPosition, ColliderSet, ColliderShape,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector};
-use crate::utils::SimdCross;
+use crate::utils::{SimdCross, SimdDot};
 use num::Zero;
 
 #[cfg(doc)]
@@ -40,6 +40,12 @@ pub struct RigidBody {
     pub(crate) dominance: RigidBodyDominance,
     pub(crate) enabled: bool,
     pub(crate) additional_solver_iterations: usize,
+    pub(crate) max_linvel: Real,
+    pub(crate) max_angvel: Real,
+    pub(crate) self_collision_enabled: bool,
+    // The pairs of attached colliders allowed to collide with each other if self-collision
+    // is enabled.
+    pub(crate) self_collision_pairs: Vec<(ColliderHandle, ColliderHandle)>,
     /// User-defined data associated to this rigid-body.
     pub user_data: u128,
 }
@@ -69,6 +75,10 @@ impl RigidBody {
             enabled: true,
             user_data: 0,
             additional_solver_iterations: 0,
+            max_linvel: Real::MAX,
+            max_angvel: Real::MAX,
+            self_collision_enabled: false,
+            self_collision_pairs: Vec::new(),
         }
     }
 
@@ -112,6 +122,10 @@ impl RigidBody {
             dominance,
             enabled,
             additional_solver_iterations,
+            max_linvel,
+            max_angvel,
+            self_collision_enabled,
+            self_collision_pairs,
             user_data,
         } = other;
 
@@ -127,6 +141,10 @@ impl RigidBody {
         self.dominance = *dominance;
         self.enabled = *enabled;
         self.additional_solver_iterations = *additional_solver_iterations;
+        self.max_linvel = *max_linvel;
+        self.max_angvel = *max_angvel;
+        self.self_collision_enabled = *self_collision_enabled;
+        self.self_collision_pairs = self_collision_pairs.clone();
         self.user_data = *user_data;
 
         self.changes = RigidBodyChanges::all();
@@ -153,6 +171,70 @@ impl RigidBody {
         self.additional_solver_iterations = additional_iterations;
     }
 
+    /// Whether contacts can be generated between colliders attached to this rigid-body.
+    ///
+    /// See [`Self::enable_self_collision`] for additional information.
+    pub fn is_self_collision_enabled(&self) -> bool {
+        self.self_collision_enabled
+    }
+
+    /// Enables or disables contacts between colliders attached to this rigid-body.
+    ///
+    /// This is disabled by default. If enabled, only the pairs of colliders explicitly allowed
+    /// with [`Self::allow_self_collision`] generate contacts, so that adjacent parts of, e.g.,
+    /// an articulated prop modeled as a single rigid-body can remain excluded.
+    ///
+    /// Because all the colliders attached to the same rigid-body share the same motion and
+    /// mass properties, self-contacts can’t be resolved by the constraints solver: they are
+    /// computed by the narrow-phase (and reported through contact pairs, collision events, and
+    /// physics hooks) but never generate any contact impulse, so they can’t inject energy
+    /// into the rigid-body.
+    ///
+    /// Self-collision must be enabled before the colliders involved start overlapping (for
+    /// example right after attaching them, before the next timestep). Otherwise, it will
+    /// only affect them after they stop overlapping and then overlap again.
+    pub fn enable_self_collision(&mut self, enabled: bool) {
+        self.self_collision_enabled = enabled;
+    }
+
+    /// The pairs of attached colliders allowed to collide with each other when self-collision
+    /// is enabled.
+    pub fn self_collision_pairs(&self) -> &[(ColliderHandle, ColliderHandle)] {
+        &self.self_collision_pairs
+    }
+
+    /// Allows the two given colliders attached to this rigid-body to collide with each other.
+    ///
+    /// This has no effect unless self-collision is enabled with [`Self::enable_self_collision`].
+    pub fn allow_self_collision(&mut self, collider1: ColliderHandle, collider2: ColliderHandle) {
+        if !self.is_self_collision_allowed(collider1, collider2) {
+            self.self_collision_pairs.push((collider1, collider2));
+        }
+    }
+
+    /// Prevents the two given colliders attached to this rigid-body from colliding with each
+    /// other.
+    pub fn disallow_self_collision(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) {
+        self.self_collision_pairs
+            .retain(|pair| *pair != (collider1, collider2) && *pair != (collider2, collider1));
+    }
+
+    /// Checks if the two given colliders attached to this rigid-body are allowed to collide
+    /// with each other.
+    pub(crate) fn is_self_collision_allowed(
+        &self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> bool {
+        self.self_collision_pairs
+            .iter()
+            .any(|pair| *pair == (collider1, collider2) || *pair == (collider2, collider1))
+    }
+
     /// The activation status of this rigid-body.
     pub fn activation(&self) -> &RigidBodyActivation {
         &self.activation
@@ -210,6 +292,42 @@ impl RigidBody {
         self.damping.angular_damping = damping
     }
 
+    /// The maximum linear velocity magnitude of this rigid-body.
+    ///
+    /// See [`Self::set_max_linvel`] for additional information.
+    #[inline]
+    pub fn max_linvel(&self) -> Real {
+        self.max_linvel
+    }
+
+    /// Sets the maximum linear velocity magnitude of this rigid-body.
+    ///
+    /// The linear velocity computed by the constraints solver is scaled down, keeping its
+    /// direction, so its magnitude doesn’t exceed this value. This prevents a bad contact from
+    /// flinging the rigid-body at a huge speed. Defaults to `Real::MAX`, i.e., no limit.
+    #[inline]
+    pub fn set_max_linvel(&mut self, max_linvel: Real) {
+        self.max_linvel = max_linvel;
+    }
+
+    /// The maximum angular velocity magnitude of this rigid-body.
+    ///
+    /// See [`Self::set_max_angvel`] for additional information.
+    #[inline]
+    pub fn max_angvel(&self) -> Real {
+        self.max_angvel
+    }
+
+    /// Sets the maximum angular velocity magnitude of this rigid-body.
+    ///
+    /// The angular velocity computed by the constraints solver is scaled down, keeping its
+    /// direction, so its magnitude doesn’t exceed this value. Defaults to `Real::MAX`, i.e.,
+    /// no limit.
+    #[inline]
+    pub fn set_max_angvel(&mut self, max_angvel: Real) {
+        self.max_angvel = max_angvel;
+    }
+
     /// The type of this rigid-body.
     pub fn body_type(&self) -> RigidBodyType {
         self.body_type
@@ -253,6 +371,11 @@ impl RigidBody {
     }
 
     /// Sets the axes along which this rigid-body cannot translate or rotate.
+    ///
+    /// This can be called at any time: the effective mass properties used by the constraints
+    /// solver are updated right away, and the current velocities along the newly locked axes
+    /// are set to zero. The same applies to the other methods locking the axes of this
+    /// rigid-body.
     #[inline]
     pub fn set_locked_axes(&mut self, locked_axes: LockedAxes, wake_up: bool) {
         if locked_axes != self.mprops.flags {
@@ -261,7 +384,7 @@ impl RigidBody {
             }
 
             self.mprops.flags = locked_axes;
-            self.update_world_mass_properties();
+            self.update_locked_axes();
         }
     }
 
@@ -271,6 +394,33 @@ impl RigidBody {
         self.mprops.flags
     }
 
+    /// Is this rigid-body taken into account by the constraints solver?
+    #[inline]
+    pub fn is_solver_enabled(&self) -> bool {
+        self.mprops.solver_enabled
+    }
+
+    /// Sets whether this rigid-body is taken into account by the constraints solver.
+    ///
+    /// Unlike [`Self::set_enabled`], disabling the solver participation of a rigid-body keeps
+    /// its colliders, contacts, and joints active: the rigid-body behaves like a fixed body with
+    /// an infinite mass and its velocities are reset to zero, so it holds its position. It
+    /// resumes moving as soon as it is enabled again. This is ignored by multibody links.
+    pub fn set_solver_enabled(&mut self, enabled: bool) {
+        if enabled != self.mprops.solver_enabled {
+            if self.is_dynamic() {
+                self.wake_up(true);
+            }
+
+            if !enabled {
+                self.vels = RigidBodyVelocity::zero();
+            }
+
+            self.mprops.solver_enabled = enabled;
+            self.update_world_mass_properties();
+        }
+    }
+
     #[inline]
     /// Locks or unlocks all the rotations of this rigid-body.
     pub fn lock_rotations(&mut self, locked: bool, wake_up: bool) {
@@ -282,7 +432,7 @@ impl RigidBody {
             self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_X, locked);
             self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Y, locked);
             self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Z, locked);
-            self.update_world_mass_properties();
+            self.update_locked_axes();
         }
     }
 
@@ -312,7 +462,7 @@ impl RigidBody {
             self.mprops
                 .flags
                 .set(LockedAxes::ROTATION_LOCKED_Z, !allow_rotations_z);
-            self.update_world_mass_properties();
+            self.update_locked_axes();
         }
     }
 
@@ -344,7 +494,7 @@ impl RigidBody {
             self.mprops
                 .flags
                 .set(LockedAxes::TRANSLATION_LOCKED, locked);
-            self.update_world_mass_properties();
+            self.update_locked_axes();
         }
     }
 
@@ -387,7 +537,7 @@ impl RigidBody {
         self.mprops
             .flags
             .set(LockedAxes::TRANSLATION_LOCKED_Z, !allow_translation_z);
-        self.update_world_mass_properties();
+        self.update_locked_axes();
     }
 
     #[inline]
@@ -665,6 +815,8 @@ impl RigidBody {
         if let Some(i) = self.colliders.0.iter().position(|e| *e == handle) {
             self.changes.set(RigidBodyChanges::COLLIDERS, true);
             self.colliders.0.swap_remove(i);
+            self.self_collision_pairs
+                .retain(|pair| pair.0 != handle && pair.1 != handle);
         }
     }
 
@@ -721,6 +873,37 @@ impl RigidBody {
         &self.vels.angvel
     }
 
+    /// The linear velocity of this rigid-body, expressed in its local-space.
+    ///
+    /// This is the world-space [`Self::linvel`] transformed by the inverse of this
+    /// rigid-body’s orientation.
+    pub fn local_linvel(&self) -> Vector<Real> {
+        self.pos
+            .position
+            .rotation
+            .inverse_transform_vector(&self.vels.linvel)
+    }
+
+    /// The angular velocity of this rigid-body, expressed in its local-space.
+    ///
+    /// In 2D, this is the same as [`Self::angvel`].
+    #[cfg(feature = "dim2")]
+    pub fn local_angvel(&self) -> Real {
+        self.vels.angvel
+    }
+
+    /// The angular velocity of this rigid-body, expressed in its local-space.
+    ///
+    /// This is the world-space [`Self::angvel`] transformed by the inverse of this
+    /// rigid-body’s orientation.
+    #[cfg(feature = "dim3")]
+    pub fn local_angvel(&self) -> Vector<Real> {
+        self.pos
+            .position
+            .rotation
+            .inverse_transform_vector(&self.vels.angvel)
+    }
+
     /// The linear velocity of this rigid-body.
     ///
     /// If `wake_up` is `true` then the rigid-body will be woken up if it was
@@ -926,9 +1109,88 @@ impl RigidBody {
             .integrate(dt, &self.pos.position, &self.mprops.local_mprops.local_com)
     }
 
+    /// Predicts the position of this rigid-body after a timestep of length `dt` during which
+    /// no force, contact, or joint affects it, without modifying it.
+    ///
+    /// This matches the position computed by [`PhysicsPipeline::step`] in that case, which
+    /// makes it suitable for client-side prediction: fixed and disabled rigid-bodies don’t
+    /// move, position-based kinematic bodies move to their next kinematic position, and the
+    /// other bodies integrate their velocities (clamped to [`Self::max_linvel`] and
+    /// [`Self::max_angvel`] for dynamic bodies). Damping isn’t taken into account.
+    ///
+    /// [`PhysicsPipeline::step`]: crate::pipeline::PhysicsPipeline::step
+    pub fn predict_position(&self, dt: Real) -> Isometry<Real> {
+        if !self.enabled {
+            return self.pos.position;
+        }
+
+        let mut vels = self.vels;
+
+        match self.body_type {
+            RigidBodyType::Fixed => return self.pos.position,
+            RigidBodyType::KinematicPositionBased => return self.pos.next_position,
+            RigidBodyType::KinematicVelocityBased => {}
+            RigidBodyType::Dynamic => {
+                // Same clamping as the constraints solver.
+                let linvel_norm = vels.linvel.norm();
+                if linvel_norm > self.max_linvel {
+                    vels.linvel *= self.max_linvel / linvel_norm;
+                }
+
+                let angvel_norm = vels.angvel.gdot(vels.angvel).sqrt();
+                if angvel_norm > self.max_angvel {
+                    vels.angvel *= self.max_angvel / angvel_norm;
+                }
+            }
+        }
+
+        vels.integrate(dt, &self.pos.position, &self.mprops.local_mprops.local_com)
+    }
+
     pub(crate) fn update_world_mass_properties(&mut self) {
         self.mprops.update_world_mass_properties(&self.pos.position);
     }
+
+    /// Updates the effective mass properties after a change of the locked axes, and cancels the
+    /// velocities along the locked axes so the new locks are respected at the next timestep.
+    ///
+    /// The velocities of non-dynamic rigid-bodies aren’t affected by the locked axes.
+    fn update_locked_axes(&mut self) {
+        self.update_world_mass_properties();
+
+        if !self.is_dynamic() {
+            return;
+        }
+
+        let flags = self.mprops.flags;
+        if flags.contains(LockedAxes::TRANSLATION_LOCKED_X) {
+            self.vels.linvel.x = 0.0;
+        }
+        if flags.contains(LockedAxes::TRANSLATION_LOCKED_Y) {
+            self.vels.linvel.y = 0.0;
+        }
+        #[cfg(feature = "dim3")]
+        if flags.contains(LockedAxes::TRANSLATION_LOCKED_Z) {
+            self.vels.linvel.z = 0.0;
+        }
+
+        #[cfg(feature = "dim2")]
+        if flags.contains(LockedAxes::ROTATION_LOCKED_Z) {
+            self.vels.angvel = 0.0;
+        }
+        #[cfg(feature = "dim3")]
+        {
+            if flags.contains(LockedAxes::ROTATION_LOCKED_X) {
+                self.vels.angvel.x = 0.0;
+            }
+            if flags.contains(LockedAxes::ROTATION_LOCKED_Y) {
+                self.vels.angvel.y = 0.0;
+            }
+            if flags.contains(LockedAxes::ROTATION_LOCKED_Z) {
+                self.vels.angvel.z = 0.0;
+            }
+        }
+    }
 }
 
 /// ## Applying forces and torques
@@ -1009,6 +1271,53 @@ impl RigidBody {
             }
         }
     }
+
+    /// Adds to this rigid-body a constant force, expressed in its local-space, applied at its
+    /// center-of-mass.
+    ///
+    /// The force is transformed into world-space using the current orientation of this
+    /// rigid-body, so it won’t follow the rotation of the body during the next timesteps.
+    /// This does nothing on non-dynamic bodies.
+    pub fn add_force_local(&mut self, force_local: Vector<Real>, wake_up: bool) {
+        let force = self.pos.position.rotation * force_local;
+        self.add_force(force, wake_up);
+    }
+
+    /// Adds to this rigid-body a constant torque, expressed in its local-space, at its
+    /// center-of-mass.
+    ///
+    /// In 2D, this is the same as [`Self::add_torque`].
+    #[cfg(feature = "dim2")]
+    pub fn add_torque_local(&mut self, torque_local: Real, wake_up: bool) {
+        self.add_torque(torque_local, wake_up);
+    }
+
+    /// Adds to this rigid-body a constant torque, expressed in its local-space, at its
+    /// center-of-mass.
+    ///
+    /// The torque is transformed into world-space using the current orientation of this
+    /// rigid-body. This does nothing on non-dynamic bodies.
+    #[cfg(feature = "dim3")]
+    pub fn add_torque_local(&mut self, torque_local: Vector<Real>, wake_up: bool) {
+        let torque = self.pos.position.rotation * torque_local;
+        self.add_torque(torque, wake_up);
+    }
+
+    /// Adds to this rigid-body a constant force at the given point, with both the force and
+    /// the point expressed in the local-space of this rigid-body.
+    ///
+    /// They are transformed into world-space using the current pose of this rigid-body. This
+    /// does nothing on non-dynamic bodies.
+    pub fn add_force_at_local_point(
+        &mut self,
+        force_local: Vector<Real>,
+        point_local: Point<Real>,
+        wake_up: bool,
+    ) {
+        let force = self.pos.position.rotation * force_local;
+        let point = self.pos.position * point_local;
+        self.add_force_at_point(force, point, wake_up);
+    }
 }
 
 /// ## Applying impulses and angular impulses
@@ -1095,6 +1404,9 @@ impl RigidBody {
 
 impl RigidBody {
     /// The velocity of the given world-space point on this rigid-body.
+    ///
+    /// This is `linvel + angvel × (point - world_com)` where `world_com` is the world-space
+    /// center-of-mass of this rigid-body. The returned velocity is expressed in world-space.
     pub fn velocity_at_point(&self, point: &Point<Real>) -> Vector<Real> {
         self.vels.velocity_at_point(point, &self.mprops.world_com)
     }
@@ -1136,6 +1448,14 @@ pub struct RigidBodyBuilder {
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
     pub angular_damping: Real,
+    /// The maximum linear velocity magnitude of the rigid-body to be built, `Real::MAX` by default.
+    ///
+    /// See [`RigidBody::set_max_linvel`] for additional information.
+    pub max_linvel: Real,
+    /// The maximum angular velocity magnitude of the rigid-body to be built, `Real::MAX` by default.
+    ///
+    /// See [`RigidBody::set_max_angvel`] for additional information.
+    pub max_angvel: Real,
     /// The type of rigid-body being constructed.
     pub body_type: RigidBodyType,
     mprops_flags: LockedAxes,
@@ -1145,6 +1465,9 @@ pub struct RigidBodyBuilder {
     pub can_sleep: bool,
     /// Whether the rigid-body is to be created asleep.
     pub sleeping: bool,
+    /// The normalized linear and the angular velocity thresholds below which the rigid-body
+    /// can fall asleep, or `None` to use the defaults from [`RigidBodyActivation`].
+    pub sleep_thresholds: Option<(Real, Real)>,
     /// Whether Continuous Collision-Detection is enabled for the rigid-body to be built.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
@@ -1189,11 +1512,14 @@ impl RigidBodyBuilder {
             gravity_scale: 1.0,
             linear_damping: 0.0,
             angular_damping: 0.0,
+            max_linvel: Real::MAX,
+            max_angvel: Real::MAX,
             body_type,
             mprops_flags: LockedAxes::empty(),
             additional_mass_properties: RigidBodyAdditionalMassProps::default(),
             can_sleep: true,
             sleeping: false,
+            sleep_thresholds: None,
             ccd_enabled: false,
             soft_ccd_prediction: 0.0,
             dominance_group: 0,
@@ -1302,6 +1628,29 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the additional mass-properties of the rigid-body being built from its local-space
+    /// center of mass, mass, and principal angular inertia.
+    ///
+    /// This is equivalent to calling [`Self::additional_mass_properties`] with
+    /// `MassProperties::new(local_com, mass, principal_inertia)`. The angular inertia is
+    /// combined with the contributions of the attached colliders around their common center of
+    /// mass, so setting `local_com` to the center of mass of the attached colliders (e.g. the
+    /// origin for colliders centered on the rigid-body) overrides their angular inertia without
+    /// moving the center of mass. Attach colliders with a density equal to zero to fully
+    /// replace their contributions.
+    ///
+    /// A dynamic rigid-body with a total mass (resp. angular inertia) equal to zero isn’t affected
+    /// by forces, impulses, and contacts along the corresponding degrees of freedom, i.e., it
+    /// behaves as if its mass (resp. angular inertia) was infinite.
+    pub fn additional_mass_properties_local(
+        self,
+        local_com: Point<Real>,
+        mass: Real,
+        principal_inertia: AngVector<Real>,
+    ) -> Self {
+        self.additional_mass_properties(MassProperties::new(local_com, mass, principal_inertia))
+    }
+
     /// Sets the additional mass of the rigid-body being built.
     ///
     /// This will be overridden by a call to [`Self::additional_mass_properties`] so it only makes
@@ -1422,6 +1771,22 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the maximum linear velocity magnitude of the rigid-body to be created.
+    ///
+    /// See [`RigidBody::set_max_linvel`] for additional information.
+    pub fn max_linvel(mut self, max_linvel: Real) -> Self {
+        self.max_linvel = max_linvel;
+        self
+    }
+
+    /// Sets the maximum angular velocity magnitude of the rigid-body to be created.
+    ///
+    /// See [`RigidBody::set_max_angvel`] for additional information.
+    pub fn max_angvel(mut self, max_angvel: Real) -> Self {
+        self.max_angvel = max_angvel;
+        self
+    }
+
     /// Sets the initial linear velocity of the rigid-body to be created.
     pub fn linvel(mut self, linvel: Vector<Real>) -> Self {
         self.linvel = linvel;
@@ -1440,6 +1805,18 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the velocity thresholds below which the rigid-body to be created can fall asleep.
+    ///
+    /// This overrides the default [`RigidBodyActivation::normalized_linear_threshold`] (which is
+    /// multiplied by [`IntegrationParameters::length_unit`](crate::dynamics::IntegrationParameters::length_unit))
+    /// and [`RigidBodyActivation::angular_threshold`] of this rigid-body, e.g., to let small fast
+    /// parts fall asleep at lower speeds than large slow ones. This has no effect if
+    /// [`Self::can_sleep`] is set to `false`.
+    pub fn sleep_thresholds(mut self, normalized_linear: Real, angular: Real) -> Self {
+        self.sleep_thresholds = Some((normalized_linear, angular));
+        self
+    }
+
     /// Sets whether Continuous Collision-Detection is enabled for this rigid-body.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
@@ -1496,6 +1873,8 @@ impl RigidBodyBuilder {
         rb.mprops.flags = self.mprops_flags;
         rb.damping.linear_damping = self.linear_damping;
         rb.damping.angular_damping = self.angular_damping;
+        rb.max_linvel = self.max_linvel;
+        rb.max_angvel = self.max_angvel;
         rb.forces.gravity_scale = self.gravity_scale;
         rb.dominance = RigidBodyDominance(self.dominance_group);
         rb.enabled = self.enabled;
@@ -1506,6 +1885,11 @@ impl RigidBodyBuilder {
             rb.sleep();
         }
 
+        if let Some((normalized_linear, angular)) = self.sleep_thresholds {
+            rb.activation.normalized_linear_threshold = normalized_linear;
+            rb.activation.angular_threshold = angular;
+        }
+
         if !self.can_sleep {
             rb.activation.normalized_linear_threshold = -1.0;
             rb.activation.angular_threshold = -1.0;
@@ -1520,3 +1904,441 @@ impl From<RigidBodyBuilder> for RigidBody {
         val.build()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::na::RealField;
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+
+    #[test]
+    fn local_velocities_and_velocity_at_point() {
+        #[cfg(feature = "dim2")]
+        let angle = Real::frac_pi_2();
+        #[cfg(feature = "dim3")]
+        let angle = Vector::z() * Real::frac_pi_2();
+        #[cfg(feature = "dim2")]
+        let angvel = 2.0;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::z() * 2.0;
+
+        let rb = RigidBodyBuilder::dynamic()
+            .rotation(angle)
+            .linvel(Vector::x())
+            .angvel(angvel)
+            .build();
+
+        // The local x axis points toward the world y axis.
+        approx::assert_relative_eq!(rb.local_linvel(), -Vector::y(), epsilon = 1.0e-6);
+        #[cfg(feature = "dim2")]
+        approx::assert_relative_eq!(rb.local_angvel(), 2.0);
+        #[cfg(feature = "dim3")]
+        approx::assert_relative_eq!(rb.local_angvel(), Vector::z() * 2.0, epsilon = 1.0e-6);
+
+        // The center of mass is at the world origin, so a point one unit above it moves
+        // toward -x because of the counterclockwise rotation.
+        let point = Point::from(Vector::y());
+        approx::assert_relative_eq!(
+            rb.velocity_at_point(&point),
+            Vector::x() - Vector::x() * 2.0,
+            epsilon = 1.0e-6
+        );
+        approx::assert_relative_eq!(
+            rb.velocity_at_point(&Point::origin()),
+            Vector::x(),
+            epsilon = 1.0e-6
+        );
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn local_angvel_3d() {
+        let rb = RigidBodyBuilder::dynamic()
+            .rotation(Vector::z() * Real::frac_pi_2())
+            .angvel(Vector::x())
+            .build();
+        approx::assert_relative_eq!(rb.local_angvel(), -Vector::y(), epsilon = 1.0e-6);
+    }
+
+    #[test]
+    fn predict_position_matches_forceless_step() {
+        let mut world = TestWorld::new();
+
+        #[cfg(feature = "dim2")]
+        let angvel = 1.5;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::new(0.3, 1.5, -0.7);
+
+        // The second body has its linear velocity clamped by the solver.
+        let mut handles = vec![];
+        for (i, max_linvel) in [Real::MAX, 0.5].into_iter().enumerate() {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i as Real) * 10.0)
+                .rotation(angvel * 0.3)
+                .linvel(Vector::x() + Vector::y() * 2.0)
+                .angvel(angvel);
+            let handle = world.bodies.insert(rb);
+            world.bodies[handle].set_max_linvel(max_linvel);
+            // Offset the collider so the center of mass isn’t at the body’s origin.
+            let co = ColliderBuilder::ball(0.5).translation(Vector::y() * 0.25);
+            world
+                .colliders
+                .insert_with_parent(co, handle, &mut world.bodies);
+            handles.push(handle);
+        }
+
+        let predicted: Vec<_> = handles
+            .iter()
+            .map(|h| world.bodies[*h].predict_position(world.params.dt))
+            .collect();
+
+        world.step();
+
+        for (handle, predicted) in handles.iter().zip(predicted.iter()) {
+            approx::assert_relative_eq!(
+                world.bodies[*handle].position(),
+                predicted,
+                epsilon = 1.0e-5
+            );
+        }
+    }
+
+    #[test]
+    fn contact_response_uses_overridden_inertia() {
+        const MASS: Real = 2.0;
+        const INERTIA: Real = 5.0;
+
+        let mut world = TestWorld::new();
+
+        // The target’s collider has no mass so only the overridden mass-properties remain.
+        #[cfg(feature = "dim2")]
+        let principal_inertia = INERTIA;
+        #[cfg(feature = "dim3")]
+        let principal_inertia = Vector::repeat(INERTIA);
+        let rb = RigidBodyBuilder::dynamic().additional_mass_properties_local(
+            Point::origin(),
+            MASS,
+            principal_inertia,
+        );
+        let target = world.bodies.insert(rb);
+        let co = cuboid(0.5, 0.5);
+        let co = co.density(0.0).friction(0.0);
+        world
+            .colliders
+            .insert_with_parent(co, target, &mut world.bodies);
+
+        // A frictionless projectile hitting the target off-center, along the x axis.
+        let rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 1.5 + Vector::y() * 0.5)
+            .linvel(-Vector::x() * 2.0);
+        let co = ColliderBuilder::ball(0.5).friction(0.0);
+        world.insert_body(rb, co);
+
+        world.steps(30);
+
+        let target = &world.bodies[target];
+        #[cfg(feature = "dim2")]
+        let inv_inertia_sqrt = target.mprops.effective_world_inv_inertia_sqrt;
+        #[cfg(feature = "dim3")]
+        let inv_inertia_sqrt = target.mprops.effective_world_inv_inertia_sqrt.m33;
+        approx::assert_relative_eq!(inv_inertia_sqrt, 1.0 / INERTIA.sqrt(), epsilon = 1.0e-6);
+
+        // The angular momentum given to the target matches the moment of its linear momentum
+        // around its center of mass, for the overridden angular inertia.
+        let momentum = target.linvel() * MASS;
+        assert!(momentum.x < -0.1);
+        #[cfg(feature = "dim2")]
+        let angvel = target.angvel();
+        #[cfg(feature = "dim3")]
+        let angvel = target.angvel().z;
+        approx::assert_relative_eq!(angvel * INERTIA, -0.5 * momentum.x, epsilon = 1.0e-3);
+    }
+
+    #[test]
+    fn solver_disabled_body_holds_position() {
+        let mut world = TestWorld::with_gravity();
+
+        let (handle, _) = world.insert_ball(Vector::y() * 10.0, 0.5);
+
+        world.steps(10);
+        assert!(world.bodies[handle].linvel().y < 0.0);
+
+        // Freeze the body mid-fall.
+        world
+            .bodies
+            .get_mut(handle)
+            .unwrap()
+            .set_solver_enabled(false);
+        assert!(!world.bodies[handle].is_solver_enabled());
+        let frozen_pos = *world.bodies[handle].position();
+        world.steps(60);
+        assert_eq!(*world.bodies[handle].position(), frozen_pos);
+        assert_eq!(*world.bodies[handle].linvel(), Vector::zeros());
+
+        // Thaw it, it falls again.
+        world
+            .bodies
+            .get_mut(handle)
+            .unwrap()
+            .set_solver_enabled(true);
+        world.steps(10);
+        assert!(world.bodies[handle].linvel().y < -0.5);
+        assert!(world.bodies[handle].translation().y < frozen_pos.translation.y);
+    }
+
+    #[test]
+    fn per_body_sleep_thresholds() {
+        use crate::dynamics::RigidBodyActivation;
+
+        let mut world = TestWorld::new();
+
+        // Both bodies move at the same speed, below the default threshold.
+        let speed = RigidBodyActivation::default_normalized_linear_threshold() / 2.0;
+        let default_thresholds = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().linvel(Vector::x() * speed));
+        let low_thresholds = world.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 5.0)
+                .linvel(Vector::x() * speed)
+                .sleep_thresholds(
+                    speed / 2.0,
+                    RigidBodyActivation::default_angular_threshold(),
+                ),
+        );
+        for handle in [default_thresholds, low_thresholds] {
+            world.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5),
+                handle,
+                &mut world.bodies,
+            );
+        }
+
+        let time_until_sleep = RigidBodyActivation::default_time_until_sleep();
+        for _ in 0..((time_until_sleep / world.params.dt) as usize + 10) {
+            world.step();
+        }
+
+        assert!(world.bodies[default_thresholds].is_sleeping());
+        assert!(!world.bodies[low_thresholds].is_sleeping());
+        assert_eq!(world.bodies[low_thresholds].linvel().x, speed);
+    }
+
+    #[test]
+    fn local_forces_follow_body_orientation() {
+        use crate::na::RealField;
+
+        let mut world = TestWorld::new();
+
+        // The local x axis of the body points toward the world y axis.
+        #[cfg(feature = "dim2")]
+        let rotation = Real::frac_pi_2();
+        #[cfg(feature = "dim3")]
+        let rotation = Vector::z() * Real::frac_pi_2();
+        let (handle, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().rotation(rotation),
+            ColliderBuilder::ball(0.5),
+        );
+
+        let rb = &mut world.bodies[handle];
+        rb.add_force_local(Vector::x() * 2.0, true);
+        approx::assert_relative_eq!(rb.user_force(), Vector::y() * 2.0, epsilon = 1.0e-6);
+
+        // A local force along y, applied at a local point along x, turns the body
+        // counterclockwise, whatever its orientation.
+        rb.reset_forces(true);
+        rb.add_force_at_local_point(Vector::y(), Point::from(Vector::x()), true);
+        approx::assert_relative_eq!(rb.user_force(), -Vector::x(), epsilon = 1.0e-6);
+        #[cfg(feature = "dim2")]
+        approx::assert_relative_eq!(rb.user_torque(), 1.0, epsilon = 1.0e-6);
+        #[cfg(feature = "dim3")]
+        {
+            approx::assert_relative_eq!(rb.user_torque(), Vector::z(), epsilon = 1.0e-6);
+            rb.reset_torques(true);
+            rb.add_torque_local(Vector::x(), true);
+            approx::assert_relative_eq!(rb.user_torque(), Vector::y(), epsilon = 1.0e-6);
+        }
+
+        rb.reset_forces(true);
+        rb.reset_torques(true);
+        rb.add_force_local(Vector::x() * 2.0, true);
+        let mass = rb.mass();
+
+        world.step();
+
+        // The body accelerates along its rotated x axis.
+        approx::assert_relative_eq!(
+            *world.bodies[handle].linvel(),
+            Vector::y() * (2.0 / mass * world.params.dt),
+            epsilon = 1.0e-6
+        );
+    }
+
+    #[test]
+    fn lock_translation_axis_mid_flight() {
+        use crate::dynamics::LockedAxes;
+
+        let mut world = TestWorld::with_gravity();
+
+        let (handle, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().linvel(Vector::x()),
+            ColliderBuilder::ball(0.5),
+        );
+
+        world.steps(30);
+        assert!(world.bodies[handle].linvel().y < -1.0);
+
+        // Lock the vertical translation while the body is falling.
+        world.bodies[handle].set_locked_axes(LockedAxes::TRANSLATION_LOCKED_Y, true);
+        assert_eq!(world.bodies[handle].linvel().y, 0.0);
+        assert_eq!(
+            world.bodies[handle].mass_properties().effective_inv_mass.y,
+            0.0
+        );
+        let locked_y = world.bodies[handle].translation().y;
+        let locked_x = world.bodies[handle].translation().x;
+
+        world.steps(30);
+
+        // The body stopped falling, but still moves horizontally.
+        let rb = &world.bodies[handle];
+        assert_eq!(rb.translation().y, locked_y);
+        approx::assert_relative_eq!(rb.translation().x, locked_x + 0.5, epsilon = 1.0e-5);
+        assert!(rb.mass_properties().effective_inv_mass.x > 0.0);
+
+        // Unlocking it lets it fall again.
+        world.bodies[handle].set_locked_axes(LockedAxes::empty(), true);
+        world.step();
+        assert!(world.bodies[handle].translation().y < locked_y);
+    }
+
+    #[test]
+    fn self_collision_between_allowed_colliders() {
+        let mut world = TestWorld::new();
+
+        let body = world.bodies.insert(RigidBodyBuilder::dynamic());
+        let [c1, c2, c3] = [-0.5, 0.5, 1.5].map(|x| {
+            let co = ColliderBuilder::ball(0.6).translation(Vector::x() * x);
+            world
+                .colliders
+                .insert_with_parent(co, body, &mut world.bodies)
+        });
+
+        let rb = world.bodies.get_mut(body).unwrap();
+        rb.enable_self_collision(true);
+        rb.allow_self_collision(c2, c1);
+        assert!(rb.is_self_collision_allowed(c1, c2));
+        assert!(!rb.is_self_collision_allowed(c2, c3));
+
+        world.gravity = Vector::y() * -9.81;
+        world.steps(10);
+
+        // Only the allowed pair generates contacts.
+        assert!(
+            world
+                .narrow_phase
+                .contact_pair(c1, c2)
+                .unwrap()
+                .has_any_active_contact
+        );
+        assert!(!world
+            .narrow_phase
+            .contact_pair(c2, c3)
+            .map(|pair| pair.has_any_active_contact)
+            .unwrap_or(false));
+
+        // Self-contacts don’t apply any impulse: the body is in free fall.
+        let rb = &world.bodies[body];
+        assert!((rb.linvel() - world.gravity * world.params.dt * 10.0).norm() < 1.0e-5);
+        assert!(num::Zero::is_zero(&rb.vels.angvel));
+
+        // Removing the collider clears it from the allowed pairs.
+        world
+            .colliders
+            .remove(c1, &mut world.islands, &mut world.bodies, true);
+        assert!(world.bodies[body].self_collision_pairs().is_empty());
+    }
+
+    #[test]
+    fn max_velocities() {
+        let mut world = TestWorld::new();
+
+        world.insert_body(RigidBodyBuilder::fixed(), cuboid(10.0, 1.0));
+
+        // Two balls deeply penetrating the floor, pushed out by the contact solver.
+        let (capped, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -5.0 + Vector::y() * 0.6)
+                .max_linvel(1.0),
+            ColliderBuilder::ball(0.5),
+        );
+        let (free, _) = world.insert_ball(Vector::x() * 5.0 + Vector::y() * 0.6, 0.5);
+
+        // A spinning ball, away from the floor.
+        #[cfg(feature = "dim2")]
+        let angvel = 10.0;
+        #[cfg(feature = "dim3")]
+        let angvel = Vector::repeat(10.0);
+        let (spinning, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 10.0)
+                .angvel(angvel)
+                .max_angvel(2.0),
+            ColliderBuilder::ball(0.5),
+        );
+
+        let mut max_free_speed: Real = 0.0;
+        for _ in 0..10 {
+            let capped_pos = *world.bodies[capped].translation();
+            let free_pos = *world.bodies[free].translation();
+
+            world.step();
+
+            // The velocity used for integrating the position is capped too.
+            let capped_speed =
+                (world.bodies[capped].translation() - capped_pos).norm() / world.params.dt;
+            assert!(capped_speed <= 1.0 + 1.0e-5);
+            assert!(world.bodies[capped].linvel().norm() <= 1.0 + 1.0e-5);
+            let free_speed = (world.bodies[free].translation() - free_pos).norm() / world.params.dt;
+            max_free_speed = max_free_speed.max(free_speed);
+
+            #[cfg(feature = "dim2")]
+            let angvel = world.bodies[spinning].angvel().abs();
+            #[cfg(feature = "dim3")]
+            let angvel = world.bodies[spinning].angvel().norm();
+            assert!(angvel <= 2.0 + 1.0e-5);
+        }
+
+        // Make sure the cap actually had an effect.
+        assert!(max_free_speed > 2.0);
+        assert!(world.bodies[capped].translation().y > 0.6);
+    }
+
+    #[test]
+    fn kinematic_velocity_based_piston_pushes_box() {
+        let mut world = TestWorld::new();
+
+        let piston_vel = Vector::x() * 2.0;
+        let (piston, _) = world.insert_body(
+            RigidBodyBuilder::kinematic_velocity_based().linvel(piston_vel),
+            cuboid(0.5, 0.5).friction(0.0),
+        );
+        let (cube, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::x() * 1.5),
+            cuboid(0.5, 0.5).friction(0.0),
+        );
+
+        for _ in 0..60 {
+            world.step();
+
+            // The piston isn’t affected by the contact.
+            assert_eq!(*world.bodies[piston].linvel(), piston_vel);
+        }
+
+        // The box was pushed by the piston and stays ahead of it.
+        assert!(world.bodies[cube].linvel().x >= piston_vel.x - 1.0e-3);
+        assert!(world.bodies[cube].translation().x > world.bodies[piston].translation().x + 0.99);
+    }
+}
@@ -265,6 +265,10 @@ pub struct RigidBodyMassProps {
     /// The square-root of the world-space inverse angular inertia tensor of the rigid-body,
     /// taking into account rotation locking.
     pub effective_world_inv_inertia_sqrt: AngularInertia<Real>,
+    /// Is the rigid-body taken into account by the constraints solver?
+    ///
+    /// If `false`, the effective inverse mass and inertia are set to zero.
+    pub solver_enabled: bool,
 }
 
 impl Default for RigidBodyMassProps {
@@ -276,6 +280,7 @@ impl Default for RigidBodyMassProps {
             world_com: Point::origin(),
             effective_inv_mass: Vector::zero(),
             effective_world_inv_inertia_sqrt: AngularInertia::zero(),
+            solver_enabled: true,
         }
     }
 }
@@ -409,6 +414,13 @@ impl RigidBodyMassProps {
         self.effective_world_inv_inertia_sqrt =
             self.local_mprops.world_inv_inertia_sqrt(&position.rotation);
 
+        if !self.solver_enabled {
+            // The rigid-body behaves as if it had an infinite mass.
+            self.effective_inv_mass = Vector::zeros();
+            self.effective_world_inv_inertia_sqrt = AngularInertia::zero();
+            return;
+        }
+
         // Take into account translation/rotation locking.
         if self.flags.contains(LockedAxes::TRANSLATION_LOCKED_X) {
             self.effective_inv_mass.x = 0.0;
@@ -616,6 +628,40 @@ impl RigidBodyVelocity {
         result
     }
 
+    /// The angular velocity of this rigid-body after applying the gyroscopic torque during a
+    /// timestep of length `dt`.
+    ///
+    /// The torque is integrated implicitly (with a single Newton iteration) so that fast-spinning
+    /// bodies don’t gain energy. This has no effect in 2D.
+    #[must_use]
+    pub fn angvel_with_gyroscopic_forces(
+        &self,
+        rb_mprops: &RigidBodyMassProps,
+        dt: Real,
+    ) -> AngVector<Real> {
+        #[cfg(feature = "dim2")]
+        {
+            let _ = (rb_mprops, dt);
+            self.angvel
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            use crate::utils::SimdCrossMatrix;
+
+            let inertia = rb_mprops.effective_angular_inertia().into_matrix();
+            let momentum = inertia * self.angvel;
+            let residual = self.angvel.cross(&momentum) * dt;
+            let jacobian =
+                inertia + (self.angvel.gcross_matrix() * inertia - momentum.gcross_matrix()) * dt;
+
+            jacobian
+                .try_inverse()
+                .map(|inv_jacobian| self.angvel - inv_jacobian * residual)
+                .unwrap_or(self.angvel)
+        }
+    }
+
     /// Are these velocities exactly equal to zero?
     #[must_use]
     pub fn is_zero(&self) -> bool {
@@ -3,6 +3,8 @@ use crate::dynamics::{
     ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBody, RigidBodyChanges, RigidBodyHandle,
 };
 use crate::geometry::ColliderSet;
+use crate::math::{Point, Real, Vector};
+use num::Zero;
 use std::ops::{Index, IndexMut};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -32,6 +34,9 @@ pub struct RigidBodySet {
     // Could we avoid this?
     pub(crate) bodies: Arena<RigidBody>,
     pub(crate) modified_bodies: Vec<RigidBodyHandle>,
+    // The bodies inserted with `insert_settled`, which still need to be settled by the physics
+    // pipeline.
+    pub(crate) bodies_to_settle: Vec<RigidBodyHandle>,
 }
 
 impl RigidBodySet {
@@ -40,6 +45,7 @@ impl RigidBodySet {
         RigidBodySet {
             bodies: Arena::new(),
             modified_bodies: Vec::new(),
+            bodies_to_settle: Vec::new(),
         }
     }
 
@@ -48,6 +54,7 @@ impl RigidBodySet {
         RigidBodySet {
             bodies: Arena::with_capacity(capacity),
             modified_bodies: Vec::with_capacity(capacity),
+            bodies_to_settle: Vec::new(),
         }
     }
 
@@ -83,6 +90,37 @@ impl RigidBodySet {
         handle
     }
 
+    /// Insert a rigid body that starts resting on the rigid-body `reference`, and retrieve its handle.
+    ///
+    /// This reduces the number of timesteps a body spawned on top of a resting body (e.g. a box
+    /// added to a stack) needs to settle:
+    /// - The new body starts with the velocity of the point of `reference` located at its
+    ///   position, so it moves along with it.
+    /// - At the next timestep, the new body is simulated alone against its contacts for a few
+    ///   timesteps, with all the other bodies considered fixed, before the actual simulation
+    ///   step. The new body is moved to the position it settled to, and its contacts are
+    ///   warmstarted with the impulses found by this local simulation instead of zero.
+    ///
+    /// The colliders of the new body must be inserted before the next timestep for it to be
+    /// settled. If `reference` doesn’t exist, the new body starts with its own velocity.
+    pub fn insert_settled(
+        &mut self,
+        rb: impl Into<RigidBody>,
+        reference: RigidBodyHandle,
+    ) -> RigidBodyHandle {
+        let mut rb = rb.into();
+
+        if let Some(reference) = self.get(reference) {
+            let point = Point::from(rb.pos.position.translation.vector);
+            rb.vels.linvel = reference.velocity_at_point(&point);
+            rb.vels.angvel = reference.vels.angvel;
+        }
+
+        let handle = self.insert(rb);
+        self.bodies_to_settle.push(handle);
+        handle
+    }
+
     /// Removes a rigid-body, and all its attached colliders and impulse_joints, from these sets.
     pub fn remove(
         &mut self,
@@ -123,6 +161,42 @@ impl RigidBodySet {
         Some(rb)
     }
 
+    /// Removes several rigid-bodies, and all their attached colliders and joints, from these sets.
+    ///
+    /// The rigid-bodies are removed by increasing index, independently from the order of
+    /// `handles`. Because the slots freed by the removals are reused by the next insertions,
+    /// this ensures that the handles of the rigid-bodies inserted afterward only depend on the
+    /// set of removed rigid-bodies, e.g., for determinism when `handles` are collected from a
+    /// `HashSet`. See [`Self::remove`] for details on the other parameters.
+    ///
+    /// Returns the removed rigid-bodies, by increasing index. Handles of rigid-bodies that
+    /// aren’t part of this set are ignored.
+    pub fn remove_batch(
+        &mut self,
+        handles: &[RigidBodyHandle],
+        islands: &mut IslandManager,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        remove_attached_colliders: bool,
+    ) -> Vec<RigidBody> {
+        let mut handles = handles.to_vec();
+        handles.sort_unstable_by_key(|handle| handle.into_raw_parts());
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                self.remove(
+                    handle,
+                    islands,
+                    colliders,
+                    impulse_joints,
+                    multibody_joints,
+                    remove_attached_colliders,
+                )
+            })
+            .collect()
+    }
+
     /// Gets the rigid-body with the given handle without a known generation.
     ///
     /// This is useful when you know you want the rigid-body at position `i` but
@@ -198,11 +272,56 @@ impl RigidBodySet {
         Some(result)
     }
 
+    /// Applies a batch of impulses, each at a world-space point, to the rigid-bodies of this set.
+    ///
+    /// All the impulses are applied right away, in the order they are given, before any
+    /// simulation step happens. Each rigid-body receiving a non-zero impulse is woken up and
+    /// marked as modified, like with [`RigidBody::apply_impulse_at_point`].
+    ///
+    /// Like [`RigidBody::apply_impulse_at_point`], this does nothing on non-dynamic bodies.
+    ///
+    /// Returns the handles from `impulses` that don’t match any rigid-body of this set.
+    pub fn apply_impulses(
+        &mut self,
+        impulses: &[(RigidBodyHandle, Vector<Real>, Point<Real>)],
+    ) -> Vec<RigidBodyHandle> {
+        let mut invalid_handles = vec![];
+
+        for (handle, impulse, point) in impulses {
+            if let Some(rb) = self.bodies.get_mut(handle.0) {
+                if rb.is_dynamic() && !impulse.is_zero() {
+                    rb.apply_impulse_at_point(*impulse, *point, true);
+                    Self::mark_as_modified(*handle, rb, &mut self.modified_bodies);
+                }
+            } else {
+                invalid_handles.push(*handle);
+            }
+        }
+
+        invalid_handles
+    }
+
     /// Iterates through all the rigid-bodies on this set.
     pub fn iter(&self) -> impl Iterator<Item = (RigidBodyHandle, &RigidBody)> {
         self.bodies.iter().map(|(h, b)| (RigidBodyHandle(h), b))
     }
 
+    /// Iterates through the awake dynamic rigid-bodies of this set.
+    ///
+    /// This relies on the set of active dynamic bodies maintained by `islands` (and used by the
+    /// constraints solver), so the bodies are yielded in the solver’s order. Bodies that were
+    /// put to sleep or removed since the last timestep are skipped.
+    pub fn iter_active_dynamic<'a>(
+        &'a self,
+        islands: &'a IslandManager,
+    ) -> impl Iterator<Item = (RigidBodyHandle, &'a RigidBody)> {
+        islands
+            .active_dynamic_bodies()
+            .iter()
+            .filter_map(|handle| Some((*handle, self.get(*handle)?)))
+            .filter(|(_, rb)| rb.is_dynamic() && !rb.is_sleeping())
+    }
+
     /// Iterates mutably through all the rigid-bodies on this set.
     #[cfg(not(feature = "dev-remove-slow-accessors"))]
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (RigidBodyHandle, &mut RigidBody)> {
@@ -259,3 +378,198 @@ impl IndexMut<RigidBodyHandle> for RigidBodySet {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::{cuboid, floor, TestWorld};
+
+    #[test]
+    fn apply_impulses_wakes_up_bodies() {
+        let mut world = TestWorld::new();
+
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * (i as Real) * 10.0)
+                    .sleeping(true);
+                world.insert_body(rb, ColliderBuilder::ball(0.5)).0
+            })
+            .collect();
+        world.bodies.remove(
+            handles[2],
+            &mut world.islands,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            true,
+        );
+
+        let impulse = Vector::y() * 2.0;
+        let invalid = world.bodies.apply_impulses(
+            &handles
+                .iter()
+                .map(|h| (*h, impulse, Point::origin() + Vector::x() * 100.0))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(invalid, vec![handles[2]]);
+
+        world.step();
+
+        for handle in &handles[..2] {
+            let rb = &world.bodies[*handle];
+            assert!(!rb.is_sleeping());
+            assert!(rb.translation().y > 0.0);
+            approx::assert_relative_eq!(rb.linvel().y, impulse.y / rb.mass(), epsilon = 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn remove_batch_reuses_slots_deterministically() {
+        // Removes the same bodies and colliders, given in `order`, and returns the handles of
+        // the bodies and colliders inserted afterward.
+        let reinserted_handles = |order: &[usize]| {
+            let mut world = TestWorld::new();
+
+            let body_handles: Vec<_> = (0..10)
+                .map(|_| world.bodies.insert(RigidBodyBuilder::dynamic()))
+                .collect();
+            let collider_handles: Vec<_> = (0..10)
+                .map(|_| world.colliders.insert(ColliderBuilder::ball(0.5)))
+                .collect();
+
+            let to_remove: Vec<_> = order.iter().map(|i| body_handles[*i]).collect();
+            let removed = world.bodies.remove_batch(
+                &to_remove,
+                &mut world.islands,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                true,
+            );
+            assert_eq!(removed.len(), order.len());
+
+            let to_remove: Vec<_> = order.iter().map(|i| collider_handles[*i]).collect();
+            let removed = world.colliders.remove_batch(
+                &to_remove,
+                &mut world.islands,
+                &mut world.bodies,
+                true,
+            );
+            assert_eq!(removed.len(), order.len());
+
+            let new_bodies: Vec<_> = (0..order.len())
+                .map(|_| world.bodies.insert(RigidBodyBuilder::dynamic()))
+                .collect();
+            let new_colliders: Vec<_> = (0..order.len())
+                .map(|_| world.colliders.insert(ColliderBuilder::ball(0.5)))
+                .collect();
+            (new_bodies, new_colliders)
+        };
+
+        let (bodies1, colliders1) = reinserted_handles(&[7, 2, 5, 3]);
+        let (bodies2, colliders2) = reinserted_handles(&[3, 5, 7, 2]);
+        assert_eq!(bodies1, bodies2);
+        assert_eq!(colliders1, colliders2);
+
+        // The last slot freed, i.e., the one with the largest index, is reused first.
+        let indices: Vec<_> = bodies1.iter().map(|h| h.into_raw_parts().0).collect();
+        assert_eq!(indices, [7, 5, 3, 2]);
+        let indices: Vec<_> = colliders1.iter().map(|h| h.into_raw_parts().0).collect();
+        assert_eq!(indices, [7, 5, 3, 2]);
+    }
+
+    #[test]
+    fn iter_active_dynamic_skips_sleeping_and_non_dynamic_bodies() {
+        let mut world = TestWorld::new();
+
+        world.bodies.insert(RigidBodyBuilder::fixed());
+        world
+            .bodies
+            .insert(RigidBodyBuilder::kinematic_velocity_based().linvel(Vector::x()));
+        let dynamic: Vec<_> = (0..4)
+            .map(|i| {
+                world.bodies.insert(
+                    RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * (i as Real * 3.0))
+                        .linvel(Vector::y()),
+                )
+            })
+            .collect();
+
+        world.step();
+        let mut active: Vec<_> = world
+            .bodies
+            .iter_active_dynamic(&world.islands)
+            .map(|e| e.0)
+            .collect();
+        active.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(active, dynamic);
+
+        // Sleeping bodies are skipped, even before the next step updates the active set.
+        for handle in [dynamic[1], dynamic[3]] {
+            world.bodies.get_mut(handle).unwrap().sleep();
+        }
+        let active: Vec<_> = world
+            .bodies
+            .iter_active_dynamic(&world.islands)
+            .map(|e| e.0)
+            .collect();
+        assert_eq!(active.len(), 2);
+
+        world.step();
+        let mut active: Vec<_> = world
+            .bodies
+            .iter_active_dynamic(&world.islands)
+            .map(|e| e.0)
+            .collect();
+        active.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(active, vec![dynamic[0], dynamic[2]]);
+        assert_eq!(world.islands.active_dynamic_bodies().len(), 2);
+    }
+
+    #[test]
+    fn insert_settled_reduces_settle_time() {
+        // Returns the number of timesteps needed for a box spawned on top of a resting box to
+        // stop moving.
+        let settle_steps = |settled: bool| {
+            let mut world = TestWorld::with_gravity();
+
+            world.colliders.insert(floor());
+
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.49)
+                .can_sleep(false);
+            let (bottom, _) = world.insert_body(rb, cuboid(0.5, 0.5));
+
+            world.steps(100);
+
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(world.bodies[bottom].translation() + Vector::y() * 1.0)
+                .can_sleep(false);
+            let top = if settled {
+                world.bodies.insert_settled(rb, bottom)
+            } else {
+                world.bodies.insert(rb)
+            };
+            world
+                .colliders
+                .insert_with_parent(cuboid(0.5, 0.5), top, &mut world.bodies);
+
+            let mut num_steps = 0;
+            for i in 1..=100 {
+                world.step();
+                if world.bodies[top].linvel().norm() > 1.0e-3 {
+                    num_steps = i;
+                }
+            }
+            num_steps
+        };
+
+        let default_steps = settle_steps(false);
+        let settled_steps = settle_steps(true);
+        assert!(settled_steps < default_steps);
+    }
+}
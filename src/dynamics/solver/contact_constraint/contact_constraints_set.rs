@@ -6,13 +6,16 @@ use crate::dynamics::solver::contact_constraint::{
 };
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::solver::solver_vel::SolverVel;
+#[cfg(feature = "graph-coloring")]
+use crate::dynamics::solver::ColoredSolve;
 use crate::dynamics::solver::{reset_buffer, ConstraintTypes, SolverConstraintsSet};
 use crate::dynamics::{
     ImpulseJoint, IntegrationParameters, IslandManager, JointAxesMask, MultibodyJointSet,
-    RigidBodySet,
+    RigidBodyHandle, RigidBodySet,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
-use crate::math::{Real, MAX_MANIFOLD_POINTS};
+use crate::math::Real;
+use crate::pipeline::ContactConstraintRow;
 use na::DVector;
 use parry::math::DIM;
 
@@ -23,6 +26,7 @@ use {
         TwoBodyConstraintSimd,
     },
     crate::math::SIMD_WIDTH,
+    simba::simd::SimdValue,
 };
 
 #[derive(Debug)]
@@ -34,10 +38,8 @@ pub struct ConstraintsCounts {
 
 impl ConstraintsCounts {
     pub fn from_contacts(manifold: &ContactManifold) -> Self {
-        let rest = manifold.data.solver_contacts.len() % MAX_MANIFOLD_POINTS != 0;
         Self {
-            num_constraints: manifold.data.solver_contacts.len() / MAX_MANIFOLD_POINTS
-                + rest as usize,
+            num_constraints: manifold.data.num_constraint_blocks(),
             num_jacobian_lines: manifold.data.solver_contacts.len() * DIM,
         }
     }
@@ -97,6 +99,7 @@ impl ContactConstraintsSet {
         multibody_joints: &MultibodyJointSet,
         manifolds: &[&mut ContactManifold],
         manifold_indices: &[ContactManifoldIndex],
+        force_scalar_solver: bool,
     ) {
         self.two_body_interactions.clear();
         self.one_body_interactions.clear();
@@ -115,6 +118,16 @@ impl ContactConstraintsSet {
         );
 
         self.interaction_groups.clear_groups();
+        self.one_body_interaction_groups.clear_groups();
+
+        if force_scalar_solver {
+            self.interaction_groups
+                .skip_grouping(&self.two_body_interactions);
+            self.one_body_interaction_groups
+                .skip_grouping(&self.one_body_interactions);
+            return;
+        }
+
         self.interaction_groups.group_manifolds(
             island_id,
             islands,
@@ -123,7 +136,6 @@ impl ContactConstraintsSet {
             &self.two_body_interactions,
         );
 
-        self.one_body_interaction_groups.clear_groups();
         self.one_body_interaction_groups.group_manifolds(
             island_id,
             islands,
@@ -143,12 +155,14 @@ impl ContactConstraintsSet {
 
     pub fn init(
         &mut self,
+        params: &IntegrationParameters,
         island_id: usize,
         islands: &IslandManager,
         bodies: &RigidBodySet,
         multibody_joints: &MultibodyJointSet,
         manifolds: &[&mut ContactManifold],
         manifold_indices: &[ContactManifoldIndex],
+        force_scalar_solver: bool,
     ) {
         self.clear_constraints();
         self.clear_builders();
@@ -160,33 +174,61 @@ impl ContactConstraintsSet {
             multibody_joints,
             manifolds,
             manifold_indices,
+            force_scalar_solver,
         );
 
         let mut jacobian_id = 0;
 
         #[cfg(feature = "simd-is-enabled")]
         {
-            self.simd_compute_constraints(bodies, manifolds);
+            self.simd_compute_constraints(params, bodies, manifolds);
         }
-        self.compute_constraints(bodies, manifolds);
-        self.compute_generic_constraints(bodies, multibody_joints, manifolds, &mut jacobian_id);
+        self.compute_constraints(params, bodies, manifolds);
+        self.compute_generic_constraints(
+            params,
+            bodies,
+            multibody_joints,
+            manifolds,
+            &mut jacobian_id,
+        );
 
         #[cfg(feature = "simd-is-enabled")]
         {
-            self.simd_compute_one_body_constraints(bodies, manifolds);
+            self.simd_compute_one_body_constraints(params, bodies, manifolds);
         }
-        self.compute_one_body_constraints(bodies, manifolds);
+        self.compute_one_body_constraints(params, bodies, manifolds);
         self.compute_generic_one_body_constraints(
+            params,
             bodies,
             multibody_joints,
             manifolds,
             &mut jacobian_id,
         );
+
+        #[cfg(feature = "graph-coloring")]
+        {
+            let mut coloring = std::mem::take(&mut self.coloring);
+            coloring.color(islands.active_island(island_id).len(), self);
+            self.coloring = coloring;
+        }
+    }
+
+    #[cfg(feature = "graph-coloring")]
+    fn solve_colored(
+        &mut self,
+        solve: ColoredSolve,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        let coloring = std::mem::take(&mut self.coloring);
+        coloring.solve(solve, self, solver_vels, generic_solver_vels);
+        self.coloring = coloring;
     }
 
     #[cfg(feature = "simd-is-enabled")]
     fn simd_compute_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         manifolds_all: &[&mut ContactManifold],
     ) {
@@ -220,6 +262,7 @@ impl ContactConstraintsSet {
             TwoBodyConstraintBuilderSimd::generate(
                 manifold_id,
                 manifolds,
+                params,
                 bodies,
                 &mut self.simd_velocity_constraints_builder[curr_start..],
                 &mut self.simd_velocity_constraints[curr_start..],
@@ -233,6 +276,7 @@ impl ContactConstraintsSet {
 
     fn compute_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         manifolds_all: &[&mut ContactManifold],
     ) {
@@ -260,6 +304,7 @@ impl ContactConstraintsSet {
             TwoBodyConstraintBuilder::generate(
                 *manifold_i,
                 manifold,
+                params,
                 bodies,
                 &mut self.velocity_constraints_builder[curr_start..],
                 &mut self.velocity_constraints[curr_start..],
@@ -272,6 +317,7 @@ impl ContactConstraintsSet {
 
     fn compute_generic_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         multibody_joints: &MultibodyJointSet,
         manifolds_all: &[&mut ContactManifold],
@@ -299,6 +345,7 @@ impl ContactConstraintsSet {
             GenericTwoBodyConstraintBuilder::generate(
                 *manifold_i,
                 manifold,
+                params,
                 bodies,
                 multibody_joints,
                 &mut self.generic_velocity_constraints_builder[curr_start..],
@@ -315,6 +362,7 @@ impl ContactConstraintsSet {
 
     fn compute_generic_one_body_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         multibody_joints: &MultibodyJointSet,
         manifolds_all: &[&mut ContactManifold],
@@ -341,6 +389,7 @@ impl ContactConstraintsSet {
             GenericOneBodyConstraintBuilder::generate(
                 *manifold_i,
                 manifold,
+                params,
                 bodies,
                 multibody_joints,
                 &mut self.generic_velocity_one_body_constraints_builder[curr_start..],
@@ -357,6 +406,7 @@ impl ContactConstraintsSet {
     #[cfg(feature = "simd-is-enabled")]
     fn simd_compute_one_body_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         manifolds_all: &[&mut ContactManifold],
     ) {
@@ -392,6 +442,7 @@ impl ContactConstraintsSet {
             SimdOneBodyConstraintBuilder::generate(
                 manifold_id,
                 manifolds,
+                params,
                 bodies,
                 &mut self.simd_velocity_one_body_constraints_builder[curr_start..],
                 &mut self.simd_velocity_one_body_constraints[curr_start..],
@@ -403,6 +454,7 @@ impl ContactConstraintsSet {
 
     fn compute_one_body_constraints(
         &mut self,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         manifolds_all: &[&mut ContactManifold],
     ) {
@@ -433,6 +485,7 @@ impl ContactConstraintsSet {
             OneBodyConstraintBuilder::generate(
                 *manifold_i,
                 manifold,
+                params,
                 bodies,
                 &mut self.velocity_one_body_constraints_builder[curr_start..],
                 &mut self.velocity_one_body_constraints[curr_start..],
@@ -448,9 +501,15 @@ impl ContactConstraintsSet {
         solver_vels: &mut [SolverVel<Real>],
         generic_solver_vels: &mut DVector<Real>,
     ) {
-        let (jac, constraints) = self.iter_constraints_mut();
-        for mut c in constraints {
-            c.warmstart(jac, solver_vels, generic_solver_vels);
+        #[cfg(feature = "graph-coloring")]
+        self.solve_colored(ColoredSolve::Warmstart, solver_vels, generic_solver_vels);
+
+        #[cfg(not(feature = "graph-coloring"))]
+        {
+            let (jac, constraints) = self.iter_constraints_mut();
+            for mut c in constraints {
+                c.warmstart(jac, solver_vels, generic_solver_vels);
+            }
         }
     }
 
@@ -459,9 +518,15 @@ impl ContactConstraintsSet {
         solver_vels: &mut [SolverVel<Real>],
         generic_solver_vels: &mut DVector<Real>,
     ) {
-        let (jac, constraints) = self.iter_constraints_mut();
-        for mut c in constraints {
-            c.solve_restitution(jac, solver_vels, generic_solver_vels);
+        #[cfg(feature = "graph-coloring")]
+        self.solve_colored(ColoredSolve::Restitution, solver_vels, generic_solver_vels);
+
+        #[cfg(not(feature = "graph-coloring"))]
+        {
+            let (jac, constraints) = self.iter_constraints_mut();
+            for mut c in constraints {
+                c.solve_restitution(jac, solver_vels, generic_solver_vels);
+            }
         }
     }
 
@@ -470,10 +535,20 @@ impl ContactConstraintsSet {
         solver_vels: &mut [SolverVel<Real>],
         generic_solver_vels: &mut DVector<Real>,
     ) {
-        let (jac, constraints) = self.iter_constraints_mut();
-        for mut c in constraints {
-            c.remove_bias();
-            c.solve_restitution(jac, solver_vels, generic_solver_vels);
+        #[cfg(feature = "graph-coloring")]
+        self.solve_colored(
+            ColoredSolve::RestitutionWithoutBias,
+            solver_vels,
+            generic_solver_vels,
+        );
+
+        #[cfg(not(feature = "graph-coloring"))]
+        {
+            let (jac, constraints) = self.iter_constraints_mut();
+            for mut c in constraints {
+                c.remove_bias();
+                c.solve_restitution(jac, solver_vels, generic_solver_vels);
+            }
         }
     }
 
@@ -482,9 +557,15 @@ impl ContactConstraintsSet {
         solver_vels: &mut [SolverVel<Real>],
         generic_solver_vels: &mut DVector<Real>,
     ) {
-        let (jac, constraints) = self.iter_constraints_mut();
-        for mut c in constraints {
-            c.solve_friction(jac, solver_vels, generic_solver_vels);
+        #[cfg(feature = "graph-coloring")]
+        self.solve_colored(ColoredSolve::Friction, solver_vels, generic_solver_vels);
+
+        #[cfg(not(feature = "graph-coloring"))]
+        {
+            let (jac, constraints) = self.iter_constraints_mut();
+            for mut c in constraints {
+                c.solve_friction(jac, solver_vels, generic_solver_vels);
+            }
         }
     }
 
@@ -538,4 +619,76 @@ impl ContactConstraintsSet {
             simd_velocity_one_body_constraints
         );
     }
+
+    /// Appends the non-penetration rows of the contact constraints not involving any multibody
+    /// to `out`.
+    ///
+    /// The solver velocity index of each body is its index in `bodies`.
+    pub fn export_normal_rows(
+        &self,
+        bodies: &[RigidBodyHandle],
+        out: &mut Vec<ContactConstraintRow>,
+    ) {
+        for constraint in &self.velocity_constraints {
+            for element in &constraint.elements[..constraint.num_contacts as usize] {
+                out.push(ContactConstraintRow {
+                    body1: Some(bodies[constraint.solver_vel1]),
+                    body2: bodies[constraint.solver_vel2],
+                    dir1: constraint.dir1,
+                    gcross1: element.normal_part.gcross1,
+                    gcross2: element.normal_part.gcross2,
+                    r: element.normal_part.r,
+                    rhs: element.normal_part.rhs,
+                });
+            }
+        }
+
+        for constraint in &self.velocity_one_body_constraints {
+            for element in &constraint.elements[..constraint.num_contacts as usize] {
+                out.push(ContactConstraintRow {
+                    body1: None,
+                    body2: bodies[constraint.solver_vel2],
+                    dir1: constraint.dir1,
+                    gcross1: na::zero(),
+                    gcross2: element.normal_part.gcross2,
+                    r: element.normal_part.r,
+                    rhs: element.normal_part.rhs,
+                });
+            }
+        }
+
+        #[cfg(feature = "simd-is-enabled")]
+        for constraint in &self.simd_velocity_constraints {
+            for ii in 0..SIMD_WIDTH {
+                for element in &constraint.elements[..constraint.num_contacts as usize] {
+                    out.push(ContactConstraintRow {
+                        body1: Some(bodies[constraint.solver_vel1[ii]]),
+                        body2: bodies[constraint.solver_vel2[ii]],
+                        dir1: constraint.dir1.extract(ii),
+                        gcross1: element.normal_part.gcross1.extract(ii),
+                        gcross2: element.normal_part.gcross2.extract(ii),
+                        r: element.normal_part.r.extract(ii),
+                        rhs: element.normal_part.rhs.extract(ii),
+                    });
+                }
+            }
+        }
+
+        #[cfg(feature = "simd-is-enabled")]
+        for constraint in &self.simd_velocity_one_body_constraints {
+            for ii in 0..SIMD_WIDTH {
+                for element in &constraint.elements[..constraint.num_contacts as usize] {
+                    out.push(ContactConstraintRow {
+                        body1: None,
+                        body2: bodies[constraint.solver_vel2[ii]],
+                        dir1: constraint.dir1.extract(ii),
+                        gcross1: na::zero(),
+                        gcross2: element.normal_part.gcross2.extract(ii),
+                        r: element.normal_part.r.extract(ii),
+                        rhs: element.normal_part.rhs.extract(ii),
+                    });
+                }
+            }
+        }
+    }
 }
@@ -6,7 +6,7 @@ use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Point, Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::SimdCross;
 
-use super::{OneBodyConstraintElement, OneBodyConstraintNormalPart};
+use super::{OneBodyConstraintElement, OneBodyConstraintNormalPart, OneBodyConstraintRollingPart};
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::solver::{ContactPointInfos, OneBodyConstraintBuilder};
 #[cfg(feature = "dim2")]
@@ -32,6 +32,7 @@ impl GenericOneBodyConstraintBuilder {
     pub fn generate(
         manifold_id: ContactManifoldIndex,
         manifold: &ContactManifold,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         multibodies: &MultibodyJointSet,
         out_builders: &mut [GenericOneBodyConstraintBuilder],
@@ -42,6 +43,7 @@ impl GenericOneBodyConstraintBuilder {
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
         let flipped = manifold.data.relative_dominance < 0;
+        let cfm_gain = manifold.data.contact_model.cfm_gain(params);
 
         let (force_dir1, flipped_multiplier) = if flipped {
             std::mem::swap(&mut handle1, &mut handle2);
@@ -71,8 +73,12 @@ impl GenericOneBodyConstraintBuilder {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction(),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let multibodies_ndof = mb2.ndofs();
         // For each solver contact we generate DIM constraints, and each constraints appends
@@ -97,12 +103,16 @@ impl GenericOneBodyConstraintBuilder {
 
             builder.inner.rb1 = rb1;
             builder.inner.vels1 = vels1;
+            builder.inner.contact_model = manifold.data.contact_model;
 
             constraint.inner.dir1 = force_dir1;
             constraint.inner.im2 = mprops2.effective_inv_mass;
             constraint.inner.solver_vel2 = solver_vel2;
             constraint.inner.manifold_id = manifold_id;
+            constraint.inner.has_fast_contact = false;
             constraint.inner.num_contacts = manifold_points.len() as u8;
+            // NOTE: rolling friction isn’t supported by multibody contacts.
+            constraint.inner.rolling_part = OneBodyConstraintRollingPart::zero();
             #[cfg(feature = "dim3")]
             {
                 constraint.inner.tangent1 = tangents1[0];
@@ -116,12 +126,17 @@ impl GenericOneBodyConstraintBuilder {
 
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
+                // One-way contacts approached from the wrong side must not apply any impulse.
+                let is_enabled = !manifold_point
+                    .is_one_way_disabled(&((vel2 - vel1) * flipped_multiplier))
+                    as u32 as Real;
 
-                constraint.inner.limit = manifold_point.friction;
+                constraint.inner.limit = manifold_point.friction_limit();
                 constraint.inner.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_damping_rhs;
                 {
                     let torque_dir2 = dp2.gcross(-force_dir1);
                     let inv_r2 = mb2
@@ -137,16 +152,16 @@ impl GenericOneBodyConstraintBuilder {
                         )
                         .0;
 
-                    let r = crate::utils::inv(inv_r2);
-
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let r = is_enabled * crate::utils::inv(inv_r2 + cfm_gain);
 
                     let proj_vel1 = vel1.dot(&force_dir1);
                     let proj_vel2 = vel2.dot(&force_dir1);
                     let dvel = proj_vel1 - proj_vel2;
+                    let is_bouncy = manifold_point.is_bouncy_at(dvel) as u32 as Real;
                     // NOTE: we add proj_vel1 since it’s not accessible through solver_vel.
                     normal_rhs_wo_bias =
                         proj_vel1 + (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_damping_rhs = params.contact_normal_damping * dvel;
 
                     constraint.inner.elements[k].normal_part = OneBodyConstraintNormalPart {
                         gcross2: na::zero(), // Unused for generic constraints.
@@ -154,14 +169,21 @@ impl GenericOneBodyConstraintBuilder {
                         rhs_wo_bias: na::zero(),
                         impulse: na::zero(),
                         impulse_accumulator: na::zero(),
+                        max_impulse: manifold_point.max_normal_impulse,
                         r,
+                        cfm_gain,
                         r_mat_elts: [0.0; 2],
                     };
                 }
 
+                let mut tangent_damping_rhs = [0.0; DIM - 1];
+
                 // Tangent parts.
                 {
                     constraint.inner.elements[k].tangent_part.impulse = na::zero();
+                    constraint.inner.elements[k]
+                        .tangent_part
+                        .impulse_accumulator = na::zero();
 
                     for j in 0..DIM - 1 {
                         let torque_dir2 = dp2.gcross(-tangents1[j]);
@@ -183,6 +205,8 @@ impl GenericOneBodyConstraintBuilder {
                         let rhs_wo_bias = (vel1
                             + flipped_multiplier * manifold_point.tangent_velocity)
                             .dot(&tangents1[j]);
+                        tangent_damping_rhs[j] =
+                            params.contact_tangent_damping * (vel1 - vel2).dot(&tangents1[j]);
 
                         constraint.inner.elements[k].tangent_part.rhs_wo_bias[j] = rhs_wo_bias;
                         constraint.inner.elements[k].tangent_part.rhs[j] = rhs_wo_bias;
@@ -204,6 +228,9 @@ impl GenericOneBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_damping_rhs,
+                    tangent_damping_rhs,
+                    penetration_correction_factor: manifold_point.penetration_correction_factor,
                 };
 
                 builder.link2 = link2;
@@ -231,8 +258,13 @@ impl GenericOneBodyConstraintBuilder {
             .unwrap()
             .local_to_world;
 
-        self.inner
-            .update_with_positions(params, solved_dt, pos2, &mut constraint.inner);
+        self.inner.update_with_positions(
+            params,
+            solved_dt,
+            pos2,
+            self.ccd_thickness,
+            &mut constraint.inner,
+        );
     }
 }
 
@@ -287,6 +319,8 @@ impl GenericOneBodyConstraint {
             elements,
             jacobians,
             self.inner.limit,
+            #[cfg(feature = "dim3")]
+            self.inner.decoupled_friction,
             self.ndofs2,
             self.j_id,
             solver_vel2,
@@ -1,10 +1,9 @@
 use crate::dynamics::solver::{
-    OneBodyConstraintElement, OneBodyConstraintNormalPart, OneBodyConstraintTangentPart,
+    FrictionLimit, OneBodyConstraintElement, OneBodyConstraintNormalPart,
+    OneBodyConstraintTangentPart,
 };
 use crate::math::{Real, DIM};
 use na::DVector;
-#[cfg(feature = "dim2")]
-use na::SimdPartialOrd;
 
 impl OneBodyConstraintTangentPart<Real> {
     #[inline]
@@ -47,7 +46,7 @@ impl OneBodyConstraintTangentPart<Real> {
         j_id2: usize,
         jacobians: &DVector<Real>,
         ndofs2: usize,
-        limit: Real,
+        limit: FrictionLimit<Real>,
         solver_vel2: usize,
         solver_vels: &mut DVector<Real>,
     ) {
@@ -58,7 +57,7 @@ impl OneBodyConstraintTangentPart<Real> {
                 .dot(&solver_vels.rows(solver_vel2, ndofs2))
                 + self.rhs[0];
 
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limit, limit);
+            let new_impulse = limit.clamp(self.impulse[0] - self.r[0] * dvel_0);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -85,7 +84,7 @@ impl OneBodyConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse = limit.clamp(new_impulse);
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -134,9 +133,11 @@ impl OneBodyConstraintNormalPart<Real> {
         let dvel = jacobians
             .rows(j_id2, ndofs2)
             .dot(&solver_vels.rows(solver_vel2, ndofs2))
-            + self.rhs;
+            + self.rhs
+            + self.cfm_gain * self.impulse;
 
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let new_impulse =
+            (cfm_factor * (self.impulse - self.r * dvel).max(0.0)).min(self.max_impulse);
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -190,7 +191,8 @@ impl OneBodyConstraintElement<Real> {
         cfm_factor: Real,
         elements: &mut [Self],
         jacobians: &DVector<Real>,
-        limit: Real,
+        limit: [Real; DIM - 1],
+        #[cfg(feature = "dim3")] decoupled_friction: bool,
         ndofs2: usize,
         // Jacobian index of the first constraint.
         j_id: usize,
@@ -220,10 +222,15 @@ impl OneBodyConstraintElement<Real> {
 
         // Solve friction.
         if solve_friction {
+            let limit = FrictionLimit::new(
+                limit,
+                #[cfg(feature = "dim3")]
+                decoupled_friction,
+            );
             let mut tng_j_id = j_id + ndofs2 * 2;
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let limit = limit.scaled(element.normal_part.impulse);
                 let part = &mut element.tangent_part;
                 part.generic_solve(tng_j_id, jacobians, ndofs2, limit, solver_vel2, solver_vels);
                 tng_j_id += j_step;
@@ -4,7 +4,10 @@ use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::{Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{SimdAngularInertia, SimdCross, SimdDot};
 
-use super::{TwoBodyConstraintBuilder, TwoBodyConstraintElement, TwoBodyConstraintNormalPart};
+use super::{
+    TwoBodyConstraintBuilder, TwoBodyConstraintElement, TwoBodyConstraintNormalPart,
+    TwoBodyConstraintRollingPart,
+};
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::solver::{ContactPointInfos, SolverVel};
 use crate::prelude::RigidBodyHandle;
@@ -33,6 +36,7 @@ impl GenericTwoBodyConstraintBuilder {
     pub fn generate(
         manifold_id: ContactManifoldIndex,
         manifold: &ContactManifold,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         multibodies: &MultibodyJointSet,
         out_builders: &mut [GenericTwoBodyConstraintBuilder],
@@ -70,12 +74,17 @@ impl GenericTwoBodyConstraintBuilder {
                 0
             });
         let force_dir1 = -manifold.data.normal;
+        let cfm_gain = manifold.data.contact_model.cfm_gain(params);
 
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction(),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let multibodies_ndof = multibody1.map(|m| m.0.ndofs()).unwrap_or(0)
             + multibody2.map(|m| m.0.ndofs()).unwrap_or(0);
@@ -98,6 +107,7 @@ impl GenericTwoBodyConstraintBuilder {
 
             let builder = &mut out_builders[l];
             let constraint = &mut out_constraints[l];
+            builder.inner.contact_model = manifold.data.contact_model;
             constraint.inner.dir1 = force_dir1;
             constraint.inner.im1 = if type1.is_dynamic() {
                 mprops1.effective_inv_mass
@@ -112,7 +122,10 @@ impl GenericTwoBodyConstraintBuilder {
             constraint.inner.solver_vel1 = solver_vel1;
             constraint.inner.solver_vel2 = solver_vel2;
             constraint.inner.manifold_id = manifold_id;
+            constraint.inner.has_fast_contact = false;
             constraint.inner.num_contacts = manifold_points.len() as u8;
+            // NOTE: rolling friction isn’t supported by multibody contacts.
+            constraint.inner.rolling_part = TwoBodyConstraintRollingPart::zero();
             #[cfg(feature = "dim3")]
             {
                 constraint.inner.tangent1 = tangents1[0];
@@ -126,12 +139,15 @@ impl GenericTwoBodyConstraintBuilder {
 
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
+                // One-way contacts approached from the wrong side must not apply any impulse.
+                let is_enabled = !manifold_point.is_one_way_disabled(&(vel2 - vel1)) as u32 as Real;
 
-                constraint.inner.limit = manifold_point.friction;
+                constraint.inner.limit = manifold_point.friction_limit();
                 constraint.inner.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_damping_rhs;
                 {
                     let torque_dir1 = dp1.gcross(force_dir1);
                     let torque_dir2 = dp2.gcross(-force_dir1);
@@ -189,12 +205,14 @@ impl GenericTwoBodyConstraintBuilder {
                         0.0
                     };
 
-                    let r = crate::utils::inv(inv_r1 + inv_r2);
+                    let r = is_enabled * crate::utils::inv(inv_r1 + inv_r2 + cfm_gain);
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let projected_velocity = (vel1 - vel2).dot(&force_dir1);
+                    let is_bouncy = manifold_point.is_bouncy_at(projected_velocity) as u32 as Real;
 
                     normal_rhs_wo_bias =
-                        (is_bouncy * manifold_point.restitution) * (vel1 - vel2).dot(&force_dir1);
+                        (is_bouncy * manifold_point.restitution) * projected_velocity;
+                    normal_damping_rhs = params.contact_normal_damping * projected_velocity;
 
                     constraint.inner.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
@@ -202,16 +220,26 @@ impl GenericTwoBodyConstraintBuilder {
                         rhs: na::zero(),
                         rhs_wo_bias: na::zero(),
                         impulse_accumulator: na::zero(),
-                        impulse: manifold_point.warmstart_impulse,
+                        impulse: is_enabled
+                            * manifold_point
+                                .warmstart_impulse
+                                .min(manifold_point.max_normal_impulse),
+                        max_impulse: manifold_point.max_normal_impulse,
                         r,
+                        cfm_gain,
                         r_mat_elts: [0.0; 2],
                     };
                 }
 
+                let mut tangent_damping_rhs = [0.0; DIM - 1];
+
                 // Tangent parts.
                 {
                     constraint.inner.elements[k].tangent_part.impulse =
-                        manifold_point.warmstart_tangent_impulse;
+                        manifold_point.warmstart_tangent_impulse * is_enabled;
+                    constraint.inner.elements[k]
+                        .tangent_part
+                        .impulse_accumulator = na::zero();
 
                     for j in 0..DIM - 1 {
                         let torque_dir1 = dp1.gcross(tangents1[j]);
@@ -274,6 +302,8 @@ impl GenericTwoBodyConstraintBuilder {
 
                         let r = crate::utils::inv(inv_r1 + inv_r2);
                         let rhs_wo_bias = manifold_point.tangent_velocity.dot(&tangents1[j]);
+                        tangent_damping_rhs[j] =
+                            params.contact_tangent_damping * (vel1 - vel2).dot(&tangents1[j]);
 
                         constraint.inner.elements[k].tangent_part.rhs_wo_bias[j] = rhs_wo_bias;
                         constraint.inner.elements[k].tangent_part.rhs[j] = rhs_wo_bias;
@@ -298,6 +328,9 @@ impl GenericTwoBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_damping_rhs,
+                    tangent_damping_rhs,
+                    penetration_correction_factor: manifold_point.penetration_correction_factor,
                 };
 
                 builder.handle1 = handle1;
@@ -342,8 +375,14 @@ impl GenericTwoBodyConstraintBuilder {
             .map(|m| &multibodies[m.multibody].link(m.id).unwrap().local_to_world)
             .unwrap_or_else(|| &bodies[constraint.inner.solver_vel2].position);
 
-        self.inner
-            .update_with_positions(params, solved_dt, pos1, pos2, &mut constraint.inner);
+        self.inner.update_with_positions(
+            params,
+            solved_dt,
+            pos1,
+            pos2,
+            self.ccd_thickness,
+            &mut constraint.inner,
+        );
     }
 }
 
@@ -444,6 +483,8 @@ impl GenericTwoBodyConstraint {
             &self.inner.im1,
             &self.inner.im2,
             self.inner.limit,
+            #[cfg(feature = "dim3")]
+            self.inner.decoupled_friction,
             self.ndofs1,
             self.ndofs2,
             self.j_id,
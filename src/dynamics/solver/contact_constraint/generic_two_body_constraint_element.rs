@@ -1,12 +1,13 @@
 use crate::dynamics::solver::SolverVel;
 use crate::dynamics::solver::{
-    TwoBodyConstraintElement, TwoBodyConstraintNormalPart, TwoBodyConstraintTangentPart,
+    FrictionLimit, TwoBodyConstraintElement, TwoBodyConstraintNormalPart,
+    TwoBodyConstraintTangentPart,
 };
 use crate::math::{AngVector, Real, Vector, DIM};
+#[cfg(feature = "dim2")]
+use crate::utils::SimdBasis;
 use crate::utils::SimdDot;
 use na::DVector;
-#[cfg(feature = "dim2")]
-use {crate::utils::SimdBasis, na::SimdPartialOrd};
 
 pub(crate) enum GenericRhs {
     SolverVel(SolverVel<Real>),
@@ -187,7 +188,7 @@ impl TwoBodyConstraintTangentPart<Real> {
         im2: &Vector<Real>,
         ndofs1: usize,
         ndofs2: usize,
-        limit: Real,
+        limit: FrictionLimit<Real>,
         solver_vel1: &mut GenericRhs,
         solver_vel2: &mut GenericRhs,
         solver_vels: &mut DVector<Real>,
@@ -215,7 +216,7 @@ impl TwoBodyConstraintTangentPart<Real> {
                 solver_vels,
             ) + self.rhs[0];
 
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel_0).simd_clamp(-limit, limit);
+            let new_impulse = limit.clamp(self.impulse[0] - self.r[0] * dvel_0);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -278,7 +279,7 @@ impl TwoBodyConstraintTangentPart<Real> {
                 self.impulse[0] - self.r[0] * dvel_0,
                 self.impulse[1] - self.r[1] * dvel_1,
             );
-            let new_impulse = new_impulse.cap_magnitude(limit);
+            let new_impulse = limit.clamp(new_impulse);
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -388,9 +389,11 @@ impl TwoBodyConstraintNormalPart<Real> {
 
         let dvel = solver_vel1.dvel(j_id1, ndofs1, jacobians, dir1, &self.gcross1, solver_vels)
             + solver_vel2.dvel(j_id2, ndofs2, jacobians, &-dir1, &self.gcross2, solver_vels)
-            + self.rhs;
+            + self.rhs
+            + self.cfm_gain * self.impulse;
 
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let new_impulse =
+            (cfm_factor * (self.impulse - self.r * dvel).max(0.0)).min(self.max_impulse);
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -495,7 +498,8 @@ impl TwoBodyConstraintElement<Real> {
         #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
         im1: &Vector<Real>,
         im2: &Vector<Real>,
-        limit: Real,
+        limit: [Real; DIM - 1],
+        #[cfg(feature = "dim3")] decoupled_friction: bool,
         // ndofs is 0 for a non-multibody body, or a multibody with zero
         // degrees of freedom.
         ndofs1: usize,
@@ -540,8 +544,14 @@ impl TwoBodyConstraintElement<Real> {
             let tangents1 = [&dir1.orthonormal_vector()];
             let mut tng_j_id = tangent_j_id(j_id, ndofs1, ndofs2);
 
+            let limit = FrictionLimit::new(
+                limit,
+                #[cfg(feature = "dim3")]
+                decoupled_friction,
+            );
+
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let limit = limit.scaled(element.normal_part.impulse);
                 let part = &mut element.tangent_part;
                 part.generic_solve(
                     tng_j_id,
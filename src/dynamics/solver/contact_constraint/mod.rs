@@ -27,3 +27,53 @@ mod two_body_constraint;
 mod two_body_constraint_element;
 #[cfg(feature = "simd-is-enabled")]
 mod two_body_constraint_simd;
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+
+    #[test]
+    fn resting_contact_impulses_exclude_the_previous_warmstart() {
+        let mut world = TestWorld::with_gravity();
+        let (_, floor_co) = world.insert_floor();
+
+        // Two boxes of mass 1, so the floor contacts are solved by one-body constraints
+        // and the contacts between the boxes by two-body constraints.
+        let boxes: Vec<_> = (0..2)
+            .map(|i| {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (0.5 + i as Real))
+                    .can_sleep(false);
+                world.insert_body(rb, cuboid(0.5, 0.5)).1
+            })
+            .collect();
+
+        world.steps(100);
+
+        // Once at rest, the impulses of a timestep only carry the weight of the boxes above
+        // the contacts. The warmstart impulses, applied again at the first substep, must not
+        // be counted twice.
+        let weight_impulse = world.gravity.norm() * world.params.dt;
+        for (pair, num_boxes_above) in [(floor_co, boxes[0]), (boxes[0], boxes[1])]
+            .into_iter()
+            .zip([2.0, 1.0])
+        {
+            let pair = world.narrow_phase.contact_pair(pair.0, pair.1).unwrap();
+            let contacts = || pair.manifolds.iter().flat_map(|m| m.points.iter());
+            let impulse: Real = contacts().map(|c| c.data.impulse).sum();
+            approx::assert_relative_eq!(
+                impulse,
+                weight_impulse * num_boxes_above,
+                max_relative = 1.0e-2
+            );
+            // Nothing pushes the boxes sideways, so the friction impulses stay well within the
+            // friction cone.
+            assert!(contacts().all(|c| {
+                let tangent_impulse = c.data.tangent_impulse.norm();
+                tangent_impulse.is_finite() && tangent_impulse < c.data.impulse * 0.1
+            }));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{OneBodyConstraintElement, OneBodyConstraintNormalPart};
+use super::{OneBodyConstraintElement, OneBodyConstraintNormalPart, OneBodyConstraintRollingPart};
 use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
 use crate::utils::SimdBasis;
@@ -8,9 +8,13 @@ use parry::math::Isometry;
 
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
 use crate::dynamics::solver::solver_body::SolverBody;
-use crate::dynamics::solver::SolverVel;
+use crate::dynamics::solver::SolverVelsAccess;
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{IntegrationParameters, MultibodyJointSet, RigidBodySet, RigidBodyVelocity};
-use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::geometry::{
+    solver_contact_data_mut, ContactManifold, ContactManifoldIndex, ContactModel,
+};
 
 // TODO: move this struct somewhere else.
 #[derive(Copy, Clone, Debug)]
@@ -20,6 +24,13 @@ pub struct ContactPointInfos<N: SimdRealCopy> {
     pub local_p2: Point<N>,
     pub dist: N,
     pub normal_rhs_wo_bias: N,
+    // The damping term added to the biased normal right-hand-side, proportional to the
+    // normal relative velocity at the contact at the beginning of the timestep.
+    pub normal_damping_rhs: N,
+    // The damping terms added to the tangent right-hand-sides, proportional to the
+    // tangent relative velocities at the contact at the beginning of the timestep.
+    pub tangent_damping_rhs: [N; DIM - 1],
+    pub penetration_correction_factor: N,
 }
 
 impl<N: SimdRealCopy> Default for ContactPointInfos<N> {
@@ -30,6 +41,9 @@ impl<N: SimdRealCopy> Default for ContactPointInfos<N> {
             local_p2: Point::origin(),
             dist: N::zero(),
             normal_rhs_wo_bias: N::zero(),
+            normal_damping_rhs: N::zero(),
+            tangent_damping_rhs: [N::zero(); DIM - 1],
+            penetration_correction_factor: N::one(),
         }
     }
 }
@@ -40,6 +54,7 @@ pub(crate) struct OneBodyConstraintBuilder {
     pub rb1: SolverBody,
     pub vels1: RigidBodyVelocity,
     pub infos: [ContactPointInfos<Real>; MAX_MANIFOLD_POINTS],
+    pub contact_model: ContactModel,
 }
 
 impl OneBodyConstraintBuilder {
@@ -48,12 +63,14 @@ impl OneBodyConstraintBuilder {
             rb1: SolverBody::default(),
             vels1: RigidBodyVelocity::zero(),
             infos: [ContactPointInfos::default(); MAX_MANIFOLD_POINTS],
+            contact_model: ContactModel::Rigid,
         }
     }
 
     pub fn generate(
         manifold_id: ContactManifoldIndex,
         manifold: &ContactManifold,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         out_builders: &mut [OneBodyConstraintBuilder],
         out_constraints: &mut [OneBodyConstraint],
@@ -61,6 +78,7 @@ impl OneBodyConstraintBuilder {
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
         let flipped = manifold.data.relative_dominance < 0;
+        let cfm_gain = manifold.data.contact_model.cfm_gain(params);
 
         let (force_dir1, flipped_multiplier) = if flipped {
             std::mem::swap(&mut handle1, &mut handle2);
@@ -87,8 +105,12 @@ impl OneBodyConstraintBuilder {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction(),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         let solver_vel2 = rb2.ids.active_set_offset;
 
@@ -103,11 +125,13 @@ impl OneBodyConstraintBuilder {
 
             builder.rb1 = rb1;
             builder.vels1 = vels1;
+            builder.contact_model = manifold.data.contact_model;
 
             constraint.dir1 = force_dir1;
             constraint.im2 = mprops2.effective_inv_mass;
             constraint.solver_vel2 = solver_vel2;
             constraint.manifold_id = manifold_id;
+            constraint.has_fast_contact = false;
             constraint.num_contacts = manifold_points.len() as u8;
             #[cfg(feature = "dim3")]
             {
@@ -121,12 +145,17 @@ impl OneBodyConstraintBuilder {
                 let dp1 = manifold_point.point - world_com1;
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
+                // One-way contacts approached from the wrong side must not apply any impulse.
+                let is_enabled = !manifold_point
+                    .is_one_way_disabled(&((vel2 - vel1) * flipped_multiplier))
+                    as u32 as Real;
 
-                constraint.limit = manifold_point.friction;
+                constraint.limit = manifold_point.friction_limit();
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_damping_rhs;
                 {
                     let gcross2 = mprops2
                         .effective_world_inv_inertia_sqrt
@@ -136,32 +165,41 @@ impl OneBodyConstraintBuilder {
                         force_dir1.dot(&mprops2.effective_inv_mass.component_mul(&force_dir1));
                     let projected_ang_mass = gcross2.gdot(gcross2);
 
-                    let projected_mass = utils::inv(projected_lin_mass + projected_ang_mass);
-
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let projected_mass =
+                        utils::inv(projected_lin_mass + projected_ang_mass + cfm_gain);
 
                     let proj_vel1 = vel1.dot(&force_dir1);
                     let proj_vel2 = vel2.dot(&force_dir1);
                     let dvel = proj_vel1 - proj_vel2;
+                    let is_bouncy = manifold_point.is_bouncy_at(dvel) as u32 as Real;
                     // NOTE: we add proj_vel1 since it’s not accessible through solver_vel.
                     normal_rhs_wo_bias =
                         proj_vel1 + (is_bouncy * manifold_point.restitution) * dvel;
+                    normal_damping_rhs = params.contact_normal_damping * dvel;
 
                     constraint.elements[k].normal_part = OneBodyConstraintNormalPart {
                         gcross2,
                         rhs: na::zero(),
                         rhs_wo_bias: na::zero(),
-                        impulse: manifold_point.warmstart_impulse,
+                        impulse: is_enabled
+                            * manifold_point
+                                .warmstart_impulse
+                                .min(manifold_point.max_normal_impulse),
                         impulse_accumulator: na::zero(),
-                        r: projected_mass,
+                        max_impulse: manifold_point.max_normal_impulse,
+                        r: is_enabled * projected_mass,
+                        cfm_gain,
                         r_mat_elts: [0.0; 2],
                     };
                 }
 
+                let mut tangent_damping_rhs = [0.0; DIM - 1];
+
                 // Tangent parts.
                 {
                     constraint.elements[k].tangent_part.impulse =
-                        manifold_point.warmstart_tangent_impulse;
+                        manifold_point.warmstart_tangent_impulse * is_enabled;
+                    constraint.elements[k].tangent_part.impulse_accumulator = na::zero();
 
                     for j in 0..DIM - 1 {
                         let gcross2 = mprops2
@@ -173,19 +211,22 @@ impl OneBodyConstraintBuilder {
                         let rhs_wo_bias = (vel1
                             + flipped_multiplier * manifold_point.tangent_velocity)
                             .dot(&tangents1[j]);
+                        tangent_damping_rhs[j] =
+                            params.contact_tangent_damping * (vel1 - vel2).dot(&tangents1[j]);
 
                         constraint.elements[k].tangent_part.gcross2[j] = gcross2;
                         constraint.elements[k].tangent_part.rhs_wo_bias[j] = rhs_wo_bias;
                         constraint.elements[k].tangent_part.rhs[j] = rhs_wo_bias;
-                        constraint.elements[k].tangent_part.r[j] = if cfg!(feature = "dim2") {
-                            utils::inv(r)
-                        } else {
-                            r
-                        };
+                        constraint.elements[k].tangent_part.r[j] =
+                            if super::independent_friction_directions(params) {
+                                utils::inv(r)
+                            } else {
+                                r
+                            };
                     }
 
                     #[cfg(feature = "dim3")]
-                    {
+                    if !super::independent_friction_directions(params) {
                         constraint.elements[k].tangent_part.r[2] = 2.0
                             * constraint.elements[k].tangent_part.gcross2[0]
                                 .gdot(constraint.elements[k].tangent_part.gcross2[1]);
@@ -205,12 +246,35 @@ impl OneBodyConstraintBuilder {
                         tangent_vel: flipped_multiplier * manifold_point.tangent_velocity,
                         dist: manifold_point.dist,
                         normal_rhs_wo_bias,
+                        normal_damping_rhs,
+                        tangent_damping_rhs,
+                        penetration_correction_factor: manifold_point.penetration_correction_factor,
                     };
 
                     builder.infos[k] = infos;
                 }
             }
 
+            // Rolling part.
+            {
+                #[cfg(feature = "dim2")]
+                let rolling_axes = [1.0];
+                #[cfg(feature = "dim3")]
+                let rolling_axes = tangents1;
+                let rolling_part = &mut constraint.rolling_part;
+                rolling_part.impulse = na::zero();
+                rolling_part.coefficient = manifold_points[0].rolling_friction;
+
+                for j in 0..DIM - 1 {
+                    let gcross2 = mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-rolling_axes[j]);
+                    rolling_part.gcross2[j] = gcross2;
+                    rolling_part.rhs[j] = rolling_axes[j].gdot(vels1.angvel);
+                    rolling_part.r[j] = utils::inv(gcross2.gdot(gcross2));
+                }
+            }
+
             if BLOCK_SOLVER_ENABLED {
                 // Coupling between consecutive pairs.
                 for k in 0..manifold_points.len() / 2 {
@@ -230,7 +294,12 @@ impl OneBodyConstraintBuilder {
                     r_mat.m11 = utils::inv(r0);
                     r_mat.m22 = utils::inv(r1);
 
-                    if let Some(inv) = r_mat.try_inverse() {
+                    if r0 == 0.0 || r1 == 0.0 {
+                        // At least one of the contacts is disabled (e.g. a one-way contact),
+                        // so the other one is solved alone.
+                        constraint.elements[k0].normal_part.r_mat_elts = [r0, r1];
+                        constraint.elements[k1].normal_part.r_mat_elts = [0.0; 2];
+                    } else if let Some(inv) = r_mat.try_inverse() {
                         constraint.elements[k0].normal_part.r_mat_elts = [inv.m11, inv.m22];
                         constraint.elements[k1].normal_part.r_mat_elts = [inv.m12, r_mat.m12];
                     } else {
@@ -260,7 +329,13 @@ impl OneBodyConstraintBuilder {
         constraint: &mut OneBodyConstraint,
     ) {
         let rb2 = &bodies[constraint.solver_vel2];
-        self.update_with_positions(params, solved_dt, &rb2.position, constraint)
+        self.update_with_positions(
+            params,
+            solved_dt,
+            &rb2.position,
+            rb2.ccd_thickness,
+            constraint,
+        )
     }
 
     // TODO: this code is SOOOO similar to TwoBodyConstraint::update.
@@ -270,11 +345,12 @@ impl OneBodyConstraintBuilder {
         params: &IntegrationParameters,
         solved_dt: Real,
         rb2_pos: &Isometry<Real>,
+        ccd_thickness: Real,
         constraint: &mut OneBodyConstraint,
     ) {
-        let cfm_factor = params.contact_cfm_factor();
+        let (cfm_factor, erp_inv_dt, allowed_linear_error, max_corrective_velocity) =
+            self.contact_model.regularization_coefficients(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.contact_erp_inv_dt();
 
         let all_infos = &self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
@@ -292,6 +368,12 @@ impl OneBodyConstraintBuilder {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
+        // NOTE: at the first substep, the impulses are the warmstart impulses from the previous
+        //       timestep. They must not be accumulated since they are already accounted for by
+        //       the impulses of the first substep.
+        let is_first_substep = solved_dt == 0.0;
+        let mut is_fast_contact = false;
+
         for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
             // NOTE: the tangent velocity is equivalent to an additional movement of the first body’s surface.
             let p1 = new_pos1 * info.local_p1 + info.tangent_vel * solved_dt;
@@ -301,29 +383,46 @@ impl OneBodyConstraintBuilder {
             // Normal part.
             {
                 let rhs_wo_bias = info.normal_rhs_wo_bias + dist.max(0.0) * inv_dt;
-                let rhs_bias = (erp_inv_dt * (dist + params.allowed_linear_error()))
-                    .clamp(-params.max_corrective_velocity(), 0.0);
-                let new_rhs = rhs_wo_bias + rhs_bias;
+                let rhs_bias = (info.penetration_correction_factor
+                    * erp_inv_dt
+                    * (dist + allowed_linear_error))
+                    .clamp(-max_corrective_velocity, 0.0);
+                let new_rhs = rhs_wo_bias + rhs_bias + info.normal_damping_rhs;
+                is_fast_contact |= -new_rhs * params.dt > ccd_thickness * 0.5;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                if !is_first_substep {
+                    element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                }
                 element.normal_part.impulse *= params.warmstart_coefficient;
             }
 
             // Tangent part.
             {
-                element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                if !is_first_substep {
+                    element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                }
                 element.tangent_part.impulse *= params.warmstart_coefficient;
 
                 for j in 0..DIM - 1 {
                     let bias = (p1 - p2).dot(&tangents1[j]) * inv_dt;
-                    element.tangent_part.rhs[j] = element.tangent_part.rhs_wo_bias[j] + bias;
+                    element.tangent_part.rhs[j] =
+                        element.tangent_part.rhs_wo_bias[j] + bias + info.tangent_damping_rhs[j];
                 }
             }
         }
 
+        constraint.rolling_part.impulse *= params.warmstart_coefficient;
+
         constraint.cfm_factor = cfm_factor;
+        #[cfg(feature = "dim3")]
+        {
+            constraint.decoupled_friction =
+                params.friction_model == FrictionModel::PyramidDecoupled;
+        }
+        constraint.is_spring = self.contact_model != ContactModel::Rigid;
+        constraint.has_fast_contact |= is_fast_contact;
     }
 }
 
@@ -335,8 +434,17 @@ pub(crate) struct OneBodyConstraint {
     pub tangent1: Vector<Real>, // One of the friction force directions.
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
-    pub limit: Real,
+    pub limit: [Real; DIM - 1], // The friction coefficient along each tangent direction.
+    // Are the tangent directions of friction solved independently?
+    #[cfg(feature = "dim3")]
+    pub decoupled_friction: bool,
+    // Spring contacts keep their regularization when the bias is removed.
+    pub is_spring: bool,
+    // Did any contact of this constraint move the bodies by more than half their CCD thickness
+    // during one substep? This is reset at each step, and written back to the contact manifold.
+    pub has_fast_contact: bool,
     pub elements: [OneBodyConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: OneBodyConstraintRollingPart<Real>,
 
     pub manifold_id: ContactManifoldIndex,
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
@@ -352,19 +460,25 @@ impl OneBodyConstraint {
             tangent1: Vector::zeros(),
             im2: Vector::zeros(),
             cfm_factor: 0.0,
-            limit: 0.0,
+            limit: [0.0; DIM - 1],
+            #[cfg(feature = "dim3")]
+            decoupled_friction: false,
+            is_spring: false,
+            has_fast_contact: false,
             elements: [OneBodyConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+            rolling_part: OneBodyConstraintRollingPart::zero(),
             manifold_id: ContactManifoldIndex::MAX,
             manifold_contact_id: [u8::MAX; MAX_MANIFOLD_POINTS],
             num_contacts: u8::MAX,
         }
     }
 
-    pub fn warmstart(&mut self, solver_vels: &mut [SolverVel<Real>]) {
-        let mut solver_vel2 = solver_vels[self.solver_vel2];
+    pub fn warmstart<V: SolverVelsAccess + ?Sized>(&mut self, solver_vels: &mut V) {
+        let mut solver_vel2 = solver_vels.get_vel(self.solver_vel2);
 
         OneBodyConstraintElement::warmstart_group(
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
@@ -372,52 +486,76 @@ impl OneBodyConstraint {
             &mut solver_vel2,
         );
 
-        solver_vels[self.solver_vel2] = solver_vel2;
+        solver_vels.set_vel(self.solver_vel2, solver_vel2);
     }
 
-    pub fn solve(
+    pub fn solve<V: SolverVelsAccess + ?Sized>(
         &mut self,
-        solver_vels: &mut [SolverVel<Real>],
+        solver_vels: &mut V,
         solve_normal: bool,
         solve_friction: bool,
     ) {
-        let mut solver_vel2 = solver_vels[self.solver_vel2];
+        let mut solver_vel2 = solver_vels.get_vel(self.solver_vel2);
 
         OneBodyConstraintElement::solve_group(
             self.cfm_factor,
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.decoupled_friction,
             &mut solver_vel2,
             solve_normal,
             solve_friction,
         );
 
-        solver_vels[self.solver_vel2] = solver_vel2;
+        solver_vels.set_vel(self.solver_vel2, solver_vel2);
     }
 
     // FIXME: duplicated code. This is exactly the same as in the two-body velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
+        let normal = manifold.data.normal;
+        manifold.data.has_fast_contact |= self.has_fast_contact;
+        manifold.data.set_tangents(super::contact_tangents(
+            &self.dir1,
+            #[cfg(feature = "dim3")]
+            &self.tangent1,
+        ));
 
         for k in 0..self.num_contacts as usize {
             let contact_id = self.manifold_contact_id[k];
-            let active_contact = &mut manifold.points[contact_id as usize];
-
-            active_contact.data.warmstart_impulse = self.elements[k].normal_part.impulse;
-            active_contact.data.warmstart_tangent_impulse = self.elements[k].tangent_part.impulse;
-            active_contact.data.impulse = self.elements[k].normal_part.total_impulse();
-            active_contact.data.tangent_impulse = self.elements[k].tangent_part.total_impulse();
+            let contact_data = solver_contact_data_mut(manifold, contact_id);
+
+            contact_data.warmstart_impulse = self.elements[k].normal_part.impulse;
+            contact_data.warmstart_tangent_impulse = self.elements[k].tangent_part.impulse;
+            contact_data.impulse = self.elements[k].normal_part.total_impulse();
+            contact_data.tangent_impulse = self.elements[k].tangent_part.total_impulse();
+            contact_data.effective_mass = self.elements[k].normal_part.r;
+            contact_data.world_impulse = super::contact_world_impulse(
+                &normal,
+                &self.dir1,
+                #[cfg(feature = "dim3")]
+                &self.tangent1,
+                contact_data.impulse,
+                &contact_data.tangent_impulse,
+            );
         }
     }
 
     pub fn remove_cfm_and_bias_from_rhs(&mut self) {
-        self.cfm_factor = 1.0;
+        if !self.is_spring {
+            self.cfm_factor = 1.0;
+        }
+
         for elt in &mut self.elements {
-            elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            if !self.is_spring {
+                elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            }
             elt.tangent_part.rhs = elt.tangent_part.rhs_wo_bias;
         }
     }
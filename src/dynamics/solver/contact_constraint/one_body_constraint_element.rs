@@ -1,7 +1,7 @@
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
-use crate::dynamics::solver::contact_constraint::TwoBodyConstraintNormalPart;
+use crate::dynamics::solver::contact_constraint::{FrictionLimit, TwoBodyConstraintNormalPart};
 use crate::dynamics::solver::SolverVel;
-use crate::math::{AngVector, TangentImpulse, Vector, DIM};
+use crate::math::{AngVector, Real, TangentImpulse, Vector, DIM};
 use crate::utils::{SimdBasis, SimdDot, SimdRealCopy};
 use na::Vector2;
 
@@ -66,7 +66,7 @@ impl<N: SimdRealCopy> OneBodyConstraintTangentPart<N> {
         &mut self,
         tangents1: [&Vector<N>; DIM - 1],
         im2: &Vector<N>,
-        limit: N,
+        limit: FrictionLimit<N>,
         solver_vel2: &mut SolverVel<N>,
     ) where
         AngVector<N>: SimdDot<AngVector<N>, Result = N>,
@@ -76,7 +76,7 @@ impl<N: SimdRealCopy> OneBodyConstraintTangentPart<N> {
             let dvel = -tangents1[0].dot(&solver_vel2.linear)
                 + self.gcross2[0].gdot(solver_vel2.angular)
                 + self.rhs[0];
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limit, limit);
+            let new_impulse = limit.clamp(self.impulse[0] - self.r[0] * dvel);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -85,7 +85,22 @@ impl<N: SimdRealCopy> OneBodyConstraintTangentPart<N> {
         }
 
         #[cfg(feature = "dim3")]
-        {
+        if limit.decoupled {
+            // NOTE: each direction is solved independently, one after the other, so `self.r`
+            //       contains the inverse of the diagonal elements of the effective mass matrix,
+            //       and the coupling term `self.r[2]` isn’t needed.
+            for j in 0..DIM - 1 {
+                let dvel = -tangents1[j].dot(&solver_vel2.linear)
+                    + self.gcross2[j].gdot(solver_vel2.angular)
+                    + self.rhs[j];
+                let new_impulse = limit.clamp_axis(j, self.impulse[j] - self.r[j] * dvel);
+                let dlambda = new_impulse - self.impulse[j];
+                self.impulse[j] = new_impulse;
+
+                solver_vel2.linear += tangents1[j].component_mul(im2) * -dlambda;
+                solver_vel2.angular += self.gcross2[j] * dlambda;
+            }
+        } else {
             let dvel_0 = -tangents1[0].dot(&solver_vel2.linear)
                 + self.gcross2[0].gdot(solver_vel2.angular)
                 + self.rhs[0];
@@ -102,12 +117,7 @@ impl<N: SimdRealCopy> OneBodyConstraintTangentPart<N> {
                 );
             let delta_impulse = na::vector![inv_lhs * dvel_0, inv_lhs * dvel_1];
             let new_impulse = self.impulse - delta_impulse;
-            let new_impulse = {
-                let _disable_fe_except =
-                    crate::utils::DisableFloatingPointExceptionsFlags::
-                    disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
-            };
+            let new_impulse = limit.clamp(new_impulse);
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
 
@@ -118,6 +128,57 @@ impl<N: SimdRealCopy> OneBodyConstraintTangentPart<N> {
     }
 }
 
+/// The part of a contact constraint opposing the rolling of its body relative to the other
+/// body, which isn’t affected by the constraint.
+///
+/// See `TwoBodyConstraintRollingPart` for details.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct OneBodyConstraintRollingPart<N: SimdRealCopy> {
+    pub gcross2: [AngVector<N>; DIM - 1],
+    // The rolling velocity of the other body along each axis.
+    pub rhs: [N; DIM - 1],
+    pub impulse: TangentImpulse<N>,
+    pub r: [N; DIM - 1],
+    // The rolling friction coefficient.
+    pub coefficient: N,
+}
+
+impl<N: SimdRealCopy> OneBodyConstraintRollingPart<N> {
+    pub fn zero() -> Self {
+        Self {
+            gcross2: [na::zero(); DIM - 1],
+            rhs: [na::zero(); DIM - 1],
+            impulse: na::zero(),
+            r: [na::zero(); DIM - 1],
+            coefficient: na::zero(),
+        }
+    }
+
+    #[inline]
+    pub fn warmstart(&mut self, solver_vel2: &mut SolverVel<N>) {
+        for j in 0..DIM - 1 {
+            solver_vel2.angular += self.gcross2[j] * self.impulse[j];
+        }
+    }
+
+    #[inline]
+    pub fn solve(&mut self, normal_impulse: N, solver_vel2: &mut SolverVel<N>)
+    where
+        AngVector<N>: SimdDot<AngVector<N>, Result = N>,
+    {
+        let limit = self.coefficient * normal_impulse;
+
+        for j in 0..DIM - 1 {
+            let dvel = self.gcross2[j].gdot(solver_vel2.angular) + self.rhs[j];
+            let new_impulse = (self.impulse[j] - self.r[j] * dvel).simd_clamp(-limit, limit);
+            let dlambda = new_impulse - self.impulse[j];
+            self.impulse[j] = new_impulse;
+
+            solver_vel2.angular += self.gcross2[j] * dlambda;
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct OneBodyConstraintNormalPart<N: SimdRealCopy> {
     pub gcross2: AngVector<N>,
@@ -125,7 +186,11 @@ pub(crate) struct OneBodyConstraintNormalPart<N: SimdRealCopy> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub impulse_accumulator: N,
+    // The maximum value of `impulse`.
+    pub max_impulse: N,
     pub r: N,
+    // The compliance of spring contacts, already included in `r`.
+    pub cfm_gain: N,
     pub r_mat_elts: [N; 2],
 }
 
@@ -137,7 +202,9 @@ impl<N: SimdRealCopy> OneBodyConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             impulse_accumulator: na::zero(),
+            max_impulse: N::splat(Real::INFINITY),
             r: na::zero(),
+            cfm_gain: na::zero(),
             r_mat_elts: [N::zero(); 2],
         }
     }
@@ -164,9 +231,12 @@ impl<N: SimdRealCopy> OneBodyConstraintNormalPart<N> {
     ) where
         AngVector<N>: SimdDot<AngVector<N>, Result = N>,
     {
-        let dvel =
-            -dir1.dot(&solver_vel2.linear) + self.gcross2.gdot(solver_vel2.angular) + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let dvel = -dir1.dot(&solver_vel2.linear)
+            + self.gcross2.gdot(solver_vel2.angular)
+            + self.rhs
+            + self.cfm_gain * self.impulse;
+        let new_impulse = (cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero()))
+            .simd_min(self.max_impulse);
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -187,10 +257,12 @@ impl<N: SimdRealCopy> OneBodyConstraintNormalPart<N> {
     {
         let dvel_a = -dir1.dot(&solver_vel2.linear)
             + constraint_a.gcross2.gdot(solver_vel2.angular)
-            + constraint_a.rhs;
+            + constraint_a.rhs
+            + constraint_a.cfm_gain * constraint_a.impulse;
         let dvel_b = -dir1.dot(&solver_vel2.linear)
             + constraint_b.gcross2.gdot(solver_vel2.angular)
-            + constraint_b.rhs;
+            + constraint_b.rhs
+            + constraint_b.cfm_gain * constraint_b.impulse;
 
         let prev_impulse = Vector2::new(constraint_a.impulse, constraint_b.impulse);
         let new_impulse = TwoBodyConstraintNormalPart::solve_mlcp_two_constraints(
@@ -202,6 +274,10 @@ impl<N: SimdRealCopy> OneBodyConstraintNormalPart<N> {
             constraint_b.r_mat_elts,
             cfm_factor,
         );
+        let new_impulse = Vector2::new(
+            new_impulse.x.simd_min(constraint_a.max_impulse),
+            new_impulse.y.simd_min(constraint_b.max_impulse),
+        );
 
         let dlambda = new_impulse - prev_impulse;
 
@@ -230,6 +306,7 @@ impl<N: SimdRealCopy> OneBodyConstraintElement<N> {
     #[inline]
     pub fn warmstart_group(
         elements: &mut [Self],
+        rolling_part: &mut OneBodyConstraintRollingPart<N>,
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im2: &Vector<N>,
@@ -244,16 +321,20 @@ impl<N: SimdRealCopy> OneBodyConstraintElement<N> {
             element.normal_part.warmstart(dir1, im2, solver_vel2);
             element.tangent_part.warmstart(tangents1, im2, solver_vel2);
         }
+
+        rolling_part.warmstart(solver_vel2);
     }
 
     #[inline]
     pub fn solve_group(
         cfm_factor: N,
         elements: &mut [Self],
+        rolling_part: &mut OneBodyConstraintRollingPart<N>,
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limit: [N; DIM - 1],
+        #[cfg(feature = "dim3")] decoupled_friction: bool,
         solver_vel2: &mut SolverVel<N>,
         solve_normal: bool,
         solve_friction: bool,
@@ -301,11 +382,22 @@ impl<N: SimdRealCopy> OneBodyConstraintElement<N> {
 
         // Solve friction.
         if solve_friction {
+            let limit = FrictionLimit::new(
+                limit,
+                #[cfg(feature = "dim3")]
+                decoupled_friction,
+            );
+
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let limit = limit.scaled(element.normal_part.impulse);
                 let part = &mut element.tangent_part;
                 part.solve(tangents1, im2, limit, solver_vel2);
             }
+
+            let normal_impulse = elements
+                .iter()
+                .fold(N::zero(), |sum, element| sum + element.normal_part.impulse);
+            rolling_part.solve(normal_impulse, solver_vel2);
         }
     }
 }
@@ -1,15 +1,18 @@
 use super::{ContactConstraintTypes, ContactPointInfos};
-use crate::dynamics::solver::SolverVel;
 use crate::dynamics::solver::{AnyConstraintMut, SolverBody};
+use crate::dynamics::solver::{SolverVel, SolverVelsAccess};
 
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{IntegrationParameters, MultibodyJointSet, RigidBodySet};
-use crate::geometry::{ContactManifold, ContactManifoldIndex};
-use crate::math::{Isometry, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+use crate::geometry::{
+    solver_contact_data_mut, ContactManifold, ContactManifoldIndex, ContactModel,
+};
+use crate::math::{Isometry, Real, TangentImpulse, Vector, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{self, SimdAngularInertia, SimdBasis, SimdCross, SimdDot};
 use na::{DVector, Matrix2};
 
-use super::{TwoBodyConstraintElement, TwoBodyConstraintNormalPart};
+use super::{TwoBodyConstraintElement, TwoBodyConstraintNormalPart, TwoBodyConstraintRollingPart};
 
 impl<'a> AnyConstraintMut<'a, ContactConstraintTypes> {
     pub fn remove_bias(&mut self) {
@@ -114,13 +117,22 @@ pub(crate) struct TwoBodyConstraint {
     pub im1: Vector<Real>,
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
-    pub limit: Real,
+    pub limit: [Real; DIM - 1], // The friction coefficient along each tangent direction.
+    // Are the tangent directions of friction solved independently?
+    #[cfg(feature = "dim3")]
+    pub decoupled_friction: bool,
+    // Spring contacts keep their regularization when the bias is removed.
+    pub is_spring: bool,
+    // Did any contact of this constraint move the bodies by more than half their CCD thickness
+    // during one substep? This is reset at each step, and written back to the contact manifold.
+    pub has_fast_contact: bool,
     pub solver_vel1: usize,
     pub solver_vel2: usize,
     pub manifold_id: ContactManifoldIndex,
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
     pub elements: [TwoBodyConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: TwoBodyConstraintRollingPart<Real>,
 }
 
 impl TwoBodyConstraint {
@@ -132,13 +144,18 @@ impl TwoBodyConstraint {
             im1: Vector::zeros(),
             im2: Vector::zeros(),
             cfm_factor: 0.0,
-            limit: 0.0,
+            limit: [0.0; DIM - 1],
+            #[cfg(feature = "dim3")]
+            decoupled_friction: false,
+            is_spring: false,
+            has_fast_contact: false,
             solver_vel1: usize::MAX,
             solver_vel2: usize::MAX,
             manifold_id: ContactManifoldIndex::MAX,
             manifold_contact_id: [u8::MAX; MAX_MANIFOLD_POINTS],
             num_contacts: u8::MAX,
             elements: [TwoBodyConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+            rolling_part: TwoBodyConstraintRollingPart::zero(),
         }
     }
 }
@@ -146,18 +163,21 @@ impl TwoBodyConstraint {
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct TwoBodyConstraintBuilder {
     pub infos: [ContactPointInfos<Real>; MAX_MANIFOLD_POINTS],
+    pub contact_model: ContactModel,
 }
 
 impl TwoBodyConstraintBuilder {
     pub fn invalid() -> Self {
         Self {
             infos: [ContactPointInfos::default(); MAX_MANIFOLD_POINTS],
+            contact_model: ContactModel::Rigid,
         }
     }
 
     pub fn generate(
         manifold_id: ContactManifoldIndex,
         manifold: &ContactManifold,
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         out_builders: &mut [TwoBodyConstraintBuilder],
         out_constraints: &mut [TwoBodyConstraint],
@@ -175,12 +195,17 @@ impl TwoBodyConstraintBuilder {
         let solver_vel1 = rb1.ids.active_set_offset;
         let solver_vel2 = rb2.ids.active_set_offset;
         let force_dir1 = -manifold.data.normal;
+        let cfm_gain = manifold.data.contact_model.cfm_gain(params);
 
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let tangents1 = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &manifold.data.friction_direction(),
+            &vels1.linvel,
+            &vels2.linvel,
+        );
 
         for (l, manifold_points) in manifold
             .data
@@ -190,12 +215,14 @@ impl TwoBodyConstraintBuilder {
         {
             let builder = &mut out_builders[l];
             let constraint = &mut out_constraints[l];
+            builder.contact_model = manifold.data.contact_model;
             constraint.dir1 = force_dir1;
             constraint.im1 = mprops1.effective_inv_mass;
             constraint.im2 = mprops2.effective_inv_mass;
             constraint.solver_vel1 = solver_vel1;
             constraint.solver_vel2 = solver_vel2;
             constraint.manifold_id = manifold_id;
+            constraint.has_fast_contact = false;
             constraint.num_contacts = manifold_points.len() as u8;
             #[cfg(feature = "dim3")]
             {
@@ -211,12 +238,15 @@ impl TwoBodyConstraintBuilder {
 
                 let vel1 = vels1.linvel + vels1.angvel.gcross(dp1);
                 let vel2 = vels2.linvel + vels2.angvel.gcross(dp2);
+                // One-way contacts approached from the wrong side must not apply any impulse.
+                let is_enabled = !manifold_point.is_one_way_disabled(&(vel2 - vel1)) as u32 as Real;
 
-                constraint.limit = manifold_point.friction;
+                constraint.limit = manifold_point.friction_limit();
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_damping_rhs;
                 {
                     let gcross1 = mprops1
                         .effective_world_inv_inertia_sqrt
@@ -229,30 +259,41 @@ impl TwoBodyConstraintBuilder {
                     let projected_mass = utils::inv(
                         force_dir1.dot(&imsum.component_mul(&force_dir1))
                             + gcross1.gdot(gcross1)
-                            + gcross2.gdot(gcross2),
+                            + gcross2.gdot(gcross2)
+                            + cfm_gain,
                     );
 
-                    let is_bouncy = manifold_point.is_bouncy() as u32 as Real;
+                    let projected_velocity = (vel1 - vel2).dot(&force_dir1);
+                    let is_bouncy = manifold_point.is_bouncy_at(projected_velocity) as u32 as Real;
 
                     normal_rhs_wo_bias =
-                        (is_bouncy * manifold_point.restitution) * (vel1 - vel2).dot(&force_dir1);
+                        (is_bouncy * manifold_point.restitution) * projected_velocity;
+                    normal_damping_rhs = params.contact_normal_damping * projected_velocity;
 
                     constraint.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
                         gcross2,
                         rhs: na::zero(),
                         rhs_wo_bias: na::zero(),
-                        impulse: manifold_point.warmstart_impulse,
+                        impulse: is_enabled
+                            * manifold_point
+                                .warmstart_impulse
+                                .min(manifold_point.max_normal_impulse),
                         impulse_accumulator: na::zero(),
-                        r: projected_mass,
+                        max_impulse: manifold_point.max_normal_impulse,
+                        r: is_enabled * projected_mass,
+                        cfm_gain,
                         r_mat_elts: [0.0; 2],
                     };
                 }
 
+                let mut tangent_damping_rhs = [0.0; DIM - 1];
+
                 // Tangent parts.
                 {
                     constraint.elements[k].tangent_part.impulse =
-                        manifold_point.warmstart_tangent_impulse;
+                        manifold_point.warmstart_tangent_impulse * is_enabled;
+                    constraint.elements[k].tangent_part.impulse_accumulator = na::zero();
 
                     for j in 0..DIM - 1 {
                         let gcross1 = mprops1
@@ -266,20 +307,23 @@ impl TwoBodyConstraintBuilder {
                             + gcross1.gdot(gcross1)
                             + gcross2.gdot(gcross2);
                         let rhs_wo_bias = manifold_point.tangent_velocity.dot(&tangents1[j]);
+                        tangent_damping_rhs[j] =
+                            params.contact_tangent_damping * (vel1 - vel2).dot(&tangents1[j]);
 
                         constraint.elements[k].tangent_part.gcross1[j] = gcross1;
                         constraint.elements[k].tangent_part.gcross2[j] = gcross2;
                         constraint.elements[k].tangent_part.rhs_wo_bias[j] = rhs_wo_bias;
                         constraint.elements[k].tangent_part.rhs[j] = rhs_wo_bias;
-                        constraint.elements[k].tangent_part.r[j] = if cfg!(feature = "dim2") {
-                            utils::inv(r)
-                        } else {
-                            r
-                        };
+                        constraint.elements[k].tangent_part.r[j] =
+                            if independent_friction_directions(params) {
+                                utils::inv(r)
+                            } else {
+                                r
+                            };
                     }
 
                     #[cfg(feature = "dim3")]
-                    {
+                    if !independent_friction_directions(params) {
                         constraint.elements[k].tangent_part.r[2] = 2.0
                             * (constraint.elements[k].tangent_part.gcross1[0]
                                 .gdot(constraint.elements[k].tangent_part.gcross1[1])
@@ -301,12 +345,38 @@ impl TwoBodyConstraintBuilder {
                     tangent_vel: manifold_point.tangent_velocity,
                     dist: manifold_point.dist,
                     normal_rhs_wo_bias,
+                    normal_damping_rhs,
+                    tangent_damping_rhs,
+                    penetration_correction_factor: manifold_point.penetration_correction_factor,
                 };
 
                 builder.infos[k] = infos;
                 constraint.manifold_contact_id[k] = manifold_point.contact_id;
             }
 
+            // Rolling part.
+            {
+                #[cfg(feature = "dim2")]
+                let rolling_axes = [1.0];
+                #[cfg(feature = "dim3")]
+                let rolling_axes = tangents1;
+                let rolling_part = &mut constraint.rolling_part;
+                rolling_part.impulse = na::zero();
+                rolling_part.coefficient = manifold_points[0].rolling_friction;
+
+                for j in 0..DIM - 1 {
+                    let gcross1 = mprops1
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(rolling_axes[j]);
+                    let gcross2 = mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-rolling_axes[j]);
+                    rolling_part.gcross1[j] = gcross1;
+                    rolling_part.gcross2[j] = gcross2;
+                    rolling_part.r[j] = utils::inv(gcross1.gdot(gcross1) + gcross2.gdot(gcross2));
+                }
+            }
+
             if BLOCK_SOLVER_ENABLED {
                 // Coupling between consecutive pairs.
                 for k in 0..manifold_points.len() / 2 {
@@ -330,7 +400,12 @@ impl TwoBodyConstraintBuilder {
                     r_mat.m11 = utils::inv(r0);
                     r_mat.m22 = utils::inv(r1);
 
-                    if let Some(inv) = r_mat.try_inverse() {
+                    if r0 == 0.0 || r1 == 0.0 {
+                        // At least one of the contacts is disabled (e.g. a one-way contact),
+                        // so the other one is solved alone.
+                        constraint.elements[k0].normal_part.r_mat_elts = [r0, r1];
+                        constraint.elements[k1].normal_part.r_mat_elts = [0.0; 2];
+                    } else if let Some(inv) = r_mat.try_inverse() {
                         constraint.elements[k0].normal_part.r_mat_elts = [inv.m11, inv.m22];
                         constraint.elements[k1].normal_part.r_mat_elts = [inv.m12, r_mat.m12];
                     } else {
@@ -361,7 +436,14 @@ impl TwoBodyConstraintBuilder {
     ) {
         let rb1 = &bodies[constraint.solver_vel1];
         let rb2 = &bodies[constraint.solver_vel2];
-        self.update_with_positions(params, solved_dt, &rb1.position, &rb2.position, constraint)
+        self.update_with_positions(
+            params,
+            solved_dt,
+            &rb1.position,
+            &rb2.position,
+            rb1.ccd_thickness + rb2.ccd_thickness,
+            constraint,
+        )
     }
 
     // Used by both generic and non-generic builders..
@@ -371,11 +453,12 @@ impl TwoBodyConstraintBuilder {
         solved_dt: Real,
         rb1_pos: &Isometry<Real>,
         rb2_pos: &Isometry<Real>,
+        ccd_thickness: Real,
         constraint: &mut TwoBodyConstraint,
     ) {
-        let cfm_factor = params.contact_cfm_factor();
+        let (cfm_factor, erp_inv_dt, allowed_linear_error, max_corrective_velocity) =
+            self.contact_model.regularization_coefficients(params);
         let inv_dt = params.inv_dt();
-        let erp_inv_dt = params.contact_erp_inv_dt();
 
         let all_infos = &self.infos[..constraint.num_contacts as usize];
         let all_elements = &mut constraint.elements[..constraint.num_contacts as usize];
@@ -388,6 +471,12 @@ impl TwoBodyConstraintBuilder {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
+        // NOTE: at the first substep, the impulses are the warmstart impulses from the previous
+        //       timestep. They must not be accumulated since they are already accounted for by
+        //       the impulses of the first substep.
+        let is_first_substep = solved_dt == 0.0;
+        let mut is_fast_contact = false;
+
         for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
             // Tangent velocity is equivalent to the first body’s surface moving artificially.
             let p1 = rb1_pos * info.local_p1 + info.tangent_vel * solved_dt;
@@ -397,39 +486,56 @@ impl TwoBodyConstraintBuilder {
             // Normal part.
             {
                 let rhs_wo_bias = info.normal_rhs_wo_bias + dist.max(0.0) * inv_dt;
-                let rhs_bias = (erp_inv_dt * (dist + params.allowed_linear_error()))
-                    .clamp(-params.max_corrective_velocity(), 0.0);
-                let new_rhs = rhs_wo_bias + rhs_bias;
+                let rhs_bias = (info.penetration_correction_factor
+                    * erp_inv_dt
+                    * (dist + allowed_linear_error))
+                    .clamp(-max_corrective_velocity, 0.0);
+                let new_rhs = rhs_wo_bias + rhs_bias + info.normal_damping_rhs;
+                is_fast_contact |= -new_rhs * params.dt > ccd_thickness * 0.5;
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                if !is_first_substep {
+                    element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                }
                 element.normal_part.impulse *= params.warmstart_coefficient;
             }
 
             // Tangent part.
             {
-                element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                if !is_first_substep {
+                    element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                }
                 element.tangent_part.impulse *= params.warmstart_coefficient;
 
                 for j in 0..DIM - 1 {
                     let bias = (p1 - p2).dot(&tangents1[j]) * inv_dt;
-                    element.tangent_part.rhs[j] = element.tangent_part.rhs_wo_bias[j] + bias;
+                    element.tangent_part.rhs[j] =
+                        element.tangent_part.rhs_wo_bias[j] + bias + info.tangent_damping_rhs[j];
                 }
             }
         }
 
         constraint.cfm_factor = cfm_factor;
+        #[cfg(feature = "dim3")]
+        {
+            constraint.decoupled_friction =
+                params.friction_model == FrictionModel::PyramidDecoupled;
+        }
+        constraint.rolling_part.impulse *= params.warmstart_coefficient;
+        constraint.is_spring = self.contact_model != ContactModel::Rigid;
+        constraint.has_fast_contact |= is_fast_contact;
     }
 }
 
 impl TwoBodyConstraint {
-    pub fn warmstart(&mut self, solver_vels: &mut [SolverVel<Real>]) {
-        let mut solver_vel1 = solver_vels[self.solver_vel1];
-        let mut solver_vel2 = solver_vels[self.solver_vel2];
+    pub fn warmstart<V: SolverVelsAccess + ?Sized>(&mut self, solver_vels: &mut V) {
+        let mut solver_vel1 = solver_vels.get_vel(self.solver_vel1);
+        let mut solver_vel2 = solver_vels.get_vel(self.solver_vel2);
 
         TwoBodyConstraintElement::warmstart_group(
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
@@ -439,55 +545,79 @@ impl TwoBodyConstraint {
             &mut solver_vel2,
         );
 
-        solver_vels[self.solver_vel1] = solver_vel1;
-        solver_vels[self.solver_vel2] = solver_vel2;
+        solver_vels.set_vel(self.solver_vel1, solver_vel1);
+        solver_vels.set_vel(self.solver_vel2, solver_vel2);
     }
 
-    pub fn solve(
+    pub fn solve<V: SolverVelsAccess + ?Sized>(
         &mut self,
-        solver_vels: &mut [SolverVel<Real>],
+        solver_vels: &mut V,
         solve_normal: bool,
         solve_friction: bool,
     ) {
-        let mut solver_vel1 = solver_vels[self.solver_vel1];
-        let mut solver_vel2 = solver_vels[self.solver_vel2];
+        let mut solver_vel1 = solver_vels.get_vel(self.solver_vel1);
+        let mut solver_vel2 = solver_vels.get_vel(self.solver_vel2);
 
         TwoBodyConstraintElement::solve_group(
             self.cfm_factor,
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
             &self.im1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.decoupled_friction,
             &mut solver_vel1,
             &mut solver_vel2,
             solve_normal,
             solve_friction,
         );
 
-        solver_vels[self.solver_vel1] = solver_vel1;
-        solver_vels[self.solver_vel2] = solver_vel2;
+        solver_vels.set_vel(self.solver_vel1, solver_vel1);
+        solver_vels.set_vel(self.solver_vel2, solver_vel2);
     }
 
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
+        let normal = manifold.data.normal;
+        manifold.data.has_fast_contact |= self.has_fast_contact;
+        manifold.data.set_tangents(contact_tangents(
+            &self.dir1,
+            #[cfg(feature = "dim3")]
+            &self.tangent1,
+        ));
 
         for k in 0..self.num_contacts as usize {
             let contact_id = self.manifold_contact_id[k];
-            let active_contact = &mut manifold.points[contact_id as usize];
-            active_contact.data.warmstart_impulse = self.elements[k].normal_part.impulse;
-            active_contact.data.warmstart_tangent_impulse = self.elements[k].tangent_part.impulse;
-            active_contact.data.impulse = self.elements[k].normal_part.total_impulse();
-            active_contact.data.tangent_impulse = self.elements[k].tangent_part.total_impulse();
+            let contact_data = solver_contact_data_mut(manifold, contact_id);
+            contact_data.warmstart_impulse = self.elements[k].normal_part.impulse;
+            contact_data.warmstart_tangent_impulse = self.elements[k].tangent_part.impulse;
+            contact_data.impulse = self.elements[k].normal_part.total_impulse();
+            contact_data.tangent_impulse = self.elements[k].tangent_part.total_impulse();
+            contact_data.effective_mass = self.elements[k].normal_part.r;
+            contact_data.world_impulse = super::contact_world_impulse(
+                &normal,
+                &self.dir1,
+                #[cfg(feature = "dim3")]
+                &self.tangent1,
+                contact_data.impulse,
+                &contact_data.tangent_impulse,
+            );
         }
     }
 
     pub fn remove_cfm_and_bias_from_rhs(&mut self) {
-        self.cfm_factor = 1.0;
+        if !self.is_spring {
+            self.cfm_factor = 1.0;
+        }
+
         for elt in &mut self.elements {
-            elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            if !self.is_spring {
+                elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            }
             // elt.normal_part.impulse = elt.normal_part.total_impulse;
 
             elt.tangent_part.rhs = elt.tangent_part.rhs_wo_bias;
@@ -500,6 +630,7 @@ impl TwoBodyConstraint {
 #[cfg(feature = "dim3")]
 pub(crate) fn compute_tangent_contact_directions<N>(
     force_dir1: &Vector<N>,
+    friction_dir: &Vector<N>,
     linvel1: &Vector<N>,
     linvel2: &Vector<N>,
 ) -> [Vector<N>; DIM - 1]
@@ -509,25 +640,86 @@ where
 {
     use na::SimdValue;
 
-    // Compute the tangent direction. Pick the direction of
-    // the linear relative velocity, if it is not too small.
-    // Otherwise use a fallback direction.
+    // Compute the tangent direction. Pick the user-defined friction
+    // direction projected on the contact plane, if it is not too small.
+    // Otherwise pick the direction of the linear relative velocity, if
+    // it is not too small. Otherwise use a fallback direction.
+    let mut tangent_friction_dir = friction_dir - force_dir1 * (force_dir1.dot(friction_dir));
     let relative_linvel = linvel1 - linvel2;
     let mut tangent_relative_linvel =
         relative_linvel - force_dir1 * (force_dir1.dot(&relative_linvel));
 
-    let tangent_linvel_norm = {
+    let (tangent_friction_dir_norm, tangent_linvel_norm) = {
         let _disable_fe_except =
             crate::utils::DisableFloatingPointExceptionsFlags::disable_floating_point_exceptions();
-        tangent_relative_linvel.normalize_mut()
+        (
+            tangent_friction_dir.normalize_mut(),
+            tangent_relative_linvel.normalize_mut(),
+        )
     };
 
     const THRESHOLD: Real = 1.0e-4;
+    let use_friction_dir = tangent_friction_dir_norm.simd_ge(N::splat(THRESHOLD));
     let use_fallback = tangent_linvel_norm.simd_lt(N::splat(THRESHOLD));
     let tangent_fallback = force_dir1.orthonormal_vector();
 
     let tangent1 = tangent_fallback.select(use_fallback, tangent_relative_linvel);
+    let tangent1 = tangent_friction_dir.select(use_friction_dir, tangent1);
     let bitangent1 = force_dir1.cross(&tangent1);
 
     [tangent1, bitangent1]
 }
+
+/// The world-space impulse applied by a contact to the rigid-body of the first collider of a
+/// contact manifold with the given `normal`, from the impulses computed by a constraint with the
+/// given force directions.
+#[inline(always)]
+pub(crate) fn contact_world_impulse(
+    normal: &Vector<Real>,
+    dir1: &Vector<Real>,
+    #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
+    impulse: Real,
+    tangent_impulse: &TangentImpulse<Real>,
+) -> Vector<Real> {
+    let tangents1 = contact_tangents(
+        dir1,
+        #[cfg(feature = "dim3")]
+        tangent1,
+    );
+
+    let mut result = dir1 * impulse;
+    for j in 0..DIM - 1 {
+        result += tangents1[j] * tangent_impulse[j];
+    }
+
+    // NOTE: the first body of a one-body constraint is attached to the second collider
+    //       if the contact was flipped because of dominance. In that case `dir1` is the
+    //       contact normal instead of its opposite.
+    if dir1.dot(normal) > 0.0 {
+        -result
+    } else {
+        result
+    }
+}
+
+/// The friction directions of a constraint with the given force directions.
+#[inline(always)]
+pub(crate) fn contact_tangents(
+    dir1: &Vector<Real>,
+    #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
+) -> [Vector<Real>; DIM - 1] {
+    #[cfg(feature = "dim2")]
+    return dir1.orthonormal_basis();
+    #[cfg(feature = "dim3")]
+    return [*tangent1, dir1.cross(tangent1)];
+}
+
+/// Are the friction directions of the contact constraints solved independently, one after the
+/// other?
+///
+/// If so, the tangent parts of the constraints store the inverse of the diagonal elements of
+/// their effective mass matrix in `r`, and the off-diagonal coupling term isn’t computed.
+#[inline(always)]
+pub(crate) fn independent_friction_directions(params: &IntegrationParameters) -> bool {
+    cfg!(feature = "dim2") || params.friction_model == FrictionModel::PyramidDecoupled
+}
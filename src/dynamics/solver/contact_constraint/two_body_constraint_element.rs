@@ -1,10 +1,99 @@
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
 use crate::dynamics::solver::SolverVel;
-use crate::math::{AngVector, TangentImpulse, Vector, DIM};
+use crate::math::{AngVector, Real, TangentImpulse, Vector, DIM};
 use crate::utils::{SimdBasis, SimdDot, SimdRealCopy};
 use na::Vector2;
 use simba::simd::SimdValue;
 
+/// The friction coefficients of a contact constraint along each of its tangent directions.
+///
+/// In 3D, the tangent impulse is capped to the ellipse with these coefficients (multiplied by
+/// the normal impulse) as semi-axes. The ellipse is rescaled into a circle so that capping an
+/// isotropic friction gives exactly the same result as capping the impulse’s magnitude. With
+/// decoupled friction, each component of the tangent impulse is capped independently instead.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FrictionLimit<N: SimdRealCopy> {
+    // The largest friction coefficient.
+    max: N,
+    // The scale factors mapping the friction circle of radius `max` to the friction ellipse,
+    // and their inverses. They are zero along the directions without friction.
+    #[cfg(feature = "dim3")]
+    scale: [N; 2],
+    #[cfg(feature = "dim3")]
+    inv_scale: [N; 2],
+    // Are the tangent directions solved and capped independently?
+    #[cfg(feature = "dim3")]
+    pub decoupled: bool,
+}
+
+impl<N: SimdRealCopy> FrictionLimit<N> {
+    #[inline(always)]
+    pub fn new(limit: [N; DIM - 1], #[cfg(feature = "dim3")] decoupled: bool) -> Self {
+        #[cfg(feature = "dim2")]
+        return Self { max: limit[0] };
+
+        #[cfg(feature = "dim3")]
+        {
+            let _disable_fe_except =
+                crate::utils::DisableFloatingPointExceptionsFlags::disable_floating_point_exceptions();
+            let zero = N::zero();
+            let max = limit[0].simd_max(limit[1]);
+            let scale = limit.map(|l| (l / max).select(l.simd_gt(zero), zero));
+            let inv_scale = limit.map(|l| (max / l).select(l.simd_gt(zero), zero));
+            Self {
+                max,
+                scale,
+                inv_scale,
+                decoupled,
+            }
+        }
+    }
+
+    /// The friction limit for a contact with the given normal impulse.
+    #[inline(always)]
+    pub fn scaled(mut self, normal_impulse: N) -> Self {
+        self.max *= normal_impulse;
+        self
+    }
+
+    #[cfg(feature = "dim2")]
+    #[inline(always)]
+    pub fn clamp(&self, impulse: N) -> N {
+        impulse.simd_clamp(-self.max, self.max)
+    }
+
+    #[cfg(feature = "dim3")]
+    #[inline(always)]
+    pub fn clamp(&self, impulse: TangentImpulse<N>) -> TangentImpulse<N> {
+        if self.decoupled {
+            return na::vector![
+                self.clamp_axis(0, impulse[0]),
+                self.clamp_axis(1, impulse[1])
+            ];
+        }
+
+        let _disable_fe_except =
+            crate::utils::DisableFloatingPointExceptionsFlags::disable_floating_point_exceptions();
+        let circle_impulse = na::vector![
+            impulse[0] * self.inv_scale[0],
+            impulse[1] * self.inv_scale[1]
+        ]
+        .simd_cap_magnitude(self.max);
+        na::vector![
+            circle_impulse[0] * self.scale[0],
+            circle_impulse[1] * self.scale[1]
+        ]
+    }
+
+    /// Clamps the impulse along the i-th tangent direction, ignoring the other direction.
+    #[cfg(feature = "dim3")]
+    #[inline(always)]
+    pub fn clamp_axis(&self, i: usize, impulse: N) -> N {
+        let max = self.max * self.scale[i];
+        impulse.simd_clamp(-max, max)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct TwoBodyConstraintTangentPart<N: SimdRealCopy> {
     pub gcross1: [AngVector<N>; DIM - 1],
@@ -87,7 +176,7 @@ impl<N: SimdRealCopy> TwoBodyConstraintTangentPart<N> {
         tangents1: [&Vector<N>; DIM - 1],
         im1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limit: FrictionLimit<N>,
         solver_vel1: &mut SolverVel<N>,
         solver_vel2: &mut SolverVel<N>,
     ) where
@@ -100,7 +189,7 @@ impl<N: SimdRealCopy> TwoBodyConstraintTangentPart<N> {
                 - tangents1[0].dot(&solver_vel2.linear)
                 + self.gcross2[0].gdot(solver_vel2.angular)
                 + self.rhs[0];
-            let new_impulse = (self.impulse[0] - self.r[0] * dvel).simd_clamp(-limit, limit);
+            let new_impulse = limit.clamp(self.impulse[0] - self.r[0] * dvel);
             let dlambda = new_impulse - self.impulse[0];
             self.impulse[0] = new_impulse;
 
@@ -112,7 +201,27 @@ impl<N: SimdRealCopy> TwoBodyConstraintTangentPart<N> {
         }
 
         #[cfg(feature = "dim3")]
-        {
+        if limit.decoupled {
+            // NOTE: each direction is solved independently, one after the other, so `self.r`
+            //       contains the inverse of the diagonal elements of the effective mass matrix,
+            //       and the coupling term `self.r[2]` isn’t needed.
+            for j in 0..DIM - 1 {
+                let dvel = tangents1[j].dot(&solver_vel1.linear)
+                    + self.gcross1[j].gdot(solver_vel1.angular)
+                    - tangents1[j].dot(&solver_vel2.linear)
+                    + self.gcross2[j].gdot(solver_vel2.angular)
+                    + self.rhs[j];
+                let new_impulse = limit.clamp_axis(j, self.impulse[j] - self.r[j] * dvel);
+                let dlambda = new_impulse - self.impulse[j];
+                self.impulse[j] = new_impulse;
+
+                solver_vel1.linear += tangents1[j].component_mul(im1) * dlambda;
+                solver_vel1.angular += self.gcross1[j] * dlambda;
+
+                solver_vel2.linear += tangents1[j].component_mul(im2) * -dlambda;
+                solver_vel2.angular += self.gcross2[j] * dlambda;
+            }
+        } else {
             let dvel_0 = tangents1[0].dot(&solver_vel1.linear)
                 + self.gcross1[0].gdot(solver_vel1.angular)
                 - tangents1[0].dot(&solver_vel2.linear)
@@ -133,12 +242,7 @@ impl<N: SimdRealCopy> TwoBodyConstraintTangentPart<N> {
                 );
             let delta_impulse = na::vector![inv_lhs * dvel_0, inv_lhs * dvel_1];
             let new_impulse = self.impulse - delta_impulse;
-            let new_impulse = {
-                let _disable_fe_except =
-                        crate::utils::DisableFloatingPointExceptionsFlags::
-                        disable_floating_point_exceptions();
-                new_impulse.simd_cap_magnitude(limit)
-            };
+            let new_impulse = limit.clamp(new_impulse);
 
             let dlambda = new_impulse - self.impulse;
             self.impulse = new_impulse;
@@ -154,6 +258,64 @@ impl<N: SimdRealCopy> TwoBodyConstraintTangentPart<N> {
     }
 }
 
+/// The part of a contact constraint opposing the relative rolling of its two bodies.
+///
+/// Its impulse is an angular impulse along each tangent direction in 3D, or along the rotation
+/// axis in 2D. It is capped by the rolling friction coefficient multiplied by the sum of the
+/// normal impulses of the contact constraint.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct TwoBodyConstraintRollingPart<N: SimdRealCopy> {
+    pub gcross1: [AngVector<N>; DIM - 1],
+    pub gcross2: [AngVector<N>; DIM - 1],
+    pub impulse: TangentImpulse<N>,
+    pub r: [N; DIM - 1],
+    // The rolling friction coefficient.
+    pub coefficient: N,
+}
+
+impl<N: SimdRealCopy> TwoBodyConstraintRollingPart<N> {
+    pub fn zero() -> Self {
+        Self {
+            gcross1: [na::zero(); DIM - 1],
+            gcross2: [na::zero(); DIM - 1],
+            impulse: na::zero(),
+            r: [na::zero(); DIM - 1],
+            coefficient: na::zero(),
+        }
+    }
+
+    #[inline]
+    pub fn warmstart(&mut self, solver_vel1: &mut SolverVel<N>, solver_vel2: &mut SolverVel<N>) {
+        for j in 0..DIM - 1 {
+            solver_vel1.angular += self.gcross1[j] * self.impulse[j];
+            solver_vel2.angular += self.gcross2[j] * self.impulse[j];
+        }
+    }
+
+    #[inline]
+    pub fn solve(
+        &mut self,
+        normal_impulse: N,
+        solver_vel1: &mut SolverVel<N>,
+        solver_vel2: &mut SolverVel<N>,
+    ) where
+        AngVector<N>: SimdDot<AngVector<N>, Result = N>,
+    {
+        let limit = self.coefficient * normal_impulse;
+
+        for j in 0..DIM - 1 {
+            let dvel = self.gcross1[j].gdot(solver_vel1.angular)
+                + self.gcross2[j].gdot(solver_vel2.angular);
+            let new_impulse = (self.impulse[j] - self.r[j] * dvel).simd_clamp(-limit, limit);
+            let dlambda = new_impulse - self.impulse[j];
+            self.impulse[j] = new_impulse;
+
+            solver_vel1.angular += self.gcross1[j] * dlambda;
+            solver_vel2.angular += self.gcross2[j] * dlambda;
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct TwoBodyConstraintNormalPart<N: SimdRealCopy> {
     pub gcross1: AngVector<N>,
@@ -162,7 +324,11 @@ pub(crate) struct TwoBodyConstraintNormalPart<N: SimdRealCopy> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub impulse_accumulator: N,
+    // The maximum value of `impulse`.
+    pub max_impulse: N,
     pub r: N,
+    // The compliance of spring contacts, already included in `r`.
+    pub cfm_gain: N,
     // For coupled constraint pairs, even constraints store the
     // diagonal of the projected mass matrix. Odd constraints
     // store the off-diagonal element of the projected mass matrix,
@@ -179,7 +345,9 @@ impl<N: SimdRealCopy> TwoBodyConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             impulse_accumulator: na::zero(),
+            max_impulse: N::splat(Real::INFINITY),
             r: na::zero(),
+            cfm_gain: na::zero(),
             r_mat_elts: [N::zero(); 2],
         }
     }
@@ -221,8 +389,10 @@ impl<N: SimdRealCopy> TwoBodyConstraintNormalPart<N> {
         let dvel = dir1.dot(&solver_vel1.linear) + self.gcross1.gdot(solver_vel1.angular)
             - dir1.dot(&solver_vel2.linear)
             + self.gcross2.gdot(solver_vel2.angular)
-            + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+            + self.rhs
+            + self.cfm_gain * self.impulse;
+        let new_impulse = (cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero()))
+            .simd_min(self.max_impulse);
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -282,11 +452,13 @@ impl<N: SimdRealCopy> TwoBodyConstraintNormalPart<N> {
         let dvel_a = dvel_lin
             + constraint_a.gcross1.gdot(solver_vel1.angular)
             + constraint_a.gcross2.gdot(solver_vel2.angular)
-            + constraint_a.rhs;
+            + constraint_a.rhs
+            + constraint_a.cfm_gain * constraint_a.impulse;
         let dvel_b = dvel_lin
             + constraint_b.gcross1.gdot(solver_vel1.angular)
             + constraint_b.gcross2.gdot(solver_vel2.angular)
-            + constraint_b.rhs;
+            + constraint_b.rhs
+            + constraint_b.cfm_gain * constraint_b.impulse;
 
         let prev_impulse = Vector2::new(constraint_a.impulse, constraint_b.impulse);
         let new_impulse = Self::solve_mlcp_two_constraints(
@@ -298,6 +470,10 @@ impl<N: SimdRealCopy> TwoBodyConstraintNormalPart<N> {
             constraint_b.r_mat_elts,
             cfm_factor,
         );
+        let new_impulse = Vector2::new(
+            new_impulse.x.simd_min(constraint_a.max_impulse),
+            new_impulse.y.simd_min(constraint_b.max_impulse),
+        );
 
         let dlambda = new_impulse - prev_impulse;
 
@@ -328,6 +504,7 @@ impl<N: SimdRealCopy> TwoBodyConstraintElement<N> {
     #[inline]
     pub fn warmstart_group(
         elements: &mut [Self],
+        rolling_part: &mut TwoBodyConstraintRollingPart<N>,
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im1: &Vector<N>,
@@ -348,17 +525,21 @@ impl<N: SimdRealCopy> TwoBodyConstraintElement<N> {
                 .tangent_part
                 .warmstart(tangents1, im1, im2, solver_vel1, solver_vel2);
         }
+
+        rolling_part.warmstart(solver_vel1, solver_vel2);
     }
 
     #[inline]
     pub fn solve_group(
         cfm_factor: N,
         elements: &mut [Self],
+        rolling_part: &mut TwoBodyConstraintRollingPart<N>,
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
         im1: &Vector<N>,
         im2: &Vector<N>,
-        limit: N,
+        limit: [N; DIM - 1],
+        #[cfg(feature = "dim3")] decoupled_friction: bool,
         solver_vel1: &mut SolverVel<N>,
         solver_vel2: &mut SolverVel<N>,
         solve_restitution: bool,
@@ -407,12 +588,22 @@ impl<N: SimdRealCopy> TwoBodyConstraintElement<N> {
             let tangents1 = [tangent1, &dir1.cross(tangent1)];
             #[cfg(feature = "dim2")]
             let tangents1 = [&dir1.orthonormal_vector()];
+            let limit = FrictionLimit::new(
+                limit,
+                #[cfg(feature = "dim3")]
+                decoupled_friction,
+            );
 
             for element in elements.iter_mut() {
-                let limit = limit * element.normal_part.impulse;
+                let limit = limit.scaled(element.normal_part.impulse);
                 let part = &mut element.tangent_part;
                 part.solve(tangents1, im1, im2, limit, solver_vel1, solver_vel2);
             }
+
+            let normal_impulse = elements
+                .iter()
+                .fold(N::zero(), |sum, element| sum + element.normal_part.impulse);
+            rolling_part.solve(normal_impulse, solver_vel1, solver_vel2);
         }
     }
 }
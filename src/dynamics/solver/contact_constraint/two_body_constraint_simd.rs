@@ -1,22 +1,24 @@
-use super::{TwoBodyConstraintElement, TwoBodyConstraintNormalPart};
+use super::{TwoBodyConstraintElement, TwoBodyConstraintNormalPart, TwoBodyConstraintRollingPart};
 use crate::dynamics::integration_parameters::BLOCK_SOLVER_ENABLED;
 use crate::dynamics::solver::solver_body::SolverBody;
-use crate::dynamics::solver::{ContactPointInfos, SolverVel};
+use crate::dynamics::solver::{ContactPointInfos, SolverVel, SolverVelsAccess};
+#[cfg(feature = "dim3")]
+use crate::dynamics::FrictionModel;
 use crate::dynamics::{
     IntegrationParameters, MultibodyJointSet, RigidBodyIds, RigidBodyMassProps, RigidBodySet,
     RigidBodyVelocity,
 };
-use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::geometry::{solver_contact_data_mut, ContactManifold, ContactManifoldIndex};
 use crate::math::{
-    AngVector, AngularInertia, Isometry, Point, Real, SimdReal, TangentImpulse, Vector, DIM,
-    MAX_MANIFOLD_POINTS, SIMD_WIDTH,
+    AngVector, AngularInertia, Isometry, Point, Real, SimdBool, SimdReal, TangentImpulse, Vector,
+    DIM, MAX_MANIFOLD_POINTS, SIMD_WIDTH,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::SimdBasis;
 use crate::utils::{self, SimdAngularInertia, SimdCross, SimdDot};
 use num::Zero;
 use parry::utils::SdpMatrix2;
-use simba::simd::{SimdPartialOrd, SimdValue};
+use simba::simd::{SimdBool as _, SimdPartialOrd, SimdValue};
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct TwoBodyConstraintBuilderSimd {
@@ -27,6 +29,7 @@ impl TwoBodyConstraintBuilderSimd {
     pub fn generate(
         manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
         manifolds: [&ContactManifold; SIMD_WIDTH],
+        params: &IntegrationParameters,
         bodies: &RigidBodySet,
         out_builders: &mut [TwoBodyConstraintBuilderSimd],
         out_constraints: &mut [TwoBodyConstraintSimd],
@@ -70,11 +73,21 @@ impl TwoBodyConstraintBuilderSimd {
         let solver_vel2 = gather![|ii| ids2[ii].active_set_offset];
 
         let num_active_contacts = manifolds[0].data.num_active_contacts();
+        let normal_damping = SimdReal::splat(params.contact_normal_damping);
+        let tangent_damping = SimdReal::splat(params.contact_tangent_damping);
 
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let tangents1 = {
+            let friction_dir = Vector::from(gather![|ii| manifolds[ii].data.friction_direction()]);
+            super::compute_tangent_contact_directions(
+                &force_dir1,
+                &friction_dir,
+                &linvel1,
+                &linvel2,
+            )
+        };
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points =
@@ -90,6 +103,7 @@ impl TwoBodyConstraintBuilderSimd {
             constraint.solver_vel1 = solver_vel1;
             constraint.solver_vel2 = solver_vel2;
             constraint.manifold_id = manifold_id;
+            constraint.has_fast_contact = SimdBool::splat(false);
             constraint.num_contacts = num_points as u8;
             #[cfg(feature = "dim3")]
             {
@@ -97,13 +111,11 @@ impl TwoBodyConstraintBuilderSimd {
             }
 
             for k in 0..num_points {
-                let friction = SimdReal::from(gather![|ii| manifold_points[ii][k].friction]);
                 let restitution = SimdReal::from(gather![|ii| manifold_points[ii][k].restitution]);
-                let is_bouncy = SimdReal::from(gather![
-                    |ii| manifold_points[ii][k].is_bouncy() as u32 as Real
-                ]);
                 let warmstart_impulse =
                     SimdReal::from(gather![|ii| manifold_points[ii][k].warmstart_impulse]);
+                let max_normal_impulse =
+                    SimdReal::from(gather![|ii| manifold_points[ii][k].max_normal_impulse]);
                 let warmstart_tangent_impulse = TangentImpulse::from(gather![|ii| manifold_points
                     [ii][k]
                     .warmstart_tangent_impulse]);
@@ -118,12 +130,20 @@ impl TwoBodyConstraintBuilderSimd {
 
                 let vel1 = linvel1 + angvel1.gcross(dp1);
                 let vel2 = linvel2 + angvel2.gcross(dp2);
-
-                constraint.limit = friction;
+                // One-way contacts approached from the wrong side must not apply any impulse.
+                let relative_vel = vel2 - vel1;
+                let is_enabled = SimdReal::from(gather![|ii| !manifold_points[ii][k]
+                    .is_one_way_disabled(&relative_vel.extract(ii))
+                    as u32 as Real]);
+
+                let friction_limit = gather![|ii| manifold_points[ii][k].friction_limit()];
+                constraint.limit =
+                    std::array::from_fn(|i| SimdReal::from(friction_limit.map(|limit| limit[i])));
                 constraint.manifold_contact_id[k] = gather![|ii| manifold_points[ii][k].contact_id];
 
                 // Normal part.
                 let normal_rhs_wo_bias;
+                let normal_damping_rhs;
                 {
                     let gcross1 = ii1.transform_vector(dp1.gcross(force_dir1));
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
@@ -136,22 +156,32 @@ impl TwoBodyConstraintBuilderSimd {
                     );
 
                     let projected_velocity = (vel1 - vel2).dot(&force_dir1);
+                    let is_bouncy = SimdReal::from(gather![|ii| manifold_points[ii][k]
+                        .is_bouncy_at(projected_velocity.extract(ii))
+                        as u32
+                        as Real]);
                     normal_rhs_wo_bias = is_bouncy * restitution * projected_velocity;
+                    normal_damping_rhs = normal_damping * projected_velocity;
 
                     constraint.elements[k].normal_part = TwoBodyConstraintNormalPart {
                         gcross1,
                         gcross2,
                         rhs: na::zero(),
                         rhs_wo_bias: na::zero(),
-                        impulse: warmstart_impulse,
+                        impulse: is_enabled * warmstart_impulse.simd_min(max_normal_impulse),
+                        max_impulse: max_normal_impulse,
                         impulse_accumulator: SimdReal::splat(0.0),
-                        r: projected_mass,
+                        r: is_enabled * projected_mass,
+                        cfm_gain: SimdReal::zero(),
                         r_mat_elts: [SimdReal::zero(); 2],
                     };
                 }
 
                 // tangent parts.
-                constraint.elements[k].tangent_part.impulse = warmstart_tangent_impulse;
+                let mut tangent_damping_rhs = [SimdReal::zero(); DIM - 1];
+                constraint.elements[k].tangent_part.impulse =
+                    warmstart_tangent_impulse * is_enabled;
+                constraint.elements[k].tangent_part.impulse_accumulator = na::zero();
 
                 for j in 0..DIM - 1 {
                     let gcross1 = ii1.transform_vector(dp1.gcross(tangents1[j]));
@@ -161,20 +191,22 @@ impl TwoBodyConstraintBuilderSimd {
                         + gcross1.gdot(gcross1)
                         + gcross2.gdot(gcross2);
                     let rhs_wo_bias = tangent_velocity.dot(&tangents1[j]);
+                    tangent_damping_rhs[j] = tangent_damping * (vel1 - vel2).dot(&tangents1[j]);
 
                     constraint.elements[k].tangent_part.gcross1[j] = gcross1;
                     constraint.elements[k].tangent_part.gcross2[j] = gcross2;
                     constraint.elements[k].tangent_part.rhs_wo_bias[j] = rhs_wo_bias;
                     constraint.elements[k].tangent_part.rhs[j] = rhs_wo_bias;
-                    constraint.elements[k].tangent_part.r[j] = if cfg!(feature = "dim2") {
-                        utils::simd_inv(r)
-                    } else {
-                        r
-                    };
+                    constraint.elements[k].tangent_part.r[j] =
+                        if super::independent_friction_directions(params) {
+                            utils::simd_inv(r)
+                        } else {
+                            r
+                        };
                 }
 
                 #[cfg(feature = "dim3")]
-                {
+                if !super::independent_friction_directions(params) {
                     constraint.elements[k].tangent_part.r[2] = SimdReal::splat(2.0)
                         * (constraint.elements[k].tangent_part.gcross1[0]
                             .gdot(constraint.elements[k].tangent_part.gcross1[1])
@@ -189,11 +221,37 @@ impl TwoBodyConstraintBuilderSimd {
                     tangent_vel: tangent_velocity,
                     dist,
                     normal_rhs_wo_bias,
+                    normal_damping_rhs,
+                    tangent_damping_rhs,
+                    penetration_correction_factor: SimdReal::from(gather![|ii| manifold_points
+                        [ii][k]
+                        .penetration_correction_factor]),
                 };
 
                 builder.infos[k] = infos;
             }
 
+            // Rolling part.
+            {
+                #[cfg(feature = "dim2")]
+                let rolling_axes = [SimdReal::splat(1.0)];
+                #[cfg(feature = "dim3")]
+                let rolling_axes = tangents1;
+                let rolling_part = &mut constraint.rolling_part;
+                rolling_part.impulse = na::zero();
+                rolling_part.coefficient =
+                    SimdReal::from(gather![|ii| manifold_points[ii][0].rolling_friction]);
+
+                for j in 0..DIM - 1 {
+                    let gcross1 = ii1.transform_vector(rolling_axes[j]);
+                    let gcross2 = ii2.transform_vector(-rolling_axes[j]);
+                    rolling_part.gcross1[j] = gcross1;
+                    rolling_part.gcross2[j] = gcross2;
+                    rolling_part.r[j] =
+                        utils::simd_inv(gcross1.gdot(gcross1) + gcross2.gdot(gcross2));
+                }
+            }
+
             if BLOCK_SOLVER_ENABLED {
                 // Coupling between consecutive pairs.
                 for k in 0..num_points / 2 {
@@ -229,9 +287,12 @@ impl TwoBodyConstraintBuilderSimd {
                     // Ignore the one with the smallest depth (it is too late to
                     // have the constraint removed from the constraint set, so just
                     // set the mass (r) matrix elements to 0.
+                    // If one of the contacts is disabled (e.g. a one-way contact), the
+                    // matrix isn’t invertible either, and the other one is solved alone.
+                    let r1_alone = r1.select(r0.simd_eq(SimdReal::zero()), SimdReal::zero());
                     constraint.elements[k0].normal_part.r_mat_elts = [
                         inv.m11.select(is_invertible, r0),
-                        inv.m22.select(is_invertible, SimdReal::zero()),
+                        inv.m22.select(is_invertible, r1_alone),
                     ];
                     constraint.elements[k1].normal_part.r_mat_elts = [
                         inv.m12.select(is_invertible, SimdReal::zero()),
@@ -274,7 +335,16 @@ impl TwoBodyConstraintBuilderSimd {
             constraint.dir1.cross(&constraint.tangent1),
         ];
 
+        // NOTE: at the first substep, the impulses are the warmstart impulses from the previous
+        //       timestep. They must not be accumulated since they are already accounted for by
+        //       the impulses of the first substep.
+        let is_first_substep = solved_dt == 0.0;
         let solved_dt = SimdReal::splat(solved_dt);
+        let dt = SimdReal::splat(params.dt);
+        let half_ccd_thickness = (SimdReal::from(gather![|ii| rb1[ii].ccd_thickness])
+            + SimdReal::from(gather![|ii| rb2[ii].ccd_thickness]))
+            * SimdReal::splat(0.5);
+        let mut is_fast_contact = SimdBool::splat(false);
 
         for (info, element) in all_infos.iter().zip(all_elements.iter_mut()) {
             // NOTE: the tangent velocity is equivalent to an additional movement of the first body’s surface.
@@ -286,29 +356,43 @@ impl TwoBodyConstraintBuilderSimd {
             {
                 let rhs_wo_bias =
                     info.normal_rhs_wo_bias + dist.simd_max(SimdReal::zero()) * inv_dt;
-                let rhs_bias = ((dist + allowed_lin_err) * erp_inv_dt)
-                    .simd_clamp(-max_corrective_velocity, SimdReal::zero());
-                let new_rhs = rhs_wo_bias + rhs_bias;
+                let rhs_bias =
+                    ((dist + allowed_lin_err) * erp_inv_dt * info.penetration_correction_factor)
+                        .simd_clamp(-max_corrective_velocity, SimdReal::zero());
+                let new_rhs = rhs_wo_bias + rhs_bias + info.normal_damping_rhs;
+                is_fast_contact = is_fast_contact | (-new_rhs * dt).simd_gt(half_ccd_thickness);
 
                 element.normal_part.rhs_wo_bias = rhs_wo_bias;
                 element.normal_part.rhs = new_rhs;
-                element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                if !is_first_substep {
+                    element.normal_part.impulse_accumulator += element.normal_part.impulse;
+                }
                 element.normal_part.impulse *= warmstart_coeff;
             }
 
             // tangent parts.
             {
-                element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                if !is_first_substep {
+                    element.tangent_part.impulse_accumulator += element.tangent_part.impulse;
+                }
                 element.tangent_part.impulse *= warmstart_coeff;
 
                 for j in 0..DIM - 1 {
                     let bias = (p1 - p2).dot(&tangents1[j]) * inv_dt;
-                    element.tangent_part.rhs[j] = element.tangent_part.rhs_wo_bias[j] + bias;
+                    element.tangent_part.rhs[j] =
+                        element.tangent_part.rhs_wo_bias[j] + bias + info.tangent_damping_rhs[j];
                 }
             }
         }
 
+        constraint.rolling_part.impulse *= warmstart_coeff;
         constraint.cfm_factor = cfm_factor;
+        constraint.has_fast_contact = constraint.has_fast_contact | is_fast_contact;
+        #[cfg(feature = "dim3")]
+        {
+            constraint.decoupled_friction =
+                params.friction_model == FrictionModel::PyramidDecoupled;
+        }
     }
 }
 
@@ -318,11 +402,18 @@ pub(crate) struct TwoBodyConstraintSimd {
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<SimdReal>, // One of the friction force directions.
     pub elements: [TwoBodyConstraintElement<SimdReal>; MAX_MANIFOLD_POINTS],
+    pub rolling_part: TwoBodyConstraintRollingPart<SimdReal>,
     pub num_contacts: u8,
     pub im1: Vector<SimdReal>,
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
-    pub limit: SimdReal,
+    // Did any contact of this constraint move the bodies by more than half their CCD thickness
+    // during one substep? This is reset at each step, and written back to the contact manifold.
+    pub has_fast_contact: SimdBool,
+    pub limit: [SimdReal; DIM - 1], // The friction coefficient along each tangent direction.
+    // Are the tangent directions of friction solved independently?
+    #[cfg(feature = "dim3")]
+    pub decoupled_friction: bool,
     pub solver_vel1: [usize; SIMD_WIDTH],
     pub solver_vel2: [usize; SIMD_WIDTH],
     pub manifold_id: [ContactManifoldIndex; SIMD_WIDTH],
@@ -330,19 +421,14 @@ pub(crate) struct TwoBodyConstraintSimd {
 }
 
 impl TwoBodyConstraintSimd {
-    pub fn warmstart(&mut self, solver_vels: &mut [SolverVel<Real>]) {
-        let mut solver_vel1 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].angular]),
-        };
+    pub fn warmstart<V: SolverVelsAccess + ?Sized>(&mut self, solver_vels: &mut V) {
+        let mut solver_vel1 = SolverVel::gather(solver_vels, self.solver_vel1);
 
-        let mut solver_vel2 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].angular]),
-        };
+        let mut solver_vel2 = SolverVel::gather(solver_vels, self.solver_vel2);
 
         TwoBodyConstraintElement::warmstart_group(
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
@@ -352,73 +438,79 @@ impl TwoBodyConstraintSimd {
             &mut solver_vel2,
         );
 
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel1[ii]].linear = solver_vel1.linear.extract(ii);
-            solver_vels[self.solver_vel1[ii]].angular = solver_vel1.angular.extract(ii);
-        }
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel2[ii]].linear = solver_vel2.linear.extract(ii);
-            solver_vels[self.solver_vel2[ii]].angular = solver_vel2.angular.extract(ii);
-        }
+        solver_vel1.scatter(solver_vels, self.solver_vel1);
+        solver_vel2.scatter(solver_vels, self.solver_vel2);
     }
 
-    pub fn solve(
+    pub fn solve<V: SolverVelsAccess + ?Sized>(
         &mut self,
-        solver_vels: &mut [SolverVel<Real>],
+        solver_vels: &mut V,
         solve_normal: bool,
         solve_friction: bool,
     ) {
-        let mut solver_vel1 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].angular]),
-        };
+        let mut solver_vel1 = SolverVel::gather(solver_vels, self.solver_vel1);
 
-        let mut solver_vel2 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].angular]),
-        };
+        let mut solver_vel2 = SolverVel::gather(solver_vels, self.solver_vel2);
 
         TwoBodyConstraintElement::solve_group(
             self.cfm_factor,
             &mut self.elements[..self.num_contacts as usize],
+            &mut self.rolling_part,
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
             &self.im1,
             &self.im2,
             self.limit,
+            #[cfg(feature = "dim3")]
+            self.decoupled_friction,
             &mut solver_vel1,
             &mut solver_vel2,
             solve_normal,
             solve_friction,
         );
 
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel1[ii]].linear = solver_vel1.linear.extract(ii);
-            solver_vels[self.solver_vel1[ii]].angular = solver_vel1.angular.extract(ii);
-        }
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel2[ii]].linear = solver_vel2.linear.extract(ii);
-            solver_vels[self.solver_vel2[ii]].angular = solver_vel2.angular.extract(ii);
-        }
+        solver_vel1.scatter(solver_vels, self.solver_vel1);
+        solver_vel2.scatter(solver_vels, self.solver_vel2);
     }
 
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
+        let has_fast_contact = self.has_fast_contact.bitmask();
+        for ii in 0..SIMD_WIDTH {
+            let manifold = &mut manifolds_all[self.manifold_id[ii]];
+            manifold.data.has_fast_contact |= (has_fast_contact >> ii) & 1 != 0;
+            manifold.data.set_tangents(super::contact_tangents(
+                &self.dir1.extract(ii),
+                #[cfg(feature = "dim3")]
+                &self.tangent1.extract(ii),
+            ));
+        }
+
         for k in 0..self.num_contacts as usize {
             let warmstart_impulses: [_; SIMD_WIDTH] = self.elements[k].normal_part.impulse.into();
             let warmstart_tangent_impulses = self.elements[k].tangent_part.impulse;
             let impulses: [_; SIMD_WIDTH] = self.elements[k].normal_part.total_impulse().into();
             let tangent_impulses = self.elements[k].tangent_part.total_impulse();
+            let effective_masses: [_; SIMD_WIDTH] = self.elements[k].normal_part.r.into();
 
             for ii in 0..SIMD_WIDTH {
                 let manifold = &mut manifolds_all[self.manifold_id[ii]];
                 let contact_id = self.manifold_contact_id[k][ii];
-                let active_contact = &mut manifold.points[contact_id as usize];
-                active_contact.data.warmstart_impulse = warmstart_impulses[ii];
-                active_contact.data.warmstart_tangent_impulse =
-                    warmstart_tangent_impulses.extract(ii);
-                active_contact.data.impulse = impulses[ii];
-                active_contact.data.tangent_impulse = tangent_impulses.extract(ii);
+                let normal = manifold.data.normal;
+                let contact_data = solver_contact_data_mut(manifold, contact_id);
+                contact_data.warmstart_impulse = warmstart_impulses[ii];
+                contact_data.warmstart_tangent_impulse = warmstart_tangent_impulses.extract(ii);
+                contact_data.impulse = impulses[ii];
+                contact_data.tangent_impulse = tangent_impulses.extract(ii);
+                contact_data.effective_mass = effective_masses[ii];
+                contact_data.world_impulse = super::contact_world_impulse(
+                    &normal,
+                    &self.dir1.extract(ii),
+                    #[cfg(feature = "dim3")]
+                    &self.tangent1.extract(ii),
+                    contact_data.impulse,
+                    &contact_data.tangent_impulse,
+                );
             }
         }
     }
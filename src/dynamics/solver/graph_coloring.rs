@@ -0,0 +1,419 @@
+use super::{
+    AnyConstraintMut, ContactConstraintTypes, ContactConstraintsSet, SolverVel, SolverVelsAccess,
+};
+use crate::math::Real;
+use na::DVector;
+use rayon::prelude::*;
+
+/// The maximum number of colors tracked per solver body.
+///
+/// Constraints that can’t be given one of these colors are solved sequentially, after all the
+/// colored constraints.
+const MAX_COLORS: usize = u64::BITS as usize;
+/// The number of constraints solved by a single parallel task.
+///
+/// Colors with fewer constraints than this are solved on the calling thread. It is much smaller
+/// under Miri so that small tests still check the parallel solver.
+const PARALLEL_CHUNK_SIZE: usize = if cfg!(miri) { 2 } else { 64 };
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ConstraintKind {
+    TwoBodies,
+    OneBody,
+    #[cfg(feature = "simd-is-enabled")]
+    SimdTwoBodies,
+    #[cfg(feature = "simd-is-enabled")]
+    SimdOneBody,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ColoredConstraint {
+    kind: ConstraintKind,
+    index: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum ColoredSolve {
+    Warmstart,
+    Restitution,
+    RestitutionWithoutBias,
+    Friction,
+}
+
+/// A pointer that can be shared between the threads solving constraints of the same color.
+#[derive(Copy, Clone)]
+struct SharedPtr<T>(*mut T);
+
+impl<T> SharedPtr<T> {
+    // NOTE: use this instead of `.0` in closures, otherwise only the raw pointer
+    //       would be captured, and it isn’t `Sync`.
+    fn get(self) -> *mut T {
+        self.0
+    }
+}
+
+// SAFETY: the constraints of the same color don’t share any solver body, and each constraint
+//         is given to a single thread, so the pointed data are never accessed concurrently.
+//         The pointers are only ever offset to the element being accessed, so no reference to
+//         the whole buffer is created by several threads at the same time.
+unsafe impl<T> Send for SharedPtr<T> {}
+unsafe impl<T> Sync for SharedPtr<T> {}
+
+/// The solver velocities shared by the threads solving the constraints of the same color.
+///
+/// Each solver velocity is read and written through the raw pointer, so the threads never
+/// create aliasing `&mut` references to the solver velocities.
+#[derive(Copy, Clone)]
+struct SharedSolverVels {
+    ptr: SharedPtr<SolverVel<Real>>,
+    len: usize,
+}
+
+impl SolverVelsAccess for SharedSolverVels {
+    #[inline(always)]
+    fn get_vel(&self, i: usize) -> SolverVel<Real> {
+        assert!(i < self.len);
+        // SAFETY: see the comment on `SharedPtr`.
+        unsafe { self.ptr.get().add(i).read() }
+    }
+
+    #[inline(always)]
+    fn set_vel(&mut self, i: usize, vel: SolverVel<Real>) {
+        assert!(i < self.len);
+        // SAFETY: see the comment on `SharedPtr`.
+        unsafe { self.ptr.get().add(i).write(vel) }
+    }
+}
+
+/// A partition of the contact constraints of an island into sets of constraints (colors) that
+/// don’t share any solver body.
+///
+/// All the constraints of the same color can be solved in parallel without data races on the
+/// solver velocities. Because they are independent, the result of solving one color doesn’t
+/// depend on the order the constraints are solved in, so it is deterministic regardless of the
+/// number of threads.
+///
+/// Constraints involving multibodies are not colored and are solved sequentially.
+#[derive(Default)]
+pub(crate) struct ContactConstraintColoring {
+    /// The constraints, sorted by color.
+    constraints: Vec<ColoredConstraint>,
+    /// `constraints[color_ranges[i]..color_ranges[i + 1]]` are the constraints with the i-th color.
+    ///
+    /// The last range contains the constraints that couldn’t be colored.
+    color_ranges: Vec<usize>,
+    /// The colors of each constraint, in the same order as they were added.
+    colors: Vec<(u32, ColoredConstraint)>,
+    /// For each solver body, the bitmask of the colors of the constraints it is involved in.
+    body_colors: Vec<u64>,
+}
+
+impl ContactConstraintColoring {
+    fn add(&mut self, kind: ConstraintKind, index: usize, solver_bodies: &[usize]) {
+        let used = solver_bodies
+            .iter()
+            .fold(0, |used, body| used | self.body_colors[*body]);
+        let color = (!used).trailing_zeros();
+
+        if (color as usize) < MAX_COLORS {
+            for body in solver_bodies {
+                self.body_colors[*body] |= 1 << color;
+            }
+        }
+
+        self.colors.push((color, ColoredConstraint { kind, index }));
+    }
+
+    /// Colors the non-generic contact constraints of `constraints`.
+    ///
+    /// The colors are assigned greedily, in the order the constraints are stored, so they only
+    /// depend on the constraints, not on the number of threads.
+    pub fn color(&mut self, num_solver_bodies: usize, constraints: &ContactConstraintsSet) {
+        self.body_colors.clear();
+        self.body_colors.resize(num_solver_bodies, 0);
+        self.colors.clear();
+
+        for (i, c) in constraints.velocity_constraints.iter().enumerate() {
+            self.add(
+                ConstraintKind::TwoBodies,
+                i,
+                &[c.solver_vel1, c.solver_vel2],
+            );
+        }
+
+        #[cfg(feature = "simd-is-enabled")]
+        for (i, c) in constraints.simd_velocity_constraints.iter().enumerate() {
+            let mut solver_bodies = [0; crate::math::SIMD_WIDTH * 2];
+            solver_bodies[..crate::math::SIMD_WIDTH].copy_from_slice(&c.solver_vel1);
+            solver_bodies[crate::math::SIMD_WIDTH..].copy_from_slice(&c.solver_vel2);
+            self.add(ConstraintKind::SimdTwoBodies, i, &solver_bodies);
+        }
+
+        for (i, c) in constraints.velocity_one_body_constraints.iter().enumerate() {
+            self.add(ConstraintKind::OneBody, i, &[c.solver_vel2]);
+        }
+
+        #[cfg(feature = "simd-is-enabled")]
+        for (i, c) in constraints
+            .simd_velocity_one_body_constraints
+            .iter()
+            .enumerate()
+        {
+            self.add(ConstraintKind::SimdOneBody, i, &c.solver_vel2);
+        }
+
+        // Sort the constraints by color, keeping their relative order within each color.
+        let num_ranges = self
+            .colors
+            .iter()
+            .map(|(color, _)| *color as usize + 1)
+            .max()
+            .unwrap_or(0);
+        self.color_ranges.clear();
+        self.color_ranges.resize(num_ranges + 1, 0);
+
+        for (color, _) in &self.colors {
+            self.color_ranges[*color as usize + 1] += 1;
+        }
+
+        for i in 1..self.color_ranges.len() {
+            self.color_ranges[i] += self.color_ranges[i - 1];
+        }
+
+        let mut insertion_points = self.color_ranges.clone();
+        self.constraints.clear();
+        self.constraints.resize(
+            self.colors.len(),
+            ColoredConstraint {
+                kind: ConstraintKind::TwoBodies,
+                index: usize::MAX,
+            },
+        );
+
+        for (color, constraint) in &self.colors {
+            let target = &mut insertion_points[*color as usize];
+            self.constraints[*target] = *constraint;
+            *target += 1;
+        }
+
+        #[cfg(debug_assertions)]
+        self.assert_colors_are_independent(num_solver_bodies, constraints);
+    }
+
+    /// Checks that the constraints of the same color don’t share any solver body.
+    #[cfg(debug_assertions)]
+    fn assert_colors_are_independent(
+        &self,
+        num_solver_bodies: usize,
+        constraints: &ContactConstraintsSet,
+    ) {
+        // The color and position of the last constraint involving each solver body.
+        let mut last_constraint = vec![(usize::MAX, usize::MAX); num_solver_bodies];
+
+        for (color, range) in self.color_ranges.windows(2).enumerate().take(MAX_COLORS) {
+            for position in range[0]..range[1] {
+                let constraint = self.constraints[position];
+                // NOTE: a SIMD constraint can reference the same body in several lanes.
+                let mut check = |body: usize| {
+                    let last = &mut last_constraint[body];
+                    assert!(
+                        last.0 != color || last.1 == position,
+                        "The constraints with the color {color} share the solver body {body}."
+                    );
+                    *last = (color, position);
+                };
+
+                match constraint.kind {
+                    ConstraintKind::TwoBodies => {
+                        let c = &constraints.velocity_constraints[constraint.index];
+                        check(c.solver_vel1);
+                        check(c.solver_vel2);
+                    }
+                    ConstraintKind::OneBody => check(
+                        constraints.velocity_one_body_constraints[constraint.index].solver_vel2,
+                    ),
+                    #[cfg(feature = "simd-is-enabled")]
+                    ConstraintKind::SimdTwoBodies => {
+                        let c = &constraints.simd_velocity_constraints[constraint.index];
+                        c.solver_vel1
+                            .iter()
+                            .chain(&c.solver_vel2)
+                            .for_each(|b| check(*b));
+                    }
+                    #[cfg(feature = "simd-is-enabled")]
+                    ConstraintKind::SimdOneBody => constraints.simd_velocity_one_body_constraints
+                        [constraint.index]
+                        .solver_vel2
+                        .iter()
+                        .for_each(|b| check(*b)),
+                }
+            }
+        }
+    }
+
+    /// Solves the contact constraints, one color after the other, with the constraints of the
+    /// same color being solved in parallel.
+    pub fn solve(
+        &self,
+        solve: ColoredSolve,
+        constraints: &mut ContactConstraintsSet,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        // The generic constraints access the multibodies’ velocities which aren’t colored.
+        {
+            let jac = &constraints.generic_jacobians;
+            let generic_constraints = constraints
+                .generic_velocity_constraints
+                .iter_mut()
+                .map(AnyConstraintMut::<ContactConstraintTypes>::GenericTwoBodies)
+                .chain(
+                    constraints
+                        .generic_velocity_one_body_constraints
+                        .iter_mut()
+                        .map(AnyConstraintMut::GenericOneBody),
+                );
+
+            for mut c in generic_constraints {
+                match solve {
+                    ColoredSolve::Warmstart => c.warmstart(jac, solver_vels, generic_solver_vels),
+                    ColoredSolve::Restitution => {
+                        c.solve_restitution(jac, solver_vels, generic_solver_vels)
+                    }
+                    ColoredSolve::RestitutionWithoutBias => {
+                        c.remove_bias();
+                        c.solve_restitution(jac, solver_vels, generic_solver_vels)
+                    }
+                    ColoredSolve::Friction => {
+                        c.solve_friction(jac, solver_vels, generic_solver_vels)
+                    }
+                }
+            }
+        }
+
+        let two_bodies = SharedPtr(constraints.velocity_constraints.as_mut_ptr());
+        let one_body = SharedPtr(constraints.velocity_one_body_constraints.as_mut_ptr());
+        #[cfg(feature = "simd-is-enabled")]
+        let simd_two_bodies = SharedPtr(constraints.simd_velocity_constraints.as_mut_ptr());
+        #[cfg(feature = "simd-is-enabled")]
+        let simd_one_body = SharedPtr(constraints.simd_velocity_one_body_constraints.as_mut_ptr());
+        let shared_solver_vels = SharedSolverVels {
+            ptr: SharedPtr(solver_vels.as_mut_ptr()),
+            len: solver_vels.len(),
+        };
+
+        let solve_one = |constraint: &ColoredConstraint| {
+            let solver_vels = &mut { shared_solver_vels };
+
+            macro_rules! solve_constraint(
+                ($c: expr) => {
+                    match solve {
+                        ColoredSolve::Warmstart => $c.warmstart(solver_vels),
+                        ColoredSolve::Restitution => $c.solve(solver_vels, true, false),
+                        ColoredSolve::RestitutionWithoutBias => {
+                            $c.remove_cfm_and_bias_from_rhs();
+                            $c.solve(solver_vels, true, false)
+                        }
+                        ColoredSolve::Friction => $c.solve(solver_vels, false, true),
+                    }
+                }
+            );
+
+            // SAFETY: see the comment on `SharedPtr`. The indices were computed from these
+            //         same constraint arrays by `Self::color`.
+            unsafe {
+                match constraint.kind {
+                    ConstraintKind::TwoBodies => {
+                        solve_constraint!((*two_bodies.get().add(constraint.index)))
+                    }
+                    ConstraintKind::OneBody => {
+                        solve_constraint!((*one_body.get().add(constraint.index)))
+                    }
+                    #[cfg(feature = "simd-is-enabled")]
+                    ConstraintKind::SimdTwoBodies => {
+                        solve_constraint!((*simd_two_bodies.get().add(constraint.index)))
+                    }
+                    #[cfg(feature = "simd-is-enabled")]
+                    ConstraintKind::SimdOneBody => {
+                        solve_constraint!((*simd_one_body.get().add(constraint.index)))
+                    }
+                }
+            }
+        };
+
+        for (i, range) in self.color_ranges.windows(2).enumerate() {
+            let colored = &self.constraints[range[0]..range[1]];
+            let is_uncolored = i >= MAX_COLORS;
+
+            if is_uncolored || colored.len() < PARALLEL_CHUNK_SIZE * 2 {
+                colored.iter().for_each(solve_one);
+            } else {
+                colored
+                    .par_chunks(PARALLEL_CHUNK_SIZE)
+                    .for_each(|chunk| chunk.iter().for_each(solve_one));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    /// Simulates a pile of `width * height` balls forming a single island, with the given number
+    /// of threads, and returns the final positions of the balls.
+    fn simulate_pile(
+        num_threads: usize,
+        width: usize,
+        height: usize,
+        num_steps: usize,
+    ) -> Vec<Isometry<Real>> {
+        let mut world = TestWorld::with_gravity();
+
+        let (floor, _) = world.insert_body(
+            RigidBodyBuilder::fixed().translation(Vector::y() * -100.0),
+            ColliderBuilder::ball(100.0),
+        );
+
+        let mut handles = vec![];
+        for i in 0..width {
+            for j in 0..height {
+                let pos = Vector::x() * (i as Real - width as Real / 2.0)
+                    + Vector::y() * (j as Real + 0.5);
+                let (handle, _) = world.insert_ball(pos, 0.5);
+                handles.push(handle);
+            }
+        }
+
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        thread_pool.install(|| {
+            world.steps(num_steps);
+        });
+
+        handles
+            .iter()
+            .map(|h| *world.bodies[*h].position())
+            .collect()
+    }
+
+    #[test]
+    fn graph_coloring_is_deterministic_regardless_of_thread_count() {
+        let sequential = simulate_pile(1, 20, 20, 20);
+        assert_eq!(sequential, simulate_pile(4, 20, 20, 20));
+        assert_eq!(sequential, simulate_pile(7, 20, 20, 20));
+    }
+
+    // NOTE: this is small enough to run under Miri to check the parallel solver for undefined
+    //       behavior. The colors are still solved in parallel since `PARALLEL_CHUNK_SIZE` is
+    //       reduced under Miri.
+    #[test]
+    fn graph_coloring_small_pile_is_deterministic() {
+        assert_eq!(simulate_pile(1, 4, 2, 3), simulate_pile(2, 4, 2, 3));
+    }
+}
@@ -0,0 +1,138 @@
+use super::contact_constraint::{OneBodyConstraint, OneBodyConstraintBuilder};
+use super::{SolverBody, SolverVel};
+use crate::dynamics::{IntegrationParameters, RigidBodyHandle, RigidBodySet, RigidBodyVelocity};
+use crate::geometry::ContactManifold;
+use crate::math::{Isometry, Real, Vector, MAX_MANIFOLD_POINTS};
+
+// The number of timesteps simulated to settle an inserted body.
+const NUM_STEPS: usize = 20;
+// The number of PGS iterations per substep used to settle an inserted body.
+const NUM_ITERATIONS: usize = 4;
+
+/// Simulates the contacts of a rigid-body that was just inserted until it comes to rest, and
+/// returns its settled position.
+///
+/// The rigid-body `handle` is simulated for a few timesteps against the contacts of `manifolds`,
+/// with all the other bodies considered fixed. The body’s velocity is left unchanged and its
+/// motion along this velocity is discarded from the returned position. The solver contacts of
+/// `manifolds` are warmstarted with the impulses of the last simulated substep, and their
+/// distance is updated to match the returned position.
+///
+/// The `relative_dominance` of the given manifolds is modified, so they must be copies of the
+/// contact manifolds stored by the narrow-phase.
+pub(crate) fn solve_inserted_body_contacts(
+    params: &IntegrationParameters,
+    gravity: &Vector<Real>,
+    bodies: &RigidBodySet,
+    handle: RigidBodyHandle,
+    manifolds: &mut [ContactManifold],
+) -> Isometry<Real> {
+    let rb = &bodies[handle];
+    let num_substeps = params.num_solver_iterations.get() * NUM_STEPS;
+    let mut params = *params;
+    params.dt /= params.num_solver_iterations.get() as Real;
+
+    let mut builders = vec![];
+    let mut constraints = vec![];
+
+    for (manifold_id, manifold) in manifolds.iter_mut().enumerate() {
+        // Solve the contacts as one-body constraints, with `handle` as the dynamic body.
+        manifold.data.relative_dominance = if manifold.data.rigid_body1 == Some(handle) {
+            -1
+        } else {
+            1
+        };
+
+        let first = builders.len();
+        let len = first + manifold.data.num_constraint_blocks();
+        builders.resize(len, OneBodyConstraintBuilder::invalid());
+        constraints.resize(len, OneBodyConstraint::invalid());
+        OneBodyConstraintBuilder::generate(
+            manifold_id,
+            manifold,
+            &params,
+            bodies,
+            &mut builders[first..],
+            &mut constraints[first..],
+        );
+    }
+
+    for constraint in &mut constraints {
+        constraint.solver_vel2 = 0;
+    }
+
+    let solver_body = SolverBody::from(rb);
+    let effective_force = rb.forces.user_force
+        + gravity.component_mul(&rb.mprops.effective_mass()) * rb.gravity_scale();
+    let solver_vel_incr = SolverVel {
+        linear: effective_force.component_mul(&rb.mprops.effective_inv_mass) * params.dt,
+        angular: rb.mprops.effective_world_inv_inertia_sqrt * rb.forces.user_torque * params.dt,
+    };
+    let mut solver_vels = [SolverVel::default()];
+    solver_vels[0].linear = rb.vels.linvel;
+    solver_vels[0]
+        .set_physical_angular(rb.vels.angvel, &rb.mprops.effective_angular_inertia_sqrt());
+
+    let mut position = solver_body.position;
+
+    for substep_id in 0..num_substeps {
+        solver_vels[0].linear += solver_vel_incr.linear;
+        solver_vels[0].angular += solver_vel_incr.angular;
+
+        let solved_dt = substep_id as Real * params.dt;
+        for (builder, constraint) in builders.iter().zip(constraints.iter_mut()) {
+            builder.update_with_positions(
+                &params,
+                solved_dt,
+                &position,
+                solver_body.ccd_thickness,
+                constraint,
+            );
+
+            if params.warmstart_coefficient != 0.0 {
+                constraint.warmstart(&mut solver_vels[..]);
+            }
+        }
+
+        for _ in 0..NUM_ITERATIONS {
+            for constraint in &mut constraints {
+                constraint.solve(&mut solver_vels[..], true, true);
+            }
+        }
+
+        let vels = RigidBodyVelocity {
+            linvel: solver_vels[0].linear,
+            angvel: solver_vels[0].physical_angular(&solver_body.sqrt_ii),
+        };
+        position = vels.integrate(params.dt, &position, &solver_body.local_com);
+    }
+
+    // Discard the motion of the body along its initial velocity.
+    let unsettled_position = rb.vels.integrate(
+        num_substeps as Real * params.dt,
+        &solver_body.position,
+        &solver_body.local_com,
+    );
+    let settled_position = position * unsettled_position.inverse() * solver_body.position;
+    let displacement = settled_position * solver_body.position.inverse();
+
+    // NOTE: the constraints were generated in the same order as the solver contacts chunks.
+    let mut constraints = constraints.iter();
+    for manifold in manifolds {
+        for contacts in manifold
+            .data
+            .solver_contacts
+            .chunks_mut(MAX_MANIFOLD_POINTS)
+        {
+            let constraint = constraints.next().unwrap();
+            for (contact, element) in contacts.iter_mut().zip(constraint.elements.iter()) {
+                contact.dist -=
+                    (displacement * contact.point - contact.point).dot(&constraint.dir1);
+                contact.warmstart_impulse = element.normal_part.impulse;
+                contact.warmstart_tangent_impulse = element.tangent_part.impulse;
+            }
+        }
+    }
+
+    settled_position
+}
@@ -263,8 +263,11 @@ impl InteractionGroups {
                 continue;
             }
 
-            if !interaction.data.supports_simd_constraints() {
-                // This joint does not support simd constraints yet.
+            if !interaction.data.supports_simd_constraints()
+                || interaction.warmstart_impulse.is_some()
+            {
+                // This joint does not support simd constraints yet, or has to be seeded
+                // with a warmstart impulse which is only supported by scalar constraints.
                 self.nongrouped_interactions.push(*interaction_i);
                 continue;
             }
@@ -359,6 +362,14 @@ impl InteractionGroups {
         self.nongrouped_interactions.clear();
     }
 
+    /// Marks all the given interactions as non-grouped, keeping their order.
+    ///
+    /// This makes them be solved by the scalar constraints even if SIMD is enabled.
+    pub fn skip_grouping(&mut self, interaction_indices: &[usize]) {
+        self.nongrouped_interactions
+            .extend_from_slice(interaction_indices);
+    }
+
     #[cfg(not(feature = "simd-is-enabled"))]
     pub fn group_manifolds(
         &mut self,
@@ -435,6 +446,12 @@ impl InteractionGroups {
                     continue;
                 }
 
+                if !interaction.data.supports_simd_constraints() {
+                    // These contacts do not support simd constraints yet.
+                    self.nongrouped_interactions.push(*interaction_i);
+                    continue;
+                }
+
                 let i1 = active_set_offset1;
                 let i2 = active_set_offset2;
                 let mask1 = if !is_fixed1 { self.body_masks[i1] } else { 0 };
@@ -2,8 +2,11 @@ use super::{JointConstraintsSet, VelocitySolver};
 use crate::counters::Counters;
 use crate::dynamics::solver::contact_constraint::ContactConstraintsSet;
 use crate::dynamics::IslandManager;
-use crate::dynamics::{IntegrationParameters, JointGraphEdge, JointIndex, RigidBodySet};
+use crate::dynamics::{
+    IntegrationParameters, JointGraphEdge, JointIndex, RigidBodyHandle, RigidBodySet,
+};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::pipeline::{ContactConstraintRow, IntegrationHook, StepStats};
 use crate::prelude::MultibodyJointSet;
 use parry::math::Real;
 
@@ -11,6 +14,11 @@ pub struct IslandSolver {
     contact_constraints: ContactConstraintsSet,
     joint_constraints: JointConstraintsSet,
     velocity_solver: VelocitySolver,
+    /// The rigid-bodies of the island solved by the last call to `init_and_solve`, indexed by
+    /// their solver velocity index.
+    bodies: Vec<RigidBodyHandle>,
+    /// Statistics about the last call to `init_and_solve`.
+    pub(crate) stats: StepStats,
 }
 
 impl Default for IslandSolver {
@@ -25,6 +33,8 @@ impl IslandSolver {
             contact_constraints: ContactConstraintsSet::new(),
             joint_constraints: JointConstraintsSet::new(),
             velocity_solver: VelocitySolver::new(),
+            bodies: Vec::new(),
+            stats: StepStats::default(),
         }
     }
 
@@ -40,8 +50,12 @@ impl IslandSolver {
         impulse_joints: &mut [JointGraphEdge],
         joint_indices: &[JointIndex],
         multibodies: &mut MultibodyJointSet,
+        integration_hook: Option<&mut dyn IntegrationHook>,
     ) {
         counters.solver.velocity_assembly_time.resume();
+        self.bodies.clear();
+        self.bodies
+            .extend_from_slice(islands.active_island(island_id));
         let num_solver_iterations = base_params.num_solver_iterations.get()
             + islands.active_island_additional_solver_iterations(island_id);
 
@@ -63,6 +77,7 @@ impl IslandSolver {
                 multibodies,
             );
         self.velocity_solver.init_constraints(
+            &params,
             island_id,
             islands,
             bodies,
@@ -76,15 +91,28 @@ impl IslandSolver {
         );
         counters.solver.velocity_assembly_time.pause();
 
+        let num_contact_constraints = self.contact_constraints.num_constraints();
+        let num_joint_constraints = self.joint_constraints.num_constraints();
+        self.stats = StepStats {
+            num_contact_constraints,
+            num_joint_constraints,
+            solver_work: (num_contact_constraints + num_joint_constraints) * num_solver_iterations,
+            ..StepStats::default()
+        };
+
         // SOLVE
         counters.solver.velocity_resolution_time.resume();
         self.velocity_solver.solve_constraints(
             &params,
             num_solver_iterations,
+            islands,
+            island_id,
             bodies,
             multibodies,
             &mut self.contact_constraints,
             &mut self.joint_constraints,
+            integration_hook,
+            &mut self.stats,
         );
         counters.solver.velocity_resolution_time.pause();
 
@@ -102,4 +130,11 @@ impl IslandSolver {
         );
         counters.solver.velocity_writeback_time.pause();
     }
+
+    /// Appends the non-penetration rows of the contact constraints solved by the last call to
+    /// `init_and_solve` to `out`.
+    pub fn export_contact_constraints(&self, out: &mut Vec<ContactConstraintRow>) {
+        self.contact_constraints
+            .export_normal_rows(&self.bodies, out);
+    }
 }
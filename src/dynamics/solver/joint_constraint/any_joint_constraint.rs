@@ -61,6 +61,27 @@ impl<'a> AnyConstraintMut<'a, JointConstraintTypes> {
         }
     }
 
+    pub fn warmstart(
+        &mut self,
+        generic_jacobians: &DVector<Real>,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        match self {
+            Self::OneBody(c) => c.warmstart(solver_vels),
+            Self::TwoBodies(c) => c.warmstart(solver_vels),
+            Self::GenericOneBody(c) => {
+                c.warmstart(generic_jacobians, solver_vels, generic_solver_vels)
+            }
+            Self::GenericTwoBodies(c) => {
+                c.warmstart(generic_jacobians, solver_vels, generic_solver_vels)
+            }
+            // NOTE: joints with a warmstart impulse are never grouped into SIMD constraints.
+            #[cfg(feature = "simd-is-enabled")]
+            Self::SimdOneBody(_) | Self::SimdTwoBodies(_) => {}
+        }
+    }
+
     pub fn writeback_impulses(&mut self, joints_all: &mut [JointGraphEdge]) {
         match self {
             Self::OneBody(c) => c.writeback_impulses(joints_all),
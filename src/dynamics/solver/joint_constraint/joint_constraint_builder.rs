@@ -5,7 +5,9 @@ use crate::dynamics::solver::joint_constraint::JointSolverBody;
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::solver::ConstraintsCounts;
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::{GenericJoint, ImpulseJoint, IntegrationParameters, JointIndex};
+use crate::dynamics::{
+    GenericJoint, ImpulseJoint, IntegrationParameters, JointImpulse, JointIndex,
+};
 use crate::math::{AngVector, Isometry, Matrix, Point, Real, Rotation, Vector, ANG_DIM, DIM};
 use crate::prelude::RigidBodySet;
 use crate::utils;
@@ -23,6 +25,7 @@ pub struct JointTwoBodyConstraintBuilder {
     body2: usize,
     joint_id: JointIndex,
     joint: GenericJoint,
+    warmstart_impulse: Option<JointImpulse>,
     constraint_id: usize,
 }
 
@@ -41,6 +44,7 @@ impl JointTwoBodyConstraintBuilder {
             body2: rb2.ids.active_set_offset,
             joint_id,
             joint: joint.data,
+            warmstart_impulse: joint.warmstart_impulse,
             constraint_id: *out_constraint_id,
         };
 
@@ -51,6 +55,7 @@ impl JointTwoBodyConstraintBuilder {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        substep_id: usize,
         bodies: &[SolverBody],
         out: &mut [JointTwoBodyConstraint<Real, 1>],
     ) {
@@ -75,7 +80,7 @@ impl JointTwoBodyConstraintBuilder {
             solver_vel: [self.body2],
         };
 
-        JointTwoBodyConstraint::<Real, 1>::lock_axes(
+        let len = JointTwoBodyConstraint::<Real, 1>::lock_axes(
             params,
             self.joint_id,
             &joint_body1,
@@ -85,6 +90,14 @@ impl JointTwoBodyConstraintBuilder {
             &self.joint,
             &mut out[self.constraint_id..],
         );
+
+        // The constraints are rebuilt from scratch at each substep, so only the first one
+        // starts from the user-provided warmstart impulse.
+        if let Some(impulse) = self.warmstart_impulse.filter(|_| substep_id == 0) {
+            for constraint in &mut out[self.constraint_id..self.constraint_id + len] {
+                constraint.seed_impulse(&impulse);
+            }
+        }
     }
 }
 
@@ -148,6 +161,7 @@ impl JointTwoBodyConstraintBuilderSimd {
     pub fn update(
         &mut self,
         params: &IntegrationParameters,
+        _substep_id: usize,
         bodies: &[SolverBody],
         out: &mut [JointTwoBodyConstraint<SimdReal, SIMD_WIDTH>],
     ) {
@@ -180,6 +194,7 @@ pub struct JointOneBodyConstraintBuilder {
     body2: usize,
     joint_id: JointIndex,
     joint: GenericJoint,
+    warmstart_impulse: Option<JointImpulse>,
     constraint_id: usize,
 }
 
@@ -217,6 +232,7 @@ impl JointOneBodyConstraintBuilder {
             body2: rb2.ids.active_set_offset,
             joint_id,
             joint: joint_data,
+            warmstart_impulse: joint.warmstart_impulse,
             constraint_id: *out_constraint_id,
         };
 
@@ -227,6 +243,7 @@ impl JointOneBodyConstraintBuilder {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        substep_id: usize,
         bodies: &[SolverBody],
         out: &mut [JointOneBodyConstraint<Real, 1>],
     ) {
@@ -243,7 +260,7 @@ impl JointOneBodyConstraintBuilder {
             solver_vel: [self.body2],
         };
 
-        JointOneBodyConstraint::<Real, 1>::lock_axes(
+        let len = JointOneBodyConstraint::<Real, 1>::lock_axes(
             params,
             self.joint_id,
             &self.body1,
@@ -253,6 +270,12 @@ impl JointOneBodyConstraintBuilder {
             &self.joint,
             &mut out[self.constraint_id..],
         );
+
+        if let Some(impulse) = self.warmstart_impulse.filter(|_| substep_id == 0) {
+            for constraint in &mut out[self.constraint_id..self.constraint_id + len] {
+                constraint.seed_impulse(&impulse);
+            }
+        }
     }
 }
 
@@ -313,6 +336,7 @@ impl JointOneBodyConstraintBuilderSimd {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        _substep_id: usize,
         bodies: &[SolverBody],
         out: &mut [JointOneBodyConstraint<SimdReal, SIMD_WIDTH>],
     ) {
@@ -422,6 +446,7 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         body2: &JointSolverBody<N, LANES>,
         limited_axis: usize,
         limits: [N; 2],
+        spring: Option<MotorParameters<N>>,
         writeback_id: WritebackId,
     ) -> JointTwoBodyConstraint<N, LANES> {
         let zero = N::zero();
@@ -436,8 +461,16 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let cfm_coeff = N::splat(params.joint_cfm_coeff());
         let rhs_bias =
             ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
-        constraint.rhs = constraint.rhs_wo_bias + rhs_bias;
-        constraint.cfm_coeff = cfm_coeff;
+
+        if let Some(spring) = spring {
+            constraint.rhs_wo_bias += spring.soft_limit_rhs(dist, limits, min_enabled);
+            constraint.rhs = constraint.rhs_wo_bias;
+            constraint.cfm_coeff = spring.cfm_coeff;
+            constraint.cfm_gain = spring.cfm_gain;
+        } else {
+            constraint.rhs = constraint.rhs_wo_bias + rhs_bias;
+            constraint.cfm_coeff = cfm_coeff;
+        }
         constraint.impulse_bounds = [
             N::splat(-Real::INFINITY).select(min_enabled, zero),
             N::splat(Real::INFINITY).select(max_enabled, zero),
@@ -686,6 +719,7 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         body2: &JointSolverBody<N, LANES>,
         _limited_axis: usize,
         limits: [N; 2],
+        spring: Option<MotorParameters<N>>,
         writeback_id: WritebackId,
     ) -> JointTwoBodyConstraint<N, LANES> {
         let zero = N::zero();
@@ -714,6 +748,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             - (s_limits[0] - s_ang).simd_max(zero))
             * erp_inv_dt;
 
+        let (rhs, rhs_wo_bias, cfm_coeff, cfm_gain) = if let Some(spring) = spring {
+            let ang = s_ang.simd_asin() * N::splat(2.0);
+            let rhs = rhs_wo_bias + spring.soft_limit_rhs(ang, limits, min_enabled);
+            (rhs, rhs, spring.cfm_coeff, spring.cfm_gain)
+        } else {
+            (rhs_wo_bias + rhs_bias, rhs_wo_bias, cfm_coeff, N::zero())
+        };
+
         let ang_jac1 = body1.sqrt_ii * ang_jac;
         let ang_jac2 = body2.sqrt_ii * ang_jac;
 
@@ -730,8 +772,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             ang_jac2,
             inv_lhs: N::zero(), // Will be set during orthogonalization.
             cfm_coeff,
-            cfm_gain: N::zero(),
-            rhs: rhs_wo_bias + rhs_bias,
+            cfm_gain,
+            rhs,
             rhs_wo_bias,
             writeback_id,
         }
@@ -887,6 +929,7 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         body2: &JointSolverBody<N, LANES>,
         limited_axis: usize,
         limits: [N; 2],
+        spring: Option<MotorParameters<N>>,
         writeback_id: WritebackId,
     ) -> JointOneBodyConstraint<N, LANES> {
         let zero = N::zero();
@@ -913,6 +956,13 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         let rhs_bias =
             ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
 
+        let (rhs, rhs_wo_bias, cfm_coeff, cfm_gain) = if let Some(spring) = spring {
+            let rhs = rhs_wo_bias + spring.soft_limit_rhs(dist, limits, min_enabled);
+            (rhs, rhs, spring.cfm_coeff, spring.cfm_gain)
+        } else {
+            (rhs_wo_bias + rhs_bias, rhs_wo_bias, cfm_coeff, N::zero())
+        };
+
         ang_jac2 = body2.sqrt_ii * ang_jac2;
 
         JointOneBodyConstraint {
@@ -925,8 +975,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             ang_jac2,
             inv_lhs: zero, // Will be set during orthogonalization.
             cfm_coeff,
-            cfm_gain: N::zero(),
-            rhs: rhs_wo_bias + rhs_bias,
+            cfm_gain,
+            rhs,
             rhs_wo_bias,
             writeback_id,
         }
@@ -1223,6 +1273,7 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
         body2: &JointSolverBody<N, LANES>,
         _limited_axis: usize,
         limits: [N; 2],
+        spring: Option<MotorParameters<N>>,
         writeback_id: WritebackId,
     ) -> JointOneBodyConstraint<N, LANES> {
         let zero = N::zero();
@@ -1252,6 +1303,14 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             - (s_limits[0] - s_ang).simd_max(zero))
             * erp_inv_dt;
 
+        let (rhs, rhs_wo_bias, cfm_coeff, cfm_gain) = if let Some(spring) = spring {
+            let ang = s_ang.simd_asin() * N::splat(2.0);
+            let rhs = rhs_wo_bias + spring.soft_limit_rhs(ang, limits, min_enabled);
+            (rhs, rhs, spring.cfm_coeff, spring.cfm_gain)
+        } else {
+            (rhs_wo_bias + rhs_bias, rhs_wo_bias, cfm_coeff, N::zero())
+        };
+
         let ang_jac2 = body2.sqrt_ii * ang_jac;
 
         JointOneBodyConstraint {
@@ -1264,8 +1323,8 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
             ang_jac2,
             inv_lhs: zero, // Will be set during orthogonalization.
             cfm_coeff,
-            cfm_gain: N::zero(),
-            rhs: rhs_wo_bias + rhs_bias,
+            cfm_gain,
+            rhs,
             rhs_wo_bias,
             writeback_id,
         }
@@ -1359,6 +1418,30 @@ impl<N: SimdRealCopy> JointTwoBodyConstraintHelper<N> {
 }
 
 impl JointTwoBodyConstraintHelper<Real> {
+    /// The helper to use for the angular motors of `joint`.
+    ///
+    /// If the joint has a motor target orientation, the angular motors are relative to the first
+    /// frame rotated by this orientation.
+    pub fn angular_motors_helper(
+        &self,
+        joint: &GenericJoint,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        world_com1: &Point<Real>,
+        world_com2: &Point<Real>,
+    ) -> Self {
+        match joint.motor_target_orientation {
+            Some(target) => Self::new(
+                &(frame1 * target),
+                frame2,
+                world_com1,
+                world_com2,
+                joint.locked_axes.bits(),
+            ),
+            None => *self,
+        }
+    }
+
     // TODO: this method is almost identical to the one_body version, except for the
     //       return type. Could they share their implementation somehow?
     #[cfg(feature = "dim3")]
@@ -36,6 +36,7 @@ impl JointConstraintsSet {
         multibody_joints: &MultibodyJointSet,
         impulse_joints: &[JointGraphEdge],
         joint_constraint_indices: &[JointIndex],
+        force_scalar_solver: bool,
     ) {
         // Generate constraints for impulse_joints.
         self.two_body_interactions.clear();
@@ -58,22 +59,29 @@ impl JointConstraintsSet {
         self.clear_builders();
 
         self.interaction_groups.clear_groups();
-        self.interaction_groups.group_joints(
-            island_id,
-            islands,
-            bodies,
-            impulse_joints,
-            &self.two_body_interactions,
-        );
-
         self.one_body_interaction_groups.clear_groups();
-        self.one_body_interaction_groups.group_joints(
-            island_id,
-            islands,
-            bodies,
-            impulse_joints,
-            &self.one_body_interactions,
-        );
+
+        if force_scalar_solver {
+            self.interaction_groups
+                .skip_grouping(&self.two_body_interactions);
+            self.one_body_interaction_groups
+                .skip_grouping(&self.one_body_interactions);
+        } else {
+            self.interaction_groups.group_joints(
+                island_id,
+                islands,
+                bodies,
+                impulse_joints,
+                &self.two_body_interactions,
+            );
+            self.one_body_interaction_groups.group_joints(
+                island_id,
+                islands,
+                bodies,
+                impulse_joints,
+                &self.one_body_interactions,
+            );
+        }
         // NOTE: uncomment this do disable SIMD joint resolution.
         // self.interaction_groups
         //     .nongrouped_interactions
@@ -384,6 +392,18 @@ impl JointConstraintsSet {
         }
     }
 
+    /// Applies the accumulated impulses the constraints were seeded with to the solver velocities.
+    pub fn warmstart(
+        &mut self,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        let (jac, constraints) = self.iter_constraints_mut();
+        for mut c in constraints {
+            c.warmstart(jac, solver_vels, generic_solver_vels);
+        }
+    }
+
     pub fn writeback_impulses(&mut self, joints_all: &mut [JointGraphEdge]) {
         let (_, constraints) = self.iter_constraints_mut();
         for mut c in constraints {
@@ -394,12 +414,14 @@ impl JointConstraintsSet {
     pub fn update(
         &mut self,
         params: &IntegrationParameters,
+        substep_id: usize,
         multibodies: &MultibodyJointSet,
         solver_bodies: &[SolverBody],
     ) {
         for builder in &mut self.generic_velocity_constraints_builder {
             builder.update(
                 params,
+                substep_id,
                 multibodies,
                 solver_bodies,
                 &mut self.generic_jacobians,
@@ -410,6 +432,7 @@ impl JointConstraintsSet {
         for builder in &mut self.generic_velocity_one_body_constraints_builder {
             builder.update(
                 params,
+                substep_id,
                 multibodies,
                 solver_bodies,
                 &mut self.generic_jacobians,
@@ -418,17 +441,28 @@ impl JointConstraintsSet {
         }
 
         for builder in &mut self.velocity_constraints_builder {
-            builder.update(params, solver_bodies, &mut self.velocity_constraints);
+            builder.update(
+                params,
+                substep_id,
+                solver_bodies,
+                &mut self.velocity_constraints,
+            );
         }
 
         #[cfg(feature = "simd-is-enabled")]
         for builder in &mut self.simd_velocity_constraints_builder {
-            builder.update(params, solver_bodies, &mut self.simd_velocity_constraints);
+            builder.update(
+                params,
+                substep_id,
+                solver_bodies,
+                &mut self.simd_velocity_constraints,
+            );
         }
 
         for builder in &mut self.velocity_one_body_constraints_builder {
             builder.update(
                 params,
+                substep_id,
                 solver_bodies,
                 &mut self.velocity_one_body_constraints,
             );
@@ -438,6 +472,7 @@ impl JointConstraintsSet {
         for builder in &mut self.simd_velocity_one_body_constraints_builder {
             builder.update(
                 params,
+                substep_id,
                 solver_bodies,
                 &mut self.simd_velocity_one_body_constraints,
             );
@@ -3,7 +3,9 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 };
 use crate::dynamics::solver::joint_constraint::{JointSolverBody, JointTwoBodyConstraintHelper};
 use crate::dynamics::solver::SolverVel;
-use crate::dynamics::{GenericJoint, IntegrationParameters, JointGraphEdge, JointIndex, Multibody};
+use crate::dynamics::{
+    GenericJoint, IntegrationParameters, JointGraphEdge, JointImpulse, JointIndex, Multibody,
+};
 use crate::math::{Isometry, Real, DIM};
 use crate::prelude::SPATIAL_DIM;
 use na::{DVector, DVectorView, DVectorViewMut};
@@ -88,11 +90,18 @@ impl JointGenericTwoBodyConstraint {
             &body2.world_com,
             locked_axes,
         );
+        let motor_builder = builder.angular_motors_helper(
+            joint,
+            frame1,
+            frame2,
+            &body1.world_com,
+            &body2.world_com,
+        );
 
         let start = len;
         for i in DIM..SPATIAL_DIM {
             if motor_axes & (1 << i) != 0 {
-                out[len] = builder.motor_angular_generic(
+                out[len] = motor_builder.motor_angular_generic(
                     jacobians,
                     j_id,
                     joint_id,
@@ -176,6 +185,7 @@ impl JointGenericTwoBodyConstraint {
                     mb2,
                     i - DIM,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -194,6 +204,7 @@ impl JointGenericTwoBodyConstraint {
                     mb2,
                     i,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -294,6 +305,36 @@ impl JointGenericTwoBodyConstraint {
         solver_vel2.axpy(-delta_impulse, &wj2, 1.0);
     }
 
+    /// Seeds the accumulated impulse of this constraint with the matching component of a
+    /// joint warmstart impulse, if this constraint locks a degree of freedom.
+    pub fn seed_impulse(&mut self, impulse: &JointImpulse) {
+        if let WritebackId::Dof(i) = self.writeback_id {
+            self.impulse = impulse[i];
+        }
+    }
+
+    /// Applies the accumulated impulse of this constraint to the solver velocities.
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        if self.impulse == 0.0 {
+            return;
+        }
+
+        let jacobians = jacobians.as_slice();
+
+        let mut solver_vel1 = self.solver_vel1_mut(solver_vels, generic_solver_vels);
+        let wj1 = DVectorView::from_slice(&jacobians[self.wj_id1()..], self.ndofs1);
+        solver_vel1.axpy(self.impulse, &wj1, 1.0);
+
+        let mut solver_vel2 = self.solver_vel2_mut(solver_vels, generic_solver_vels);
+        let wj2 = DVectorView::from_slice(&jacobians[self.wj_id2()..], self.ndofs2);
+        solver_vel2.axpy(-self.impulse, &wj2, 1.0);
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let joint = &mut joints_all[self.joint_id].weight;
         match self.writeback_id {
@@ -376,11 +417,18 @@ impl JointGenericOneBodyConstraint {
             &body2.world_com,
             locked_axes,
         );
+        let motor_builder = builder.angular_motors_helper(
+            joint,
+            frame1,
+            frame2,
+            &body1.world_com,
+            &body2.world_com,
+        );
 
         let start = len;
         for i in DIM..SPATIAL_DIM {
             if motor_axes & (1 << i) != 0 {
-                out[len] = builder.motor_angular_generic_one_body(
+                out[len] = motor_builder.motor_angular_generic_one_body(
                     jacobians,
                     j_id,
                     joint_id,
@@ -459,6 +507,7 @@ impl JointGenericOneBodyConstraint {
                     mb2,
                     i - DIM,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -475,6 +524,7 @@ impl JointGenericOneBodyConstraint {
                     mb2,
                     i,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -534,6 +584,32 @@ impl JointGenericOneBodyConstraint {
         solver_vel2.axpy(-delta_impulse, &wj2, 1.0);
     }
 
+    /// Seeds the accumulated impulse of this constraint with the matching component of a
+    /// joint warmstart impulse, if this constraint locks a degree of freedom.
+    pub fn seed_impulse(&mut self, impulse: &JointImpulse) {
+        if let WritebackId::Dof(i) = self.writeback_id {
+            self.impulse = impulse[i];
+        }
+    }
+
+    /// Applies the accumulated impulse of this constraint to the solver velocities.
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        solver_vels: &mut [SolverVel<Real>],
+        generic_solver_vels: &mut DVector<Real>,
+    ) {
+        if self.impulse == 0.0 {
+            return;
+        }
+
+        let jacobians = jacobians.as_slice();
+
+        let mut solver_vel2 = self.solver_vel2_mut(solver_vels, generic_solver_vels);
+        let wj2 = DVectorView::from_slice(&jacobians[self.wj_id2()..], self.ndofs2);
+        solver_vel2.axpy(-self.impulse, &wj2, 1.0);
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         // FIXME: impulse writeback isn’t supported yet for internal multibody_joint constraints.
         if self.joint_id != usize::MAX {
@@ -7,8 +7,8 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 use crate::dynamics::solver::joint_constraint::{JointSolverBody, JointTwoBodyConstraintHelper};
 use crate::dynamics::solver::MotorParameters;
 use crate::dynamics::{
-    GenericJoint, ImpulseJoint, IntegrationParameters, JointIndex, Multibody, MultibodyJointSet,
-    MultibodyLinkId, RigidBodySet,
+    GenericJoint, ImpulseJoint, IntegrationParameters, JointImpulse, JointIndex, Multibody,
+    MultibodyJointSet, MultibodyLinkId, RigidBodySet,
 };
 use crate::math::{Real, Vector, ANG_DIM, DIM, SPATIAL_DIM};
 use crate::utils;
@@ -36,6 +36,7 @@ pub struct JointGenericTwoBodyConstraintBuilder {
     link2: LinkOrBody,
     joint_id: JointIndex,
     joint: GenericJoint,
+    warmstart_impulse: Option<JointImpulse>,
     j_id: usize,
     // These are solver body for both joints, except that
     // the world_com is actually in local-space.
@@ -52,6 +53,7 @@ impl JointGenericTwoBodyConstraintBuilder {
             link2: LinkOrBody::Body(usize::MAX),
             joint_id: JointIndex::MAX,
             joint: GenericJoint::default(),
+            warmstart_impulse: None,
             j_id: usize::MAX,
             local_body1: JointSolverBody::invalid(),
             local_body2: JointSolverBody::invalid(),
@@ -134,6 +136,7 @@ impl JointGenericTwoBodyConstraintBuilder {
             link2,
             joint_id,
             joint: joint.data,
+            warmstart_impulse: joint.warmstart_impulse,
             j_id: starting_j_id,
             local_body1,
             local_body2,
@@ -147,6 +150,7 @@ impl JointGenericTwoBodyConstraintBuilder {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        substep_id: usize,
         multibodies: &MultibodyJointSet,
         bodies: &[SolverBody],
         jacobians: &mut DVector<Real>,
@@ -201,7 +205,7 @@ impl JointGenericTwoBodyConstraintBuilder {
 
         let mut j_id = self.j_id;
 
-        JointGenericTwoBodyConstraint::lock_axes(
+        let len = JointGenericTwoBodyConstraint::lock_axes(
             params,
             self.joint_id,
             &joint_body1,
@@ -215,6 +219,12 @@ impl JointGenericTwoBodyConstraintBuilder {
             &mut j_id,
             &mut out[self.constraint_id..],
         );
+
+        if let Some(impulse) = self.warmstart_impulse.filter(|_| substep_id == 0) {
+            for constraint in &mut out[self.constraint_id..self.constraint_id + len] {
+                constraint.seed_impulse(&impulse);
+            }
+        }
     }
 }
 
@@ -241,6 +251,7 @@ impl JointGenericOneBodyConstraintBuilder {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        substep_id: usize,
         multibodies: &MultibodyJointSet,
         bodies: &[SolverBody],
         jacobians: &mut DVector<Real>,
@@ -249,7 +260,9 @@ impl JointGenericOneBodyConstraintBuilder {
         match self {
             Self::Empty => {}
             Self::Internal(builder) => builder.update(params, multibodies, jacobians, out),
-            Self::External(builder) => builder.update(params, multibodies, bodies, jacobians, out),
+            Self::External(builder) => {
+                builder.update(params, substep_id, multibodies, bodies, jacobians, out)
+            }
         }
     }
 }
@@ -318,6 +331,7 @@ pub struct JointGenericVelocityOneBodyExternalConstraintBuilder {
     link2: MultibodyLinkId,
     joint_id: JointIndex,
     joint: GenericJoint,
+    warmstart_impulse: Option<JointImpulse>,
     j_id: usize,
     constraint_id: usize,
     // These are solver body for both joints, except that
@@ -393,6 +407,7 @@ impl JointGenericVelocityOneBodyExternalConstraintBuilder {
             link2,
             joint_id,
             joint: joint_data,
+            warmstart_impulse: joint.warmstart_impulse,
             j_id: starting_j_id,
             frame1,
             local_body2,
@@ -405,6 +420,7 @@ impl JointGenericVelocityOneBodyExternalConstraintBuilder {
     pub fn update(
         &self,
         params: &IntegrationParameters,
+        substep_id: usize,
         multibodies: &MultibodyJointSet,
         _bodies: &[SolverBody],
         jacobians: &mut DVector<Real>,
@@ -423,7 +439,7 @@ impl JointGenericVelocityOneBodyExternalConstraintBuilder {
 
         let mut j_id = self.j_id;
 
-        JointGenericOneBodyConstraint::lock_axes(
+        let len = JointGenericOneBodyConstraint::lock_axes(
             params,
             self.joint_id,
             &self.body1,
@@ -436,6 +452,12 @@ impl JointGenericVelocityOneBodyExternalConstraintBuilder {
             &mut j_id,
             &mut out[self.constraint_id..],
         );
+
+        if let Some(impulse) = self.warmstart_impulse.filter(|_| substep_id == 0) {
+            for constraint in &mut out[self.constraint_id..self.constraint_id + len] {
+                constraint.seed_impulse(&impulse);
+            }
+        }
     }
 }
 
@@ -610,6 +632,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         mb2: Option<(&Multibody, usize)>,
         limited_axis: usize,
         limits: [Real; 2],
+        spring: Option<MotorParameters<Real>>,
         writeback_id: WritebackId,
     ) -> JointGenericTwoBodyConstraint {
         let lin_jac = self.basis.column(limited_axis).into_owned();
@@ -636,7 +659,15 @@ impl JointTwoBodyConstraintHelper<Real> {
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
-        constraint.rhs += rhs_bias;
+
+        if let Some(spring) = spring {
+            constraint.rhs_wo_bias += spring.soft_limit_rhs(dist, limits, min_enabled);
+            constraint.rhs = constraint.rhs_wo_bias;
+            constraint.cfm_coeff = spring.cfm_coeff;
+            constraint.cfm_gain = spring.cfm_gain;
+        } else {
+            constraint.rhs += rhs_bias;
+        }
         constraint.impulse_bounds = [
             min_enabled as u32 as Real * -Real::MAX,
             max_enabled as u32 as Real * Real::MAX,
@@ -753,6 +784,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         mb2: Option<(&Multibody, usize)>,
         _limited_axis: usize,
         limits: [Real; 2],
+        spring: Option<MotorParameters<Real>>,
         writeback_id: WritebackId,
     ) -> JointGenericTwoBodyConstraint {
         #[cfg(feature = "dim2")]
@@ -790,7 +822,15 @@ impl JointTwoBodyConstraintHelper<Real> {
         let rhs_bias =
             ((s_ang - s_limits[1]).max(0.0) - (s_limits[0] - s_ang).max(0.0)) * erp_inv_dt;
 
-        constraint.rhs += rhs_bias;
+        if let Some(spring) = spring {
+            let ang = 2.0 * s_ang.asin();
+            constraint.rhs_wo_bias += spring.soft_limit_rhs(ang, limits, min_enabled);
+            constraint.rhs = constraint.rhs_wo_bias;
+            constraint.cfm_coeff = spring.cfm_coeff;
+            constraint.cfm_gain = spring.cfm_gain;
+        } else {
+            constraint.rhs += rhs_bias;
+        }
         constraint.impulse_bounds = impulse_bounds;
         constraint
     }
@@ -995,6 +1035,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         mb2: (&Multibody, usize),
         limited_axis: usize,
         limits: [Real; 2],
+        spring: Option<MotorParameters<Real>>,
         writeback_id: WritebackId,
     ) -> JointGenericOneBodyConstraint {
         let lin_jac = self.basis.column(limited_axis).into_owned();
@@ -1019,7 +1060,15 @@ impl JointTwoBodyConstraintHelper<Real> {
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         let rhs_bias = ((dist - limits[1]).max(0.0) - (limits[0] - dist).max(0.0)) * erp_inv_dt;
-        constraint.rhs += rhs_bias;
+
+        if let Some(spring) = spring {
+            constraint.rhs_wo_bias += spring.soft_limit_rhs(dist, limits, min_enabled);
+            constraint.rhs = constraint.rhs_wo_bias;
+            constraint.cfm_coeff = spring.cfm_coeff;
+            constraint.cfm_gain = spring.cfm_gain;
+        } else {
+            constraint.rhs += rhs_bias;
+        }
         constraint.impulse_bounds = [
             min_enabled as u32 as Real * -Real::MAX,
             max_enabled as u32 as Real * Real::MAX,
@@ -1127,6 +1176,7 @@ impl JointTwoBodyConstraintHelper<Real> {
         mb2: (&Multibody, usize),
         _limited_axis: usize,
         limits: [Real; 2],
+        spring: Option<MotorParameters<Real>>,
         writeback_id: WritebackId,
     ) -> JointGenericOneBodyConstraint {
         #[cfg(feature = "dim2")]
@@ -1162,7 +1212,15 @@ impl JointTwoBodyConstraintHelper<Real> {
         let rhs_bias =
             ((s_ang - s_limits[1]).max(0.0) - (s_limits[0] - s_ang).max(0.0)) * erp_inv_dt;
 
-        constraint.rhs += rhs_bias;
+        if let Some(spring) = spring {
+            let ang = 2.0 * s_ang.asin();
+            constraint.rhs_wo_bias += spring.soft_limit_rhs(ang, limits, min_enabled);
+            constraint.rhs = constraint.rhs_wo_bias;
+            constraint.cfm_coeff = spring.cfm_coeff;
+            constraint.cfm_gain = spring.cfm_gain;
+        } else {
+            constraint.rhs += rhs_bias;
+        }
         constraint.impulse_bounds = impulse_bounds;
         constraint
     }
@@ -1,17 +1,14 @@
 use crate::dynamics::solver::joint_constraint::JointTwoBodyConstraintHelper;
 use crate::dynamics::solver::SolverVel;
 use crate::dynamics::{
-    GenericJoint, IntegrationParameters, JointAxesMask, JointGraphEdge, JointIndex,
+    GenericJoint, IntegrationParameters, JointAxesMask, JointGraphEdge, JointImpulse, JointIndex,
 };
 use crate::math::{AngVector, AngularInertia, Isometry, Point, Real, Vector, DIM, SPATIAL_DIM};
 use crate::num::Zero;
 use crate::utils::{SimdDot, SimdRealCopy};
 
 #[cfg(feature = "simd-is-enabled")]
-use {
-    crate::math::{SimdReal, SIMD_WIDTH},
-    na::SimdValue,
-};
+use crate::math::{SimdReal, SIMD_WIDTH};
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct MotorParameters<N: SimdRealCopy> {
@@ -36,6 +33,18 @@ impl<N: SimdRealCopy> Default for MotorParameters<N> {
     }
 }
 
+impl<N: SimdRealCopy> MotorParameters<N> {
+    /// The velocity bias of the spring of a soft limit, pulling the joint back toward the bound
+    /// it exceeded.
+    ///
+    /// The `dist` is the current joint coordinate, and `min_enabled` is set if it exceeded the
+    /// lower bound of `limits` (instead of the upper bound).
+    pub fn soft_limit_rhs(&self, dist: N, limits: [N; 2], min_enabled: N::SimdBool) -> N {
+        let bound = limits[0].select(min_enabled, limits[1]);
+        (dist - bound) * self.erp_inv_dt
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WritebackId {
     Dof(usize),
@@ -135,6 +144,21 @@ impl<N: SimdRealCopy, const LANES: usize> JointTwoBodyConstraint<N, LANES> {
         solver_vel2.angular -= ang_impulse2;
     }
 
+    pub fn warmstart_generic(
+        &self,
+        solver_vel1: &mut SolverVel<N>,
+        solver_vel2: &mut SolverVel<N>,
+    ) {
+        let lin_impulse = self.lin_jac * self.impulse;
+        let ang_impulse1 = self.ang_jac1 * self.impulse;
+        let ang_impulse2 = self.ang_jac2 * self.impulse;
+
+        solver_vel1.linear += lin_impulse.component_mul(&self.im1);
+        solver_vel1.angular += ang_impulse1;
+        solver_vel2.linear -= lin_impulse.component_mul(&self.im2);
+        solver_vel2.angular -= ang_impulse2;
+    }
+
     pub fn remove_bias_from_rhs(&mut self) {
         self.rhs = self.rhs_wo_bias;
     }
@@ -175,11 +199,18 @@ impl JointTwoBodyConstraint<Real, 1> {
             &body2.world_com,
             locked_axes,
         );
+        let motor_builder = builder.angular_motors_helper(
+            joint,
+            frame1,
+            frame2,
+            &body1.world_com,
+            &body2.world_com,
+        );
 
         let start = len;
         for i in DIM..SPATIAL_DIM {
             if (motor_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.motor_angular(
+                out[len] = motor_builder.motor_angular(
                     [joint_id],
                     body1,
                     body2,
@@ -272,6 +303,7 @@ impl JointTwoBodyConstraint<Real, 1> {
                     body2,
                     i - DIM,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -286,6 +318,7 @@ impl JointTwoBodyConstraint<Real, 1> {
                     body2,
                     i,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -339,6 +372,29 @@ impl JointTwoBodyConstraint<Real, 1> {
         solver_vels[self.solver_vel2[0]] = solver_vel2;
     }
 
+    /// Seeds the accumulated impulse of this constraint with the matching component of a
+    /// joint warmstart impulse, if this constraint locks a degree of freedom.
+    pub fn seed_impulse(&mut self, impulse: &JointImpulse) {
+        if let WritebackId::Dof(i) = self.writeback_id {
+            self.impulse = impulse[i];
+        }
+    }
+
+    /// Applies the accumulated impulse of this constraint to the solver velocities.
+    pub fn warmstart(&self, solver_vels: &mut [SolverVel<Real>]) {
+        if self.impulse == 0.0 {
+            return;
+        }
+
+        let mut solver_vel1 = solver_vels[self.solver_vel1[0]];
+        let mut solver_vel2 = solver_vels[self.solver_vel2[0]];
+
+        self.warmstart_generic(&mut solver_vel1, &mut solver_vel2);
+
+        solver_vels[self.solver_vel1[0]] = solver_vel1;
+        solver_vels[self.solver_vel2[0]] = solver_vel2;
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let joint = &mut joints_all[self.joint_id[0]].weight;
         match self.writeback_id {
@@ -397,23 +453,13 @@ impl JointTwoBodyConstraint<SimdReal, SIMD_WIDTH> {
     }
 
     pub fn solve(&mut self, solver_vels: &mut [SolverVel<Real>]) {
-        let mut solver_vel1 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel1[ii]].angular]),
-        };
-        let mut solver_vel2 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].angular]),
-        };
+        let mut solver_vel1 = SolverVel::gather(solver_vels, self.solver_vel1);
+        let mut solver_vel2 = SolverVel::gather(solver_vels, self.solver_vel2);
 
         self.solve_generic(&mut solver_vel1, &mut solver_vel2);
 
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel1[ii]].linear = solver_vel1.linear.extract(ii);
-            solver_vels[self.solver_vel1[ii]].angular = solver_vel1.angular.extract(ii);
-            solver_vels[self.solver_vel2[ii]].linear = solver_vel2.linear.extract(ii);
-            solver_vels[self.solver_vel2[ii]].angular = solver_vel2.angular.extract(ii);
-        }
+        solver_vel1.scatter(solver_vels, self.solver_vel1);
+        solver_vel2.scatter(solver_vels, self.solver_vel2);
     }
 
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
@@ -471,6 +517,14 @@ impl<N: SimdRealCopy, const LANES: usize> JointOneBodyConstraint<N, LANES> {
         solver_vel2.angular -= ang_impulse;
     }
 
+    pub fn warmstart_generic(&self, solver_vel2: &mut SolverVel<N>) {
+        let lin_impulse = self.lin_jac * self.impulse;
+        let ang_impulse = self.ang_jac2 * self.impulse;
+
+        solver_vel2.linear -= lin_impulse.component_mul(&self.im2);
+        solver_vel2.angular -= ang_impulse;
+    }
+
     pub fn remove_bias_from_rhs(&mut self) {
         self.rhs = self.rhs_wo_bias;
     }
@@ -511,11 +565,18 @@ impl JointOneBodyConstraint<Real, 1> {
             &body2.world_com,
             locked_axes,
         );
+        let motor_builder = builder.angular_motors_helper(
+            joint,
+            frame1,
+            frame2,
+            &body1.world_com,
+            &body2.world_com,
+        );
 
         let start = len;
         for i in DIM..SPATIAL_DIM {
             if (motor_axes & !coupled_axes) & (1 << i) != 0 {
-                out[len] = builder.motor_angular_one_body(
+                out[len] = motor_builder.motor_angular_one_body(
                     [joint_id],
                     body1,
                     body2,
@@ -615,6 +676,7 @@ impl JointOneBodyConstraint<Real, 1> {
                     body2,
                     i - DIM,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -629,6 +691,7 @@ impl JointOneBodyConstraint<Real, 1> {
                     body2,
                     i,
                     [joint.limits[i].min, joint.limits[i].max],
+                    joint.limits[i].spring_params(params.dt),
                     WritebackId::Limit(i),
                 );
                 len += 1;
@@ -678,6 +741,25 @@ impl JointOneBodyConstraint<Real, 1> {
         solver_vels[self.solver_vel2[0]] = solver_vel2;
     }
 
+    /// Seeds the accumulated impulse of this constraint with the matching component of a
+    /// joint warmstart impulse, if this constraint locks a degree of freedom.
+    pub fn seed_impulse(&mut self, impulse: &JointImpulse) {
+        if let WritebackId::Dof(i) = self.writeback_id {
+            self.impulse = impulse[i];
+        }
+    }
+
+    /// Applies the accumulated impulse of this constraint to the solver velocities.
+    pub fn warmstart(&self, solver_vels: &mut [SolverVel<Real>]) {
+        if self.impulse == 0.0 {
+            return;
+        }
+
+        let mut solver_vel2 = solver_vels[self.solver_vel2[0]];
+        self.warmstart_generic(&mut solver_vel2);
+        solver_vels[self.solver_vel2[0]] = solver_vel2;
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let joint = &mut joints_all[self.joint_id[0]].weight;
         match self.writeback_id {
@@ -741,17 +823,11 @@ impl JointOneBodyConstraint<SimdReal, SIMD_WIDTH> {
     }
 
     pub fn solve(&mut self, solver_vels: &mut [SolverVel<Real>]) {
-        let mut solver_vel2 = SolverVel {
-            linear: Vector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].linear]),
-            angular: AngVector::from(gather![|ii| solver_vels[self.solver_vel2[ii]].angular]),
-        };
+        let mut solver_vel2 = SolverVel::gather(solver_vels, self.solver_vel2);
 
         self.solve_generic(&mut solver_vel2);
 
-        for ii in 0..SIMD_WIDTH {
-            solver_vels[self.solver_vel2[ii]].linear = solver_vel2.linear.extract(ii);
-            solver_vels[self.solver_vel2[ii]].angular = solver_vel2.angular.extract(ii);
-        }
+        solver_vel2.scatter(solver_vels, self.solver_vel2);
     }
 
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
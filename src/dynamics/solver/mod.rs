@@ -1,3 +1,4 @@
+pub(crate) use self::inserted_body_solver::solve_inserted_body_contacts;
 // #[cfg(not(feature = "parallel"))]
 pub(crate) use self::island_solver::IslandSolver;
 // #[cfg(feature = "parallel")]
@@ -12,15 +13,20 @@ use self::solver_constraints_set::SolverConstraintsSet;
 use self::velocity_solver::VelocitySolver;
 
 use contact_constraint::*;
+#[cfg(feature = "graph-coloring")]
+use graph_coloring::{ColoredSolve, ContactConstraintColoring};
 use interaction_groups::*;
 pub(crate) use joint_constraint::MotorParameters;
 pub use joint_constraint::*;
 use solver_body::SolverBody;
 use solver_constraints_set::{AnyConstraintMut, ConstraintTypes};
-use solver_vel::SolverVel;
+use solver_vel::{SolverVel, SolverVelsAccess};
 
 mod categorization;
 mod contact_constraint;
+#[cfg(feature = "graph-coloring")]
+mod graph_coloring;
+mod inserted_body_solver;
 mod interaction_groups;
 // #[cfg(not(feature = "parallel"))]
 mod island_solver;
@@ -1,6 +1,8 @@
+use super::SolverVel;
 use crate::dynamics::{RigidBody, RigidBodyVelocity};
 use crate::math::{AngularInertia, Isometry, Point, Real, Vector};
 use crate::prelude::RigidBodyDamping;
+use crate::utils::SimdDot;
 
 #[cfg(feature = "dim2")]
 use crate::num::Zero;
@@ -15,6 +17,8 @@ pub(crate) struct SolverBody {
     pub ccd_thickness: Real,
     pub damping: RigidBodyDamping,
     pub local_com: Point<Real>,
+    pub max_linvel: Real,
+    pub max_angvel: Real,
 }
 
 impl Default for SolverBody {
@@ -28,6 +32,8 @@ impl Default for SolverBody {
             ccd_thickness: 0.0,
             damping: RigidBodyDamping::default(),
             local_com: Point::origin(),
+            max_linvel: Real::MAX,
+            max_angvel: Real::MAX,
         }
     }
 }
@@ -43,6 +49,8 @@ impl SolverBody {
             ccd_thickness: rb.ccd.ccd_thickness,
             damping: rb.damping,
             local_com: rb.mprops.local_mprops.local_com,
+            max_linvel: rb.max_linvel,
+            max_angvel: rb.max_angvel,
         }
     }
 
@@ -55,5 +63,26 @@ impl SolverBody {
         self.ccd_thickness = rb.ccd.ccd_thickness;
         self.damping = rb.damping;
         self.local_com = rb.mprops.local_mprops.local_com;
+        self.max_linvel = rb.max_linvel;
+        self.max_angvel = rb.max_angvel;
+    }
+
+    /// Scales down the given solver velocities so their magnitudes don’t exceed the maximum
+    /// velocities of this body.
+    pub fn clamp_vels(&self, vels: &mut SolverVel<Real>) {
+        let linvel_sq = vels.linear.norm_squared();
+        if linvel_sq > self.max_linvel * self.max_linvel {
+            vels.linear *= self.max_linvel / linvel_sq.sqrt();
+        }
+
+        if self.max_angvel != Real::MAX {
+            // NOTE: `vels.angular` is the angular velocity multiplied by the square root of the
+            //       inertia tensor. Since that’s linear, scaling it scales the angular velocity.
+            let angvel = vels.physical_angular(&self.sqrt_ii);
+            let angvel_sq = angvel.gdot(angvel);
+            if angvel_sq > self.max_angvel * self.max_angvel {
+                vels.angular *= self.max_angvel / angvel_sq.sqrt();
+            }
+        }
     }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "graph-coloring")]
+use super::ContactConstraintColoring;
 use super::InteractionGroups;
 use crate::math::Real;
 use na::DVector;
@@ -63,6 +65,10 @@ pub(crate) struct SolverConstraintsSet<Constraints: ConstraintTypes> {
     pub generic_velocity_one_body_constraints_builder: Vec<Constraints::GenericBuilderOneBody>,
     #[cfg(feature = "simd-is-enabled")]
     pub simd_velocity_one_body_constraints_builder: Vec<Constraints::SimdBuilderOneBody>,
+
+    // NOTE: this is only used for contact constraints.
+    #[cfg(feature = "graph-coloring")]
+    pub coloring: ContactConstraintColoring,
 }
 
 impl<Constraints: ConstraintTypes> SolverConstraintsSet<Constraints> {
@@ -93,9 +99,25 @@ impl<Constraints: ConstraintTypes> SolverConstraintsSet<Constraints> {
             generic_velocity_one_body_constraints_builder: vec![],
             #[cfg(feature = "simd-is-enabled")]
             simd_velocity_one_body_constraints_builder: vec![],
+
+            #[cfg(feature = "graph-coloring")]
+            coloring: ContactConstraintColoring::default(),
         }
     }
 
+    /// The number of velocity constraints, where each SIMD constraint counts as one.
+    pub fn num_constraints(&self) -> usize {
+        let num_constraints = self.velocity_constraints.len()
+            + self.generic_velocity_constraints.len()
+            + self.velocity_one_body_constraints.len()
+            + self.generic_velocity_one_body_constraints.len();
+        #[cfg(feature = "simd-is-enabled")]
+        let num_constraints = num_constraints
+            + self.simd_velocity_constraints.len()
+            + self.simd_velocity_one_body_constraints.len();
+        num_constraints
+    }
+
     #[allow(dead_code)] // Useful for debugging.
     pub fn print_counts(&self) {
         println!("Solver constraints:");
@@ -1,7 +1,12 @@
-use crate::math::{AngVector, Vector, SPATIAL_DIM};
-use crate::utils::SimdRealCopy;
+use crate::math::{AngVector, AngularInertia, Real, Vector, SPATIAL_DIM};
+use crate::utils::{SimdAngularInertia, SimdRealCopy};
 use na::{DVectorView, DVectorViewMut, Scalar};
-use std::ops::{AddAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+#[cfg(feature = "simd-is-enabled")]
+use {
+    crate::math::{SimdReal, SIMD_WIDTH},
+    na::SimdValue,
+};
 
 #[derive(Copy, Clone, Debug, Default)]
 #[repr(C)]
@@ -31,6 +36,89 @@ impl<N: Scalar + Copy> SolverVel<N> {
     }
 }
 
+/// Indexed access to the solver velocities read and written by the contact constraints.
+pub(crate) trait SolverVelsAccess {
+    /// The solver velocity of the `i`-th solver body.
+    fn get_vel(&self, i: usize) -> SolverVel<Real>;
+    /// Sets the solver velocity of the `i`-th solver body.
+    fn set_vel(&mut self, i: usize, vel: SolverVel<Real>);
+}
+
+impl SolverVelsAccess for [SolverVel<Real>] {
+    #[inline(always)]
+    fn get_vel(&self, i: usize) -> SolverVel<Real> {
+        self[i]
+    }
+
+    #[inline(always)]
+    fn set_vel(&mut self, i: usize, vel: SolverVel<Real>) {
+        self[i] = vel;
+    }
+}
+
+impl SolverVel<Real> {
+    /// The actual angular velocity of this solver body, in radians per second.
+    ///
+    /// Because [`Self::angular`] is scaled by the square root of the angular inertia,
+    /// this needs the body’s world-space inverse angular inertia square root (i.e.,
+    /// `RigidBodyMassProps::effective_world_inv_inertia_sqrt`).
+    pub fn physical_angular(&self, inv_inertia_sqrt: &AngularInertia<Real>) -> AngVector<Real> {
+        inv_inertia_sqrt.transform_vector(self.angular)
+    }
+
+    /// Sets [`Self::angular`] from an actual angular velocity, in radians per second.
+    ///
+    /// This is the inverse of [`Self::physical_angular`]. It needs the body’s world-space
+    /// angular inertia square root (i.e., `RigidBodyMassProps::effective_angular_inertia_sqrt`)
+    /// rather than its inverse so that bodies with locked rotations are handled properly.
+    pub fn set_physical_angular(
+        &mut self,
+        angvel: AngVector<Real>,
+        inertia_sqrt: &AngularInertia<Real>,
+    ) {
+        self.angular = inertia_sqrt.transform_vector(angvel);
+    }
+}
+
+#[cfg(feature = "simd-is-enabled")]
+impl SolverVel<Real> {
+    /// Gathers the solver velocities with the given indices into the lanes of a SIMD solver
+    /// velocity.
+    #[inline(always)]
+    pub(crate) fn gather<V: SolverVelsAccess + ?Sized>(
+        vels: &V,
+        indices: [usize; SIMD_WIDTH],
+    ) -> SolverVel<SimdReal> {
+        let vels: [_; SIMD_WIDTH] = gather![|ii| vels.get_vel(indices[ii])];
+        SolverVel {
+            linear: Vector::from(gather![|ii| vels[ii].linear]),
+            angular: AngVector::from(gather![|ii| vels[ii].angular]),
+        }
+    }
+}
+
+#[cfg(feature = "simd-is-enabled")]
+impl SolverVel<SimdReal> {
+    /// Writes the lanes of this SIMD solver velocity to the solver velocities with the given
+    /// indices.
+    ///
+    /// This is the inverse of [`SolverVel::gather`].
+    #[inline(always)]
+    pub(crate) fn scatter<V: SolverVelsAccess + ?Sized>(
+        self,
+        vels: &mut V,
+        indices: [usize; SIMD_WIDTH],
+    ) {
+        for ii in 0..SIMD_WIDTH {
+            let vel = SolverVel {
+                linear: self.linear.extract(ii),
+                angular: self.angular.extract(ii),
+            };
+            vels.set_vel(indices[ii], vel);
+        }
+    }
+}
+
 impl<N: SimdRealCopy> SolverVel<N> {
     pub fn zero() -> Self {
         Self {
@@ -64,3 +152,127 @@ impl<N: SimdRealCopy> Sub for SolverVel<N> {
         }
     }
 }
+
+impl<N: SimdRealCopy> Add for SolverVel<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        SolverVel {
+            linear: self.linear + rhs.linear,
+            angular: self.angular + rhs.angular,
+        }
+    }
+}
+
+impl<N: SimdRealCopy> Mul<N> for SolverVel<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: N) -> Self {
+        SolverVel {
+            linear: self.linear * rhs,
+            angular: self.angular * rhs,
+        }
+    }
+}
+
+impl<N: SimdRealCopy> Neg for SolverVel<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        SolverVel {
+            linear: -self.linear,
+            angular: -self.angular,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SolverVel;
+    use crate::math::{Real, SPATIAL_DIM};
+    #[cfg(feature = "simd-is-enabled")]
+    use {
+        crate::math::{SimdReal, Vector, SIMD_WIDTH},
+        na::SimdValue,
+    };
+
+    fn solver_vels() -> Vec<SolverVel<Real>> {
+        (0..10)
+            .map(|i| {
+                let mut vel = SolverVel::default();
+                for (k, val) in vel.as_mut_slice().iter_mut().enumerate() {
+                    *val = (i * SPATIAL_DIM + k) as Real;
+                }
+                vel
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arithmetic_operators() {
+        let vels = solver_vels();
+        let (v1, v2) = (vels[1], vels[2]);
+        let half = 0.5;
+
+        let delta = (v1 - v2) * half;
+        let sum = v1 + v2;
+        let neg = -v1;
+        for k in 0..SPATIAL_DIM {
+            let (a, b) = (v1.as_slice()[k], v2.as_slice()[k]);
+            assert_eq!(delta.as_slice()[k], (a - b) * half);
+            assert_eq!(sum.as_slice()[k], a + b);
+            assert_eq!(neg.as_slice()[k], -a);
+        }
+    }
+
+    #[cfg(feature = "simd-is-enabled")]
+    #[test]
+    fn simd_arithmetic_operators() {
+        let vels = solver_vels();
+        let v1 = SolverVel::gather(&vels[..], [0, 1, 2, 3]);
+        let v2 = SolverVel::gather(&vels[..], [9, 8, 7, 6]);
+        let scale = SimdReal::from([0.5, -1.0, 2.0, 0.0]);
+
+        let delta = (v1 - v2) * scale;
+        let sum = v1 + v2;
+        let neg = -v1;
+        for ii in 0..SIMD_WIDTH {
+            let (a, b) = (vels[ii], vels[9 - ii]);
+            let s = scale.extract(ii);
+            assert_eq!(delta.linear.extract(ii), (a.linear - b.linear) * s);
+            assert_eq!(delta.angular.extract(ii), (a.angular - b.angular) * s);
+            assert_eq!(sum.linear.extract(ii), a.linear + b.linear);
+            assert_eq!(sum.angular.extract(ii), a.angular + b.angular);
+            assert_eq!(neg.linear.extract(ii), -a.linear);
+            assert_eq!(neg.angular.extract(ii), -a.angular);
+        }
+    }
+
+    #[cfg(feature = "simd-is-enabled")]
+    #[test]
+    fn gather_scatter_match_per_lane_copies() {
+        let indices = [7, 2, 9, 0];
+        let mut vels = solver_vels();
+
+        let gathered = SolverVel::gather(&vels[..], indices);
+        for ii in 0..SIMD_WIDTH {
+            assert_eq!(gathered.linear.extract(ii), vels[indices[ii]].linear);
+            assert_eq!(gathered.angular.extract(ii), vels[indices[ii]].angular);
+        }
+
+        let mut modified = gathered;
+        modified.linear += Vector::repeat(SimdReal::splat(0.5));
+        modified.scatter(&mut vels[..], indices);
+
+        let mut expected = solver_vels();
+        for ii in 0..SIMD_WIDTH {
+            expected[indices[ii]].linear = modified.linear.extract(ii);
+            expected[indices[ii]].angular = modified.angular.extract(ii);
+        }
+
+        for (vel, expected) in vels.iter().zip(expected.iter()) {
+            assert_eq!(vel.linear, expected.linear);
+            assert_eq!(vel.angular, expected.angular);
+        }
+    }
+}
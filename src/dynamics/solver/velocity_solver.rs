@@ -2,13 +2,14 @@ use super::{JointConstraintTypes, SolverConstraintsSet};
 use crate::dynamics::solver::solver_body::SolverBody;
 use crate::dynamics::{
     solver::{ContactConstraintTypes, SolverVel},
-    IntegrationParameters, IslandManager, JointGraphEdge, JointIndex, MultibodyJointSet,
-    MultibodyLinkId, RigidBodySet,
+    IntegrationParameters, IntegrationScheme, IslandManager, JointGraphEdge, JointIndex,
+    MultibodyJointSet, MultibodyLinkId, RigidBodySet,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::Real;
+use crate::pipeline::{IntegrationHook, IslandBodies, StepStats};
 use crate::prelude::RigidBodyVelocity;
-use crate::utils::SimdAngularInertia;
+use crate::utils::SimdDot;
 use na::DVector;
 
 pub(crate) struct VelocitySolver {
@@ -18,6 +19,15 @@ pub(crate) struct VelocitySolver {
     pub generic_solver_vels: DVector<Real>,
     pub generic_solver_vels_increment: DVector<Real>,
     pub multibody_roots: Vec<MultibodyLinkId>,
+    // The velocities before the last PGS iteration, used to check convergence if
+    // `IntegrationParameters::adaptive_iterations` is enabled.
+    prev_solver_vels: Vec<SolverVel<Real>>,
+    prev_generic_solver_vels: DVector<Real>,
+    // The velocities before the last normal or friction constraints resolution, used to
+    // measure the residuals reported in the `StepStats` if
+    // `IntegrationParameters::measure_solver_residuals` is enabled.
+    residual_solver_vels: Vec<SolverVel<Real>>,
+    residual_generic_solver_vels: DVector<Real>,
 }
 
 impl VelocitySolver {
@@ -29,11 +39,16 @@ impl VelocitySolver {
             generic_solver_vels: DVector::zeros(0),
             generic_solver_vels_increment: DVector::zeros(0),
             multibody_roots: Vec::new(),
+            prev_solver_vels: Vec::new(),
+            prev_generic_solver_vels: DVector::zeros(0),
+            residual_solver_vels: Vec::new(),
+            residual_generic_solver_vels: DVector::zeros(0),
         }
     }
 
     pub fn init_constraints(
         &self,
+        params: &IntegrationParameters,
         island_id: usize,
         islands: &IslandManager,
         bodies: &mut RigidBodySet,
@@ -46,12 +61,14 @@ impl VelocitySolver {
         joint_constraints: &mut SolverConstraintsSet<JointConstraintTypes>,
     ) {
         contact_constraints.init(
+            params,
             island_id,
             islands,
             bodies,
             multibodies,
             manifolds_all,
             manifold_indices,
+            params.force_scalar_solver,
         );
 
         joint_constraints.init(
@@ -61,6 +78,7 @@ impl VelocitySolver {
             multibodies,
             joints_all,
             joint_indices,
+            params.force_scalar_solver,
         );
     }
 
@@ -119,9 +137,16 @@ impl VelocitySolver {
                 solver_vel_incr.linear =
                     rb.forces.force.component_mul(&rb.mprops.effective_inv_mass) * params.dt;
 
+                let angvel = if params.enable_gyroscopic_forces {
+                    rb.vels.angvel_with_gyroscopic_forces(&rb.mprops, params.dt)
+                } else {
+                    rb.vels.angvel
+                };
+
                 solver_vel.linear = rb.vels.linvel;
                 // PERF: can we avoid the call to effective_angular_inertia_sqrt?
-                solver_vel.angular = rb.mprops.effective_angular_inertia_sqrt() * rb.vels.angvel;
+                solver_vel
+                    .set_physical_angular(angvel, &rb.mprops.effective_angular_inertia_sqrt());
             }
         }
 
@@ -149,18 +174,31 @@ impl VelocitySolver {
         }
     }
 
+    /// Solves the constraints, adding the number of PGS iterations run to `stats`.
     pub fn solve_constraints(
         &mut self,
         params: &IntegrationParameters,
         num_substeps: usize,
+        islands: &IslandManager,
+        island_id: usize,
         bodies: &mut RigidBodySet,
         multibodies: &mut MultibodyJointSet,
         contact_constraints: &mut SolverConstraintsSet<ContactConstraintTypes>,
         joint_constraints: &mut SolverConstraintsSet<JointConstraintTypes>,
+        mut integration_hook: Option<&mut dyn IntegrationHook>,
+        stats: &mut StepStats,
     ) {
+        // Set once the contact constraints converged, if `params.adaptive_iterations` is
+        // enabled. The remaining substeps then only apply their warmstart impulses.
+        let mut contacts_converged = false;
+
         for substep_id in 0..num_substeps {
             let is_last_substep = substep_id == num_substeps - 1;
 
+            if let Some(hook) = integration_hook.as_deref_mut() {
+                self.apply_integration_hook(hook, params, islands, island_id, bodies, multibodies);
+            }
+
             for (solver_vels, incr) in self
                 .solver_vels
                 .iter_mut()
@@ -175,25 +213,75 @@ impl VelocitySolver {
             /*
              * Update & solve constraints.
              */
-            joint_constraints.update(params, multibodies, &self.solver_bodies);
+            joint_constraints.update(params, substep_id, multibodies, &self.solver_bodies);
             contact_constraints.update(params, substep_id, multibodies, &self.solver_bodies);
 
+            if substep_id == 0 {
+                joint_constraints.warmstart(&mut self.solver_vels, &mut self.generic_solver_vels);
+            }
+
             if params.warmstart_coefficient != 0.0 {
                 contact_constraints.warmstart(&mut self.solver_vels, &mut self.generic_solver_vels);
             }
 
-            for _ in 0..params.num_internal_pgs_iterations {
+            let measure_residuals = is_last_substep && params.measure_solver_residuals;
+            let num_internal_pgs_iterations = if contacts_converged {
+                0
+            } else {
+                params.num_internal_pgs_iterations
+            };
+
+            if contacts_converged {
+                joint_constraints.solve(&mut self.solver_vels, &mut self.generic_solver_vels);
+            }
+
+            for _ in 0..num_internal_pgs_iterations {
+                if params.adaptive_iterations {
+                    self.prev_solver_vels.clone_from(&self.solver_vels);
+                    self.prev_generic_solver_vels
+                        .clone_from(&self.generic_solver_vels);
+                }
+
                 joint_constraints.solve(&mut self.solver_vels, &mut self.generic_solver_vels);
+
+                if measure_residuals {
+                    self.save_residual_velocities();
+                }
                 contact_constraints
                     .solve_restitution(&mut self.solver_vels, &mut self.generic_solver_vels);
+                if measure_residuals {
+                    stats.max_normal_residual = self.residual();
+                    self.save_residual_velocities();
+                }
                 contact_constraints
                     .solve_friction(&mut self.solver_vels, &mut self.generic_solver_vels);
+                if measure_residuals {
+                    stats.max_friction_residual = self.residual();
+                }
+                stats.num_pgs_iterations += 1;
+
+                if params.adaptive_iterations
+                    && self
+                        .max_velocity_change(&self.prev_solver_vels, &self.prev_generic_solver_vels)
+                        < params.adaptive_iterations_tolerance
+                {
+                    // NOTE: the warmstart impulses are only the impulses of the previous
+                    //       substep if they aren’t scaled down.
+                    contacts_converged = params.warmstart_coefficient == 1.0;
+                    break;
+                }
             }
 
-            if is_last_substep {
+            if is_last_substep && !contacts_converged {
                 for _ in 0..params.num_additional_friction_iterations {
+                    if measure_residuals {
+                        self.save_residual_velocities();
+                    }
                     contact_constraints
                         .solve_friction(&mut self.solver_vels, &mut self.generic_solver_vels);
+                    if measure_residuals {
+                        stats.max_friction_residual = self.residual();
+                    }
                 }
             }
 
@@ -209,16 +297,125 @@ impl VelocitySolver {
                 for _ in 0..params.num_internal_stabilization_iterations {
                     joint_constraints
                         .solve_wo_bias(&mut self.solver_vels, &mut self.generic_solver_vels);
-                    contact_constraints.solve_restitution_wo_bias(
-                        &mut self.solver_vels,
-                        &mut self.generic_solver_vels,
-                    );
+                    if !contacts_converged {
+                        contact_constraints.solve_restitution_wo_bias(
+                            &mut self.solver_vels,
+                            &mut self.generic_solver_vels,
+                        );
+                    }
                 }
 
-                contact_constraints
-                    .solve_friction(&mut self.solver_vels, &mut self.generic_solver_vels);
+                if !contacts_converged {
+                    contact_constraints
+                        .solve_friction(&mut self.solver_vels, &mut self.generic_solver_vels);
+                }
+            }
+        }
+    }
+
+    /// Lets the integration hook update the forces applied to the bodies of this island during
+    /// the next substep.
+    fn apply_integration_hook(
+        &mut self,
+        hook: &mut dyn IntegrationHook,
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        island_id: usize,
+        bodies: &mut RigidBodySet,
+        multibodies: &mut MultibodyJointSet,
+    ) {
+        // Give the hook the current velocities. Only the user forces are modified by the
+        // hook, so the other forces (gravity) are kept in `force` in the meantime.
+        for handle in islands.active_island(island_id) {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.forces.force -= rb.forces.user_force;
+
+            if multibodies.rigid_body_link(*handle).is_none() {
+                let solver_body = &self.solver_bodies[rb.ids.active_set_offset];
+                let solver_vels = &self.solver_vels[rb.ids.active_set_offset];
+                rb.vels = RigidBodyVelocity {
+                    linvel: solver_vels.linear,
+                    angvel: solver_vels.physical_angular(&solver_body.sqrt_ii),
+                };
             }
         }
+
+        hook.apply_forces(
+            &mut IslandBodies::new(bodies, islands.active_island(island_id)),
+            params.dt,
+        );
+
+        for handle in islands.active_island(island_id) {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.forces.force += rb.forces.user_force;
+            rb.forces.torque = rb.forces.user_torque;
+
+            if multibodies.rigid_body_link(*handle).is_none() {
+                let solver_vel_incr = &mut self.solver_vels_increment[rb.ids.active_set_offset];
+                solver_vel_incr.angular =
+                    rb.mprops.effective_world_inv_inertia_sqrt * rb.forces.torque * params.dt;
+                solver_vel_incr.linear =
+                    rb.forces.force.component_mul(&rb.mprops.effective_inv_mass) * params.dt;
+            }
+        }
+
+        for link in &self.multibody_roots {
+            let multibody = multibodies
+                .get_multibody_mut_internal(link.multibody)
+                .unwrap();
+            multibody.update_acceleration(bodies);
+
+            let mut solver_vels_incr = self
+                .generic_solver_vels_increment
+                .rows_mut(multibody.solver_id, multibody.ndofs());
+            solver_vels_incr.axpy(params.dt, &multibody.accelerations, 0.0);
+        }
+    }
+
+    /// The largest change of velocity of a body since the velocities were saved into
+    /// `prev_solver_vels` and `prev_generic_solver_vels`.
+    fn max_velocity_change(
+        &self,
+        prev_solver_vels: &[SolverVel<Real>],
+        prev_generic_solver_vels: &DVector<Real>,
+    ) -> Real {
+        let mut max_change: Real = 0.0;
+
+        for ((vels, prev_vels), solver_body) in self
+            .solver_vels
+            .iter()
+            .zip(prev_solver_vels.iter())
+            .zip(self.solver_bodies.iter())
+        {
+            let dvel = *vels - *prev_vels;
+            let dangvel = dvel.physical_angular(&solver_body.sqrt_ii);
+            max_change = max_change
+                .max(dvel.linear.norm())
+                .max(dangvel.gdot(dangvel).sqrt());
+        }
+
+        if !self.generic_solver_vels.is_empty() {
+            max_change =
+                max_change.max((&self.generic_solver_vels - prev_generic_solver_vels).amax());
+        }
+
+        max_change
+    }
+
+    /// Saves the current velocities so that the residual of the next constraints resolution
+    /// can be measured with [`Self::residual`].
+    fn save_residual_velocities(&mut self) {
+        self.residual_solver_vels.clone_from(&self.solver_vels);
+        self.residual_generic_solver_vels
+            .clone_from(&self.generic_solver_vels);
+    }
+
+    /// The largest velocity change since the last call to [`Self::save_residual_velocities`].
+    fn residual(&self) -> Real {
+        self.max_velocity_change(
+            &self.residual_solver_vels,
+            &self.residual_generic_solver_vels,
+        )
     }
 
     pub fn integrate_positions(
@@ -229,10 +426,31 @@ impl VelocitySolver {
         multibodies: &mut MultibodyJointSet,
     ) {
         // Integrate positions.
-        for (solver_vels, solver_body) in self.solver_vels.iter().zip(self.solver_bodies.iter_mut())
+        for ((solver_vels, solver_body), incr) in self
+            .solver_vels
+            .iter_mut()
+            .zip(self.solver_bodies.iter_mut())
+            .zip(self.solver_vels_increment.iter())
         {
-            let linvel = solver_vels.linear;
-            let angvel = solver_body.sqrt_ii.transform_vector(solver_vels.angular);
+            solver_body.clamp_vels(solver_vels);
+            let (linvel, angvel) = match params.integration_scheme {
+                IntegrationScheme::SemiImplicitEuler => (
+                    solver_vels.linear,
+                    solver_vels.physical_angular(&solver_body.sqrt_ii),
+                ),
+                IntegrationScheme::SymplecticRK2 => {
+                    // Integrate with the mid-substep velocities, i.e., remove half of the
+                    // velocity change due to the external forces.
+                    let mid_vels = SolverVel {
+                        linear: solver_vels.linear - incr.linear * 0.5,
+                        angular: solver_vels.angular - incr.angular * 0.5,
+                    };
+                    (
+                        mid_vels.linear,
+                        mid_vels.physical_angular(&solver_body.sqrt_ii),
+                    )
+                }
+            };
 
             let mut new_vels = RigidBodyVelocity { linvel, angvel };
             new_vels = new_vels.apply_damping(params.dt, &solver_body.damping);
@@ -301,9 +519,10 @@ impl VelocitySolver {
             } else {
                 let rb = bodies.index_mut_internal(*handle);
                 let solver_body = &self.solver_bodies[rb.ids.active_set_offset];
-                let solver_vels = &self.solver_vels[rb.ids.active_set_offset];
+                let solver_vels = &mut self.solver_vels[rb.ids.active_set_offset];
+                solver_body.clamp_vels(solver_vels);
 
-                let dangvel = solver_body.sqrt_ii.transform_vector(solver_vels.angular);
+                let dangvel = solver_vels.physical_angular(&solver_body.sqrt_ii);
 
                 let mut new_vels = RigidBodyVelocity {
                     linvel: solver_vels.linear,
@@ -1,10 +1,25 @@
 use crate::dynamics::RigidBodySet;
 use crate::geometry::{BroadPhasePairEvent, ColliderHandle, ColliderSet};
 use parry::math::Real;
+use std::any::Any;
 
 /// An internal index stored in colliders by some broad-phase algorithms.
 pub type BroadPhaseProxyIndex = u32;
 
+/// A copy of the state of a broad-phase, as returned by [`BroadPhase::save_state`].
+pub type BroadPhaseState = Box<dyn Any + Send + Sync>;
+
+/// Error returned by [`BroadPhase::restore_state`] when a broad-phase state can’t be restored.
+#[derive(thiserror::Error, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BroadPhaseStateError {
+    /// The broad-phase doesn’t support restoring its state.
+    #[error("the broad-phase doesn’t support restoring its state")]
+    Unsupported,
+    /// The state wasn’t saved by a broad-phase of the same type.
+    #[error("the broad-phase state was saved by a broad-phase of another type")]
+    MismatchedType,
+}
+
 /// Trait implemented by broad-phase algorithms supported by Rapier.
 ///
 /// The task of a broad-phase algorithm is to detect potential collision pairs, usually based on
@@ -47,4 +62,23 @@ pub trait BroadPhase: Send + Sync + 'static {
         removed_colliders: &[ColliderHandle],
         events: &mut Vec<BroadPhasePairEvent>,
     );
+
+    /// Saves a copy of the current state of this broad-phase.
+    ///
+    /// This is used by [`PhysicsPipeline::step_back`](crate::pipeline::PhysicsPipeline::step_back)
+    /// to restore the broad-phase at the same time as the rest of the physics state. The default
+    /// implementation returns `None`, in which case the broad-phase isn’t restored, and might
+    /// no longer be consistent with the restored colliders.
+    fn save_state(&self) -> Option<BroadPhaseState> {
+        None
+    }
+
+    /// Restores a state previously returned by [`BroadPhase::save_state`].
+    ///
+    /// Returns an error, leaving the broad-phase unchanged, if `state` wasn’t saved by a
+    /// broad-phase of the same type. The default implementation doesn’t support restoring any
+    /// state.
+    fn restore_state(&mut self, _state: &BroadPhaseState) -> Result<(), BroadPhaseStateError> {
+        Err(BroadPhaseStateError::Unsupported)
+    }
 }
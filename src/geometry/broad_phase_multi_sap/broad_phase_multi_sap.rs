@@ -2,8 +2,8 @@ use super::{
     BroadPhasePairEvent, ColliderPair, SAPLayer, SAPProxies, SAPProxy, SAPProxyData, SAPRegionPool,
 };
 use crate::geometry::{
-    BroadPhaseProxyIndex, Collider, ColliderBroadPhaseData, ColliderChanges, ColliderHandle,
-    ColliderSet,
+    BroadPhaseProxyIndex, BroadPhaseState, BroadPhaseStateError, Collider, ColliderBroadPhaseData,
+    ColliderChanges, ColliderHandle, ColliderSet,
 };
 use crate::math::{Isometry, Real};
 use crate::prelude::{BroadPhase, RigidBodySet};
@@ -644,6 +644,18 @@ impl BroadPhase for BroadPhaseMultiSap {
         // layers to possible remove regions from larger layers that would become empty that way.
         self.complete_removals(colliders, removed_colliders);
     }
+
+    fn save_state(&self) -> Option<BroadPhaseState> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn restore_state(&mut self, state: &BroadPhaseState) -> Result<(), BroadPhaseStateError> {
+        let state = state
+            .downcast_ref::<Self>()
+            .ok_or(BroadPhaseStateError::MismatchedType)?;
+        *self = state.clone();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -651,7 +663,10 @@ mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, BroadPhaseMultiSap, ColliderBuilder, ColliderSet};
+    use crate::geometry::{
+        BroadPhase, BroadPhaseMultiSap, BroadPhaseState, BroadPhaseStateError, ColliderBuilder,
+        ColliderSet,
+    };
 
     #[test]
     fn test_add_update_remove() {
@@ -689,4 +704,17 @@ mod test {
         // Make sure the proxy handles is recycled properly.
         broad_phase.update(0.0, 0.0, &mut colliders, &bodies, &[coh], &[], &mut events);
     }
+
+    #[test]
+    fn restore_state_rejects_foreign_states() {
+        let mut broad_phase = BroadPhaseMultiSap::new();
+        let state = broad_phase.save_state().unwrap();
+        assert_eq!(broad_phase.restore_state(&state), Ok(()));
+
+        let foreign: BroadPhaseState = Box::new(0u32);
+        assert_eq!(
+            broad_phase.restore_state(&foreign),
+            Err(BroadPhaseStateError::MismatchedType)
+        );
+    }
 }
@@ -2,7 +2,8 @@ use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
 use crate::geometry::{
     ActiveCollisionTypes, BroadPhaseProxyIndex, ColliderBroadPhaseData, ColliderChanges,
     ColliderFlags, ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition,
-    ColliderShape, ColliderType, InteractionGroups, MeshConverter, MeshConverterError, SharedShape,
+    ColliderShape, ColliderType, ContactModel, InteractionGroups, MeshConverter,
+    MeshConverterError, SharedShape,
 };
 use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
@@ -10,7 +11,7 @@ use crate::pipeline::{ActiveEvents, ActiveHooks};
 use crate::prelude::ColliderEnabled;
 use na::Unit;
 use parry::bounding_volume::{Aabb, BoundingVolume};
-use parry::shape::{Shape, TriMeshFlags};
+use parry::shape::{Capsule, Shape, TriMeshFlags, TypedShape};
 
 #[cfg(feature = "dim3")]
 use crate::geometry::HeightFieldFlags;
@@ -32,6 +33,7 @@ pub struct Collider {
     pub(crate) bf_data: ColliderBroadPhaseData,
     contact_skin: Real,
     contact_force_event_threshold: Real,
+    pub(crate) contact_force: Vector<Real>,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -74,6 +76,16 @@ impl Collider {
         self.parent.map(|parent| parent.handle)
     }
 
+    /// The rigid body this collider is attached to, and the position of this collider relative
+    /// to that rigid body.
+    ///
+    /// Use [`ColliderSet::set_parent_and_position_wrt_parent`](crate::geometry::ColliderSet::set_parent_and_position_wrt_parent)
+    /// to change both at once.
+    pub fn parent_and_position_wrt_parent(&self) -> Option<(RigidBodyHandle, Isometry<Real>)> {
+        self.parent
+            .map(|parent| (parent.handle, parent.pos_wrt_parent))
+    }
+
     /// Is this collider a sensor?
     pub fn is_sensor(&self) -> bool {
         self.coll_type.is_sensor()
@@ -109,6 +121,7 @@ impl Collider {
             flags,
             bf_data: _bf_data, // Internal ids must not be overwritten.
             contact_force_event_threshold,
+            contact_force: _contact_force, // Computed by the physics pipeline.
             user_data,
             contact_skin,
         } = other;
@@ -206,6 +219,18 @@ impl Collider {
         self.material.restitution = coefficient
     }
 
+    /// The minimum relative normal velocity at a contact point for the restitution of this
+    /// collider to apply.
+    pub fn restitution_velocity_threshold(&self) -> Real {
+        self.material.restitution_velocity_threshold
+    }
+
+    /// Sets the minimum relative normal velocity at a contact point for the restitution of this
+    /// collider to apply.
+    pub fn set_restitution_velocity_threshold(&mut self, threshold: Real) {
+        self.material.restitution_velocity_threshold = threshold;
+    }
+
     /// The combine rule used by this collider to combine its restitution
     /// coefficient with the restitution coefficient of the other collider it
     /// is in contact with.
@@ -220,6 +245,19 @@ impl Collider {
         self.material.restitution_combine_rule = rule;
     }
 
+    /// The model used by the constraints solver to resolve contacts with this collider.
+    pub fn contact_model(&self) -> ContactModel {
+        self.material.contact_model
+    }
+
+    /// Sets the model used by the constraints solver to resolve contacts with this collider.
+    ///
+    /// See [`ContactModel::combine`] for the model applied when two colliders with
+    /// different contact models are in contact.
+    pub fn set_contact_model(&mut self, model: ContactModel) {
+        self.material.contact_model = model;
+    }
+
     /// Sets the total force magnitude beyond which a contact force event can be emitted.
     pub fn set_contact_force_event_threshold(&mut self, threshold: Real) {
         self.contact_force_event_threshold = threshold;
@@ -260,6 +298,38 @@ impl Collider {
         }
     }
 
+    /// Does this collider take part in collision-detection?
+    ///
+    /// If this is `false`, no contact or intersection is computed between this collider
+    /// and any other collider, but it can still be found by scene queries if
+    /// [`Self::is_query_enabled`] is `true`.
+    pub fn is_collision_enabled(&self) -> bool {
+        self.flags.collision_enabled
+    }
+
+    /// Sets whether or not this collider takes part in collision-detection.
+    pub fn set_collision_enabled(&mut self, enabled: bool) {
+        if self.flags.collision_enabled != enabled {
+            // NOTE: this affects the narrow-phase the same way as a collision groups change.
+            self.changes.insert(ColliderChanges::GROUPS);
+            self.flags.collision_enabled = enabled;
+        }
+    }
+
+    /// Can this collider be found by scene queries from the `QueryPipeline`?
+    ///
+    /// If this is `false`, ray-casts, shape-casts, point and shape intersection queries
+    /// ignore this collider, but it still takes part in collision-detection if
+    /// [`Self::is_collision_enabled`] is `true`.
+    pub fn is_query_enabled(&self) -> bool {
+        self.flags.query_enabled
+    }
+
+    /// Sets whether or not this collider can be found by scene queries from the `QueryPipeline`.
+    pub fn set_query_enabled(&mut self, enabled: bool) {
+        self.flags.query_enabled = enabled;
+    }
+
     /// Sets the translational part of this collider's position.
     pub fn set_translation(&mut self, translation: Vector<Real>) {
         self.changes.insert(ColliderChanges::POSITION);
@@ -484,6 +554,20 @@ impl Collider {
     pub fn contact_force_event_threshold(&self) -> Real {
         self.contact_force_event_threshold
     }
+
+    /// The net contact force applied to this collider during the last timestep.
+    ///
+    /// This is the world-space sum of the normal and friction impulses computed by the
+    /// constraints solver for all the contacts involving this collider, divided by the
+    /// timestep length. Because forces from opposite sides cancel out, this is not a measure
+    /// of the magnitude of the individual contact forces: use the [`NarrowPhase`](crate::geometry::NarrowPhase)
+    /// contact pairs or contact force events for per-contact forces.
+    ///
+    /// This is zero if the collider didn’t have any active contact during the last timestep,
+    /// including if it is a sensor or if its rigid-body is sleeping.
+    pub fn contact_force(&self) -> Vector<Real> {
+        self.contact_force
+    }
 }
 
 /// A structure responsible for building a new collider.
@@ -501,8 +585,12 @@ pub struct ColliderBuilder {
     pub friction_combine_rule: CoefficientCombineRule,
     /// The restitution coefficient of the collider to be built.
     pub restitution: Real,
+    /// The minimum relative normal velocity at a contact point for restitution to apply.
+    pub restitution_velocity_threshold: Real,
     /// The rule used to combine two restitution coefficients.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The model used to resolve contacts with the collider to be built.
+    pub contact_model: ContactModel,
     /// The position of this collider.
     pub position: Isometry<Real>,
     /// Is this collider a sensor?
@@ -521,6 +609,10 @@ pub struct ColliderBuilder {
     pub solver_groups: InteractionGroups,
     /// Will the collider being built be enabled?
     pub enabled: bool,
+    /// Will the collider being built take part in collision-detection?
+    pub collision_enabled: bool,
+    /// Will the collider being built be found by scene queries?
+    pub query_enabled: bool,
     /// The total force magnitude beyond which a contact force event can be emitted.
     pub contact_force_event_threshold: Real,
     /// An extra thickness around the collider shape to keep them further apart when colliding.
@@ -541,6 +633,7 @@ impl ColliderBuilder {
             mass_properties: ColliderMassProps::default(),
             friction: Self::default_friction(),
             restitution: 0.0,
+            restitution_velocity_threshold: 0.0,
             position: Isometry::identity(),
             is_sensor: false,
             user_data: 0,
@@ -548,10 +641,13 @@ impl ColliderBuilder {
             solver_groups: InteractionGroups::all(),
             friction_combine_rule: CoefficientCombineRule::Average,
             restitution_combine_rule: CoefficientCombineRule::Average,
+            contact_model: ContactModel::Rigid,
             active_collision_types: ActiveCollisionTypes::default(),
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: true,
+            collision_enabled: true,
+            query_enabled: true,
             contact_force_event_threshold: 0.0,
             contact_skin: 0.0,
         }
@@ -836,6 +932,29 @@ impl ColliderBuilder {
         Self::new(SharedShape::heightfield_with_flags(heights, scale, flags))
     }
 
+    /// Initializes a collider builder with a copy of `shape` scaled by the (possibly non-uniform)
+    /// factor `scale` along each local axis.
+    ///
+    /// Negative scaling factors are supported and mirror the shape along the corresponding axes.
+    /// If an odd number of components of `scale` are negative, the handedness of the shape is
+    /// flipped: the winding of triangles, triangle meshes, polylines, and convex polygons/polyhedra
+    /// is reversed so that their normals keep pointing outward.
+    ///
+    /// Shapes that can’t represent the scaled result exactly (for example a ball scaled
+    /// non-uniformly) are approximated by a convex polygon (in 2D) or polyhedron (in 3D). The
+    /// sub-shapes of a compound shape are scaled in their own local frame, so the result is exact
+    /// only if they aren’t rotated or if the magnitude of the scaling factor is uniform.
+    ///
+    /// Returns `None` if any component of `scale` is zero, or if the shape can’t be scaled (for
+    /// example a mirrored heightfield).
+    pub fn scaled(shape: &SharedShape, scale: Vector<Real>) -> Option<Self> {
+        if scale.iter().any(|s| *s == 0.0) {
+            return None;
+        }
+
+        scale_shape(&**shape, &scale).map(Self::new)
+    }
+
     /// The default friction coefficient used by the collider builder.
     pub fn default_friction() -> Real {
         0.5
@@ -864,7 +983,9 @@ impl ColliderBuilder {
     /// Sets the solver groups used by this collider.
     ///
     /// Forces between two colliders in contact will be computed iff their solver groups are
-    /// compatible. See [InteractionGroups::test] for details.
+    /// compatible. See [InteractionGroups::test] for details. Contacts and collision events are
+    /// still computed between colliders with incompatible solver groups, as long as their
+    /// collision groups are compatible.
     pub fn solver_groups(mut self, groups: InteractionGroups) -> Self {
         self.solver_groups = groups;
         self
@@ -876,6 +997,19 @@ impl ColliderBuilder {
         self
     }
 
+    /// Makes the collider built by this builder compute contacts without applying any force.
+    ///
+    /// Like a [sensor](Self::sensor), this collider doesn’t apply any contact force to the bodies
+    /// it touches. But unlike a sensor, the contact manifolds between this collider and other
+    /// colliders are computed by the narrow-phase, including the penetration depth of each contact
+    /// point. This is equivalent to disabling [`Self::sensor`] and setting [`Self::solver_groups`] to
+    /// [`InteractionGroups::none`].
+    pub fn sensor_with_manifolds(mut self) -> Self {
+        self.is_sensor = false;
+        self.solver_groups = InteractionGroups::none();
+        self
+    }
+
     /// The set of physics hooks enabled for this collider.
     pub fn active_hooks(mut self, active_hooks: ActiveHooks) -> Self {
         self.active_hooks = active_hooks;
@@ -912,12 +1046,29 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the minimum relative normal velocity at a contact point for the restitution of the
+    /// collider this builder will build to apply.
+    ///
+    /// Contacts approaching slower than this don’t bounce, e.g., to let heavy rubber bounce while
+    /// small debris doesn’t chatter. The greatest threshold of two colliders in contact is used.
+    pub fn restitution_velocity_threshold(mut self, threshold: Real) -> Self {
+        self.restitution_velocity_threshold = threshold;
+        self
+    }
+
     /// Sets the rule to be used to combine two restitution coefficients in a contact.
     pub fn restitution_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
         self.restitution_combine_rule = rule;
         self
     }
 
+    /// Sets the model used by the constraints solver to resolve contacts with the collider
+    /// this builder will build.
+    pub fn contact_model(mut self, model: ContactModel) -> Self {
+        self.contact_model = model;
+        self
+    }
+
     /// Sets the uniform density of the collider this builder will build.
     ///
     /// This will be overridden by a call to [`Self::mass`] or [`Self::mass_properties`] so it only
@@ -1019,14 +1170,32 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets whether or not the collider built by this builder takes part in collision-detection.
+    ///
+    /// This defaults to `true`.
+    pub fn collision_enabled(mut self, enabled: bool) -> Self {
+        self.collision_enabled = enabled;
+        self
+    }
+
+    /// Sets whether or not the collider built by this builder can be found by scene queries.
+    ///
+    /// This defaults to `true`.
+    pub fn query_enabled(mut self, enabled: bool) -> Self {
+        self.query_enabled = enabled;
+        self
+    }
+
     /// Builds a new collider attached to the given rigid-body.
     pub fn build(&self) -> Collider {
         let shape = self.shape.clone();
         let material = ColliderMaterial {
             friction: self.friction,
             restitution: self.restitution,
+            restitution_velocity_threshold: self.restitution_velocity_threshold,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
+            contact_model: self.contact_model,
         };
         let flags = ColliderFlags {
             collision_groups: self.collision_groups,
@@ -1039,6 +1208,8 @@ impl ColliderBuilder {
             } else {
                 ColliderEnabled::Disabled
             },
+            collision_enabled: self.collision_enabled,
+            query_enabled: self.query_enabled,
         };
         let changes = ColliderChanges::all();
         let pos = ColliderPosition(self.position);
@@ -1060,6 +1231,7 @@ impl ColliderBuilder {
             flags,
             coll_type,
             contact_force_event_threshold: self.contact_force_event_threshold,
+            contact_force: Vector::zeros(),
             contact_skin: self.contact_skin,
             user_data: self.user_data,
         }
@@ -1071,3 +1243,653 @@ impl From<ColliderBuilder> for Collider {
         val.build()
     }
 }
+
+/// Number of subdivisions used to approximate curved shapes that can’t be scaled exactly.
+const SCALED_SHAPE_SUBDIVISIONS: u32 = 10;
+
+/// Scales `shape` by `scale`, making sure the result still has outward-pointing normals if
+/// `scale` flips its handedness.
+fn scale_shape(shape: &dyn Shape, scale: &Vector<Real>) -> Option<SharedShape> {
+    let mirrored = scale.iter().filter(|s| **s < 0.0).count() % 2 == 1;
+    let abs_scale = scale.abs();
+    let scale_dyn = |shape: &dyn Shape, scale: &Vector<Real>| {
+        shape
+            .scale_dyn(scale, SCALED_SHAPE_SUBDIVISIONS)
+            .map(|scaled| SharedShape(scaled.into()))
+    };
+
+    match shape.as_typed_shape() {
+        // These shapes are symmetric wrt. their local coordinate planes so mirroring them
+        // leaves them unchanged.
+        TypedShape::Ball(_) | TypedShape::Cuboid(_) | TypedShape::RoundCuboid(_) => {
+            scale_dyn(shape, &abs_scale)
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::Cylinder(_) | TypedShape::RoundCylinder(_) => scale_dyn(shape, &abs_scale),
+        // The cone is only symmetric wrt. the planes containing its axis. A cone flipped along
+        // `y` can’t be represented as a `Cone` so we fall back to its convex hull.
+        #[cfg(feature = "dim3")]
+        TypedShape::Cone(cone) if scale.y < 0.0 => {
+            let (vertices, _) = cone.to_trimesh(SCALED_SHAPE_SUBDIVISIONS);
+            let points = scaled_points(&vertices, scale);
+            SharedShape::convex_hull(&points)
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::RoundCone(cone) if scale.y < 0.0 => {
+            let (vertices, _) = cone.inner_shape.to_trimesh(SCALED_SHAPE_SUBDIVISIONS);
+            let points = scaled_points(&vertices, scale);
+            SharedShape::round_convex_hull(&points, cone.border_radius)
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::Cone(_) | TypedShape::RoundCone(_) => scale_dyn(shape, &abs_scale),
+        TypedShape::Capsule(capsule) => {
+            // Mirror the capsule’s segment first, then scale by the magnitude of `scale`.
+            let sign = scale.map(|s| s.signum());
+            let mirrored_capsule = Capsule::new(
+                capsule.segment.a.coords.component_mul(&sign).into(),
+                capsule.segment.b.coords.component_mul(&sign).into(),
+                capsule.radius,
+            );
+            scale_dyn(&mirrored_capsule, &abs_scale)
+        }
+        TypedShape::Segment(segment) => {
+            let mut segment = segment.scaled(scale);
+            if mirrored {
+                std::mem::swap(&mut segment.a, &mut segment.b);
+            }
+            Some(SharedShape::new(segment))
+        }
+        TypedShape::Triangle(triangle) => {
+            let mut triangle = triangle.scaled(scale);
+            if mirrored {
+                triangle.reverse();
+            }
+            Some(SharedShape::new(triangle))
+        }
+        TypedShape::RoundTriangle(triangle) => {
+            let mut inner = triangle.inner_shape.scaled(scale);
+            if mirrored {
+                inner.reverse();
+            }
+            Some(SharedShape::round_triangle(
+                inner.a,
+                inner.b,
+                inner.c,
+                triangle.border_radius,
+            ))
+        }
+        TypedShape::TriMesh(trimesh) => {
+            let mut trimesh = trimesh.clone().scaled(scale);
+            if mirrored {
+                trimesh.reverse();
+            }
+            Some(SharedShape::new(trimesh))
+        }
+        TypedShape::Polyline(polyline) => {
+            let mut polyline = polyline.clone().scaled(scale);
+            if mirrored {
+                polyline.reverse();
+            }
+            Some(SharedShape::new(polyline))
+        }
+        #[cfg(feature = "dim2")]
+        TypedShape::ConvexPolygon(poly) if mirrored => {
+            let points = scaled_points(poly.points(), scale);
+            SharedShape::convex_hull(&points)
+        }
+        #[cfg(feature = "dim2")]
+        TypedShape::RoundConvexPolygon(poly) if mirrored => {
+            let points = scaled_points(poly.inner_shape.points(), scale);
+            SharedShape::round_convex_hull(&points, poly.border_radius)
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::ConvexPolyhedron(poly) if mirrored => {
+            let points = scaled_points(poly.points(), scale);
+            SharedShape::convex_hull(&points)
+        }
+        #[cfg(feature = "dim3")]
+        TypedShape::RoundConvexPolyhedron(poly) if mirrored => {
+            let points = scaled_points(poly.inner_shape.points(), scale);
+            SharedShape::round_convex_hull(&points, poly.border_radius)
+        }
+        TypedShape::HeightField(_) if scale.iter().any(|s| *s < 0.0) => None,
+        TypedShape::Compound(compound) => {
+            // Mirroring a sub-shape’s position requires conjugating its rotation by the
+            // mirroring matrix.
+            let sign = na::SMatrix::<Real, DIM, DIM>::from_diagonal(&scale.map(|s| s.signum()));
+            let shapes = compound
+                .shapes()
+                .iter()
+                .map(|(pos, part)| {
+                    let rotmat = sign * pos.rotation.to_rotation_matrix().into_inner() * sign;
+                    let rotation = Rotation::from_rotation_matrix(
+                        &na::Rotation::from_matrix_unchecked(rotmat),
+                    );
+                    let translation = pos.translation.vector.component_mul(scale);
+                    let part = scale_shape(&**part, scale)?;
+                    Some((Isometry::from_parts(translation.into(), rotation), part))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(SharedShape::compound(shapes))
+        }
+        _ => scale_dyn(shape, scale),
+    }
+}
+
+fn scaled_points(points: &[Point<Real>], scale: &Vector<Real>) -> Vec<Point<Real>> {
+    points
+        .iter()
+        .map(|pt| pt.coords.component_mul(scale).into())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, SharedShape, Triangle};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+    use parry::shape::Shape;
+
+    #[test]
+    fn contact_skin_stops_fast_sphere_at_thin_wall() {
+        const WALL_X: Real = 10.0;
+        const WALL_HALF_THICKNESS: Real = 0.05;
+        const RADIUS: Real = 0.25;
+
+        // Returns the largest x reached by the front of the sphere.
+        let run = |contact_skin: Real| -> Real {
+            let mut world = TestWorld::new();
+
+            let wall = world
+                .bodies
+                .insert(RigidBodyBuilder::fixed().translation(Vector::x() * WALL_X));
+            #[cfg(feature = "dim2")]
+            let co = ColliderBuilder::cuboid(WALL_HALF_THICKNESS, 5.0);
+            #[cfg(feature = "dim3")]
+            let co = ColliderBuilder::cuboid(WALL_HALF_THICKNESS, 5.0, 5.0);
+            world
+                .colliders
+                .insert_with_parent(co, wall, &mut world.bodies);
+
+            // The sphere moves by 2m at each timestep, much more than the thickness of the wall.
+            let rb = RigidBodyBuilder::dynamic().linvel(Vector::x() * 120.0);
+            let co = ColliderBuilder::ball(RADIUS).contact_skin(contact_skin);
+            let (handle, _) = world.insert_body(rb, co);
+
+            let mut max_x = Real::MIN;
+
+            for _ in 0..60 {
+                world.step();
+                max_x = max_x.max(world.bodies[handle].translation().x + RADIUS);
+            }
+
+            max_x
+        };
+
+        // Without a contact skin, the sphere tunnels through the wall.
+        assert!(run(0.0) > WALL_X + WALL_HALF_THICKNESS);
+        // With a contact skin wider than the distance traveled in one timestep, the contact
+        // is generated before the sphere reaches the wall, and it never penetrates it.
+        assert!(run(2.5) < WALL_X - WALL_HALF_THICKNESS);
+    }
+
+    #[test]
+    fn sensor_with_manifolds_computes_contacts_without_forces() {
+        use crate::geometry::SolverFlags;
+
+        let mut world = TestWorld::new();
+
+        let (_, sensor) = world.insert_body(
+            RigidBodyBuilder::fixed(),
+            ColliderBuilder::ball(1.0).sensor_with_manifolds(),
+        );
+
+        // Penetrates the sensor by 0.3.
+        let body = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 1.2));
+        let collider =
+            world
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut world.bodies);
+
+        for _ in 0..10 {
+            world.step();
+
+            let pair = world.narrow_phase.contact_pair(sensor, collider).unwrap();
+            let (_, contact) = pair.find_deepest_contact().unwrap();
+            approx::assert_relative_eq!(contact.dist, -0.3, epsilon = 1.0e-5);
+            assert!(pair
+                .manifolds
+                .iter()
+                .all(|m| !m.data.solver_flags.contains(SolverFlags::COMPUTE_IMPULSES)));
+            assert_eq!(pair.total_impulse_magnitude(), 0.0);
+            assert_eq!(*world.bodies[body].linvel(), Vector::zeros());
+            assert_eq!(world.bodies[body].translation().x, 1.2);
+        }
+    }
+
+    #[test]
+    fn disjoint_solver_groups_pass_through_with_events() {
+        use crate::geometry::{Group, InteractionGroups, SolverFlags};
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut world = TestWorld::new();
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        // Both colliders collide, but their solver groups are disjoint.
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.5, 2.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.5, 2.0, 2.0);
+        let wall = world
+            .colliders
+            .insert(wall.solver_groups(InteractionGroups::new(Group::GROUP_1, Group::GROUP_1)));
+
+        let (ball_rb, ball) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -3.0)
+                .linvel(Vector::x() * 10.0),
+            ColliderBuilder::ball(0.5)
+                .solver_groups(InteractionGroups::new(Group::GROUP_2, Group::GROUP_2))
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+        );
+
+        let mut had_contacts = false;
+        let mut collision_events = vec![];
+        for _ in 0..60 {
+            world.step_with(&(), &events);
+
+            if let Some(pair) = world.narrow_phase.contact_pair(wall, ball) {
+                if pair.has_any_active_contact {
+                    had_contacts = true;
+                    // The manifolds are computed, but never solved.
+                    assert!(pair
+                        .manifolds
+                        .iter()
+                        .all(|m| !m.data.solver_flags.contains(SolverFlags::COMPUTE_IMPULSES)));
+                }
+            }
+
+            collision_events.extend(collision_recv.try_iter());
+        }
+
+        // The ball went through the wall without being slowed down…
+        assert!(had_contacts);
+        assert_eq!(*world.bodies[ball_rb].linvel(), Vector::x() * 10.0);
+        assert!(world.bodies[ball_rb].translation().x > 2.0);
+
+        // …but the collision was still reported.
+        assert_eq!(collision_events.len(), 2);
+        assert!(collision_events[0].started());
+        assert!(collision_events[1].stopped());
+        for event in collision_events {
+            let pair = [event.collider1(), event.collider2()];
+            assert!(pair.contains(&wall) && pair.contains(&ball));
+        }
+    }
+
+    #[test]
+    fn restitution_velocity_threshold_prevents_slow_bounces() {
+        // Returns the velocity of a bouncy ball after hitting the floor at the given speed.
+        let velocity_after_impact = |impact_speed: Real| {
+            let mut world = TestWorld::new();
+
+            let floor = cuboid(10.0, 0.5);
+            world.colliders.insert(
+                floor
+                    .translation(Vector::y() * -0.5)
+                    .restitution(1.0)
+                    .restitution_velocity_threshold(1.0),
+            );
+
+            let co = ColliderBuilder::ball(0.5)
+                .restitution(1.0)
+                .restitution_velocity_threshold(2.0);
+            let (ball, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 1.0)
+                    .linvel(Vector::y() * -impact_speed),
+                co,
+            );
+
+            world.steps(60);
+
+            *world.bodies[ball].linvel()
+        };
+
+        // The greatest threshold of both colliders is used, so a hit at 1.5 doesn’t bounce…
+        assert!(velocity_after_impact(1.5).norm() < 1.0e-3);
+        // …while a faster hit does.
+        approx::assert_relative_eq!(
+            velocity_after_impact(5.0),
+            Vector::y() * 5.0,
+            epsilon = 1.0e-2
+        );
+    }
+
+    /// Checks that the mass properties of `shape` scaled by `scale` are those of `shape`
+    /// mirrored and stretched along each axis.
+    fn assert_scaled_mass_properties(shape: &SharedShape, scale: Vector<Real>, epsilon: Real) {
+        let scaled = ColliderBuilder::scaled(shape, scale).unwrap().shape;
+        let mprops = shape.mass_properties(1.0);
+        let scaled_mprops = scaled.mass_properties(1.0);
+
+        // A reversed winding would make the mass of meshes negative.
+        approx::assert_relative_eq!(
+            scaled_mprops.mass(),
+            mprops.mass() * scale.iter().product::<Real>().abs(),
+            max_relative = epsilon
+        );
+        approx::assert_relative_eq!(
+            scaled_mprops.local_com.coords,
+            mprops.local_com.coords.component_mul(&scale),
+            epsilon = epsilon
+        );
+    }
+
+    #[cfg(feature = "dim2")]
+    const MIRRORS: [[Real; 2]; 3] = [[-1.0, 2.0], [1.5, -0.5], [-2.0, -1.0]];
+    #[cfg(feature = "dim3")]
+    const MIRRORS: [[Real; 3]; 4] = [
+        [-1.0, 2.0, 0.5],
+        [1.5, -0.5, 1.0],
+        [-2.0, -1.0, 1.0],
+        [-1.0, -1.0, -1.0],
+    ];
+
+    fn mirrors() -> impl Iterator<Item = Vector<Real>> {
+        MIRRORS.iter().map(|s| Vector::from_row_slice(s))
+    }
+
+    #[test]
+    fn scaled_primitive_shapes_mass_properties() {
+        // Asymmetric convex hull, to check that the center of mass is mirrored.
+        #[cfg(feature = "dim2")]
+        let hull_points = [
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.5, 1.0),
+        ];
+        #[cfg(feature = "dim3")]
+        let hull_points = [
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(2.0, 0.0, 0.0),
+            Point::new(0.5, 1.0, 0.0),
+            Point::new(0.3, 0.2, 1.5),
+        ];
+        let hull = SharedShape::convex_hull(&hull_points).unwrap();
+        let round_hull = SharedShape::round_convex_hull(&hull_points, 0.1).unwrap();
+
+        for scale in mirrors() {
+            #[cfg(feature = "dim2")]
+            assert_scaled_mass_properties(&SharedShape::cuboid(1.0, 2.0), scale, 1.0e-5);
+            #[cfg(feature = "dim3")]
+            assert_scaled_mass_properties(&SharedShape::cuboid(1.0, 2.0, 3.0), scale, 1.0e-5);
+            assert_scaled_mass_properties(&hull, scale, 1.0e-5);
+            // The rounded border isn’t scaled so only compare the inner shapes.
+            let scaled = ColliderBuilder::scaled(&round_hull, scale).unwrap().shape;
+            #[cfg(feature = "dim2")]
+            let inner = &scaled.as_round_convex_polygon().unwrap().inner_shape;
+            #[cfg(feature = "dim3")]
+            let inner = &scaled.as_round_convex_polyhedron().unwrap().inner_shape;
+            approx::assert_relative_eq!(
+                inner.mass_properties(1.0).mass(),
+                hull.mass_properties(1.0).mass() * scale.iter().product::<Real>().abs(),
+                max_relative = 1.0e-5
+            );
+        }
+
+        // Mirroring a ball or a capsule with a uniform magnitude is exact.
+        let ball = SharedShape::ball(0.5);
+        let capsule = SharedShape::capsule(Point::origin(), Point::from(Vector::y()), 0.5);
+        for scale in mirrors() {
+            let uniform = scale.map(|s| s.signum() * 2.0);
+            assert_scaled_mass_properties(&ball, uniform, 1.0e-5);
+            assert_scaled_mass_properties(&capsule, uniform, 1.0e-5);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn scaled_cone_flipped_along_its_axis() {
+        let cone = SharedShape::cone(1.0, 0.5);
+        let scale = Vector::new(1.0, -2.0, 1.0);
+        let scaled = ColliderBuilder::scaled(&cone, scale).unwrap().shape;
+
+        // A cone can’t point downward so it is replaced by a convex polyhedron.
+        assert!(scaled.as_convex_polyhedron().is_some());
+        // The apex moved from `+y` to `-y`, and the base from `-y` to `+y`.
+        assert!(scaled.contains_local_point(&Point::new(0.4, 1.8, 0.0)));
+        assert!(!scaled.contains_local_point(&Point::new(0.4, -1.8, 0.0)));
+        // The polyhedron only approximates the curved cone.
+        assert_scaled_mass_properties(&cone, scale, 0.1);
+    }
+
+    #[test]
+    fn scaled_meshes_keep_outward_normals() {
+        #[cfg(feature = "dim2")]
+        let (vertices, indices) = (
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 1.0),
+                Point::new(0.0, 1.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+        );
+        #[cfg(feature = "dim3")]
+        let (vertices, indices) = {
+            use crate::geometry::Cuboid;
+            let (vertices, indices) = Cuboid::new(Vector::new(1.0, 0.5, 0.25)).to_trimesh();
+            // Offset the mesh so that its center of mass isn’t at the origin.
+            let vertices: Vec<_> = vertices.iter().map(|pt| pt + Vector::x()).collect();
+            (vertices, indices)
+        };
+        let trimesh = SharedShape::trimesh(vertices, indices);
+
+        for scale in mirrors() {
+            assert_scaled_mass_properties(&trimesh, scale, 1.0e-5);
+
+            // Each triangle’s normal follows the mirrored geometry.
+            let scaled = ColliderBuilder::scaled(&trimesh, scale).unwrap().shape;
+            let scaled = scaled.as_trimesh().unwrap();
+            for (tri, scaled_tri) in trimesh
+                .as_trimesh()
+                .unwrap()
+                .triangles()
+                .zip(scaled.triangles())
+            {
+                #[cfg(feature = "dim2")]
+                assert_eq!(tri.orientation(1.0e-6), scaled_tri.orientation(1.0e-6));
+                #[cfg(feature = "dim3")]
+                approx::assert_relative_eq!(
+                    *scaled_tri.normal().unwrap(),
+                    tri.normal().unwrap().component_div(&scale).normalize(),
+                    epsilon = 1.0e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_triangles_and_segments_keep_their_orientation() {
+        #[cfg(feature = "dim2")]
+        let triangle = Triangle::new(
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.2, 1.0),
+        );
+        #[cfg(feature = "dim3")]
+        let triangle = Triangle::new(
+            Point::new(0.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.2),
+            Point::new(0.2, 1.0, 0.5),
+        );
+
+        for scale in mirrors() {
+            let scaled = ColliderBuilder::scaled(&SharedShape::new(triangle), scale)
+                .unwrap()
+                .shape;
+            let scaled = scaled.as_triangle().unwrap();
+            let scaled_round = ColliderBuilder::scaled(
+                &SharedShape::round_triangle(triangle.a, triangle.b, triangle.c, 0.1),
+                scale,
+            )
+            .unwrap()
+            .shape;
+            let scaled_round = &scaled_round.as_round_triangle().unwrap().inner_shape;
+
+            for tri in [scaled, scaled_round] {
+                #[cfg(feature = "dim2")]
+                assert_eq!(tri.orientation(1.0e-6), triangle.orientation(1.0e-6));
+                #[cfg(feature = "dim3")]
+                approx::assert_relative_eq!(
+                    *tri.normal().unwrap(),
+                    triangle.normal().unwrap().component_div(&scale).normalize(),
+                    epsilon = 1.0e-5
+                );
+            }
+        }
+
+        // In 2D, segments and polylines have normals too.
+        #[cfg(feature = "dim2")]
+        {
+            let points = vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.2),
+                Point::new(2.0, 1.0),
+            ];
+            let polyline = SharedShape::polyline(points.clone(), None);
+            let segment = SharedShape::segment(points[0], points[1]);
+
+            for scale in mirrors() {
+                let scaled = ColliderBuilder::scaled(&polyline, scale).unwrap().shape;
+                let mut scaled_segments: Vec<_> =
+                    scaled.as_polyline().unwrap().segments().collect();
+                let scaled_segment = ColliderBuilder::scaled(&segment, scale).unwrap().shape;
+
+                // If the winding is reversed, the segments are listed in reverse order.
+                if scale.x * scale.y < 0.0 {
+                    scaled_segments.reverse();
+                }
+                let segments = polyline.as_polyline().unwrap().segments();
+                for (seg, scaled_seg) in segments.zip(scaled_segments.iter()) {
+                    approx::assert_relative_eq!(
+                        *scaled_seg.normal().unwrap(),
+                        seg.normal().unwrap().component_div(&scale).normalize(),
+                        epsilon = 1.0e-5
+                    );
+                }
+                approx::assert_relative_eq!(
+                    *scaled_segment.as_segment().unwrap().normal().unwrap(),
+                    segment
+                        .as_segment()
+                        .unwrap()
+                        .normal()
+                        .unwrap()
+                        .component_div(&scale)
+                        .normalize(),
+                    epsilon = 1.0e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scaled_compound_mirrors_rotated_parts() {
+        use crate::math::Isometry;
+        use crate::na::RealField;
+
+        // A thin bar rotated by 45 degrees, next to the origin.
+        #[cfg(feature = "dim2")]
+        let (bar, rotation) = (SharedShape::cuboid(1.0, 0.1), 1.0);
+        #[cfg(feature = "dim3")]
+        let (bar, rotation) = (SharedShape::cuboid(1.0, 0.1, 0.1), Vector::z());
+        let pos = Isometry::new(Vector::x() * 2.0, rotation * Real::frac_pi_4());
+        let compound = SharedShape::compound(vec![(pos, bar)]);
+
+        let mut scale = Vector::repeat(1.0);
+        scale.x = -1.0;
+        let scaled = ColliderBuilder::scaled(&compound, scale).unwrap().shape;
+
+        // The bar goes up and right from its center. Mirrored along `x`, it goes up and left.
+        let along = Vector::x() * 0.5 + Vector::y() * 0.5;
+        let across = Vector::x() * 0.5 - Vector::y() * 0.5;
+        let center = Point::from(Vector::x() * 2.0);
+        let mirrored_center = Point::from(Vector::x() * -2.0);
+        assert!(compound.contains_local_point(&(center + along)));
+        assert!(!compound.contains_local_point(&(center + across)));
+        assert!(scaled.contains_local_point(&(mirrored_center + along.component_mul(&scale))));
+        assert!(!scaled.contains_local_point(&(mirrored_center + across.component_mul(&scale))));
+
+        for scale in mirrors() {
+            assert_scaled_mass_properties(&compound, scale, 1.0e-4);
+        }
+    }
+
+    #[test]
+    fn unscalable_shapes() {
+        #[cfg(feature = "dim2")]
+        let heightfield = SharedShape::heightfield(
+            na::DVector::from_row_slice(&[0.0, 1.0, 0.5]),
+            Vector::repeat(1.0),
+        );
+        #[cfg(feature = "dim3")]
+        let heightfield = SharedShape::heightfield(
+            na::DMatrix::from_row_slice(2, 2, &[0.0, 1.0, 0.5, 0.0]),
+            Vector::repeat(1.0),
+        );
+
+        for scale in mirrors() {
+            assert!(ColliderBuilder::scaled(&heightfield, scale).is_none());
+        }
+        // Positive scales are fine.
+        assert!(ColliderBuilder::scaled(&heightfield, Vector::repeat(2.0)).is_some());
+
+        // Zero scaling factors would make the shape degenerate.
+        let mut scale = Vector::repeat(1.0);
+        scale.y = 0.0;
+        assert!(ColliderBuilder::scaled(&SharedShape::ball(1.0), scale).is_none());
+    }
+
+    #[test]
+    fn collider_contact_force() {
+        let mut world = TestWorld::with_gravity();
+
+        let floor_co = ColliderBuilder::ball(100.0).friction(1.0);
+        let (_, floor_co) = world.insert_body(
+            RigidBodyBuilder::fixed().translation(Vector::y() * -100.0),
+            floor_co,
+        );
+
+        // A box of mass 1 pushed sideways, but held in place by friction.
+        let rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.49)
+            .lock_rotations()
+            .can_sleep(false);
+        let handle = world.bodies.insert(rb);
+        world.bodies[handle].add_force(Vector::x() * 1.0, true);
+        let co = cuboid(0.5, 0.5).friction(1.0);
+        let box_co = world
+            .colliders
+            .insert_with_parent(co, handle, &mut world.bodies);
+
+        world.steps(100);
+
+        let expected = Vector::y() * 9.81 - Vector::x() * 1.0;
+        let force = world.colliders[box_co].contact_force();
+        assert!((force - expected).norm() < 1.0e-2, "{force:?}");
+        assert!((world.colliders[floor_co].contact_force() + force).norm() < 1.0e-5);
+
+        // The force is reset once the contact is gone.
+        world.bodies[handle].set_translation(Vector::y() * 10.0, true);
+        world.step();
+        assert_eq!(world.colliders[box_co].contact_force(), Vector::zeros());
+        assert_eq!(world.colliders[floor_co].contact_force(), Vector::zeros());
+    }
+}
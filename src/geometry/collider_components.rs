@@ -1,4 +1,7 @@
-use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
+use crate::dynamics::{
+    CoefficientCombineRule, IntegrationParameters, MassProperties, MotorModel, RigidBodyHandle,
+    RigidBodyType,
+};
 use crate::geometry::{BroadPhaseProxyIndex, InteractionGroups, Shape, SharedShape};
 use crate::math::{Isometry, Real};
 use crate::parry::partitioning::IndexedData;
@@ -272,10 +275,17 @@ pub struct ColliderMaterial {
     /// Should be `>= 0` and should generally not be greater than `1` (perfectly elastic
     /// collision).
     pub restitution: Real,
+    /// The minimum relative normal velocity at a contact point for restitution to apply.
+    ///
+    /// Contacts approaching slower than this don’t bounce, which prevents resting objects from
+    /// chattering. The greatest threshold of two colliders in contact is used. Should be `>= 0`.
+    pub restitution_velocity_threshold: Real,
     /// The rule applied to combine the friction coefficients of two colliders in contact.
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
+    /// The model used by the constraints solver to resolve penetrations with this collider.
+    pub contact_model: ContactModel,
 }
 
 impl ColliderMaterial {
@@ -294,8 +304,122 @@ impl Default for ColliderMaterial {
         Self {
             friction: 1.0,
             restitution: 0.0,
+            restitution_velocity_threshold: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
+            contact_model: ContactModel::default(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+/// The model used by the constraints solver to resolve penetrations between two colliders.
+pub enum ContactModel {
+    /// Contacts are resolved as non-penetration constraints, pushing the colliders apart
+    /// as fast as the contact regularization parameters of the `IntegrationParameters` allow.
+    #[default]
+    Rigid,
+    /// Contacts are resolved as damped springs, applying a restoring force proportional
+    /// to the penetration depth.
+    ///
+    /// The solved spring-like equation is:
+    /// `force = stiffness * penetration_depth - damping * penetration_velocity`
+    ///
+    /// Because the spring applies a force rather than an acceleration, heavier bodies sink
+    /// deeper: a body of mass `m` resting on a spring contact under a gravity `g` settles at a
+    /// penetration depth of `m * g / stiffness`.
+    ///
+    /// This is useful for simulating soft or squishy materials. Without damping, this is
+    /// equivalent to a compliant contact (as in XPBD) with a compliance equal to
+    /// `1.0 / stiffness`. See [`SolverContact::compliance`](crate::geometry::SolverContact::compliance)
+    /// to set the compliance of the contacts between two specific colliders.
+    Spring {
+        /// The stiffness of the contact spring. Should be `> 0`.
+        stiffness: Real,
+        /// The damping of the contact spring. Should be `>= 0`.
+        damping: Real,
+    },
+}
+
+impl ContactModel {
+    /// Combines the contact models of two colliders in contact.
+    ///
+    /// Contacts between a rigid and a spring material use the spring model. Contacts between two
+    /// spring materials use the equivalent of both springs connected in series.
+    pub fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (ContactModel::Rigid, model) | (model, ContactModel::Rigid) => model,
+            (
+                ContactModel::Spring {
+                    stiffness: k1,
+                    damping: c1,
+                },
+                ContactModel::Spring {
+                    stiffness: k2,
+                    damping: c2,
+                },
+            ) => ContactModel::Spring {
+                stiffness: k1 * k2 * crate::utils::inv(k1 + k2),
+                damping: c1 * c2 * crate::utils::inv(c1 + c2),
+            },
+        }
+    }
+
+    /// This contact model, in series with a spring with a stiffness of `1.0 / compliance`.
+    ///
+    /// The damping of a spring model is kept unchanged. This returns `self` if `compliance` is
+    /// zero.
+    pub fn with_compliance(self, compliance: Real) -> Self {
+        if compliance <= 0.0 {
+            return self;
+        }
+
+        match self {
+            ContactModel::Rigid => ContactModel::Spring {
+                stiffness: 1.0 / compliance,
+                damping: 0.0,
+            },
+            ContactModel::Spring { stiffness, damping } => ContactModel::Spring {
+                stiffness: stiffness / (1.0 + stiffness * compliance),
+                damping,
+            },
+        }
+    }
+
+    /// The coefficients `(cfm_factor, erp_inv_dt, allowed_linear_error, max_corrective_velocity)`
+    /// used by the constraints solver for contacts following this model.
+    pub(crate) fn regularization_coefficients(
+        self,
+        params: &IntegrationParameters,
+    ) -> (Real, Real, Real, Real) {
+        match self {
+            ContactModel::Rigid => (
+                params.contact_cfm_factor(),
+                params.contact_erp_inv_dt(),
+                params.allowed_linear_error(),
+                params.max_corrective_velocity(),
+            ),
+            ContactModel::Spring { stiffness, damping } => {
+                let (erp_inv_dt, _, _) =
+                    MotorModel::ForceBased.combine_coefficients(params.dt, stiffness, damping);
+                // NOTE: the softness of the spring is applied by each contact point through
+                //       `Self::cfm_gain` instead of a global cfm factor. The spring force is
+                //       applied for any penetration, and isn’t bounded.
+                (1.0, erp_inv_dt, 0.0, Real::MAX)
+            }
+        }
+    }
+
+    /// The compliance added to the effective mass of each contact point following this model.
+    pub(crate) fn cfm_gain(self, params: &IntegrationParameters) -> Real {
+        match self {
+            ContactModel::Rigid => 0.0,
+            ContactModel::Spring { stiffness, damping } => {
+                MotorModel::ForceBased
+                    .combine_coefficients(params.dt, stiffness, damping)
+                    .2
+            }
         }
     }
 }
@@ -411,6 +535,11 @@ pub struct ColliderFlags {
     pub active_events: ActiveEvents,
     /// Whether or not the collider is enabled.
     pub enabled: ColliderEnabled,
+    /// Whether or not the collider takes part in collision-detection (contacts and intersections
+    /// with other colliders).
+    pub collision_enabled: bool,
+    /// Whether or not the collider can be found by scene queries from the `QueryPipeline`.
+    pub query_enabled: bool,
 }
 
 impl Default for ColliderFlags {
@@ -422,6 +551,8 @@ impl Default for ColliderFlags {
             active_hooks: ActiveHooks::empty(),
             active_events: ActiveEvents::empty(),
             enabled: ColliderEnabled::Enabled,
+            collision_enabled: true,
+            query_enabled: true,
         }
     }
 }
@@ -443,3 +574,78 @@ impl From<ActiveEvents> for ColliderFlags {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ContactModel;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::{floor, TestWorld};
+
+    /// Drops a ball of the given mass on a spring floor, and returns the number of times its
+    /// vertical velocity changed direction, its final speed, and its final penetration depth.
+    fn drop_ball_on_spring_floor(mass: Real, stiffness: Real) -> (usize, Real, Real) {
+        let mut world = TestWorld::with_gravity();
+
+        world
+            .colliders
+            .insert(floor().contact_model(ContactModel::Spring {
+                stiffness,
+                damping: 10.0,
+            }));
+        // Don’t let the ball fall asleep before it reached its resting depth.
+        let (ball, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false),
+            ColliderBuilder::ball(0.5).mass(mass),
+        );
+
+        let mut num_direction_changes = 0;
+        let mut prev_vel_y = 0.0;
+
+        for _ in 0..600 {
+            world.step();
+
+            let vel_y = world.bodies[ball].linvel().y;
+            if vel_y * prev_vel_y < 0.0 {
+                num_direction_changes += 1;
+            }
+            prev_vel_y = vel_y;
+        }
+
+        let rb = &world.bodies[ball];
+        (
+            num_direction_changes,
+            rb.linvel().norm(),
+            0.5 - rb.translation().y,
+        )
+    }
+
+    #[test]
+    fn spring_contact_oscillates_and_settles() {
+        let stiffness = 1000.0;
+        let (num_direction_changes, speed, depth) = drop_ball_on_spring_floor(1.0, stiffness);
+
+        // The ball bounced on the spring a few times before settling.
+        assert!(num_direction_changes >= 4, "{num_direction_changes}");
+        assert!(speed < 1.0e-3, "{speed}");
+
+        // At rest, the spring force compensates gravity.
+        approx::assert_relative_eq!(depth, 9.81 / stiffness, max_relative = 1.0e-2);
+    }
+
+    #[test]
+    fn spring_contact_depth_scales_with_mass() {
+        let stiffness = 1000.0;
+        let (_, speed1, depth1) = drop_ball_on_spring_floor(1.0, stiffness);
+        let (_, speed4, depth4) = drop_ball_on_spring_floor(4.0, stiffness);
+
+        assert!(speed1 < 1.0e-3, "{speed1}");
+        assert!(speed4 < 1.0e-3, "{speed4}");
+        // The spring applies a force, so a four times heavier ball sinks four times deeper.
+        approx::assert_relative_eq!(depth1, 9.81 / stiffness, max_relative = 1.0e-2);
+        approx::assert_relative_eq!(depth4, 4.0 * 9.81 / stiffness, max_relative = 1.0e-2);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::data::arena::Arena;
 use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
 use crate::geometry::{Collider, ColliderChanges, ColliderHandle, ColliderParent};
-use crate::math::Isometry;
+use crate::math::{Isometry, Real};
 use std::ops::{Index, IndexMut};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -145,17 +145,53 @@ impl ColliderSet {
     }
 
     /// Sets the parent of the given collider.
+    ///
+    /// If the collider already had a parent, its position relative to its new parent is the same
+    /// as its position relative to its previous parent. Otherwise, it is set to the identity.
     // TODO: find a way to define this as a method of Collider.
     pub fn set_parent(
         &mut self,
         handle: ColliderHandle,
         new_parent_handle: Option<RigidBodyHandle>,
         bodies: &mut RigidBodySet,
+    ) {
+        self.set_parent_internal(handle, new_parent_handle, None, bodies);
+    }
+
+    /// Sets the parent of the given collider, as well as its position relative to that parent.
+    ///
+    /// This can be used to move a collider from one rigid-body to another at runtime. Unlike
+    /// removing the collider and inserting it again, this preserves its handle and its contact
+    /// state. The mass properties of both the previous and the new parent are updated
+    /// accordingly. The position relative to the parent is ignored if `new_parent_handle` is `None`.
+    pub fn set_parent_and_position_wrt_parent(
+        &mut self,
+        handle: ColliderHandle,
+        new_parent_handle: Option<RigidBodyHandle>,
+        pos_wrt_parent: Isometry<Real>,
+        bodies: &mut RigidBodySet,
+    ) {
+        self.set_parent_internal(handle, new_parent_handle, Some(pos_wrt_parent), bodies);
+    }
+
+    fn set_parent_internal(
+        &mut self,
+        handle: ColliderHandle,
+        new_parent_handle: Option<RigidBodyHandle>,
+        new_pos_wrt_parent: Option<Isometry<Real>>,
+        bodies: &mut RigidBodySet,
     ) {
         if let Some(collider) = self.get_mut(handle) {
             let curr_parent = collider.parent.map(|p| p.handle);
             if new_parent_handle == curr_parent {
-                return; // Nothing to do, this is the same parent.
+                // This is the same parent, only the relative position may need to be updated.
+                if let (Some(parent), Some(pos_wrt_parent)) =
+                    (&mut collider.parent, new_pos_wrt_parent)
+                {
+                    collider.changes |= ColliderChanges::PARENT;
+                    parent.pos_wrt_parent = pos_wrt_parent;
+                }
+                return;
             }
 
             collider.changes |= ColliderChanges::PARENT;
@@ -168,14 +204,13 @@ impl ColliderSet {
 
             match new_parent_handle {
                 Some(new_parent_handle) => {
-                    if let Some(parent) = &mut collider.parent {
-                        parent.handle = new_parent_handle;
-                    } else {
-                        collider.parent = Some(ColliderParent {
-                            handle: new_parent_handle,
-                            pos_wrt_parent: Isometry::identity(),
-                        })
-                    };
+                    let pos_wrt_parent = new_pos_wrt_parent
+                        .or(collider.parent.map(|p| p.pos_wrt_parent))
+                        .unwrap_or(Isometry::identity());
+                    collider.parent = Some(ColliderParent {
+                        handle: new_parent_handle,
+                        pos_wrt_parent,
+                    });
 
                     if let Some(rb) = bodies.get_mut(new_parent_handle) {
                         rb.add_collider_internal(
@@ -230,6 +265,31 @@ impl ColliderSet {
         Some(collider)
     }
 
+    /// Removes several colliders from this set and update their parents accordingly.
+    ///
+    /// The colliders are removed by increasing index, independently from the order of
+    /// `handles`. Because the slots freed by the removals are reused by the next insertions,
+    /// this ensures that the handles of the colliders inserted afterward only depend on the
+    /// set of removed colliders, e.g., for determinism when `handles` are collected from a
+    /// `HashSet`. See [`Self::remove`] for details on the other parameters.
+    ///
+    /// Returns the removed colliders, by increasing index. Handles of colliders that aren’t
+    /// part of this set are ignored.
+    pub fn remove_batch(
+        &mut self,
+        handles: &[ColliderHandle],
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        wake_up: bool,
+    ) -> Vec<Collider> {
+        let mut handles = handles.to_vec();
+        handles.sort_unstable_by_key(|handle| handle.into_raw_parts());
+        handles
+            .into_iter()
+            .filter_map(|handle| self.remove(handle, islands, bodies, wake_up))
+            .collect()
+    }
+
     /// Gets the collider with the given handle without a known generation.
     ///
     /// This is useful when you know you want the collider at position `i` but
@@ -331,3 +391,58 @@ impl IndexMut<ColliderHandle> for ColliderSet {
         collider
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Isometry, Point, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn reparent_collider_with_position() {
+        let mut world = TestWorld::new();
+
+        let (body1, handle) = world.insert_body(
+            RigidBodyBuilder::dynamic(),
+            ColliderBuilder::ball(1.0).density(1.0),
+        );
+        let body2 = world
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 5.0));
+        let collider_mass = world.colliders[handle].mass();
+
+        world.step();
+        assert_eq!(world.bodies[body1].mass(), collider_mass);
+        assert_eq!(world.bodies[body2].mass(), 0.0);
+
+        let pos_wrt_parent = Isometry::new(Vector::x() * 2.0, na::zero());
+        world.colliders.set_parent_and_position_wrt_parent(
+            handle,
+            Some(body2),
+            pos_wrt_parent,
+            &mut world.bodies,
+        );
+        assert_eq!(
+            world.colliders[handle].parent_and_position_wrt_parent(),
+            Some((body2, pos_wrt_parent))
+        );
+        world.step();
+
+        // The mass properties of both bodies are updated.
+        assert_eq!(world.bodies[body1].mass(), 0.0);
+        assert!((world.bodies[body2].mass() - collider_mass).abs() < 1.0e-6);
+        assert!(
+            (world.bodies[body2].center_of_mass()
+                - Point::from(Vector::x() * 2.0 + Vector::y() * 5.0))
+            .norm()
+                < 1.0e-6
+        );
+        assert!(world.bodies[body1].colliders().is_empty());
+        assert_eq!(world.bodies[body2].colliders(), &[handle]);
+        assert!(
+            (world.colliders[handle].translation() - Vector::x() * 2.0 - Vector::y() * 5.0).norm()
+                < 1.0e-6
+        );
+    }
+}
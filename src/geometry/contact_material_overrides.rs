@@ -0,0 +1,232 @@
+use crate::geometry::{ColliderHandle, Group};
+use crate::math::Real;
+use std::collections::HashMap;
+
+#[cfg(doc)]
+use crate::geometry::{InteractionGroups, NarrowPhase};
+
+/// Friction and restitution coefficients used instead of the ones computed with the
+/// combine rules of the colliders involved in a contact.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ContactMaterialOverride {
+    /// The friction coefficient to use, or `None` to use the friction combine rule.
+    pub friction: Option<Real>,
+    /// The restitution coefficient to use, or `None` to use the restitution combine rule.
+    pub restitution: Option<Real>,
+}
+
+impl ContactMaterialOverride {
+    /// An override of the friction coefficient only.
+    pub fn friction(friction: Real) -> Self {
+        Self {
+            friction: Some(friction),
+            restitution: None,
+        }
+    }
+
+    /// An override of the restitution coefficient only.
+    pub fn restitution(restitution: Real) -> Self {
+        Self {
+            friction: None,
+            restitution: Some(restitution),
+        }
+    }
+}
+
+/// A table of friction and restitution overrides for specific pairs of colliders or of
+/// collision groups.
+///
+/// This is consulted by the [`NarrowPhase`] when computing the solver contacts of a contact
+/// pair. An override registered for a pair of colliders takes precedence over overrides
+/// registered for pairs of groups. If no override matches, the combine rules of the colliders
+/// are used.
+///
+/// Overrides for pairs of colliders are stored in a hash-map. Overrides for pairs of groups are
+/// tested linearly, in insertion order, so there should only be a few of them.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default)]
+pub struct ContactMaterialOverrides {
+    colliders: HashMap<(ColliderHandle, ColliderHandle), ContactMaterialOverride>,
+    groups: Vec<(Group, Group, ContactMaterialOverride)>,
+}
+
+impl ContactMaterialOverrides {
+    /// Creates an empty table of overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Is this table empty?
+    pub fn is_empty(&self) -> bool {
+        self.colliders.is_empty() && self.groups.is_empty()
+    }
+
+    /// Removes all the overrides from this table.
+    pub fn clear(&mut self) {
+        self.colliders.clear();
+        self.groups.clear();
+    }
+
+    /// Sets the override applied to the contacts between two colliders.
+    ///
+    /// The order of the colliders doesn’t matter.
+    pub fn insert_for_colliders(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+        material: ContactMaterialOverride,
+    ) {
+        let _ = self
+            .colliders
+            .insert(Self::colliders_key(collider1, collider2), material);
+    }
+
+    /// Removes the override applied to the contacts between two colliders.
+    pub fn remove_for_colliders(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<ContactMaterialOverride> {
+        self.colliders
+            .remove(&Self::colliders_key(collider1, collider2))
+    }
+
+    /// Sets the override applied to the contacts between a collider that is a member of
+    /// (at least) one of the groups `groups1`, and a collider that is a member of (at least) one
+    /// of the groups `groups2`.
+    ///
+    /// The group memberships of a collider are given by its
+    /// [`InteractionGroups::memberships`]. The order of the groups doesn’t matter.
+    pub fn insert_for_groups(
+        &mut self,
+        groups1: Group,
+        groups2: Group,
+        material: ContactMaterialOverride,
+    ) {
+        if let Some(entry) = self
+            .groups
+            .iter_mut()
+            .find(|(g1, g2, _)| Self::same_groups(*g1, *g2, groups1, groups2))
+        {
+            entry.2 = material;
+        } else {
+            self.groups.push((groups1, groups2, material));
+        }
+    }
+
+    /// Removes the override applied to the contacts between colliders of the given groups.
+    pub fn remove_for_groups(
+        &mut self,
+        groups1: Group,
+        groups2: Group,
+    ) -> Option<ContactMaterialOverride> {
+        let i = self
+            .groups
+            .iter()
+            .position(|(g1, g2, _)| Self::same_groups(*g1, *g2, groups1, groups2))?;
+        Some(self.groups.remove(i).2)
+    }
+
+    /// The override applied to the contacts between the two given colliders, if any.
+    ///
+    /// `memberships1` and `memberships2` are the group memberships of the first and second
+    /// colliders.
+    pub fn get(
+        &self,
+        collider1: ColliderHandle,
+        memberships1: Group,
+        collider2: ColliderHandle,
+        memberships2: Group,
+    ) -> Option<ContactMaterialOverride> {
+        if !self.colliders.is_empty() {
+            if let Some(material) = self
+                .colliders
+                .get(&Self::colliders_key(collider1, collider2))
+            {
+                return Some(*material);
+            }
+        }
+
+        self.groups
+            .iter()
+            .find(|(g1, g2, _)| {
+                (memberships1.intersects(*g1) && memberships2.intersects(*g2))
+                    || (memberships1.intersects(*g2) && memberships2.intersects(*g1))
+            })
+            .map(|entry| entry.2)
+    }
+
+    fn colliders_key(
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> (ColliderHandle, ColliderHandle) {
+        if collider1.0 <= collider2.0 {
+            (collider1, collider2)
+        } else {
+            (collider2, collider1)
+        }
+    }
+
+    fn same_groups(a1: Group, a2: Group, b1: Group, b2: Group) -> bool {
+        (a1 == b1 && a2 == b2) || (a1 == b2 && a2 == b1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ContactMaterialOverride;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::{ColliderBuilder, Group, InteractionGroups};
+    use crate::math::Vector;
+    use crate::pipeline::test_world::{floor, TestWorld};
+
+    #[test]
+    fn contact_material_overrides() {
+        let mut world = TestWorld::with_gravity();
+
+        let floor_co = floor()
+            .friction(1.0)
+            .collision_groups(InteractionGroups::new(Group::GROUP_2, Group::ALL));
+        let (_, floor_co) = world.insert_body(RigidBodyBuilder::fixed(), floor_co);
+        let [ball1, ball2] = [-2.0, 2.0].map(|x| {
+            let rb = RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * 0.45);
+            let co = ColliderBuilder::ball(0.5).friction(1.0).restitution(0.3);
+            world.insert_body(rb, co).1
+        });
+        world.colliders[ball2]
+            .set_collision_groups(InteractionGroups::new(Group::GROUP_3, Group::ALL));
+
+        let overrides = world.narrow_phase.material_overrides_mut();
+        overrides.insert_for_colliders(ball1, floor_co, ContactMaterialOverride::friction(0.05));
+        overrides.insert_for_groups(
+            Group::GROUP_3,
+            Group::GROUP_2,
+            ContactMaterialOverride::restitution(0.9),
+        );
+
+        world.step();
+
+        let solver_contact = |collider| {
+            world
+                .narrow_phase
+                .contact_pair(floor_co, collider)
+                .unwrap()
+                .manifolds[0]
+                .data
+                .solver_contacts[0]
+        };
+        let contact1 = solver_contact(ball1);
+        assert_eq!(contact1.friction, 0.05);
+        assert_eq!(contact1.restitution, 0.15);
+        let contact2 = solver_contact(ball2);
+        assert_eq!(contact2.friction, 1.0);
+        assert_eq!(contact2.restitution, 0.9);
+
+        assert!(world
+            .narrow_phase
+            .material_overrides_mut()
+            .remove_for_groups(Group::GROUP_2, Group::GROUP_3)
+            .is_some());
+    }
+}
@@ -1,14 +1,21 @@
 use crate::dynamics::{RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactManifold};
-use crate::math::{Point, Real, TangentImpulse, Vector};
+use crate::geometry::{
+    ColliderHandle, ColliderSet, Contact, ContactManifold, ContactMaterialOverride, ContactModel,
+};
+use crate::math::{Point, Real, TangentImpulse, Vector, DIM, MAX_MANIFOLD_POINTS};
 use crate::pipeline::EventHandler;
 use crate::prelude::CollisionEventFlags;
 use parry::query::ContactManifoldsWorkspace;
 
+#[cfg(feature = "dim3")]
+use {crate::math::DEFAULT_EPSILON, crate::utils::SimdBasis, na::Point2};
+
 use super::CollisionEvent;
 
 #[cfg(doc)]
-use super::Collider;
+use super::{Collider, Shape};
+#[cfg(doc)]
+use crate::pipeline::PhysicsHooks;
 
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -38,10 +45,29 @@ pub struct ContactData {
     /// The friction impulse along the vector orthonormal to the contact normal, applied to the first
     /// collider's rigid-body.
     pub tangent_impulse: TangentImpulse<Real>,
+    /// The world-space sum of the normal and friction impulses applied by this contact to the
+    /// first collider's rigid-body.
+    ///
+    /// The impulse applied to the second collider's rigid-body is given by `-world_impulse`.
+    pub world_impulse: Vector<Real>,
     /// The impulse retained for warmstarting the next simulation step.
     pub warmstart_impulse: Real,
     /// The friction impulse retained for warmstarting the next simulation step.
     pub warmstart_tangent_impulse: TangentImpulse<Real>,
+    /// The effective mass of the normal constraint of this contact during the last solve, or
+    /// zero if it wasn’t solved.
+    pub(crate) effective_mass: Real,
+    /// The number of consecutive timesteps during which this contact was handed to the
+    /// constraints solver.
+    ///
+    /// This counts timesteps, not solver substeps. It is reset to zero when the contact is too
+    /// far apart to generate a solver contact, and is used by
+    /// [`IntegrationParameters::penetration_warmup_steps`](crate::dynamics::IntegrationParameters::penetration_warmup_steps)
+    /// to identify newly created contacts. Like the rest of the contact data, it follows the
+    /// contact when the contact points of the manifold are updated, so it isn’t reset when the
+    /// contact gets another index in [`ContactManifold::points`] (i.e. another
+    /// [`SolverContact::contact_id`]).
+    pub age: u32,
 }
 
 impl Default for ContactData {
@@ -49,8 +75,11 @@ impl Default for ContactData {
         Self {
             impulse: 0.0,
             tangent_impulse: na::zero(),
+            world_impulse: Vector::zeros(),
             warmstart_impulse: 0.0,
             warmstart_tangent_impulse: na::zero(),
+            effective_mass: 0.0,
+            age: 0,
         }
     }
 }
@@ -128,6 +157,14 @@ pub struct ContactPair {
     /// Was a `CollisionEvent::Started` emitted for this collider?
     pub(crate) start_event_emitted: bool,
     pub(crate) workspace: Option<ContactManifoldsWorkspace>,
+    /// The material computed by `PhysicsHooks::contact_pair_material` for this pair.
+    pub(crate) material: Option<ContactMaterialOverride>,
+    /// Was `PhysicsHooks::contact_pair_material` already called for this pair?
+    pub(crate) material_computed: bool,
+    /// The contacts of the previous timestep, with the sub-shape ids of their manifold, used
+    /// to match contacts based on their positions.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub(crate) prev_contacts: Vec<(u32, u32, Contact)>,
 }
 
 impl ContactPair {
@@ -139,14 +176,92 @@ impl ContactPair {
             manifolds: Vec::new(),
             start_event_emitted: false,
             workspace: None,
+            material: None,
+            material_computed: false,
+            prev_contacts: Vec::new(),
         }
     }
 
+    /// The material computed for this pair by [`PhysicsHooks::contact_pair_material`], if any.
+    ///
+    /// This is computed once, the first time the contacts of this pair are computed, and kept
+    /// for as long as this pair exists.
+    pub fn material(&self) -> Option<ContactMaterialOverride> {
+        self.material
+    }
+
     /// Clears all the contacts of this contact pair.
     pub fn clear(&mut self) {
         self.manifolds.clear();
         self.has_any_active_contact = false;
         self.workspace = None;
+        self.prev_contacts.clear();
+    }
+
+    /// Saves the current contacts so that [`Self::match_contacts_using_positions`] can be
+    /// called after they are updated.
+    pub(crate) fn save_contacts_for_matching(&mut self) {
+        self.prev_contacts.clear();
+
+        for manifold in &self.manifolds {
+            for contact in &manifold.points {
+                self.prev_contacts
+                    .push((manifold.subshape1, manifold.subshape2, *contact));
+            }
+        }
+    }
+
+    /// Transfers the data of the contacts lost since [`Self::save_contacts_for_matching`] was
+    /// called to the closest new contacts that didn’t match any previous contact by features.
+    ///
+    /// A lost contact is only transferred if both its local points are closer than `max_dist`
+    /// to the ones of the new contact, and only to a single new contact.
+    pub(crate) fn match_contacts_using_positions(&mut self, max_dist: Real) {
+        let same_features = |a: &Contact, b: &Contact| a.fid1 == b.fid1 && a.fid2 == b.fid2;
+
+        for manifold in &mut self.manifolds {
+            let subshapes = (manifold.subshape1, manifold.subshape2);
+
+            // Only keep the previous contacts of this manifold that were lost.
+            let mut lost: Vec<_> = self
+                .prev_contacts
+                .iter()
+                .filter(|(s1, s2, old)| {
+                    (*s1, *s2) == subshapes
+                        && !manifold.points.iter().any(|pt| same_features(pt, old))
+                })
+                .map(|(_, _, old)| *old)
+                .collect();
+
+            if lost.is_empty() {
+                continue;
+            }
+
+            for contact in &mut manifold.points {
+                let is_matched = self
+                    .prev_contacts
+                    .iter()
+                    .any(|(s1, s2, old)| (*s1, *s2) == subshapes && same_features(contact, old));
+                if is_matched {
+                    continue;
+                }
+
+                let mut closest = None;
+                let mut closest_dist = max_dist;
+                for (i, old) in lost.iter().enumerate() {
+                    let dist = na::distance(&contact.local_p1, &old.local_p1)
+                        .max(na::distance(&contact.local_p2, &old.local_p2));
+                    if dist < closest_dist {
+                        closest = Some(i);
+                        closest_dist = dist;
+                    }
+                }
+
+                if let Some(i) = closest {
+                    contact.data = lost.swap_remove(i).data;
+                }
+            }
+        }
     }
 
     /// The sum of all the impulses applied by contacts on this contact pair.
@@ -208,6 +323,121 @@ impl ContactPair {
         deepest
     }
 
+    /// Moves the solver contacts of the manifolds sharing the same contact normal and contact
+    /// plane into a single manifold, keeping at most `MAX_MANIFOLD_POINTS` of them.
+    ///
+    /// The moved solver contacts keep the warmstart impulses of their original tracked contact.
+    /// The impulses computed for them by the constraints solver are copied back to their original
+    /// tracked contact by [`Self::writeback_merged_contacts`]. The tracked contacts whose solver
+    /// contact is discarded to keep at most `MAX_MANIFOLD_POINTS` get zero impulses.
+    pub(crate) fn merge_coincident_manifolds(&mut self, tolerance: Real) {
+        for i in 0..self.manifolds.len() {
+            let (head, tail) = self.manifolds.split_at_mut(i + 1);
+            let target = &mut head[i];
+
+            let Some(reference) = target.data.solver_contacts.first() else {
+                continue;
+            };
+            let normal = target.data.normal;
+            let reference_point = reference.point;
+
+            for (j, source) in tail.iter_mut().enumerate() {
+                let is_coincident = source.data.normal.dot(&normal) >= COINCIDENT_NORMALS_MIN_DOT
+                    && source
+                        .data
+                        .solver_contacts
+                        .iter()
+                        .all(|c| (c.point - reference_point).dot(&normal).abs() <= tolerance);
+                // NOTE: the solver contact ids must fit in a `u8`.
+                let num_contact_ids = target.points.len()
+                    + target.data.merged_contacts.len()
+                    + source.data.solver_contacts.len();
+
+                if source.data.solver_contacts.is_empty()
+                    || !is_coincident
+                    || num_contact_ids > u8::MAX as usize + 1
+                {
+                    continue;
+                }
+
+                for mut contact in source.data.solver_contacts.drain(..) {
+                    let merged_id = target.points.len() + target.data.merged_contacts.len();
+                    target.data.merged_contacts.push(MergedContact {
+                        manifold_id: i + 1 + j,
+                        contact_id: contact.contact_id,
+                        data: source.points[contact.contact_id as usize].data,
+                    });
+                    contact.contact_id = merged_id as u8;
+                    target.data.solver_contacts.push(contact);
+                }
+            }
+
+            if !target.data.merged_contacts.is_empty()
+                && target.data.solver_contacts.len() > MAX_MANIFOLD_POINTS
+            {
+                consolidate_solver_contacts(target);
+            }
+        }
+    }
+
+    /// Keeps at most `max_contacts` solver contacts in total, across all the manifolds of this
+    /// pair.
+    ///
+    /// The deepest contact is kept, then, iteratively, the one furthest from the contacts
+    /// already kept, so the kept contacts span the contact area of the whole pair. The tracked
+    /// contacts of the discarded solver contacts get zero impulses.
+    pub(crate) fn cull_solver_contacts(&mut self, max_contacts: usize) {
+        let contacts: Vec<_> = self
+            .manifolds
+            .iter()
+            .flat_map(|manifold| manifold.data.solver_contacts.iter())
+            .collect();
+        if contacts.len() <= max_contacts {
+            return;
+        }
+
+        let points: Vec<_> = contacts.iter().map(|contact| contact.point).collect();
+        let deepest =
+            (0..contacts.len()).min_by(|a, b| contacts[*a].dist.total_cmp(&contacts[*b].dist));
+        let mut is_kept = vec![false; contacts.len()];
+        for i in spread_contacts(&points, deepest.unwrap_or(0), max_contacts) {
+            is_kept[i] = true;
+        }
+
+        let mut is_kept = is_kept.into_iter();
+        for manifold in &mut self.manifolds {
+            let manifold_is_kept: Vec<_> = is_kept
+                .by_ref()
+                .take(manifold.data.solver_contacts.len())
+                .collect();
+
+            for k in 0..manifold_is_kept.len() {
+                if !manifold_is_kept[k] {
+                    let contact_id = manifold.data.solver_contacts[k].contact_id;
+                    *solver_contact_data_mut(manifold, contact_id) = ContactData::default();
+                }
+            }
+
+            let mut manifold_is_kept = manifold_is_kept.into_iter();
+            manifold
+                .data
+                .solver_contacts
+                .retain(|_| manifold_is_kept.next().unwrap());
+        }
+    }
+
+    /// Copies the impulses computed by the constraints solver for the solver contacts moved by
+    /// [`Self::merge_coincident_manifolds`] back to their original tracked contacts.
+    pub(crate) fn writeback_merged_contacts(&mut self) {
+        for i in 0..self.manifolds.len() {
+            for k in 0..self.manifolds[i].data.merged_contacts.len() {
+                let merged = self.manifolds[i].data.merged_contacts[k];
+                let manifold = &mut self.manifolds[merged.manifold_id];
+                manifold.points[merged.contact_id as usize].data = merged.data;
+            }
+        }
+    }
+
     pub(crate) fn emit_start_event(
         &mut self,
         bodies: &RigidBodySet,
@@ -261,6 +491,19 @@ pub struct ContactManifoldData {
     // NOTE: read the comment of `solver_contacts` regarding serialization. It applies
     // to this field as well.
     pub normal: Vector<Real>,
+    /// The first friction direction used by the constraints solver for this contact manifold.
+    ///
+    /// This is set by the constraints solver, and is perpendicular to [`Self::normal`]. The
+    /// [`ContactData::tangent_impulse`] of the contacts of this manifold are expressed
+    /// along this direction and [`Self::tangent2`] (in 3D). It isn’t updated while the bodies
+    /// are sleeping.
+    pub tangent1: Vector<Real>,
+    /// The second friction direction used by the constraints solver for this contact manifold.
+    ///
+    /// This is set by the constraints solver, and is perpendicular to both [`Self::normal`] and
+    /// [`Self::tangent1`].
+    #[cfg(feature = "dim3")]
+    pub tangent2: Vector<Real>,
     /// The contacts that will be seen by the constraints solver for computing forces.
     // NOTE: unfortunately, we can't ignore this field when serialize
     // the contact manifold data. The reason is that the solver contacts
@@ -283,6 +526,167 @@ pub struct ContactManifoldData {
     pub relative_dominance: i16,
     /// A user-defined piece of data.
     pub user_data: u32,
+    /// The model used by the constraints solver to resolve these contacts.
+    pub contact_model: ContactModel,
+    /// If `true`, the solver won’t be warmstarted with the impulses of the previous timestep
+    /// for the contacts of this manifold.
+    ///
+    /// This is useful after teleporting a body, when the impulses of the previous timestep
+    /// don’t match the new contact configuration anymore. This flag is automatically cleared
+    /// the next time the solver contacts of this manifold are computed, so only one timestep
+    /// is affected.
+    pub disable_warmstart: bool,
+    /// Did the constraints solver see a contact of this manifold requiring the bodies to move by
+    /// more than half their CCD thickness during a single substep?
+    ///
+    /// This indicates contacts that nearly tunneled, typically between fast or thin bodies. The
+    /// CCD thickness of a rigid-body is the smallest [`Shape::ccd_thickness`] of its colliders,
+    /// and only the thickness of the dynamic bodies is accounted for. This is reset at each step
+    /// before the constraints resolution. Since the manifold may not exist anymore at the end of
+    /// the step if the bodies separated, this is best read from
+    /// [`EventHandler::handle_contact_force_event`].
+    pub has_fast_contact: bool,
+    /// The solver contacts moved to this manifold from other manifolds of the same contact pair.
+    ///
+    /// The solver contacts of this manifold with an id greater or equal to the number of tracked
+    /// contacts refer to these instead of one of `ContactManifold::points`.
+    // NOTE: this needs to be serialized alongside `solver_contacts`.
+    pub(crate) merged_contacts: Vec<MergedContact>,
+}
+
+/// The minimum dot product between the normals of two contact manifolds for them to be
+/// merged by [`ContactPair::merge_coincident_manifolds`].
+const COINCIDENT_NORMALS_MIN_DOT: Real = 0.999;
+
+/// Keeps only `MAX_MANIFOLD_POINTS` solver contacts of the given manifold, spread as much as
+/// possible: the one furthest from the contacts’ centroid, then, iteratively, the one furthest
+/// from the contacts already kept.
+///
+/// The contacts’ depths aren’t taken into account since the merged contacts all lie on the same
+/// contact plane.
+fn consolidate_solver_contacts(manifold: &mut ContactManifold) {
+    let contacts = &manifold.data.solver_contacts;
+    let centroid = contacts
+        .iter()
+        .fold(Vector::zeros(), |acc, c| acc + c.point.coords)
+        / contacts.len() as Real;
+    let first = first_furthest(0..contacts.len(), |i| {
+        ((contacts[i].point.coords - centroid).norm_squared(), 0.0)
+    });
+
+    keep_spread_solver_contacts(manifold, first.unwrap_or(0), MAX_MANIFOLD_POINTS);
+}
+
+/// Keeps only `max_contacts` solver contacts of the given manifold: the one at index `first`,
+/// then, iteratively, the one furthest from the contacts already kept.
+///
+/// The tracked contacts of the discarded solver contacts get zero impulses.
+fn keep_spread_solver_contacts(manifold: &mut ContactManifold, first: usize, max_contacts: usize) {
+    let contacts = &manifold.data.solver_contacts;
+    if contacts.len() <= max_contacts {
+        return;
+    }
+
+    let points: Vec<_> = contacts.iter().map(|contact| contact.point).collect();
+    let kept = spread_contacts(&points, first, max_contacts);
+
+    for i in 0..points.len() {
+        if !kept.contains(&i) {
+            let contact_id = manifold.data.solver_contacts[i].contact_id;
+            *solver_contact_data_mut(manifold, contact_id) = ContactData::default();
+        }
+    }
+    manifold.data.solver_contacts = kept
+        .into_iter()
+        .map(|i| manifold.data.solver_contacts[i])
+        .collect();
+}
+
+/// The indices of `max_contacts` of the given contact points: `first`, then, iteratively, the
+/// one furthest from the points already selected.
+///
+/// This selects the points spanning the largest area, so the contact remains stable.
+fn spread_contacts(points: &[Point<Real>], first: usize, max_contacts: usize) -> Vec<usize> {
+    let mut ids: Vec<_> = (0..points.len()).collect();
+    ids.swap(0, first);
+
+    for k in 1..max_contacts.min(ids.len()) {
+        // The distance to the closest kept contact, then to all the kept contacts, to prefer
+        // the corners of a box over the middle of its edges.
+        let dist_to_kept = |i: usize| {
+            let dists = ids[..k]
+                .iter()
+                .map(|kept| na::distance_squared(&points[*kept], &points[ids[i]]));
+            (dists.clone().fold(Real::MAX, Real::min), dists.sum())
+        };
+
+        if let Some(best) = first_furthest(k..ids.len(), dist_to_kept) {
+            ids.swap(k, best);
+        }
+    }
+
+    ids.truncate(max_contacts);
+    ids
+}
+
+/// The first of the `candidates` with the largest squared distance, up to a small relative
+/// tolerance.
+///
+/// `dists` gives two squared distances for each candidate: the second one is only compared
+/// between the candidates with (nearly) the same first distance. The tolerance keeps the choice
+/// between contacts at (nearly) the same distance stable from one timestep to the next, e.g.,
+/// between the corners of a box, instead of letting rounding errors decide. Otherwise, the
+/// solver contacts kept would change at each timestep, discarding the impulses used for
+/// warmstarting and making the bodies jitter.
+fn first_furthest(
+    candidates: std::ops::Range<usize>,
+    dists: impl Fn(usize) -> (Real, Real),
+) -> Option<usize> {
+    let dists: Vec<_> = candidates.clone().map(dists).collect();
+    let max_dist0 = dists.iter().map(|d| d.0).fold(Real::MIN, Real::max);
+    let min_dist0 = max_dist0 * (1.0 - FURTHEST_CONTACT_RELATIVE_TOLERANCE);
+    let max_dist1 = dists
+        .iter()
+        .filter(|d| d.0 >= min_dist0)
+        .map(|d| d.1)
+        .fold(Real::MIN, Real::max);
+    let min_dist1 = max_dist1 * (1.0 - FURTHEST_CONTACT_RELATIVE_TOLERANCE);
+
+    dists
+        .iter()
+        .position(|d| d.0 >= min_dist0 && d.1 >= min_dist1)
+        .map(|i| candidates.start + i)
+}
+
+/// The relative tolerance on the squared distances compared by [`first_furthest`].
+const FURTHEST_CONTACT_RELATIVE_TOLERANCE: Real = 1.0e-3;
+
+/// The contact data the constraints solver writes the impulses of the solver contact with the
+/// given id to.
+pub(crate) fn solver_contact_data_mut(
+    manifold: &mut ContactManifold,
+    contact_id: u8,
+) -> &mut ContactData {
+    let contact_id = contact_id as usize;
+    let num_points = manifold.points.len();
+
+    if contact_id < num_points {
+        &mut manifold.points[contact_id].data
+    } else {
+        &mut manifold.data.merged_contacts[contact_id - num_points].data
+    }
+}
+
+/// A solver contact moved to another manifold by [`ContactPair::merge_coincident_manifolds`].
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub(crate) struct MergedContact {
+    /// The index, in [`ContactPair::manifolds`], of the manifold this contact comes from.
+    pub manifold_id: usize,
+    /// The index of the tracked contact in the manifold this contact comes from.
+    pub contact_id: u8,
+    /// The impulses computed by the constraints solver for this contact.
+    pub data: ContactData,
 }
 
 /// A contact seen by the constraints solver for computing forces.
@@ -300,13 +704,87 @@ pub struct SolverContact {
     pub friction: Real,
     /// The effective restitution coefficient at this contact point.
     pub restitution: Real,
+    /// The minimum relative normal velocity at this contact point for restitution to apply.
+    ///
+    /// This is the greatest of the restitution velocity thresholds of both colliders. The
+    /// contact doesn’t bounce if the bodies approach each other slower than this.
+    pub restitution_velocity_threshold: Real,
     /// The desired tangent relative velocity at the contact point.
     ///
     /// This is set to zero by default. Set to a non-zero value to
     /// simulate, e.g., conveyor belts.
     pub tangent_velocity: Vector<Real>,
+    /// The friction coefficients along each tangent direction, relative to [`Self::friction`].
+    ///
+    /// This is set to `[1.0; DIM - 1]` (isotropic friction) by default. Set to different
+    /// values to simulate, e.g., ice skates or treads, with a friction along a direction
+    /// different from the friction across it. In 2D, there is a single tangent direction. In 3D,
+    /// the first tangent direction is [`Self::friction_direction`] and the second is orthogonal
+    /// to it and to the contact normal. All the solver contacts of the same contact manifold
+    /// are expected to have the same anisotropy.
+    pub friction_anisotropy: [Real; DIM - 1],
+    /// The world-space direction of the first friction tangent direction.
+    ///
+    /// It is projected on the plane orthogonal to the contact normal. This is set to zero by
+    /// default, in which case the tangent directions are derived from the relative velocity at
+    /// the contact, so [`Self::friction_anisotropy`] is only meaningful if this is set too. The
+    /// direction of the first solver contact of each contact manifold is used.
+    #[cfg(feature = "dim3")]
+    pub friction_direction: Vector<Real>,
+    /// The rolling friction coefficient at this contact point, in meters.
+    ///
+    /// This is set to zero (no rolling friction) by default. Rolling friction opposes the
+    /// relative rolling of the two bodies with a torque of at most this coefficient multiplied by
+    /// the normal force. The coefficient of the first solver contact of each contact manifold is
+    /// used. This isn’t supported for contacts involving multibody links.
+    pub rolling_friction: Real,
+    /// If set, makes this a one-way contact that only pushes the second body along this
+    /// world-space direction.
+    ///
+    /// This is set to `None` by default. When set, the contact is ignored by the solver if, at
+    /// the beginning of the timestep, the second body moves along this direction relative to the
+    /// first body. This can be used to simulate, e.g., platforms that characters can jump
+    /// through from below but land on from above. It can be set with
+    /// [`PhysicsHooks::modify_solver_contacts`](crate::pipeline::PhysicsHooks::modify_solver_contacts).
+    pub one_way_normal: Option<Vector<Real>>,
+    /// The maximum normal impulse this contact can apply at each solver substep.
+    ///
+    /// This is set to `Real::INFINITY` by default. When the contact needs a larger impulse to
+    /// keep the bodies from moving toward each other, it only applies this impulse and the bodies
+    /// slip through each other, e.g., to simulate a suction gripper releasing its grip beyond a
+    /// given force. The corresponding force is this impulse divided by the substep length
+    /// ([`IntegrationParameters::dt`](crate::dynamics::IntegrationParameters::dt) divided by
+    /// the number of solver iterations).
+    pub max_normal_impulse: Real,
+    /// The compliance of this contact, i.e., the inverse of its stiffness.
+    ///
+    /// This is set to zero by default, in which case the contact follows the
+    /// [`ContactModel`](crate::geometry::ContactModel) of the two colliders. A non-zero compliance
+    /// makes the contact behave like a spring with a stiffness of `1.0 / compliance`, in series
+    /// with that contact model, so the bodies sink into each other until the spring force
+    /// compensates the forces pushing them together, as with the compliant contacts of XPBD. The
+    /// compliance of the first solver contact of each contact manifold is used. It can be set
+    /// with [`PhysicsHooks::modify_solver_contacts`](crate::pipeline::PhysicsHooks::modify_solver_contacts).
+    pub compliance: Real,
+    /// Is this contact taken into account by the constraints solver?
+    ///
+    /// This is set to `true` by default. Setting it to `false` with
+    /// [`PhysicsHooks::modify_solver_contacts`](crate::pipeline::PhysicsHooks::modify_solver_contacts)
+    /// removes this contact before any constraint is generated from it, so it doesn’t apply any
+    /// impulse, e.g., to let a body pass through another one for a single timestep. Since the
+    /// solver contacts are recomputed at each timestep, the contact is enabled again at the
+    /// next timestep unless the hook disables it again.
+    pub enabled: bool,
     /// Whether or not this contact existed during the last timestep.
     pub is_new: bool,
+    /// The fraction, in `[0, 1]`, of the penetration correction applied by the solver to this
+    /// contact.
+    ///
+    /// This is set to `1.0` unless the contact is younger than
+    /// [`IntegrationParameters::penetration_warmup_steps`](crate::dynamics::IntegrationParameters::penetration_warmup_steps).
+    /// It scales the velocity the solver uses to push the bodies out of penetration, but doesn’t
+    /// prevent them from moving further into each other.
+    pub penetration_correction_factor: Real,
     /// Impulse used to warmstart the solve for the normal constraint.
     pub warmstart_impulse: Real,
     /// Impulse used to warmstart the solve for the friction constraints.
@@ -314,6 +792,21 @@ pub struct SolverContact {
 }
 
 impl SolverContact {
+    /// The friction coefficients along each tangent direction.
+    pub(crate) fn friction_limit(&self) -> [Real; DIM - 1] {
+        self.friction_anisotropy.map(|a| self.friction * a)
+    }
+
+    /// Is this a one-way contact that must be ignored because the second body moves along
+    /// [`Self::one_way_normal`] relative to the first body?
+    ///
+    /// `relative_vel` is the velocity of the second body relative to the first body at the
+    /// contact point.
+    pub(crate) fn is_one_way_disabled(&self, relative_vel: &Vector<Real>) -> bool {
+        self.one_way_normal
+            .is_some_and(|normal| relative_vel.dot(&normal) > 0.0)
+    }
+
     /// Should we treat this contact as a bouncy contact?
     /// If `true`, use [`Self::restitution`].
     pub fn is_bouncy(&self) -> bool {
@@ -326,6 +819,15 @@ impl SolverContact {
             self.restitution >= 1.0
         }
     }
+
+    /// Should we treat this contact as a bouncy contact, given the relative velocity of the two
+    /// bodies along the contact normal?
+    ///
+    /// This is [`Self::is_bouncy`], excluding contacts with a relative normal velocity smaller
+    /// than [`Self::restitution_velocity_threshold`].
+    pub(crate) fn is_bouncy_at(&self, normal_vel: Real) -> bool {
+        self.is_bouncy() && normal_vel.abs() >= self.restitution_velocity_threshold
+    }
 }
 
 impl Default for ContactManifoldData {
@@ -345,9 +847,24 @@ impl ContactManifoldData {
             rigid_body2,
             solver_flags,
             normal: Vector::zeros(),
+            tangent1: Vector::zeros(),
+            #[cfg(feature = "dim3")]
+            tangent2: Vector::zeros(),
             solver_contacts: Vec::new(),
             relative_dominance: 0,
             user_data: 0,
+            contact_model: ContactModel::Rigid,
+            disable_warmstart: false,
+            has_fast_contact: false,
+            merged_contacts: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_tangents(&mut self, tangents: [Vector<Real>; DIM - 1]) {
+        self.tangent1 = tangents[0];
+        #[cfg(feature = "dim3")]
+        {
+            self.tangent2 = tangents[1];
         }
     }
 
@@ -357,16 +874,886 @@ impl ContactManifoldData {
     pub fn num_active_contacts(&self) -> usize {
         self.solver_contacts.len()
     }
+
+    /// Number of constraints the solver will generate for this manifold.
+    ///
+    /// The active contacts are solved in blocks of at most `MAX_MANIFOLD_POINTS` contacts, so
+    /// this is `num_active_contacts` divided by `MAX_MANIFOLD_POINTS`, rounded up.
+    #[inline]
+    pub fn num_constraint_blocks(&self) -> usize {
+        self.num_active_contacts().div_ceil(MAX_MANIFOLD_POINTS)
+    }
+
+    /// The user-defined friction direction of the contacts of this manifold.
+    #[cfg(feature = "dim3")]
+    pub(crate) fn friction_direction(&self) -> Vector<Real> {
+        self.solver_contacts
+            .first()
+            .map(|contact| contact.friction_direction)
+            .unwrap_or_else(Vector::zeros)
+    }
+
+    /// Can the contacts of this manifold be solved with SIMD-accelerated constraint formulations?
+    #[cfg(feature = "simd-is-enabled")]
+    pub(crate) fn supports_simd_constraints(&self) -> bool {
+        self.contact_model == ContactModel::Rigid
+    }
 }
 
 /// Additional methods for the contact manifold.
 pub trait ContactManifoldExt {
     /// Computes the sum of all the impulses applied by contacts from this contact manifold.
     fn total_impulse(&self) -> Real;
+
+    /// The effective mass along the contact normal of the contact `self.points[contact_index]`,
+    /// as used by the constraints solver during the last timestep.
+    ///
+    /// This is the mass the bodies appear to have at this contact point when pushed along the
+    /// contact normal, taking their inertia into account. Returns `None` if there is no such
+    /// contact or if it wasn’t solved during the last timestep (e.g. because it was too far
+    /// apart or the bodies were sleeping).
+    fn effective_mass_at(&self, contact_index: usize) -> Option<Real>;
+
+    /// The velocity of the second rigid-body relative to the first one at the solver contact
+    /// `self.data.solver_contacts[contact_index]`.
+    ///
+    /// This is computed from the current velocities of the rigid-bodies in `bodies`, so calling
+    /// this after `PhysicsPipeline::step` gives the relative velocity after the constraints
+    /// resolution, e.g., the slide velocity along the contact plane. The contact point is the
+    /// one computed at the beginning of the last timestep. A collider without parent rigid-body
+    /// has a zero velocity. Returns `None` if there is no such solver contact.
+    fn point_relative_velocity(
+        &self,
+        bodies: &RigidBodySet,
+        contact_index: usize,
+    ) -> Option<Vector<Real>>;
+
+    /// The centroid and the area of the contact patch spanned by the solver contacts of this
+    /// manifold, as computed during the last timestep.
+    ///
+    /// The patch is the convex hull of the solver contact points, projected on the plane
+    /// orthogonal to the contact normal, and the centroid is the area-weighted centroid of this
+    /// polygon. If the contacts don’t span a polygon, e.g., with one or two contacts, the area is
+    /// zero and the centroid is the average of the contact points. In 2D, the contact points are
+    /// always aligned, so the area is always zero. Returns `None` if there is no solver contact.
+    fn contact_patch(&self) -> Option<(Point<Real>, Real)>;
 }
 
 impl ContactManifoldExt for ContactManifold {
     fn total_impulse(&self) -> Real {
         self.points.iter().map(|pt| pt.data.impulse).sum()
     }
+
+    fn effective_mass_at(&self, contact_index: usize) -> Option<Real> {
+        let mass = self.points.get(contact_index)?.data.effective_mass;
+        (mass != 0.0).then_some(mass)
+    }
+
+    fn point_relative_velocity(
+        &self,
+        bodies: &RigidBodySet,
+        contact_index: usize,
+    ) -> Option<Vector<Real>> {
+        let point = self.data.solver_contacts.get(contact_index)?.point;
+        let velocity = |body: Option<RigidBodyHandle>| {
+            body.and_then(|handle| bodies.get(handle))
+                .map(|rb| rb.velocity_at_point(&point))
+                .unwrap_or_else(Vector::zeros)
+        };
+
+        Some(velocity(self.data.rigid_body2) - velocity(self.data.rigid_body1))
+    }
+
+    fn contact_patch(&self) -> Option<(Point<Real>, Real)> {
+        let contacts = &self.data.solver_contacts;
+        if contacts.is_empty() {
+            return None;
+        }
+
+        let mean = contacts
+            .iter()
+            .map(|c| c.point.coords)
+            .sum::<Vector<Real>>()
+            / contacts.len() as Real;
+        let mean = Point::from(mean);
+
+        #[cfg(feature = "dim2")]
+        return Some((mean, 0.0));
+
+        #[cfg(feature = "dim3")]
+        {
+            if contacts.len() < 3 {
+                return Some((mean, 0.0));
+            }
+
+            // Compute the convex hull of the points projected on the contact plane.
+            let [tangent1, tangent2] = self.data.normal.orthonormal_basis();
+            let projected = contacts
+                .iter()
+                .map(|c| {
+                    let dpt = c.point - mean;
+                    Point2::new(dpt.dot(&tangent1), dpt.dot(&tangent2))
+                })
+                .collect();
+            let hull = convex_hull2(projected);
+
+            // Area and centroid of the hull polygon.
+            let mut area = 0.0;
+            let mut centroid = na::Vector2::zeros();
+            for (i, a) in hull.iter().enumerate() {
+                let b = hull[(i + 1) % hull.len()];
+                let cross = a.x * b.y - a.y * b.x;
+                area += cross;
+                centroid += (a.coords + b.coords) * cross;
+            }
+            area *= 0.5;
+
+            if area <= DEFAULT_EPSILON {
+                return Some((mean, 0.0));
+            }
+
+            centroid /= 6.0 * area;
+            Some((mean + tangent1 * centroid.x + tangent2 * centroid.y, area))
+        }
+    }
+}
+
+/// The convex hull of a set of 2D points, in counterclockwise order (Andrew’s monotone chain).
+#[cfg(feature = "dim3")]
+fn convex_hull2(mut points: Vec<Point2<Real>>) -> Vec<Point2<Real>> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+    let cross = |o: &Point2<Real>, a: &Point2<Real>, b: &Point2<Real>| (a - o).perp(&(b - o));
+    let mut hull: Vec<Point2<Real>> = Vec::with_capacity(points.len() * 2);
+
+    // Lower hull, then upper hull.
+    for pass in 0..2 {
+        let start = hull.len();
+        for pt in &points {
+            while hull.len() >= start + 2
+                && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], pt) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(*pt);
+        }
+        // The last point is the first point of the next chain.
+        hull.pop();
+
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+
+    hull
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ContactManifoldData, ContactModel, SolverContact};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+    use crate::pipeline::test_world::{cuboid, floor, TestWorld};
+    use crate::pipeline::{ActiveHooks, ContactModificationContext, PhysicsHooks};
+
+    fn manifold_data(num_active_contacts: usize) -> ContactManifoldData {
+        let mut data = ContactManifoldData::default();
+        let contact = SolverContact {
+            contact_id: 0,
+            point: Point::origin(),
+            dist: 0.0,
+            friction: 0.5,
+            restitution: 0.0,
+            restitution_velocity_threshold: 0.0,
+            tangent_velocity: Vector::zeros(),
+            friction_anisotropy: [1.0; DIM - 1],
+            #[cfg(feature = "dim3")]
+            friction_direction: Vector::zeros(),
+            rolling_friction: 0.0,
+            one_way_normal: None,
+            max_normal_impulse: Real::INFINITY,
+            compliance: 0.0,
+            enabled: true,
+            is_new: false,
+            penetration_correction_factor: 1.0,
+            warmstart_impulse: 0.0,
+            warmstart_tangent_impulse: na::zero(),
+        };
+        data.solver_contacts = vec![contact; num_active_contacts];
+        data
+    }
+
+    #[test]
+    fn num_constraint_blocks() {
+        assert_eq!(manifold_data(0).num_constraint_blocks(), 0);
+        assert_eq!(manifold_data(1).num_constraint_blocks(), 1);
+        assert_eq!(
+            manifold_data(MAX_MANIFOLD_POINTS).num_constraint_blocks(),
+            1
+        );
+        assert_eq!(
+            manifold_data(MAX_MANIFOLD_POINTS + 1).num_constraint_blocks(),
+            2
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dim3")]
+    fn contact_patch_edge_cases() {
+        use super::ContactManifoldExt;
+        use crate::geometry::ContactManifold;
+
+        let manifold = |points: &[[Real; 2]]| {
+            let mut data = manifold_data(points.len());
+            data.normal = Vector::y();
+            for (contact, pt) in data.solver_contacts.iter_mut().zip(points) {
+                contact.point = Point::new(pt[0], 1.0, pt[1]);
+            }
+            ContactManifold::with_data(0, 0, data)
+        };
+
+        assert!(manifold(&[]).contact_patch().is_none());
+
+        // Single-point and two-point patches have no area.
+        let (centroid, area) = manifold(&[[1.0, 2.0]]).contact_patch().unwrap();
+        assert_eq!((centroid, area), (Point::new(1.0, 1.0, 2.0), 0.0));
+        let (centroid, area) = manifold(&[[0.0, 0.0], [2.0, 0.0]]).contact_patch().unwrap();
+        assert_eq!((centroid, area), (Point::new(1.0, 1.0, 0.0), 0.0));
+
+        // Interior points don’t affect the patch, only its convex hull does.
+        let (centroid, area) = manifold(&[
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [0.5, 0.1],
+            [2.0, 1.0],
+            [0.0, 1.0],
+            [0.2, 0.9],
+        ])
+        .contact_patch()
+        .unwrap();
+        approx::assert_relative_eq!(centroid, Point::new(1.0, 1.0, 0.5), epsilon = 1.0e-5);
+        approx::assert_relative_eq!(area, 2.0, epsilon = 1.0e-5);
+    }
+
+    #[test]
+    fn contact_effective_mass() {
+        use crate::geometry::ContactManifoldExt;
+
+        let mut world = TestWorld::new();
+
+        // A box pushed against a larger box. With locked rotations, the effective mass at each
+        // contact point is the reduced mass of the two bodies.
+        let (floor, floor_co) = world.insert_body(
+            RigidBodyBuilder::dynamic().lock_rotations(),
+            cuboid(2.0, 0.5).density(3.0),
+        );
+        let (cube, cube_co) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.0)
+                .linvel(-Vector::y())
+                .lock_rotations(),
+            cuboid(0.5, 0.5),
+        );
+
+        let mut step = || {
+            world.step();
+        };
+        step();
+        step();
+
+        let m1 = world.bodies[floor].mass();
+        let m2 = world.bodies[cube].mass();
+        let reduced_mass = m1 * m2 / (m1 + m2);
+
+        let pair = world.narrow_phase.contact_pair(floor_co, cube_co).unwrap();
+        let manifold = &pair.manifolds[0];
+        assert!(manifold.data.num_active_contacts() > 0);
+
+        for contact in &manifold.data.solver_contacts {
+            let mass = manifold
+                .effective_mass_at(contact.contact_id as usize)
+                .unwrap();
+            assert!((mass - reduced_mass).abs() < 1.0e-5);
+        }
+        assert_eq!(manifold.effective_mass_at(manifold.points.len()), None);
+    }
+
+    #[test]
+    fn disabled_solver_contacts_are_ignored() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        struct PassThrough(AtomicBool);
+
+        impl PhysicsHooks for PassThrough {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                if self.0.load(Ordering::SeqCst) {
+                    for contact in context.solver_contacts.iter_mut() {
+                        contact.enabled = false;
+                    }
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_body(
+            RigidBodyBuilder::fixed(),
+            floor().active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS),
+        );
+
+        let (ball, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false),
+            ColliderBuilder::ball(0.5),
+        );
+
+        let hooks = PassThrough(AtomicBool::new(false));
+        let dt = world.params.dt;
+        let mut step = |pass_through: bool| {
+            hooks.0.store(pass_through, Ordering::SeqCst);
+            world.step_with(&hooks, &());
+            *world.bodies[ball].linvel()
+        };
+
+        for _ in 0..60 {
+            step(false);
+        }
+        assert!(step(false).norm() < 1.0e-3);
+
+        // NOTE: the solver contacts are computed at the end of each timestep, so they are
+        //       disabled for the next timestep.
+        assert!(step(true).norm() < 1.0e-3);
+
+        // The ball falls freely through the floor while its contacts are disabled.
+        let linvel = step(false);
+        assert!((linvel.y + 9.81 * dt).abs() < 1.0e-3);
+
+        // It collides with the floor again at the next timestep.
+        let linvel = step(false);
+        assert!(linvel.y > -1.0e-3);
+        for _ in 0..60 {
+            step(false);
+        }
+        assert!((world.bodies[ball].translation().y - 0.5).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn max_normal_impulse_releases_overloaded_contact() {
+        const MAX_FORCE: Real = 50.0;
+
+        // A gripper that can only hold payloads weighing less than `MAX_FORCE`.
+        struct Gripper {
+            max_impulse: Real,
+        }
+
+        impl PhysicsHooks for Gripper {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.max_normal_impulse = self.max_impulse;
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_floor();
+
+        let mut insert_payload = |x: Real, mass: Real| {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x + Vector::y() * 0.5)
+                .lock_rotations();
+            let co = ColliderBuilder::ball(0.5)
+                .mass(mass)
+                .active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+            world.insert_body(rb, co).0
+        };
+
+        let light = insert_payload(-10.0, 2.0);
+        let heavy = insert_payload(10.0, 10.0);
+
+        let substep_dt = world.params.dt / world.params.num_solver_iterations.get() as Real;
+        let hooks = Gripper {
+            max_impulse: MAX_FORCE * substep_dt,
+        };
+
+        for _ in 0..60 {
+            world.step_with(&hooks, &());
+        }
+
+        // The light payload is held, the heavy one slips through the gripper.
+        assert!((world.bodies[light].translation().y - 0.5).abs() < 1.0e-2);
+        assert!(world.bodies[light].linvel().norm() < 1.0e-3);
+        assert!(world.bodies[heavy].translation().y < 0.0);
+    }
+
+    #[test]
+    fn contact_point_relative_velocity_after_oblique_impact() {
+        use crate::geometry::ContactManifoldExt;
+
+        let mut world = TestWorld::new();
+
+        world.colliders.insert(floor().friction(0.0));
+
+        // A frictionless ball hitting the ground obliquely, without bouncing.
+        let rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * 0.6)
+            .linvel(Vector::x() * 3.0 - Vector::y() * 2.0);
+        let co = ColliderBuilder::ball(0.5).friction(0.0).restitution(0.0);
+        world.insert_body(rb, co);
+
+        world.steps(20);
+
+        let manifold = world
+            .narrow_phase
+            .contact_pairs()
+            .flat_map(|pair| &pair.manifolds)
+            .find(|manifold| manifold.data.num_active_contacts() > 0)
+            .unwrap();
+        let normal = manifold.data.normal;
+        let relvel = manifold.point_relative_velocity(&world.bodies, 0).unwrap();
+        let tangent_vel = relvel - normal * relvel.dot(&normal);
+
+        // The ball stopped moving toward the ground but keeps sliding along it.
+        assert!(relvel.dot(&normal).abs() < 1.0e-3);
+        approx::assert_relative_eq!(tangent_vel.norm(), 3.0, epsilon = 1.0e-3);
+        approx::assert_relative_eq!(relvel.x.abs(), 3.0, epsilon = 1.0e-3);
+        assert!(manifold
+            .point_relative_velocity(&world.bodies, manifold.data.num_active_contacts())
+            .is_none());
+    }
+
+    #[test]
+    fn contact_patch_of_box_on_floor() {
+        use crate::geometry::ContactManifoldExt;
+
+        let mut world = TestWorld::with_gravity();
+
+        let floor_co = world.colliders.insert(floor());
+
+        #[cfg(feature = "dim2")]
+        let box_center = Vector::new(1.0, 0.5);
+        #[cfg(feature = "dim3")]
+        let box_center = Vector::new(1.0, 0.5, -2.0);
+        let (_, box_co) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(box_center),
+            cuboid(0.5, 0.5),
+        );
+
+        world.steps(60);
+
+        let pair = world.narrow_phase.contact_pair(floor_co, box_co).unwrap();
+        let (centroid, area) = pair.manifolds[0].contact_patch().unwrap();
+
+        // The patch is centered below the box, on the floor.
+        let mut expected_centroid = Point::from(box_center);
+        expected_centroid.y = 0.0;
+        approx::assert_relative_eq!(centroid, expected_centroid, epsilon = 1.0e-2);
+
+        // In 3D, the patch is the whole bottom face of the box. In 2D, there are only two
+        // contact points, so the area is zero.
+        #[cfg(feature = "dim2")]
+        assert_eq!(area, 0.0);
+        #[cfg(feature = "dim3")]
+        approx::assert_relative_eq!(area, 1.0, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn compliant_contact_sinks_and_settles() {
+        struct Compliant(Real);
+
+        impl PhysicsHooks for Compliant {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.compliance = self.0;
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        // A very stiff spring floor, only there to damp the compliant contact.
+        let floor_model = ContactModel::Spring {
+            stiffness: 1.0e6,
+            damping: 10.0,
+        };
+        world.insert_body(
+            RigidBodyBuilder::fixed(),
+            floor().contact_model(floor_model),
+        );
+
+        let co = ColliderBuilder::ball(0.5)
+            .mass(1.0)
+            .active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        let (ball, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false),
+            co,
+        );
+
+        let compliance = 0.01;
+        let hooks = Compliant(compliance);
+        let mut depths = vec![];
+
+        for _ in 0..600 {
+            world.step_with(&hooks, &());
+            depths.push(0.5 - world.bodies[ball].translation().y);
+        }
+
+        // The ball sank visibly into the floor, until the contact compensates gravity.
+        let depth = *depths.last().unwrap();
+        approx::assert_relative_eq!(depth, 9.81 * compliance, max_relative = 1.0e-2);
+
+        // And it rests there without jittering.
+        let last_second = &depths[depths.len() - 60..];
+        let min = last_second.iter().copied().fold(Real::MAX, Real::min);
+        let max = last_second.iter().copied().fold(-Real::MAX, Real::max);
+        assert!(max - min < 1.0e-4, "{min} {max}");
+        assert!(world.bodies[ball].linvel().norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn anisotropic_friction() {
+        // Frictionless along the x axis, and regular friction across it.
+        struct Skates;
+
+        impl PhysicsHooks for Skates {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.friction_anisotropy[0] = 0.0;
+                    #[cfg(feature = "dim3")]
+                    {
+                        contact.friction_direction = Vector::x();
+                    }
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_body(RigidBodyBuilder::fixed(), floor().friction(1.0));
+
+        let mut insert_slider = |x: Real, linvel: Vector<Real>, hooks: ActiveHooks| {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x + Vector::y() * 0.5)
+                .linvel(linvel)
+                .lock_rotations();
+            let co = ColliderBuilder::ball(0.5).friction(1.0).active_hooks(hooks);
+            world.insert_body(rb, co).0
+        };
+
+        let regular = insert_slider(-10.0, Vector::x() * 2.0, ActiveHooks::empty());
+        let along = insert_slider(0.0, Vector::x() * 2.0, ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        #[cfg(feature = "dim3")]
+        let across = insert_slider(10.0, Vector::z() * 2.0, ActiveHooks::MODIFY_SOLVER_CONTACTS);
+
+        for _ in 0..30 {
+            world.step_with(&Skates, &());
+        }
+
+        assert!(world.bodies[regular].linvel().norm() < 1.0e-3);
+        assert!((world.bodies[along].linvel().x - 2.0).abs() < 1.0e-3);
+        #[cfg(feature = "dim3")]
+        assert!(world.bodies[across].linvel().norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn disable_manifold_warmstart() {
+        use std::sync::Mutex;
+
+        // Records the warmstart impulses of the solver contacts seen by the solver.
+        struct WarmstartImpulses(Mutex<Vec<Real>>);
+
+        impl PhysicsHooks for WarmstartImpulses {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                let mut impulses = self.0.lock().unwrap();
+                impulses.extend(context.solver_contacts.iter().map(|c| c.warmstart_impulse));
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+        let hooks = WarmstartImpulses(Mutex::new(vec![]));
+
+        let (_, floor_co) = world.insert_floor();
+        let ball_co = ColliderBuilder::ball(0.5).active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        let (ball, ball_co) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5),
+            ball_co,
+        );
+
+        let step = |world: &mut TestWorld| {
+            hooks.0.lock().unwrap().clear();
+            world.step_with(&hooks, &());
+            hooks.0.lock().unwrap()[0]
+        };
+
+        for _ in 0..20 {
+            step(&mut world);
+        }
+        assert!(step(&mut world) > 0.0);
+
+        // Teleport the ball while keeping its contact with the floor.
+        world.bodies[ball].set_translation(Vector::x() * 10.0 + Vector::y() * 0.5, true);
+        for manifold in &mut world
+            .narrow_phase
+            .contact_pair_mut(floor_co, ball_co)
+            .unwrap()
+            .manifolds
+        {
+            manifold.data.disable_warmstart = true;
+        }
+        assert_eq!(step(&mut world), 0.0);
+
+        let pair = world.narrow_phase.contact_pair(floor_co, ball_co).unwrap();
+        let weight_impulse = world.bodies[ball].mass() * world.gravity.norm() * world.params.dt;
+        assert!(!pair.manifolds[0].data.disable_warmstart);
+        assert!(pair.total_impulse().norm() < weight_impulse * 1.5);
+
+        // The flag only affects one timestep.
+        assert!(step(&mut world) > 0.0);
+    }
+
+    #[test]
+    fn fast_contact_flag() {
+        use crate::geometry::{CollisionEvent, ContactPair};
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Records if a contact was flagged as fast. The contact force events are emitted right
+        // after the constraints resolution, while the flags are set.
+        #[derive(Default)]
+        struct FastContactRecorder(AtomicBool);
+
+        impl EventHandler for FastContactRecorder {
+            fn handle_collision_event(
+                &self,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                _event: CollisionEvent,
+                _contact_pair: Option<&ContactPair>,
+            ) {
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                _dt: Real,
+                _bodies: &RigidBodySet,
+                _colliders: &ColliderSet,
+                contact_pair: &ContactPair,
+                _total_force_magnitude: Real,
+            ) {
+                if contact_pair
+                    .manifolds
+                    .iter()
+                    .any(|manifold| manifold.data.has_fast_contact)
+                {
+                    self.0.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let run = |speed: Real| -> bool {
+            let mut world = TestWorld::new();
+            let recorder = FastContactRecorder::default();
+
+            #[cfg(feature = "dim2")]
+            let wall = ColliderBuilder::cuboid(0.05, 5.0);
+            #[cfg(feature = "dim3")]
+            let wall = ColliderBuilder::cuboid(0.05, 5.0, 5.0);
+            let wall = wall
+                .restitution(1.0)
+                .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(0.0);
+            world.insert_body(RigidBodyBuilder::fixed(), wall);
+
+            // A small bouncy projectile, with CCD so it can’t go through the wall.
+            let (projectile, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * -2.0)
+                    .linvel(Vector::x() * speed)
+                    .ccd_enabled(true),
+                ColliderBuilder::ball(0.1).restitution(1.0),
+            );
+
+            for _ in 0..200 {
+                world.step_with(&(), &recorder);
+            }
+
+            // The projectile bounced off the wall.
+            assert!(world.bodies[projectile].linvel().x < 0.0);
+            recorder.0.load(Ordering::Relaxed)
+        };
+
+        assert!(run(100.0));
+        assert!(!run(1.0));
+    }
+
+    #[test]
+    fn one_way_platform() {
+        use crate::geometry::ColliderHandle;
+
+        // Only pushes bodies upward, so they can jump through the platform from below.
+        struct OneWayPlatform(ColliderHandle);
+
+        impl PhysicsHooks for OneWayPlatform {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                // The one-way normal is the direction the second body is pushed along.
+                let normal = if context.collider1 == self.0 {
+                    Vector::y()
+                } else {
+                    -Vector::y()
+                };
+
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.one_way_normal = Some(normal);
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        let mut insert_platform = |x: Real, hooks: ActiveHooks| {
+            let rb = RigidBodyBuilder::fixed().translation(Vector::x() * x);
+            world
+                .insert_body(rb, cuboid(2.0, 0.1).active_hooks(hooks))
+                .1
+        };
+        let one_way_platform = insert_platform(-5.0, ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        let _ = insert_platform(5.0, ActiveHooks::empty());
+
+        let mut insert_jumper = |x: Real| {
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x - Vector::y())
+                .linvel(Vector::y() * 8.0)
+                .lock_rotations();
+            world.insert_body(rb, cuboid(0.5, 0.5)).0
+        };
+        let jumper = insert_jumper(-5.0);
+        let blocked_jumper = insert_jumper(5.0);
+
+        let hooks = OneWayPlatform(one_way_platform);
+        let mut max_height = -Real::MAX;
+
+        for _ in 0..200 {
+            world.step_with(&hooks, &());
+            max_height = max_height.max(world.bodies[jumper].translation().y);
+        }
+
+        // The jumper went through the platform, then landed and rests on top of it.
+        assert!(max_height > 2.0);
+        assert!((world.bodies[jumper].translation().y - 0.6).abs() < 1.0e-2);
+        assert!(world.bodies[jumper].linvel().norm() < 1.0e-3);
+        // Without the hook, the platform is a regular obstacle.
+        assert!(world.bodies[blocked_jumper].translation().y < -0.5);
+    }
+
+    #[test]
+    fn rolling_friction() {
+        struct RollingFriction;
+
+        impl PhysicsHooks for RollingFriction {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.rolling_friction = 0.05;
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_floor();
+
+        let mut insert_ball = |x: Real, hooks: ActiveHooks| {
+            // Rolls along +x without slipping.
+            #[cfg(feature = "dim2")]
+            let angvel = -4.0;
+            #[cfg(feature = "dim3")]
+            let angvel = Vector::z() * -4.0;
+            let rb = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x + Vector::y() * 0.5)
+                .linvel(Vector::x() * 2.0)
+                .angvel(angvel);
+            let co = ColliderBuilder::ball(0.5).friction(1.0).active_hooks(hooks);
+            world.insert_body(rb, co).0
+        };
+        // The control ball starts ahead so the slower ball never catches up with it.
+        let ball = insert_ball(-20.0, ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        let control_ball = insert_ball(-15.0, ActiveHooks::empty());
+
+        for _ in 0..400 {
+            world.step_with(&RollingFriction, &());
+        }
+
+        // The ball with rolling friction stopped.
+        assert!(world.bodies[ball].linvel().norm() < 1.0e-3);
+        #[cfg(feature = "dim2")]
+        assert!(world.bodies[ball].angvel().abs() < 1.0e-3);
+        #[cfg(feature = "dim3")]
+        assert!(world.bodies[ball].angvel().norm() < 1.0e-3);
+        // The other one keeps rolling.
+        assert!(world.bodies[control_ball].linvel().x > 1.5);
+    }
+
+    #[test]
+    fn contact_manifold_tangents() {
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_floor();
+
+        // A sliding box, so friction impulses are applied.
+        #[cfg(feature = "dim2")]
+        let linvel = Vector::new(3.0, 0.0);
+        #[cfg(feature = "dim3")]
+        let linvel = Vector::new(3.0, 0.0, 1.0);
+        world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .linvel(linvel),
+            cuboid(0.5, 0.5),
+        );
+
+        world.steps(5);
+
+        let mut num_friction_impulses = 0;
+
+        for manifold in world
+            .narrow_phase
+            .contact_pairs()
+            .flat_map(|pair| &pair.manifolds)
+        {
+            let data = &manifold.data;
+            assert!(!data.solver_contacts.is_empty());
+            assert!((data.tangent1.norm() - 1.0).abs() < 1.0e-5);
+            assert!(data.tangent1.dot(&data.normal).abs() < 1.0e-5);
+            #[cfg(feature = "dim3")]
+            {
+                assert!((data.tangent2.norm() - 1.0).abs() < 1.0e-5);
+                assert!(data.tangent2.dot(&data.normal).abs() < 1.0e-5);
+                assert!(data.tangent2.dot(&data.tangent1).abs() < 1.0e-5);
+            }
+
+            // The friction impulses are expressed along these tangents.
+            for point in &manifold.points {
+                let impulse = point.data.world_impulse;
+                let world_friction = impulse - data.normal * impulse.dot(&data.normal);
+                #[cfg(feature = "dim2")]
+                let friction = data.tangent1 * point.data.tangent_impulse[0];
+                #[cfg(feature = "dim3")]
+                let friction = data.tangent1 * point.data.tangent_impulse[0]
+                    + data.tangent2 * point.data.tangent_impulse[1];
+                assert!(
+                    (world_friction - friction).norm() < 1.0e-5
+                        || (world_friction + friction).norm() < 1.0e-5
+                );
+
+                if friction.norm() > 1.0e-3 {
+                    num_friction_impulses += 1;
+                }
+            }
+        }
+
+        assert!(num_friction_impulses > 0);
+    }
 }
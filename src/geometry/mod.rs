@@ -1,12 +1,15 @@
 //! Structures related to geometry: colliders, shapes, etc.
 
-pub use self::broad_phase::BroadPhase;
+pub use self::broad_phase::{BroadPhase, BroadPhaseState, BroadPhaseStateError};
 pub use self::broad_phase_multi_sap::{BroadPhaseMultiSap, BroadPhasePairEvent, ColliderPair};
 pub use self::collider::{Collider, ColliderBuilder};
 pub use self::collider_components::*;
 pub use self::collider_set::ColliderSet;
+pub use self::contact_material_overrides::{ContactMaterialOverride, ContactMaterialOverrides};
+pub(crate) use self::contact_pair::solver_contact_data_mut;
 pub use self::contact_pair::{
-    ContactData, ContactManifoldData, ContactPair, IntersectionPair, SolverContact, SolverFlags,
+    ContactData, ContactManifoldData, ContactManifoldExt, ContactPair, IntersectionPair,
+    SolverContact, SolverFlags,
 };
 pub use self::interaction_graph::{
     ColliderGraphIndex, InteractionGraph, RigidBodyGraphIndex, TemporaryInteractionIndex,
@@ -199,6 +202,7 @@ pub(crate) fn default_query_dispatcher() -> std::sync::Arc<dyn parry::query::Que
 
 mod broad_phase_multi_sap;
 mod collider_components;
+mod contact_material_overrides;
 mod contact_pair;
 mod interaction_graph;
 mod interaction_groups;
@@ -9,11 +9,11 @@ use crate::dynamics::{
 };
 use crate::geometry::{
     BoundingVolume, BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle,
-    ColliderPair, ColliderSet, CollisionEvent, ContactData, ContactManifold, ContactManifoldData,
-    ContactPair, InteractionGraph, IntersectionPair, SolverContact, SolverFlags,
-    TemporaryInteractionIndex,
+    ColliderPair, ColliderSet, CollisionEvent, Contact, ContactData, ContactManifold,
+    ContactManifoldData, ContactMaterialOverrides, ContactPair, InteractionGraph, IntersectionPair,
+    SolverContact, SolverFlags, TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
+use crate::math::{Real, Vector, DIM};
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
@@ -22,6 +22,7 @@ use crate::prelude::{CollisionEventFlags, MultibodyJointSet};
 use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
 use parry::utils::IsometryOpt;
 use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -59,6 +60,7 @@ pub struct NarrowPhase {
     contact_graph: InteractionGraph<ColliderHandle, ContactPair>,
     intersection_graph: InteractionGraph<ColliderHandle, IntersectionPair>,
     graph_indices: Coarena<ColliderGraphIndices>,
+    material_overrides: ContactMaterialOverrides,
 }
 
 pub(crate) type ContactManifoldIndex = usize;
@@ -85,9 +87,23 @@ impl NarrowPhase {
             contact_graph: InteractionGraph::new(),
             intersection_graph: InteractionGraph::new(),
             graph_indices: Coarena::new(),
+            material_overrides: ContactMaterialOverrides::new(),
         }
     }
 
+    /// The friction and restitution overrides applied to specific pairs of colliders or groups.
+    pub fn material_overrides(&self) -> &ContactMaterialOverrides {
+        &self.material_overrides
+    }
+
+    /// The friction and restitution overrides applied to specific pairs of colliders or groups.
+    ///
+    /// Changes to the overrides are taken into account the next time the contacts of the
+    /// affected pairs are computed.
+    pub fn material_overrides_mut(&mut self) -> &mut ContactMaterialOverrides {
+        &mut self.material_overrides
+    }
+
     /// The query dispatcher used by this narrow-phase to select the right collision-detection
     /// algorithms depending of the shape types.
     pub fn query_dispatcher(
@@ -184,6 +200,27 @@ impl NarrowPhase {
         &self.contact_graph.graph.edges[id.index()].weight
     }
 
+    /// The contact pair at the given index, with mutable access.
+    pub(crate) fn contact_pair_at_index_mut(
+        &mut self,
+        id: TemporaryInteractionIndex,
+    ) -> &mut ContactPair {
+        &mut self.contact_graph.graph.edges[id.index()].weight
+    }
+
+    /// The indices of all the contact pairs involving any of the given colliders.
+    pub(crate) fn contact_pair_indices_with(
+        &self,
+        colliders: &[ColliderHandle],
+    ) -> Vec<TemporaryInteractionIndex> {
+        colliders
+            .iter()
+            .filter_map(|collider| self.graph_indices.get(collider.0))
+            .flat_map(|id| self.contact_graph.graph.edges(id.contact_graph_index))
+            .map(|edge| edge.id())
+            .collect()
+    }
+
     /// The contact pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::contact_pair`] method instead. This
@@ -217,6 +254,22 @@ impl NarrowPhase {
             .map(|c| c.2)
     }
 
+    /// The contact pair involving two specific colliders, with mutable access.
+    ///
+    /// This can be used to modify the data of its contact manifolds between two timesteps,
+    /// e.g., to set [`ContactManifoldData::disable_warmstart`].
+    pub fn contact_pair_mut(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<&mut ContactPair> {
+        let id1 = self.graph_indices.get(collider1.0)?;
+        let id2 = self.graph_indices.get(collider2.0)?;
+        self.contact_graph
+            .interaction_pair_mut(id1.contact_graph_index, id2.contact_graph_index)
+            .map(|c| c.2)
+    }
+
     /// The intersection pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::intersection_pair`] method instead. This
@@ -253,6 +306,40 @@ impl NarrowPhase {
         self.contact_graph.interactions()
     }
 
+    /// The `n` contacts with the largest normal impulse, sorted by decreasing impulse.
+    ///
+    /// The impulses are the ones computed by the constraints solver during the last simulation
+    /// step, so this should be called after `PhysicsPipeline::step`. Contacts with a zero impulse
+    /// are ignored so less than `n` contacts may be returned.
+    ///
+    /// Each contact is returned alongside the contact pair and contact manifold it is part of.
+    pub fn strongest_contacts(&self, n: usize) -> Vec<(&ContactPair, &ContactManifold, &Contact)> {
+        let mut result: Vec<_> = self
+            .contact_pairs()
+            .flat_map(|pair| {
+                pair.manifolds.iter().flat_map(move |manifold| {
+                    manifold
+                        .points
+                        .iter()
+                        .filter(|pt| pt.data.impulse > 0.0)
+                        .map(move |pt| (pair, manifold, pt))
+                })
+            })
+            .collect();
+
+        let by_decreasing_impulse = |a: &(_, _, &Contact), b: &(_, _, &Contact)| {
+            b.2.data.impulse.total_cmp(&a.2.data.impulse)
+        };
+
+        if n < result.len() {
+            let _ = result.select_nth_unstable_by(n, by_decreasing_impulse);
+            result.truncate(n);
+        }
+
+        result.sort_unstable_by(by_decreasing_impulse);
+        result
+    }
+
     /// All the intersection pairs maintained by this narrow-phase.
     pub fn intersection_pairs(
         &self,
@@ -509,7 +596,7 @@ impl NarrowPhase {
 
         // Add the paid removed pair to the relevant graph.
         for pair in pairs_to_remove {
-            self.add_pair(colliders, &pair.0);
+            self.add_pair(colliders, bodies, &pair.0);
         }
     }
 
@@ -584,14 +671,20 @@ impl NarrowPhase {
         }
     }
 
-    fn add_pair(&mut self, colliders: &ColliderSet, pair: &ColliderPair) {
+    fn add_pair(&mut self, colliders: &ColliderSet, bodies: &RigidBodySet, pair: &ColliderPair) {
         if let (Some(co1), Some(co2)) =
             (colliders.get(pair.collider1), colliders.get(pair.collider2))
         {
-            if co1.parent.map(|p| p.handle) == co2.parent.map(|p| p.handle) && co1.parent.is_some()
-            {
-                // Same parents. Ignore collisions.
-                return;
+            if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
+                if co_parent1.handle == co_parent2.handle
+                    && !bodies
+                        .get(co_parent1.handle)
+                        .is_some_and(|rb| rb.is_self_collision_enabled())
+                {
+                    // Same parents. Ignore collisions unless self-collision is enabled.
+                    // The per-collider-pair filtering is done when computing contacts.
+                    return;
+                }
             }
 
             // These colliders have no parents - continue.
@@ -671,7 +764,7 @@ impl NarrowPhase {
         for event in broad_phase_events {
             match event {
                 BroadPhasePairEvent::AddPair(pair) => {
-                    self.add_pair(colliders, pair);
+                    self.add_pair(colliders, bodies, pair);
                 }
                 BroadPhasePairEvent::DeletePair(pair) => {
                     self.remove_pair(
@@ -730,6 +823,24 @@ impl NarrowPhase {
                     rb_type2 = bodies[co_parent2.handle].body_type;
                 }
 
+                // Filter the pairs of colliders attached to the same body.
+                if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
+                    let rb = &bodies[co_parent1.handle];
+                    if co_parent1.handle == co_parent2.handle
+                        && !(rb.is_self_collision_enabled()
+                            && rb.is_self_collision_allowed(handle1, handle2))
+                    {
+                        edge.weight.intersecting = false;
+                        break 'emit_events;
+                    }
+                }
+
+                // Filter based on the collision-detection being disabled.
+                if !co1.flags.collision_enabled || !co2.flags.collision_enabled {
+                    edge.weight.intersecting = false;
+                    break 'emit_events;
+                }
+
                 // Filter based on the rigid-body types.
                 if !co1.flags.active_collision_types.test(rb_type1, rb_type2)
                     && !co2.flags.active_collision_types.test(rb_type1, rb_type2)
@@ -789,6 +900,10 @@ impl NarrowPhase {
         &mut self,
         prediction_distance: Real,
         dt: Real,
+        contact_matching_distance: Real,
+        merge_coincident_manifolds: bool,
+        max_contacts_per_manifold: Option<NonZeroUsize>,
+        penetration_warmup_steps: u32,
         bodies: &RigidBodySet,
         colliders: &ColliderSet,
         impulse_joints: &ImpulseJointSet,
@@ -802,6 +917,7 @@ impl NarrowPhase {
         }
 
         let query_dispatcher = &*self.query_dispatcher;
+        let material_overrides = &self.material_overrides;
 
         // TODO: don't iterate on all the edges.
         par_iter_mut!(&mut self.contact_graph.graph.edges).for_each(|edge| {
@@ -826,6 +942,17 @@ impl NarrowPhase {
 
                 // Deal with contacts disabled between bodies attached by joints.
                 if let (Some(co_parent1), Some(co_parent2)) = (&co1.parent, &co2.parent) {
+                    // Filter the pairs of colliders attached to the same body.
+                    if co_parent1.handle == co_parent2.handle
+                        && !rb1.is_some_and(|rb| {
+                            rb.is_self_collision_enabled()
+                                && rb.is_self_collision_allowed(pair.collider1, pair.collider2)
+                        })
+                    {
+                        pair.clear();
+                        break 'emit_events;
+                    }
+
                     for (_, joint) in
                         impulse_joints.joints_between(co_parent1.handle, co_parent2.handle)
                     {
@@ -863,6 +990,12 @@ impl NarrowPhase {
                     }
                 }
 
+                // Filter based on the collision-detection being disabled.
+                if !co1.flags.collision_enabled || !co2.flags.collision_enabled {
+                    pair.clear();
+                    break 'emit_events;
+                }
+
                 // Filter based on the rigid-body types.
                 if !co1.flags.active_collision_types.test(rb_type1, rb_type2)
                     && !co2.flags.active_collision_types.test(rb_type1, rb_type2)
@@ -904,6 +1037,12 @@ impl NarrowPhase {
                     solver_flags.remove(SolverFlags::COMPUTE_IMPULSES);
                 }
 
+                // Self-contacts can’t be solved since both colliders share the same motion.
+                if co1.parent.is_some() && co1.parent.map(|p| p.handle) == co2.parent.map(|p| p.handle)
+                {
+                    solver_flags.remove(SolverFlags::COMPUTE_IMPULSES);
+                }
+
                 if co1.changes.contains(ColliderChanges::SHAPE)
                     || co2.changes.contains(ColliderChanges::SHAPE)
                 {
@@ -938,6 +1077,10 @@ impl NarrowPhase {
                     prediction_distance + contact_skin_sum
                 };
 
+                if contact_matching_distance > 0.0 {
+                    pair.save_contacts_for_matching();
+                }
+
                 let _ = query_dispatcher.contact_manifolds(
                     &pos12,
                     &*co1.shape,
@@ -947,19 +1090,56 @@ impl NarrowPhase {
                     &mut pair.workspace,
                 );
 
-                let friction = CoefficientCombineRule::combine(
+                if contact_matching_distance > 0.0 {
+                    pair.match_contacts_using_positions(contact_matching_distance);
+                }
+
+                let mut friction = CoefficientCombineRule::combine(
                     co1.material.friction,
                     co2.material.friction,
                     co1.material.friction_combine_rule as u8,
                     co2.material.friction_combine_rule as u8,
                 );
-                let restitution = CoefficientCombineRule::combine(
+                let mut restitution = CoefficientCombineRule::combine(
                     co1.material.restitution,
                     co2.material.restitution,
                     co1.material.restitution_combine_rule as u8,
                     co2.material.restitution_combine_rule as u8,
                 );
 
+                if !pair.material_computed {
+                    pair.material_computed = true;
+
+                    if active_hooks.contains(ActiveHooks::COMPUTE_CONTACT_PAIR_MATERIAL) {
+                        let context = PairFilterContext {
+                            bodies,
+                            colliders,
+                            rigid_body1: co1.parent.map(|p| p.handle),
+                            rigid_body2: co2.parent.map(|p| p.handle),
+                            collider1: pair.collider1,
+                            collider2: pair.collider2,
+                        };
+                        pair.material = hooks.contact_pair_material(&context);
+                    }
+                }
+
+                if let Some(material) = pair.material.or_else(|| {
+                    material_overrides.get(
+                        pair.collider1,
+                        co1.flags.collision_groups.memberships,
+                        pair.collider2,
+                        co2.flags.collision_groups.memberships,
+                    )
+                }) {
+                    friction = material.friction.unwrap_or(friction);
+                    restitution = material.restitution.unwrap_or(restitution);
+                }
+                let restitution_velocity_threshold = co1
+                    .material
+                    .restitution_velocity_threshold
+                    .max(co2.material.restitution_velocity_threshold);
+                let contact_model = co1.material.contact_model.combine(co2.material.contact_model);
+
                 let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
                 let dominance1 = rb1.map(|rb| rb.dominance).unwrap_or(zero);
                 let dominance2 = rb2.map(|rb| rb.dominance).unwrap_or(zero);
@@ -970,15 +1150,19 @@ impl NarrowPhase {
                     let world_pos1 = manifold.subshape_pos1.prepend_to(&co1.pos);
                     let world_pos2 = manifold.subshape_pos2.prepend_to(&co2.pos);
                     manifold.data.solver_contacts.clear();
+                    manifold.data.merged_contacts.clear();
                     manifold.data.rigid_body1 = co1.parent.map(|p| p.handle);
                     manifold.data.rigid_body2 = co2.parent.map(|p| p.handle);
                     manifold.data.solver_flags = solver_flags;
+                    manifold.data.contact_model = contact_model;
                     manifold.data.relative_dominance = dominance1.effective_group(&rb_type1)
                         - dominance2.effective_group(&rb_type2);
                     manifold.data.normal = world_pos1 * manifold.local_n1;
+                    let disable_warmstart =
+                        std::mem::take(&mut manifold.data.disable_warmstart);
 
                     // Generate solver contacts.
-                    for (contact_id, contact) in manifold.points.iter().enumerate() {
+                    for (contact_id, contact) in manifold.points.iter_mut().enumerate() {
                         if contact_id > u8::MAX as usize {
                             log::warn!("A contact manifold cannot contain more than 255 contacts currently, dropping contact in excess.");
                             break;
@@ -995,6 +1179,13 @@ impl NarrowPhase {
                         };
 
                         if keep_solver_contact {
+                            let penetration_correction_factor = if contact.data.age >= penetration_warmup_steps {
+                                1.0
+                            } else {
+                                contact.data.age as Real / penetration_warmup_steps as Real
+                            };
+                            contact.data.age = contact.data.age.saturating_add(1);
+
                             // Generate the solver contact.
                             let world_pt1 = world_pos1 * contact.local_p1;
                             let world_pt2 = world_pos2 * contact.local_p2;
@@ -1006,14 +1197,34 @@ impl NarrowPhase {
                                 dist: effective_contact_dist,
                                 friction,
                                 restitution,
+                                restitution_velocity_threshold,
                                 tangent_velocity: Vector::zeros(),
+                                friction_anisotropy: [1.0; DIM - 1],
+                                #[cfg(feature = "dim3")]
+                                friction_direction: Vector::zeros(),
+                                rolling_friction: 0.0,
+                                one_way_normal: None,
+                                max_normal_impulse: Real::INFINITY,
+                                compliance: 0.0,
+                                enabled: true,
                                 is_new: contact.data.impulse == 0.0,
-                                warmstart_impulse: contact.data.warmstart_impulse,
-                                warmstart_tangent_impulse: contact.data.warmstart_tangent_impulse,
+                                penetration_correction_factor,
+                                warmstart_impulse: if disable_warmstart {
+                                    0.0
+                                } else {
+                                    contact.data.warmstart_impulse
+                                },
+                                warmstart_tangent_impulse: if disable_warmstart {
+                                    na::zero()
+                                } else {
+                                    contact.data.warmstart_tangent_impulse
+                                },
                             };
 
                             manifold.data.solver_contacts.push(solver_contact);
                             pair.has_any_active_contact = true;
+                        } else {
+                            contact.data.age = 0;
                         }
                     }
 
@@ -1038,12 +1249,17 @@ impl NarrowPhase {
                         };
 
                         hooks.modify_solver_contacts(&mut context);
+                        modifiable_solver_contacts.retain(|contact| contact.enabled);
 
                         manifold.data.solver_contacts = modifiable_solver_contacts;
                         manifold.data.normal = modifiable_normal;
                         manifold.data.user_data = modifiable_user_data;
                     }
 
+                    if let Some(contact) = manifold.data.solver_contacts.first() {
+                        manifold.data.contact_model = contact_model.with_compliance(contact.compliance);
+                    }
+
                     /*
                      * TODO: When using the block solver in 3D, I’d expect this sort to help, but
                      *       it makes the domino demo worse. Needs more investigation.
@@ -1073,6 +1289,14 @@ impl NarrowPhase {
                     sort_solver_contacts(&mut manifold.data.solver_contacts);
                     */
                 }
+
+                if merge_coincident_manifolds {
+                    pair.merge_coincident_manifolds(prediction_distance);
+                }
+
+                if let Some(max_contacts) = max_contacts_per_manifold {
+                    pair.cull_solver_contacts(max_contacts.get());
+                }
             }
 
             let active_events = co1.flags.active_events | co2.flags.active_events;
@@ -1089,7 +1313,20 @@ impl NarrowPhase {
         });
     }
 
+    /// Copies the impulses of the contacts moved by `ContactPair::merge_coincident_manifolds`
+    /// back to their original contact manifold, for the given contact pairs.
+    pub(crate) fn writeback_merged_contacts(&mut self, pair_ids: &[TemporaryInteractionIndex]) {
+        for pair_id in pair_ids {
+            self.contact_graph.graph.edges[pair_id.index()]
+                .weight
+                .writeback_merged_contacts();
+        }
+    }
+
     /// Retrieve all the interactions with at least one contact point, happening between two active bodies.
+    ///
+    /// If `deterministic_order` is `true`, the manifold indices of each island are sorted by
+    /// rigid-body handles, collider handles, and sub-shape indices.
     // NOTE: this is very similar to the code from ImpulseJointSet::select_active_interactions.
     pub(crate) fn select_active_contacts<'a>(
         &'a mut self,
@@ -1098,16 +1335,27 @@ impl NarrowPhase {
         out_contact_pairs: &mut Vec<TemporaryInteractionIndex>,
         out_manifolds: &mut Vec<&'a mut ContactManifold>,
         out: &mut [Vec<ContactManifoldIndex>],
+        deterministic_order: bool,
     ) {
         for out_island in &mut out[..islands.num_islands()] {
             out_island.clear();
         }
 
+        // The collider handles of each selected manifold, used for sorting.
+        let mut manifold_colliders = vec![];
+
         // TODO: don't iterate through all the interactions.
         for (pair_id, inter) in self.contact_graph.graph.edges.iter_mut().enumerate() {
             let mut push_pair = false;
+            let pair_colliders = (inter.weight.collider1, inter.weight.collider2);
 
             for manifold in &mut inter.weight.manifolds {
+                // These are set again by the constraints solver.
+                manifold.data.has_fast_contact = false;
+                for point in &mut manifold.points {
+                    point.data.effective_mass = 0.0;
+                }
+
                 if manifold
                     .data
                     .solver_flags
@@ -1151,6 +1399,10 @@ impl NarrowPhase {
                         out[island_index].push(out_manifolds.len());
                         out_manifolds.push(manifold);
                         push_pair = true;
+
+                        if deterministic_order {
+                            manifold_colliders.push(pair_colliders);
+                        }
                     }
                 }
             }
@@ -1159,5 +1411,268 @@ impl NarrowPhase {
                 out_contact_pairs.push(EdgeIndex::new(pair_id as u32));
             }
         }
+
+        if deterministic_order {
+            for out_island in &mut out[..islands.num_islands()] {
+                // NOTE: the sort is stable, so the manifolds with the same key keep the order
+                //       they have in their contact pair.
+                out_island.sort_by_key(|manifold_id| {
+                    let manifold = &out_manifolds[*manifold_id];
+                    let (collider1, collider2) = manifold_colliders[*manifold_id];
+                    (
+                        manifold.data.rigid_body1.map(|h| h.into_raw_parts()),
+                        manifold.data.rigid_body2.map(|h| h.into_raw_parts()),
+                        collider1.into_raw_parts(),
+                        collider2.into_raw_parts(),
+                        manifold.subshape1,
+                        manifold.subshape2,
+                    )
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::{cuboid, floor, TestWorld};
+    use na::point;
+
+    #[test]
+    fn strongest_contacts_sorted_by_impulse() {
+        let mut world = TestWorld::with_gravity();
+
+        // Two balls stacked on a fixed ball: the lowest contact supports both balls.
+        let (_, ground_co) =
+            world.insert_body(RigidBodyBuilder::fixed(), ColliderBuilder::ball(1.0));
+        for i in 1..3 {
+            let rb = RigidBodyBuilder::dynamic().translation(Vector::y() * (i as Real) * 2.0);
+            world.insert_body(rb, ColliderBuilder::ball(1.0));
+        }
+
+        world.steps(100);
+
+        let strongest = world.narrow_phase.strongest_contacts(10);
+        assert_eq!(strongest.len(), 2);
+        assert!(strongest[0].2.data.impulse > strongest[1].2.data.impulse);
+        assert!(strongest[0].0.collider1 == ground_co || strongest[0].0.collider2 == ground_co);
+
+        let top = world.narrow_phase.strongest_contacts(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].2.data.impulse, strongest[0].2.data.impulse);
+    }
+
+    #[test]
+    fn merge_coincident_manifolds_of_compound() {
+        use crate::geometry::SharedShape;
+        use crate::math::{Isometry, MAX_MANIFOLD_POINTS};
+
+        let mut world = TestWorld::with_gravity();
+
+        #[cfg(feature = "dim2")]
+        let part = SharedShape::cuboid(0.5, 0.5);
+        #[cfg(feature = "dim3")]
+        let part = SharedShape::cuboid(0.5, 0.5, 0.5);
+
+        world.colliders.insert(floor());
+        let compound = ColliderBuilder::compound(vec![
+            (Isometry::from(Vector::x() * -0.5), part.clone()),
+            (Isometry::from(Vector::x() * 0.5), part),
+        ]);
+        let (body, _) = world.insert_body(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.5)
+                .can_sleep(false),
+            compound,
+        );
+
+        world.params = IntegrationParameters {
+            merge_coincident_manifolds: true,
+            ..IntegrationParameters::default()
+        };
+
+        world.steps(60);
+
+        let pair = world.narrow_phase.contact_pairs().next().unwrap();
+        assert_eq!(pair.manifolds.len(), 2);
+
+        // All the solver contacts are now in a single manifold.
+        let num_solver_contacts: Vec<_> = pair
+            .manifolds
+            .iter()
+            .map(|m| m.data.num_active_contacts())
+            .collect();
+        assert!(num_solver_contacts.contains(&0));
+        assert!(num_solver_contacts.iter().sum::<usize>() <= MAX_MANIFOLD_POINTS);
+
+        // The impulses of the merged contacts are written back to their original manifold.
+        assert!(pair
+            .manifolds
+            .iter()
+            .all(|m| m.points.iter().any(|pt| pt.data.impulse > 0.0)));
+
+        // The body rests on the floor, without jittering since the contacts kept by the merge
+        // don’t change from one step to the next.
+        for _ in 0..120 {
+            world.step();
+            let rb = &world.bodies[body];
+            assert!(rb.linvel().norm() < 1.0e-4, "{:?}", rb.linvel());
+            #[cfg(feature = "dim2")]
+            assert!(rb.angvel().abs() < 1.0e-4, "{:?}", rb.angvel());
+            #[cfg(feature = "dim3")]
+            assert!(rb.angvel().norm() < 1.0e-4, "{:?}", rb.angvel());
+        }
+        approx::assert_relative_eq!(world.bodies[body].translation().y, 0.5, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn max_contacts_per_manifold_on_dense_mesh() {
+        use crate::math::DIM;
+        use std::num::NonZeroUsize;
+
+        // NOTE: each segment (resp. triangle) of the mesh gets its own contact manifold with up
+        //       to 2 (resp. 3) contacts.
+        #[cfg(feature = "dim2")]
+        let (mesh, max_contacts) = {
+            let vertices = (0..=40)
+                .map(|i| point![i as Real * 0.5 - 10.0, 0.0])
+                .collect();
+            (ColliderBuilder::polyline(vertices, None), 2)
+        };
+        #[cfg(feature = "dim3")]
+        let (mesh, max_contacts) = {
+            let n = 40;
+            let vertices = (0..=n)
+                .flat_map(|i| {
+                    (0..=n)
+                        .map(move |j| point![i as Real * 0.5 - 10.0, 0.0, j as Real * 0.5 - 10.0])
+                })
+                .collect();
+            let indices = (0..n)
+                .flat_map(|i| {
+                    (0..n).flat_map(move |j| {
+                        let id = |i: u32, j: u32| i * (n + 1) + j;
+                        [
+                            [id(i, j), id(i, j + 1), id(i + 1, j)],
+                            [id(i + 1, j), id(i, j + 1), id(i + 1, j + 1)],
+                        ]
+                    })
+                })
+                .collect();
+            (ColliderBuilder::trimesh(vertices, indices), 4)
+        };
+
+        let run = |max_contacts_per_manifold: Option<NonZeroUsize>| {
+            let mut world = TestWorld::with_gravity();
+
+            world.colliders.insert(mesh.clone());
+            let (body, _) = world.insert_body(
+                RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * 1.0)
+                    .can_sleep(false),
+                cuboid(1.0, 1.0),
+            );
+
+            world.params = IntegrationParameters {
+                max_contacts_per_manifold,
+                ..IntegrationParameters::default()
+            };
+
+            world.steps(100);
+
+            // The solver contacts of the pair, across all its manifolds.
+            let points: Vec<_> = world
+                .narrow_phase
+                .contact_pairs()
+                .flat_map(|pair| &pair.manifolds)
+                .flat_map(|m| &m.data.solver_contacts)
+                .map(|contact| contact.point)
+                .collect();
+            (points, world.bodies[body].clone())
+        };
+
+        let (uncapped_points, _) = run(None);
+        assert!(uncapped_points.len() > max_contacts);
+
+        let (points, body) = run(NonZeroUsize::new(max_contacts));
+        assert_eq!(points.len(), max_contacts);
+
+        // The kept contacts span the whole bottom face of the box.
+        for i in (0..DIM).filter(|i| *i != 1) {
+            let min = points.iter().map(|p| p[i]).fold(Real::MAX, Real::min);
+            let max = points.iter().map(|p| p[i]).fold(Real::MIN, Real::max);
+            approx::assert_relative_eq!(max - min, 2.0, epsilon = 1.0e-2);
+        }
+
+        // The box still rests on the mesh.
+        assert!(body.linvel().norm() < 1.0e-3);
+        approx::assert_relative_eq!(body.translation().y, 1.0, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn contact_matching_distance_keeps_stack_contacts_warmstarted() {
+        use parry::shape::PackedFeatureId;
+
+        fn num_new_contacts(contact_matching_distance: Real) -> usize {
+            let mut world = TestWorld::with_gravity();
+
+            // A frictionless ground, so that the stack slides on it and its contacts are
+            // updated at each timestep.
+            let (_, ground_co) =
+                world.insert_body(RigidBodyBuilder::fixed(), floor().friction(0.0));
+            let mut stack = vec![ground_co];
+
+            for i in 0..5 {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (0.5 + i as Real))
+                    .linvel(Vector::x())
+                    .can_sleep(false);
+                stack.push(world.insert_body(rb, cuboid(0.5, 0.5)).1);
+            }
+
+            world.params = IntegrationParameters {
+                normalized_contact_matching_distance: contact_matching_distance,
+                ..IntegrationParameters::default()
+            };
+            let mut num_new_contacts = 0;
+
+            for i in 0..300 {
+                world.step();
+
+                // Simulate the feature ids of the contacts flickering between timesteps.
+                for pair in stack.windows(2) {
+                    let pair = world
+                        .narrow_phase
+                        .contact_pair_mut(pair[0], pair[1])
+                        .unwrap();
+                    for manifold in &mut pair.manifolds {
+                        for contact in &mut manifold.points {
+                            contact.fid1 = PackedFeatureId::UNKNOWN;
+                            contact.fid2 = PackedFeatureId::UNKNOWN;
+                        }
+                    }
+                }
+
+                // Count the contacts that lost their impulses once the stack settled.
+                if i >= 20 {
+                    num_new_contacts += world
+                        .narrow_phase
+                        .contact_pairs()
+                        .flat_map(|pair| &pair.manifolds)
+                        .flat_map(|manifold| &manifold.data.solver_contacts)
+                        .filter(|contact| contact.is_new)
+                        .count();
+                }
+            }
+
+            num_new_contacts
+        }
+
+        let without_matching = num_new_contacts(0.0);
+        let with_matching = num_new_contacts(0.005);
+        assert!(with_matching < without_matching);
     }
 }
@@ -83,6 +83,10 @@ impl CollisionPipeline {
         narrow_phase.compute_contacts(
             prediction_distance,
             0.0,
+            0.0,
+            false,
+            None,
+            0,
             bodies,
             colliders,
             &ImpulseJointSet::new(),
@@ -273,4 +277,61 @@ mod tests {
 
         assert!(hit, "No hit found");
     }
+
+    #[test]
+    pub fn test_collision_and_query_enabled() {
+        use crate::prelude::*;
+        let mut rigid_body_set = RigidBodySet::new();
+        let mut collider_set = ColliderSet::new();
+
+        let builder =
+            ColliderBuilder::ball(1.0).active_collision_types(ActiveCollisionTypes::all());
+        let a_handle = collider_set.insert(builder.clone());
+        let b_handle = collider_set.insert(builder.clone().collision_enabled(false));
+        let c_handle = collider_set.insert(builder.query_enabled(false));
+
+        let integration_parameters = IntegrationParameters::default();
+        let mut broad_phase = BroadPhaseMultiSap::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut collision_pipeline = CollisionPipeline::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        collision_pipeline.step(
+            integration_parameters.prediction_distance(),
+            &mut broad_phase,
+            &mut narrow_phase,
+            &mut rigid_body_set,
+            &mut collider_set,
+            Some(&mut query_pipeline),
+            &(),
+            &(),
+        );
+
+        let has_contact = |h1, h2| {
+            narrow_phase
+                .contact_pair(h1, h2)
+                .map(|pair| pair.has_any_active_contact)
+                .unwrap_or(false)
+        };
+        assert!(has_contact(a_handle, c_handle));
+        assert!(!has_contact(a_handle, b_handle));
+        assert!(!has_contact(b_handle, c_handle));
+
+        let mut hits = vec![];
+        let ray = Ray::new(Point::origin() - Vector::x() * 10.0, Vector::x());
+        query_pipeline.intersections_with_ray(
+            &rigid_body_set,
+            &collider_set,
+            &ray,
+            Real::MAX,
+            true,
+            QueryFilter::default(),
+            |handle, _| {
+                hits.push(handle);
+                true
+            },
+        );
+        hits.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(hits, vec![a_handle, b_handle]);
+    }
 }
@@ -0,0 +1,132 @@
+use crate::dynamics::RigidBodyHandle;
+use crate::math::{AngVector, Real, Vector};
+
+#[cfg(doc)]
+use crate::pipeline::PhysicsPipeline;
+
+/// The contact constraints assembled by the constraints solver during the last timestep, as
+/// returned by [`PhysicsPipeline::export_constraints`].
+///
+/// Each row is the non-penetration constraint of a single solver contact. The rows are sorted
+/// by rigid-body handles, so the layout doesn’t depend on how the solver batched the
+/// constraints (e.g. for SIMD). The rows of the same pair of rigid-bodies keep the order of
+/// their contacts. The friction constraints, and the contacts involving a multibody, aren’t
+/// exported.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConstraintExport {
+    /// The non-penetration rows of all the contact constraints.
+    pub contacts: Vec<ContactConstraintRow>,
+}
+
+/// The non-penetration constraint of a single solver contact.
+///
+/// The solver expresses the angular velocities in a space scaled by the square root of the
+/// world-space inverse angular inertia `sqrt(I⁻¹)`, i.e., it solves for `sqrt(I)·ω`. With
+/// `p` the contact point and `c1`, `c2` the world-space centers of mass of the bodies, the
+/// Jacobian of this row is:
+/// - `[dir1, gcross1]` for the first body, with `gcross1 = sqrt(I1⁻¹)·((p - c1) × dir1)`.
+/// - `[-dir1, gcross2]` for the second body, with `gcross2 = sqrt(I2⁻¹)·((p - c2) × -dir1)`.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ContactConstraintRow {
+    /// The first rigid-body, or `None` if it isn’t dynamic (or if the contact involves a
+    /// collider without parent).
+    ///
+    /// In that case, the first body is treated as having an infinite mass, and
+    /// [`Self::gcross1`] is zero.
+    pub body1: Option<RigidBodyHandle>,
+    /// The second rigid-body. This is always a dynamic rigid-body.
+    pub body2: RigidBodyHandle,
+    /// The direction of the non-penetration force applied to the first body.
+    ///
+    /// This is the opposite of the contact normal when the first body is
+    /// [`ContactManifoldData::rigid_body1`](crate::geometry::ContactManifoldData::rigid_body1).
+    pub dir1: Vector<Real>,
+    /// The angular part of the Jacobian for the first body.
+    pub gcross1: AngVector<Real>,
+    /// The angular part of the Jacobian for the second body.
+    pub gcross2: AngVector<Real>,
+    /// The effective mass of this row, i.e., the inverse of `J·M⁻¹·Jᵀ`.
+    ///
+    /// This is zero for disabled contacts, e.g., one-way contacts approached from the wrong
+    /// side.
+    pub r: Real,
+    /// The right-hand side of this row, i.e., the target relative velocity along
+    /// [`Self::dir1`] that the solver adds to `J·v` before computing the impulse.
+    ///
+    /// This is the value used by the last resolution pass of the timestep, which doesn’t
+    /// include the penetration correction bias (unless the contact is a spring).
+    pub rhs: Real,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+
+    #[test]
+    fn export_constraints_of_two_body_contact() {
+        use crate::utils::{SimdAngularInertia, SimdCross, SimdDot};
+
+        let mut world = TestWorld::new();
+
+        // Two slightly overlapping cubes, side by side and vertically offset so the contact
+        // points are off their line of centers.
+        let cube = cuboid(0.5, 0.5);
+        let (body1, _) = world.insert_body(RigidBodyBuilder::dynamic(), cube.clone());
+        let (body2, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::x() * 0.99 + Vector::y() * 0.5),
+            cube,
+        );
+
+        // The constraints are assembled from the mass properties at the beginning of the step.
+        let mut mprops = vec![];
+        for handle in [body1, body2] {
+            let rb = &mut world.bodies[handle];
+            rb.mprops.update_world_mass_properties(&rb.pos.position);
+            mprops.push(rb.mprops.clone());
+        }
+
+        world.step();
+
+        let export = world.pipeline.export_constraints();
+        assert!(export
+            .contacts
+            .iter()
+            .any(|row| row.gcross1.gdot(row.gcross1) > 1.0e-3));
+
+        for row in &export.contacts {
+            let (mprops1, mprops2) = if row.body1 == Some(body1) {
+                assert_eq!(row.body2, body2);
+                (&mprops[0], &mprops[1])
+            } else {
+                assert_eq!((row.body1, row.body2), (Some(body2), body1));
+                (&mprops[1], &mprops[0])
+            };
+
+            // The contact normal is along the x axis, pushing the first body away.
+            let dir_to_body2 = mprops2.world_com - mprops1.world_com;
+            approx::assert_relative_eq!(row.dir1.norm(), 1.0, epsilon = 1.0e-5);
+            approx::assert_relative_eq!(row.dir1.x.abs(), 1.0, epsilon = 1.0e-5);
+            assert!(row.dir1.dot(&dir_to_body2) < 0.0);
+
+            // Both cubes have the same isotropic inertia, so the contact point cancels out of
+            // `gcross1 + gcross2 = sqrt(I⁻¹)·((c2 - c1) × dir1)`.
+            let expected = mprops1
+                .effective_world_inv_inertia_sqrt
+                .transform_vector(dir_to_body2.gcross(row.dir1));
+            approx::assert_relative_eq!(row.gcross1 + row.gcross2, expected, epsilon = 1.0e-5);
+
+            let imsum = mprops1.effective_inv_mass + mprops2.effective_inv_mass;
+            let inv_r = row.dir1.dot(&imsum.component_mul(&row.dir1))
+                + row.gcross1.gdot(row.gcross1)
+                + row.gcross2.gdot(row.gcross2);
+            approx::assert_relative_eq!(row.r, 1.0 / inv_r, epsilon = 1.0e-5);
+
+            // The bodies start at rest, and the bias pushing them apart isn’t exported.
+            assert_eq!(row.rhs, 0.0);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::dynamics::RigidBodySet;
+use crate::dynamics::{JointBreakEvent, JointLimitEvent, RigidBodySet};
 use crate::geometry::{ColliderSet, CollisionEvent, ContactForceEvent, ContactPair};
 use crate::math::Real;
 use crossbeam::channel::Sender;
@@ -67,6 +67,24 @@ pub trait EventHandler: Send + Sync {
         contact_pair: &ContactPair,
         total_force_magnitude: Real,
     );
+
+    /// Handle a joint limit event.
+    ///
+    /// A joint limit event is emitted whenever an impulse joint starts hitting one of its
+    /// limits, i.e., when the solver starts applying an impulse to enforce that limit. It
+    /// isn’t emitted again until the joint leaves its limit and hits it again.
+    ///
+    /// The default implementation does nothing.
+    fn handle_joint_limit_event(&self, _bodies: &RigidBodySet, _event: JointLimitEvent) {}
+
+    /// Handle a joint break event.
+    ///
+    /// A joint break event is emitted whenever an impulse joint breaks because the force or
+    /// torque it applied exceeded its breaking force or torque. The joint has already been
+    /// removed from the impulse joint set at this point.
+    ///
+    /// The default implementation does nothing.
+    fn handle_joint_break_event(&self, _bodies: &RigidBodySet, _event: JointBreakEvent) {}
 }
 
 impl EventHandler for () {
@@ -94,6 +112,8 @@ impl EventHandler for () {
 pub struct ChannelEventCollector {
     collision_event_sender: Sender<CollisionEvent>,
     contact_force_event_sender: Sender<ContactForceEvent>,
+    joint_limit_event_sender: Option<Sender<JointLimitEvent>>,
+    joint_break_event_sender: Option<Sender<JointBreakEvent>>,
 }
 
 impl ChannelEventCollector {
@@ -105,8 +125,26 @@ impl ChannelEventCollector {
         Self {
             collision_event_sender,
             contact_force_event_sender,
+            joint_limit_event_sender: None,
+            joint_break_event_sender: None,
         }
     }
+
+    /// Sets the crossbeam channel sender used for collecting joint limit events.
+    ///
+    /// Joint limit events are not collected if this isn’t set.
+    pub fn with_joint_limit_event_sender(mut self, sender: Sender<JointLimitEvent>) -> Self {
+        self.joint_limit_event_sender = Some(sender);
+        self
+    }
+
+    /// Sets the crossbeam channel sender used for collecting joint break events.
+    ///
+    /// Joint break events are not collected if this isn’t set.
+    pub fn with_joint_break_event_sender(mut self, sender: Sender<JointBreakEvent>) -> Self {
+        self.joint_break_event_sender = Some(sender);
+        self
+    }
 }
 
 impl EventHandler for ChannelEventCollector {
@@ -131,4 +169,72 @@ impl EventHandler for ChannelEventCollector {
         let result = ContactForceEvent::from_contact_pair(dt, contact_pair, total_force_magnitude);
         let _ = self.contact_force_event_sender.send(result);
     }
+
+    fn handle_joint_limit_event(&self, _bodies: &RigidBodySet, event: JointLimitEvent) {
+        if let Some(sender) = &self.joint_limit_event_sender {
+            let _ = sender.send(event);
+        }
+    }
+
+    fn handle_joint_break_event(&self, _bodies: &RigidBodySet, event: JointBreakEvent) {
+        if let Some(sender) = &self.joint_break_event_sender {
+            let _ = sender.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::{cuboid, floor, TestWorld};
+    use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+    #[test]
+    fn contact_force_event_on_impact_only() {
+        let mut world = TestWorld::with_gravity();
+
+        let (collision_send, _) = crossbeam::channel::unbounded();
+        let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
+        let events = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let floor_co = world.colliders.insert(floor());
+
+        let box_co = cuboid(0.5, 0.5).density(10.0);
+        let weight = box_co.shape.mass_properties(10.0).mass() * world.gravity.norm();
+        // The resting contact force is equal to the weight of the box, which must not
+        // trigger any event.
+        let box_co = box_co
+            .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+            .contact_force_event_threshold(weight * 5.0);
+        let (box_rb, box_co) = world.insert_body(
+            RigidBodyBuilder::dynamic().translation(Vector::y() * 3.0),
+            box_co,
+        );
+
+        let mut event_steps = vec![];
+        let mut first_contact_step = None;
+        for i in 0..120 {
+            world.step_with(&(), &events);
+
+            if first_contact_step.is_none()
+                && world.colliders[box_co].contact_force() != Vector::zeros()
+            {
+                first_contact_step = Some(i);
+            }
+
+            for event in contact_force_recv.try_iter() {
+                let pair = [event.collider1, event.collider2];
+                assert!(pair.contains(&floor_co) && pair.contains(&box_co));
+                assert!(event.total_force_magnitude > weight * 5.0);
+                event_steps.push(i);
+            }
+        }
+
+        // A single event is generated, at the step of the impact.
+        assert!(first_contact_step.is_some());
+        assert_eq!(event_steps, vec![first_contact_step.unwrap()]);
+        // The box is resting on the floor.
+        assert!(world.bodies[box_rb].linvel().norm() < 1.0e-3);
+    }
 }
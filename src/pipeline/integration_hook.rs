@@ -0,0 +1,177 @@
+use crate::dynamics::{RigidBody, RigidBodyHandle, RigidBodySet};
+use crate::math::Real;
+
+#[cfg(doc)]
+use crate::dynamics::IntegrationParameters;
+#[cfg(doc)]
+use crate::pipeline::PhysicsPipeline;
+
+/// User-defined forces recomputed at each solver substep.
+///
+/// This is set with [`PhysicsPipeline::set_integration_hook`]. Unlike the forces applied with
+/// [`RigidBody::add_force`] before calling [`PhysicsPipeline::step`], which stay constant during
+/// the whole timestep, the forces applied by this hook can depend on the velocities of the
+/// rigid-bodies at each substep (see [`IntegrationParameters::num_solver_iterations`]), e.g., for
+/// aerodynamic drag.
+pub trait IntegrationHook: Send + Sync {
+    /// Applies forces to the rigid-bodies of an island right before the velocity solve of a
+    /// substep of length `dt`.
+    ///
+    /// The islands of rigid-bodies are solved independently, so this is called once per
+    /// substep for each island of awake rigid-bodies, and `bodies` only gives access to the
+    /// bodies of that island. When a hook is set, islands are always solved sequentially, even
+    /// with the `parallel` feature.
+    ///
+    /// The velocities of the rigid-bodies are the ones at the beginning of the substep. Only
+    /// the user forces and torques (modified with, e.g., [`RigidBody::add_force`] and
+    /// [`RigidBody::reset_forces`]) are taken into account; they are kept after the timestep,
+    /// like any other user force. Any other modification of the rigid-bodies is not supported.
+    fn apply_forces(&mut self, bodies: &mut IslandBodies, dt: Real);
+}
+
+/// The rigid-bodies of the island being solved, given to [`IntegrationHook::apply_forces`].
+pub struct IslandBodies<'a> {
+    bodies: &'a mut RigidBodySet,
+    handles: &'a [RigidBodyHandle],
+}
+
+impl<'a> IslandBodies<'a> {
+    pub(crate) fn new(bodies: &'a mut RigidBodySet, handles: &'a [RigidBodyHandle]) -> Self {
+        Self { bodies, handles }
+    }
+
+    /// The handles of the rigid-bodies of this island.
+    pub fn handles(&self) -> &'a [RigidBodyHandle] {
+        self.handles
+    }
+
+    /// Is the rigid-body with the given handle part of this island?
+    pub fn contains(&self, handle: RigidBodyHandle) -> bool {
+        self.bodies
+            .get(handle)
+            .is_some_and(|rb| self.handles.get(rb.ids.active_set_offset) == Some(&handle))
+    }
+
+    /// Gets the rigid-body with the given handle, if it is part of this island.
+    pub fn get(&self, handle: RigidBodyHandle) -> Option<&RigidBody> {
+        if self.contains(handle) {
+            self.bodies.get(handle)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the rigid-body with the given handle, if it is part of this
+    /// island.
+    pub fn get_mut(&mut self, handle: RigidBodyHandle) -> Option<&mut RigidBody> {
+        if self.contains(handle) {
+            self.bodies.get_mut(handle)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn integration_hook_drag_reaches_terminal_velocity() {
+        use crate::pipeline::{IntegrationHook, IslandBodies};
+        use std::num::NonZeroUsize;
+
+        // Drag proportional to the velocity, recomputed at each substep.
+        struct Drag {
+            coefficient: Real,
+        }
+
+        impl IntegrationHook for Drag {
+            fn apply_forces(&mut self, bodies: &mut IslandBodies, _dt: Real) {
+                for handle in bodies.handles() {
+                    let body = bodies.get_mut(*handle).unwrap();
+                    let drag = -body.linvel() * body.mass() * self.coefficient;
+                    body.reset_forces(false);
+                    body.add_force(drag, false);
+                }
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        let (handle, _) = world.insert_body(
+            RigidBodyBuilder::dynamic().linvel((Vector::x() + Vector::y()) * 10.0),
+            ColliderBuilder::ball(0.5),
+        );
+
+        let coefficient = 2.0;
+        assert!(world
+            .pipeline
+            .set_integration_hook(Some(Box::new(Drag { coefficient })))
+            .is_none());
+
+        world.params.num_solver_iterations = NonZeroUsize::new(4).unwrap();
+        world.steps(600);
+
+        // The drag balances the gravity at the terminal velocity.
+        let body = &world.bodies[handle];
+        let terminal_velocity = world.gravity / coefficient;
+        approx::assert_relative_eq!(*body.linvel(), terminal_velocity, epsilon = 1.0e-3);
+        assert!(world.pipeline.set_integration_hook(None).is_some());
+    }
+
+    #[test]
+    fn integration_hook_only_modifies_the_solved_island() {
+        use crate::pipeline::{IntegrationHook, IslandBodies};
+        use std::num::NonZeroUsize;
+
+        // Adds the same force to the bodies at each substep, so the user forces of the bodies
+        // keep increasing during the timestep.
+        struct Push;
+
+        impl IntegrationHook for Push {
+            fn apply_forces(&mut self, bodies: &mut IslandBodies, _dt: Real) {
+                for handle in bodies.handles() {
+                    bodies
+                        .get_mut(*handle)
+                        .unwrap()
+                        .add_force(Vector::x(), true);
+                }
+            }
+        }
+
+        let mut world = TestWorld::new();
+
+        // Two bodies far from each other, so they are in separate islands.
+        let handles: Vec<_> = [-10.0, 10.0]
+            .into_iter()
+            .map(|y| {
+                let (handle, _) = world.insert_ball(Vector::y() * y, 0.5);
+                handle
+            })
+            .collect();
+
+        let _ = world.pipeline.set_integration_hook(Some(Box::new(Push)));
+        let num_substeps = 4;
+        world.params.num_solver_iterations = NonZeroUsize::new(num_substeps).unwrap();
+        world.params.min_island_size = 1;
+        world.step();
+        assert_eq!(world.islands.num_islands(), 2);
+
+        // The force applied during the k-th substep is `k * Vector::x()`.
+        let substep_dt = world.params.dt / num_substeps as Real;
+        let total_force = (1..=num_substeps).sum::<usize>() as Real;
+        for handle in handles {
+            let body = &world.bodies[handle];
+            assert_eq!(body.user_force(), Vector::x() * num_substeps as Real);
+            approx::assert_relative_eq!(
+                *body.linvel(),
+                Vector::x() * total_force * substep_dt / body.mass(),
+                epsilon = 1.0e-6
+            );
+        }
+    }
+}
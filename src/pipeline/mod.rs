@@ -1,12 +1,17 @@
 //! Structure for combining the various physics components to perform an actual simulation.
 
 pub use collision_pipeline::CollisionPipeline;
+pub use constraint_export::{ConstraintExport, ContactConstraintRow};
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
+pub use integration_hook::{IntegrationHook, IslandBodies};
 pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
 pub use physics_pipeline::PhysicsPipeline;
 pub use query_pipeline::{
     generators as query_pipeline_generators, QueryFilter, QueryFilterFlags, QueryPipeline,
+    ShapeCastHitWithOverlap,
 };
+pub use step_history::PhysicsSnapshot;
+pub use step_stats::StepStats;
 
 #[cfg(feature = "debug-render")]
 pub use self::debug_render_pipeline::{
@@ -15,11 +20,21 @@ pub use self::debug_render_pipeline::{
 };
 
 mod collision_pipeline;
+mod constraint_export;
 mod event_handler;
+mod integration_hook;
 mod physics_hooks;
 mod physics_pipeline;
 mod query_pipeline;
+mod step_history;
+mod step_stats;
 mod user_changes;
 
+#[cfg(test)]
+pub(crate) mod test_world;
+
+#[cfg(feature = "sanitize")]
+mod sanitize;
+
 #[cfg(feature = "debug-render")]
 mod debug_render_pipeline;
@@ -1,5 +1,8 @@
 use crate::dynamics::{RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderHandle, ColliderSet, ContactManifold, SolverContact, SolverFlags};
+use crate::geometry::{
+    ColliderHandle, ColliderSet, ContactManifold, ContactMaterialOverride, SolverContact,
+    SolverFlags,
+};
 use crate::math::{Real, Vector};
 use na::ComplexField;
 
@@ -127,6 +130,8 @@ bitflags::bitflags! {
         const FILTER_INTERSECTION_PAIR = 0b0010;
         /// If set, Rapier will call `PhysicsHooks::modify_solver_contact` whenever relevant.
         const MODIFY_SOLVER_CONTACTS = 0b0100;
+        /// If set, Rapier will call `PhysicsHooks::contact_pair_material` whenever relevant.
+        const COMPUTE_CONTACT_PAIR_MATERIAL = 0b1000;
     }
 }
 impl Default for ActiveHooks {
@@ -154,6 +159,14 @@ pub trait PhysicsHooks {
 
     /// Modifies the set of contacts seen by the constraints solver.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Computes the material of a new contact pair.
+    fn contact_pair_material(
+        &self,
+        _context: &PairFilterContext,
+    ) -> Option<ContactMaterialOverride> {
+        None
+    }
 }
 
 /// User-defined functions called by the physics engines during one timestep in order to customize its behavior.
@@ -223,7 +236,8 @@ pub trait PhysicsHooks: Send + Sync {
     /// can be removed and modified.
     ///
     /// Note that if all the contacts have to be ignored by the constraint solver, you may simply
-    /// do `context.solver_contacts.clear()`.
+    /// do `context.solver_contacts.clear()`. Individual contacts can also be ignored by setting
+    /// their [`SolverContact::enabled`] flag to `false`.
     ///
     /// Modifying the solver contacts allow you to achieve various effects, including:
     /// - Simulating conveyor belts by setting the `surface_velocity` of a solver contact.
@@ -237,6 +251,28 @@ pub trait PhysicsHooks: Send + Sync {
     ///
     /// The world-space contact normal can be modified in `context.normal`.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Computes the material of a new contact pair.
+    ///
+    /// Note that this method will only be called if at least one of the colliders
+    /// involved in the contact contains the `ActiveHooks::COMPUTE_CONTACT_PAIR_MATERIAL` flags
+    /// in its physics hooks flags.
+    ///
+    /// This is called only once per contact pair, the first time the narrow-phase computes its
+    /// contacts, and the result is kept for as long as the pair exists (i.e. until the
+    /// colliders’ AABBs stop overlapping). This can be used to blend the materials of two
+    /// colliders based on external logic without paying for it at every timestep.
+    ///
+    /// If this returns `Some`, the friction and restitution it specifies replace the ones
+    /// computed with the combine rules of the colliders, as well as any override registered
+    /// in the [`NarrowPhase::material_overrides`](crate::geometry::NarrowPhase::material_overrides).
+    /// The result can be read with [`ContactPair::material`](crate::geometry::ContactPair::material).
+    fn contact_pair_material(
+        &self,
+        _context: &PairFilterContext,
+    ) -> Option<ContactMaterialOverride> {
+        None
+    }
 }
 
 impl PhysicsHooks for () {
@@ -250,3 +286,69 @@ impl PhysicsHooks for () {
 
     fn modify_solver_contacts(&self, _: &mut ContactModificationContext) {}
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::test_world::TestWorld;
+
+    #[test]
+    fn contact_pair_material_hook() {
+        use crate::geometry::ContactMaterialOverride;
+        use crate::pipeline::{ActiveHooks, PairFilterContext, PhysicsHooks};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct WetSurfaces(AtomicUsize);
+
+        impl PhysicsHooks for WetSurfaces {
+            fn contact_pair_material(
+                &self,
+                context: &PairFilterContext,
+            ) -> Option<ContactMaterialOverride> {
+                let _ = self.0.fetch_add(1, Ordering::SeqCst);
+                let wet = context.colliders[context.collider1].user_data == 1
+                    || context.colliders[context.collider2].user_data == 1;
+                wet.then_some(ContactMaterialOverride::friction(0.1))
+            }
+        }
+
+        let mut world = TestWorld::with_gravity();
+
+        let floor_co = ColliderBuilder::ball(100.0).friction(1.0);
+        let (_, floor_co) = world.insert_body(
+            RigidBodyBuilder::fixed().translation(Vector::y() * -100.0),
+            floor_co,
+        );
+        let [dry, wet] = [(-2.0, 0), (2.0, 1)].map(|(x, user_data)| {
+            let rb = RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * 0.45);
+            let handle = world.bodies.insert(rb);
+            let co = ColliderBuilder::ball(0.5)
+                .friction(1.0)
+                .user_data(user_data)
+                .active_hooks(ActiveHooks::COMPUTE_CONTACT_PAIR_MATERIAL);
+            world
+                .colliders
+                .insert_with_parent(co, handle, &mut world.bodies)
+        });
+
+        let hooks = WetSurfaces(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            world.step_with(&hooks, &());
+        }
+
+        // The hook is only called once per pair.
+        assert_eq!(hooks.0.load(Ordering::SeqCst), 2);
+
+        let pair = |collider| world.narrow_phase.contact_pair(floor_co, collider).unwrap();
+        assert_eq!(pair(dry).material(), None);
+        assert_eq!(pair(dry).manifolds[0].data.solver_contacts[0].friction, 1.0);
+        assert_eq!(
+            pair(wet).material(),
+            Some(ContactMaterialOverride::friction(0.1))
+        );
+        assert_eq!(pair(wet).manifolds[0].data.solver_contacts[0].friction, 0.1);
+    }
+}
@@ -2,24 +2,32 @@
 
 use crate::counters::Counters;
 // #[cfg(not(feature = "parallel"))]
-use crate::dynamics::IslandSolver;
 #[cfg(feature = "parallel")]
 use crate::dynamics::JointGraphEdge;
+use crate::dynamics::{solve_inserted_body_contacts, IslandSolver};
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    BuoyancyField, CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager,
+    MultibodyJointSet, RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
 };
 use crate::geometry::{
     BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
     ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
 };
 use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
+use crate::pipeline::step_history::StepHistory;
+use crate::pipeline::{
+    ConstraintExport, EventHandler, IntegrationHook, PhysicsHooks, PhysicsSnapshot, QueryPipeline,
+    StepStats,
+};
+use std::num::NonZeroUsize;
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 
 /// The physics pipeline, responsible for stepping the whole physics simulation.
 ///
-/// This structure only contains temporary data buffers. It can be dropped and replaced by a fresh
+/// This structure only contains temporary data buffers (and the step history if it was enabled
+/// with [`PhysicsPipeline::enable_history`], the hook set with
+/// [`PhysicsPipeline::set_integration_hook`], and the fluid set with
+/// [`PhysicsPipeline::set_buoyancy_field`]). It can be dropped and replaced by a fresh
 /// copy at any time. For performance reasons it is recommended to reuse the same physics pipeline
 /// instance to benefit from the cached data.
 ///
@@ -31,12 +39,21 @@ use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 pub struct PhysicsPipeline {
     /// Counters used for benchmarking only.
     pub counters: Counters,
+    /// Statistics about the constraints solved during the last call to [`PhysicsPipeline::step`]
+    /// or [`PhysicsPipeline::step_substeps`].
+    pub step_stats: StepStats,
     contact_pair_indices: Vec<TemporaryInteractionIndex>,
     manifold_indices: Vec<Vec<ContactManifoldIndex>>,
     joint_constraint_indices: Vec<Vec<ContactManifoldIndex>>,
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    // The number of islands solved during the last step, i.e., the number of `solvers`
+    // containing up-to-date constraints.
+    num_solved_islands: usize,
+    history: StepHistory,
+    integration_hook: Option<Box<dyn IntegrationHook>>,
+    buoyancy_field: Option<BuoyancyField>,
 }
 
 impl Default for PhysicsPipeline {
@@ -56,15 +73,180 @@ impl PhysicsPipeline {
     pub fn new() -> PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(true),
+            step_stats: StepStats::default(),
             solvers: vec![],
+            num_solved_islands: 0,
             contact_pair_indices: vec![],
             manifold_indices: vec![],
             joint_constraint_indices: vec![],
             broadphase_collider_pairs: vec![],
             broad_phase_events: vec![],
+            history: StepHistory::default(),
+            integration_hook: None,
+            buoyancy_field: None,
         }
     }
 
+    /// Sets the hook applying user-defined forces at each solver substep, or removes it if
+    /// `hook` is `None`.
+    ///
+    /// Returns the previous hook, if any. See [`IntegrationHook`] for details.
+    pub fn set_integration_hook(
+        &mut self,
+        hook: Option<Box<dyn IntegrationHook>>,
+    ) -> Option<Box<dyn IntegrationHook>> {
+        std::mem::replace(&mut self.integration_hook, hook)
+    }
+
+    /// Sets the fluid applying buoyancy forces to the submerged colliders, or removes it if
+    /// `field` is `None`.
+    ///
+    /// Returns the previous fluid, if any. See [`BuoyancyField`] for details.
+    pub fn set_buoyancy_field(&mut self, field: Option<BuoyancyField>) -> Option<BuoyancyField> {
+        std::mem::replace(&mut self.buoyancy_field, field)
+    }
+
+    /// Exports the contact constraints assembled by the constraints solver during the last
+    /// timestep.
+    ///
+    /// This is meant for validating the solver against a reference implementation. See
+    /// [`ConstraintExport`] for the layout of the exported data.
+    pub fn export_constraints(&self) -> ConstraintExport {
+        let mut contacts = vec![];
+        for solver in &self.solvers[..self.num_solved_islands] {
+            solver.export_contact_constraints(&mut contacts);
+        }
+
+        // NOTE: the sort is stable, so the rows of the same pair of bodies keep the order of
+        //       their contacts.
+        contacts.sort_by_key(|row| {
+            (
+                row.body1.map(|h| h.into_raw_parts()),
+                row.body2.into_raw_parts(),
+            )
+        });
+        ConstraintExport { contacts }
+    }
+
+    /// The maximum number of timesteps that can be kept in the history of this pipeline.
+    pub const MAX_HISTORY_LENGTH: usize = 256;
+
+    /// Keeps a copy of the physics state at the beginning of each of the last `n_steps`
+    /// timesteps, so they can be restored with [`Self::step_back`].
+    ///
+    /// This is purely a debugging aid (e.g. for diagnosing instabilities), not a physically
+    /// reversed integration. Each snapshot is a full copy of the island manager, broad-phase,
+    /// narrow-phase, rigid-body set, collider set, joint sets, and CCD solver, taken at each
+    /// call to [`Self::step`]. The memory used by the history is thus about `n_steps` times
+    /// the memory used by the whole physics state, and each timestep pays for that copy.
+    ///
+    /// `n_steps` is capped to [`Self::MAX_HISTORY_LENGTH`]. Setting it to 0 disables the
+    /// history and frees its memory.
+    pub fn enable_history(&mut self, n_steps: usize) {
+        self.history
+            .set_capacity(n_steps.min(Self::MAX_HISTORY_LENGTH));
+    }
+
+    /// The maximum number of timesteps kept in the history of this pipeline.
+    ///
+    /// This is 0 unless the history was enabled with [`Self::enable_history`].
+    pub fn history_capacity(&self) -> usize {
+        self.history.capacity()
+    }
+
+    /// The number of timesteps that can currently be undone with [`Self::step_back`].
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Takes a full copy of the physics state, to be restored later with
+    /// [`Self::restore_snapshot`].
+    ///
+    /// Unlike the history (see [`Self::enable_history`]), snapshots are owned by the caller,
+    /// can be restored any number of times, and can be serialized (with the `serde-serialize`
+    /// feature), which makes them suitable for rollback networking. See [`PhysicsSnapshot`] for
+    /// details.
+    pub fn snapshot(
+        &self,
+        islands: &IslandManager,
+        broad_phase: &dyn BroadPhase,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        ccd_solver: &CCDSolver,
+    ) -> PhysicsSnapshot {
+        PhysicsSnapshot::new(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        )
+    }
+
+    /// Restores the physics state saved by [`Self::snapshot`].
+    ///
+    /// The broad-phase is only restored if it supports [`BroadPhase::save_state`] and the
+    /// snapshot wasn’t deserialized. The query pipeline isn’t part of the snapshot so it needs
+    /// to be updated after this call.
+    pub fn restore_snapshot(
+        &mut self,
+        snapshot: &PhysicsSnapshot,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+    ) {
+        snapshot.restore_cloned(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        );
+    }
+
+    /// Restores the physics state as it was at the beginning of the last call to [`Self::step`].
+    ///
+    /// Returns `false` (and doesn’t modify anything) if there is no state left in the history.
+    /// See [`Self::enable_history`] for details. The broad-phase is only restored if it
+    /// supports [`BroadPhase::save_state`]. The query pipeline isn’t part of the history so it
+    /// needs to be updated after this call.
+    pub fn step_back(
+        &mut self,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+    ) -> bool {
+        self.history.pop(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        )
+    }
+
     fn clear_modified_colliders(
         &mut self,
         colliders: &mut ColliderSet,
@@ -145,6 +327,10 @@ impl PhysicsPipeline {
         narrow_phase.compute_contacts(
             integration_parameters.prediction_distance(),
             integration_parameters.dt,
+            integration_parameters.contact_matching_distance(),
+            integration_parameters.merge_coincident_manifolds,
+            integration_parameters.max_contacts_per_manifold,
+            integration_parameters.penetration_warmup_steps,
             bodies,
             colliders,
             impulse_joints,
@@ -163,6 +349,7 @@ impl PhysicsPipeline {
         &mut self,
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
+        step_dt: Real,
         islands: &mut IslandManager,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
@@ -200,6 +387,7 @@ impl PhysicsPipeline {
             &mut self.contact_pair_indices,
             &mut manifolds,
             &mut self.manifold_indices,
+            integration_parameters.deterministic_contact_order,
         );
         impulse_joints.select_active_interactions(
             islands,
@@ -217,6 +405,31 @@ impl PhysicsPipeline {
             let effective_mass = rb.mprops.effective_mass();
             rb.forces
                 .compute_effective_force_and_torque(gravity, &effective_mass);
+
+            if let Some(field) = &self.buoyancy_field {
+                field.apply(rb, colliders, gravity);
+            }
+
+            if let Some(link) = multibody_joints.rigid_body_link(*handle).copied() {
+                if let Some(link) = multibody_joints
+                    .get_multibody_mut_internal(link.multibody)
+                    .and_then(|multibody| multibody.link_mut(link.id))
+                {
+                    link.joint.data.ramp_motors(integration_parameters.dt);
+                }
+            }
+        }
+
+        // Ramp the velocities targeted by the joint motors.
+        let joints = impulse_joints.joints_mut();
+        for joint_id in self.joint_constraint_indices[..islands.num_islands()]
+            .iter()
+            .flatten()
+        {
+            joints[*joint_id]
+                .weight
+                .data
+                .ramp_motors(integration_parameters.dt);
         }
         self.counters.stages.update_time.pause();
 
@@ -225,6 +438,7 @@ impl PhysicsPipeline {
             self.solvers
                 .resize_with(islands.num_islands(), IslandSolver::new);
         }
+        self.num_solved_islands = islands.num_islands();
 
         #[cfg(not(feature = "parallel"))]
         {
@@ -242,6 +456,9 @@ impl PhysicsPipeline {
                     impulse_joints.joints_mut(),
                     &self.joint_constraint_indices[island_id],
                     multibody_joints,
+                    self.integration_hook
+                        .as_deref_mut()
+                        .map(|hook| hook as &mut dyn IntegrationHook),
                 )
             }
         }
@@ -262,6 +479,16 @@ impl PhysicsPipeline {
             let manifold_indices = &self.manifold_indices[..];
             let joint_constraint_indices = &self.joint_constraint_indices[..];
 
+            let min_parallel_island_size = integration_parameters.min_parallel_island_size;
+            // NOTE: the integration hook has mutable access to all the rigid-bodies so the
+            //       islands can’t be solved in parallel if it is set.
+            let mut integration_hook = self.integration_hook.as_deref_mut();
+            let has_integration_hook = integration_hook.is_some();
+            let is_parallel_island = |island_id: usize| {
+                !has_integration_hook
+                    && islands.active_island(island_id).len() >= min_parallel_island_size
+            };
+
             // PERF: right now, we are only doing islands-based parallelism.
             //       Intra-island parallelism (that hasn’t been ported to the new
             //       solver yet) will be supported in the future.
@@ -272,6 +499,7 @@ impl PhysicsPipeline {
                 solvers
                     .par_iter_mut()
                     .enumerate()
+                    .filter(|(island_id, _)| is_parallel_island(*island_id))
                     .for_each(|(island_id, solver)| {
                         let bodies: &mut RigidBodySet =
                             unsafe { &mut *bodies.load(Ordering::Relaxed) };
@@ -294,16 +522,77 @@ impl PhysicsPipeline {
                             impulse_joints,
                             &joint_constraint_indices[island_id],
                             multibody_joints,
+                            None,
                         )
                     });
             });
+
+            // Solve the islands too small to be worth a parallel task on this thread.
+            // NOTE: this happens after all the parallel tasks completed so the pointers
+            //       below are no longer aliased.
+            {
+                enable_flush_to_zero!();
+
+                for (island_id, solver) in solvers.iter_mut().enumerate() {
+                    if is_parallel_island(island_id) {
+                        continue;
+                    }
+
+                    let bodies: &mut RigidBodySet = unsafe { &mut *bodies.load(Ordering::Relaxed) };
+                    let manifolds: &mut Vec<&mut ContactManifold> =
+                        unsafe { &mut *manifolds.load(Ordering::Relaxed) };
+                    let impulse_joints: &mut Vec<JointGraphEdge> =
+                        unsafe { &mut *impulse_joints.load(Ordering::Relaxed) };
+                    let multibody_joints: &mut MultibodyJointSet =
+                        unsafe { &mut *multibody_joints.load(Ordering::Relaxed) };
+
+                    let mut counters = Counters::new(false);
+                    solver.init_and_solve(
+                        island_id,
+                        &mut counters,
+                        integration_parameters,
+                        islands,
+                        bodies,
+                        &mut manifolds[..],
+                        &manifold_indices[island_id],
+                        impulse_joints,
+                        &joint_constraint_indices[island_id],
+                        multibody_joints,
+                        integration_hook
+                            .as_deref_mut()
+                            .map(|hook| hook as &mut dyn IntegrationHook),
+                    )
+                }
+            }
             self.counters.solver.velocity_resolution_time.pause();
         }
 
+        for solver in &self.solvers[..islands.num_islands()] {
+            self.step_stats += solver.stats;
+        }
+
+        if integration_parameters.merge_coincident_manifolds {
+            narrow_phase.writeback_merged_contacts(&self.contact_pair_indices);
+        }
+
         // Generate contact force events if needed.
         let inv_dt = crate::utils::inv(integration_parameters.dt);
+        let inv_step_dt = crate::utils::inv(step_dt);
         for pair_id in self.contact_pair_indices.drain(..) {
             let pair = narrow_phase.contact_pair_at_index(pair_id);
+
+            // NOTE: divide by the length of the whole timestep so that the forces of
+            //       all the CCD substeps are averaged over the timestep.
+            let world_impulse: Vector<Real> = pair
+                .manifolds
+                .iter()
+                .flat_map(|m| m.points.iter())
+                .map(|pt| pt.data.world_impulse)
+                .sum();
+            let force = world_impulse * inv_step_dt;
+            colliders.index_mut_internal(pair.collider1).contact_force += force;
+            colliders.index_mut_internal(pair.collider2).contact_force -= force;
+
             let co1 = &colliders[pair.collider1];
             let co2 = &colliders[pair.collider2];
             let threshold = co1
@@ -327,6 +616,35 @@ impl PhysicsPipeline {
             }
         }
 
+        // Generate joint limit events if needed, and find the joints that break.
+        let mut broken_joints = vec![];
+        let joints = impulse_joints.joints_mut();
+        for (island_id, island_joints) in self.joint_constraint_indices[..islands.num_islands()]
+            .iter()
+            .enumerate()
+        {
+            // NOTE: the joint impulses are the ones of the last solver substep.
+            let num_substeps = integration_parameters.num_solver_iterations.get()
+                + islands.active_island_additional_solver_iterations(island_id);
+            let inv_substep_dt = inv_dt * num_substeps as Real;
+
+            for joint_id in island_joints {
+                let joint = &mut joints[*joint_id].weight;
+                // The warmstart impulse, if any, was consumed by the solver.
+                joint.warmstart_impulse = None;
+                joint.update_limits_hit(|event| events.handle_joint_limit_event(bodies, event));
+
+                if let Some(event) = joint.check_breaking(inv_substep_dt) {
+                    broken_joints.push(event);
+                }
+            }
+        }
+
+        for event in broken_joints {
+            let _ = impulse_joints.remove(event.joint, true);
+            events.handle_joint_break_event(bodies, event);
+        }
+
         self.counters.stages.solver_time.pause();
     }
 
@@ -404,6 +722,63 @@ impl PhysicsPipeline {
         }
     }
 
+    fn settle_inserted_body(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        modified_colliders: &mut Vec<ColliderHandle>,
+        handle: RigidBodyHandle,
+    ) {
+        let Some(rb) = bodies.get(handle) else {
+            return;
+        };
+
+        if !rb.is_dynamic() {
+            return;
+        }
+
+        let pair_ids = narrow_phase.contact_pair_indices_with(rb.colliders());
+        let mut manifolds: Vec<_> = pair_ids
+            .iter()
+            .flat_map(|id| narrow_phase.contact_pair_at_index(*id).manifolds.iter())
+            .cloned()
+            .collect();
+        let settled_position = solve_inserted_body_contacts(
+            integration_parameters,
+            gravity,
+            bodies,
+            handle,
+            &mut manifolds,
+        );
+
+        let mut settled_manifolds = manifolds.iter();
+        for id in pair_ids {
+            for manifold in &mut narrow_phase.contact_pair_at_index_mut(id).manifolds {
+                let settled_manifold = settled_manifolds.next().unwrap();
+                for (contact, settled_contact) in manifold
+                    .data
+                    .solver_contacts
+                    .iter_mut()
+                    .zip(settled_manifold.data.solver_contacts.iter())
+                {
+                    contact.dist = settled_contact.dist;
+                    contact.warmstart_impulse = settled_contact.warmstart_impulse;
+                    contact.warmstart_tangent_impulse = settled_contact.warmstart_tangent_impulse;
+                }
+            }
+        }
+
+        let rb = bodies.index_mut_internal(handle);
+        rb.pos.position = settled_position;
+        rb.pos.next_position = settled_position;
+        rb.mprops.update_world_mass_properties(&rb.pos.position);
+        rb.colliders
+            .update_positions(colliders, modified_colliders, &rb.pos.position);
+    }
+
     /// Executes one timestep of the physics simulation.
     pub fn step(
         &mut self,
@@ -423,6 +798,18 @@ impl PhysicsPipeline {
     ) {
         self.counters.reset();
         self.counters.step_started();
+        self.step_stats = StepStats::default();
+
+        self.history.push(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        );
 
         // Apply some of delayed wake-ups.
         self.counters.stages.user_changes.start();
@@ -434,6 +821,14 @@ impl PhysicsPipeline {
             islands.wake_up(bodies, handle.0, true);
         }
 
+        // Handle non-finite states before they reach the solver.
+        #[cfg(feature = "sanitize")]
+        super::sanitize::sanitize_non_finite_bodies(
+            integration_parameters.non_finite_body_handling,
+            islands,
+            bodies,
+        );
+
         // Apply modifications.
         let mut modified_colliders = colliders.take_modified();
         let mut removed_colliders = colliders.take_removed();
@@ -491,6 +886,19 @@ impl PhysicsPipeline {
             true,
         );
 
+        // Settle the bodies inserted with `RigidBodySet::insert_settled`.
+        for handle in std::mem::take(&mut bodies.bodies_to_settle) {
+            self.settle_inserted_body(
+                gravity,
+                integration_parameters,
+                narrow_phase,
+                bodies,
+                colliders,
+                &mut modified_colliders,
+                handle,
+            );
+        }
+
         if let Some(queries) = query_pipeline.as_deref_mut() {
             self.counters.stages.query_pipeline_time.start();
             queries.update_incremental(colliders, &modified_colliders, &removed_colliders, false);
@@ -503,6 +911,12 @@ impl PhysicsPipeline {
         removed_colliders.clear();
         self.counters.stages.user_changes.pause();
 
+        // Reset the contact forces computed during the previous timestep.
+        for (_, collider) in colliders.colliders.iter_mut() {
+            collider.contact_force = Vector::zeros();
+        }
+
+        let step_dt = integration_parameters.dt;
         let mut remaining_time = integration_parameters.dt;
         let mut integration_parameters = *integration_parameters;
 
@@ -572,11 +986,13 @@ impl PhysicsPipeline {
             }
 
             self.counters.ccd.num_substeps += 1;
+            self.step_stats.ccd_substeps_used += 1;
 
             self.interpolate_kinematic_velocities(&integration_parameters, islands, bodies);
             self.build_islands_and_solve_velocity_constraints(
                 gravity,
                 &integration_parameters,
+                step_dt,
                 islands,
                 narrow_phase,
                 bodies,
@@ -658,6 +1074,102 @@ impl PhysicsPipeline {
 
         self.counters.step_completed();
     }
+
+    /// Executes one timestep of the physics simulation, subdivided into `substeps` substeps of
+    /// equal length.
+    ///
+    /// If `resolve_collisions_each_substep` is `true`, this runs the full collision detection and
+    /// constraints resolution cycle `substeps` times, with a timestep length equal to
+    /// `integration_parameters.dt / substeps`. This is equivalent to calling [`Self::step`]
+    /// `substeps` times, except that only one state is recorded by the history
+    /// (see [`Self::enable_history`]), and that [`Self::step_stats`] accounts for all the
+    /// substeps. The [`Self::counters`] only account for the last substep.
+    ///
+    /// Otherwise, the collision detection runs only once, and only the constraints resolution
+    /// is subdivided: this is equivalent to calling [`Self::step`] with
+    /// [`IntegrationParameters::num_solver_iterations`] multiplied by `substeps`. This is
+    /// cheaper, but contacts appearing during the timestep are only detected at the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `substeps` is zero.
+    pub fn step_substeps(
+        &mut self,
+        gravity: &Vector<Real>,
+        integration_parameters: &IntegrationParameters,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+        mut query_pipeline: Option<&mut QueryPipeline>,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+        substeps: usize,
+        resolve_collisions_each_substep: bool,
+    ) {
+        assert!(substeps > 0, "The number of substeps must not be zero.");
+        let mut params = *integration_parameters;
+
+        if !resolve_collisions_each_substep {
+            params.num_solver_iterations = params
+                .num_solver_iterations
+                .checked_mul(NonZeroUsize::new(substeps).unwrap())
+                .expect("Too many solver iterations.");
+            self.step(
+                gravity,
+                &params,
+                islands,
+                broad_phase,
+                narrow_phase,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+                ccd_solver,
+                query_pipeline,
+                hooks,
+                events,
+            );
+            return;
+        }
+
+        params.dt /= substeps as Real;
+        let mut history = None;
+        let mut step_stats = StepStats::default();
+
+        for _ in 0..substeps {
+            self.step(
+                gravity,
+                &params,
+                islands,
+                broad_phase,
+                narrow_phase,
+                bodies,
+                colliders,
+                impulse_joints,
+                multibody_joints,
+                ccd_solver,
+                query_pipeline.as_deref_mut(),
+                hooks,
+                events,
+            );
+            step_stats += self.step_stats;
+
+            // Only record the state at the beginning of the first substep.
+            if history.is_none() {
+                history = Some(std::mem::take(&mut self.history));
+            }
+        }
+
+        if let Some(history) = history {
+            self.history = history;
+        }
+        self.step_stats = step_stats;
+    }
 }
 
 #[cfg(test)]
@@ -669,7 +1181,8 @@ mod test {
         RigidBodySet,
     };
     use crate::geometry::{BroadPhaseMultiSap, ColliderBuilder, ColliderSet, NarrowPhase};
-    use crate::math::Vector;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::test_world::TestWorld;
     use crate::pipeline::PhysicsPipeline;
     use crate::prelude::{MultibodyJointSet, RevoluteJointBuilder, RigidBodyType};
 
@@ -1005,4 +1518,163 @@ mod test {
             assert!(rotation.w.is_finite());
         }
     }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn min_parallel_island_size_does_not_change_results() {
+        use crate::pipeline::test_world::cuboid;
+
+        let simulate = |min_parallel_island_size: usize| {
+            let mut world = TestWorld::with_gravity();
+
+            // Islands of different sizes: stacks of `i` balls on their own fixed ground.
+            let mut handles = vec![];
+            for i in 1..6 {
+                let x = (i as Real) * 10.0;
+                let (ground, _) = world.insert_body(
+                    RigidBodyBuilder::fixed().translation(Vector::x() * x),
+                    ColliderBuilder::ball(1.0),
+                );
+
+                for j in 1..=i {
+                    let rb = RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * x + Vector::y() * (j as Real) * 2.0);
+                    let (handle, _) = world.insert_body(rb, ColliderBuilder::ball(1.0));
+                    handles.push(handle);
+                }
+            }
+
+            world.params = IntegrationParameters {
+                min_island_size: 1,
+                min_parallel_island_size,
+                ..IntegrationParameters::default()
+            };
+
+            world.steps(20);
+
+            handles
+                .iter()
+                .map(|h| *world.bodies[*h].translation())
+                .collect::<Vec<_>>()
+        };
+
+        let all_parallel = simulate(0);
+        assert_eq!(simulate(3), all_parallel);
+        assert_eq!(simulate(usize::MAX), all_parallel);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn parallel_island_solve_matches_sequential_solve() {
+        use crate::pipeline::test_world::cuboid;
+
+        let simulate = |num_threads: usize, min_parallel_island_size: usize| {
+            let mut world = TestWorld::with_gravity();
+
+            // Separate stacks of boxes, each on its own fixed ground, so each stack is an island.
+            let mut handles = vec![];
+            for i in 0..12 {
+                let x = (i as Real) * 5.0;
+                let (ground, _) = world.insert_body(
+                    RigidBodyBuilder::fixed().translation(Vector::x() * x),
+                    cuboid(1.0, 0.5),
+                );
+
+                for j in 0..4 {
+                    let rb = RigidBodyBuilder::dynamic().translation(
+                        Vector::x() * (x + 0.05 * j as Real) + Vector::y() * (1.0 + j as Real),
+                    );
+                    let (handle, _) = world.insert_body(rb, cuboid(0.5, 0.5));
+                    handles.push(handle);
+                }
+            }
+
+            world.params = IntegrationParameters {
+                min_island_size: 1,
+                min_parallel_island_size,
+                ..IntegrationParameters::default()
+            };
+
+            let thread_pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            thread_pool.install(|| world.steps(50));
+            assert_eq!(world.islands.num_islands(), 12);
+
+            handles
+                .iter()
+                .map(|h| (*world.bodies[*h].position(), *world.bodies[*h].linvel()))
+                .collect::<Vec<_>>()
+        };
+
+        // The islands don’t share any body, so solving them on several threads must give
+        // exactly the same results as solving them one after the other.
+        let sequential = simulate(1, usize::MAX);
+        for num_threads in [1, 4, 8] {
+            assert_eq!(simulate(num_threads, 0), sequential);
+        }
+    }
+
+    #[test]
+    fn step_substeps_stabilize_heavy_chain() {
+        let run = |substeps: usize, resolve_collisions_each_substep: bool| {
+            let mut world = TestWorld::with_gravity();
+
+            // A horizontal chain of light links holding a heavy weight, swinging down.
+            let mut parent = world.bodies.insert(RigidBodyBuilder::fixed());
+            let chain: Vec<_> = (1..=10)
+                .map(|i| {
+                    let density = if i == 10 { 100.0 } else { 1.0 };
+                    let rb = RigidBodyBuilder::dynamic().translation(Vector::x() * i as Real);
+                    let co = ColliderBuilder::ball(0.25).density(density);
+                    let (handle, _) = world.insert_body(rb, co);
+                    #[cfg(feature = "dim2")]
+                    let joint = RevoluteJointBuilder::new();
+                    #[cfg(feature = "dim3")]
+                    let joint = crate::dynamics::SphericalJointBuilder::new();
+                    let joint = joint.local_anchor2(Point::from(Vector::x() * -1.0));
+                    world.impulse_joints.insert(parent, handle, joint, true);
+                    parent = handle;
+                    handle
+                })
+                .collect();
+
+            world.params.num_solver_iterations = std::num::NonZeroUsize::new(1).unwrap();
+            let mut max_error: Real = 0.0;
+            for _ in 0..120 {
+                world.pipeline.step_substeps(
+                    &world.gravity,
+                    &world.params,
+                    &mut world.islands,
+                    &mut world.broad_phase,
+                    &mut world.narrow_phase,
+                    &mut world.bodies,
+                    &mut world.colliders,
+                    &mut world.impulse_joints,
+                    &mut world.multibody_joints,
+                    &mut world.ccd_solver,
+                    None,
+                    &(),
+                    &(),
+                    substeps,
+                    resolve_collisions_each_substep,
+                );
+
+                // The largest stretch of a link of the chain.
+                let mut prev = Vector::zeros();
+                for handle in &chain {
+                    let pos = *world.bodies[*handle].translation();
+                    max_error = max_error.max(((pos - prev).norm() - 1.0).abs());
+                    prev = pos;
+                }
+            }
+            max_error
+        };
+
+        // Without substeps, the chain is stretched by the weight.
+        assert!(run(1, true) > 1.0);
+        assert!(run(4, true) < 0.5);
+        assert!(run(4, false) < 0.5);
+    }
 }
@@ -4,8 +4,9 @@ use crate::dynamics::RigidBodyHandle;
 use crate::geometry::{
     Aabb, Collider, ColliderHandle, InteractionGroups, PointProjection, Qbvh, Ray, RayIntersection,
 };
-use crate::math::{Isometry, Point, Real, Vector};
+use crate::math::{Isometry, Point, Real, UnitVector, Vector};
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
+use parry::bounding_volume::BoundingVolume;
 use parry::partitioning::{QbvhDataGenerator, QbvhUpdateWorkspace};
 use parry::query::details::{
     NonlinearTOICompositeShapeShapeBestFirstVisitor, NormalConstraints,
@@ -16,7 +17,10 @@ use parry::query::details::{
 use parry::query::visitors::{
     BoundingVolumeIntersectionsVisitor, PointIntersectionsVisitor, RayIntersectionsVisitor,
 };
-use parry::query::{DefaultQueryDispatcher, NonlinearRigidMotion, QueryDispatcher, ShapeCastHit};
+use parry::query::{
+    DefaultQueryDispatcher, NonlinearRigidMotion, PointQuery, QueryDispatcher, ShapeCastHit,
+    ShapeCastStatus,
+};
 use parry::shape::{FeatureId, Shape, TypedSimdCompositeShape};
 use std::sync::Arc;
 
@@ -56,6 +60,10 @@ bitflags::bitflags! {
         const EXCLUDE_SENSORS = 1 << 3;
         /// Exclude from the query any collider that is not a sensor.
         const EXCLUDE_SOLIDS = 1 << 4;
+        /// Exclude from the query any collider attached to a sleeping rigid-body.
+        ///
+        /// The sleep state is read from the rigid-body set given to the scene query.
+        const EXCLUDE_SLEEPING = 1 << 5;
         /// Excludes all colliders not attached to a dynamic rigid-body.
         const ONLY_DYNAMIC = Self::EXCLUDE_FIXED.bits() | Self::EXCLUDE_KINEMATIC.bits();
         /// Excludes all colliders not attached to a kinematic rigid-body.
@@ -93,6 +101,7 @@ impl QueryFilterFlags {
                 || (self.contains(QueryFilterFlags::EXCLUDE_KINEMATIC)
                     && parent_type.is_kinematic())
                 || (self.contains(QueryFilterFlags::EXCLUDE_DYNAMIC) && parent_type.is_dynamic())
+                || (self.contains(QueryFilterFlags::EXCLUDE_SLEEPING) && parent.is_sleeping())
             {
                 return false;
             }
@@ -124,7 +133,8 @@ impl<'a> QueryFilter<'a> {
     /// included in a scene query (`true`) or not (`false`).
     #[inline]
     pub fn test(&self, bodies: &RigidBodySet, handle: ColliderHandle, collider: &Collider) -> bool {
-        self.exclude_collider != Some(handle)
+        collider.is_query_enabled()
+            && self.exclude_collider != Some(handle)
             && (self.exclude_rigid_body.is_none() // NOTE: deal with the `None` case separately otherwise the next test is incorrect if the collider’s parent is `None` too.
                 || self.exclude_rigid_body != collider.parent.map(|p| p.handle))
             && self
@@ -203,6 +213,17 @@ impl<'a> QueryFilter<'a> {
         self
     }
 
+    /// Exclude from the query any collider attached to a sleeping rigid-body.
+    ///
+    /// This is checked while traversing the acceleration structure, so it is cheaper than
+    /// filtering the query results afterward. The sleep state is read from the rigid-body
+    /// set given to the scene query, so no island manager is needed. Colliders not attached
+    /// to any rigid-body are never excluded by this filter.
+    pub fn exclude_sleeping(mut self) -> Self {
+        self.flags |= QueryFilterFlags::EXCLUDE_SLEEPING;
+        self
+    }
+
     /// Only colliders with collision groups compatible with this one will
     /// be included in the scene query.
     pub fn groups(mut self, groups: InteractionGroups) -> Self {
@@ -229,6 +250,21 @@ impl<'a> QueryFilter<'a> {
     }
 }
 
+/// The result of [`QueryPipeline::cast_shape_with_initial_overlap`].
+#[derive(Copy, Clone, Debug)]
+pub struct ShapeCastHitWithOverlap {
+    /// The first collider hit by the cast shape.
+    pub collider: ColliderHandle,
+    /// The shape-cast hit, as returned by [`QueryPipeline::cast_shape`].
+    pub hit: ShapeCastHit,
+    /// The penetration depth and world-space normal of the deepest penetration between the
+    /// cast shape and the colliders it overlaps at the start of the cast, if any.
+    ///
+    /// The normal points toward the cast shape so that translating it by `normal * depth`
+    /// resolves that penetration. The deepest penetration isn’t necessarily with `collider`.
+    pub initial_overlap: Option<(Real, UnitVector<Real>)>,
+}
+
 impl<'a> TypedSimdCompositeShape for QueryPipelineAsCompositeShape<'a> {
     type PartShape = dyn Shape;
     type PartNormalConstraints = dyn NormalConstraints;
@@ -384,6 +420,88 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Find the closest intersections between many rays and a set of colliders.
+    ///
+    /// This is equivalent to calling [`Self::cast_ray`] for each ray, but the filtered view of
+    /// the colliders is only set up once per batch of rays and, if the `parallel` feature is
+    /// enabled, the rays are cast in parallel. The rays are only cast in parallel if the
+    /// `filter` has no predicate since predicates can’t be shared between threads.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `rays`: the rays to cast.
+    /// * `max_toi`: the maximum time-of-impact that can be reported by each cast.
+    /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///   it starts inside of a shape.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    /// * `out`: the result of the cast of `rays[i]` is written to `out[i]`. It must have the same
+    ///   length as `rays`.
+    pub fn cast_rays(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+        out: &mut [Option<(ColliderHandle, Real)>],
+    ) {
+        assert_eq!(
+            rays.len(),
+            out.len(),
+            "There must be exactly one output slot per ray."
+        );
+
+        #[cfg(feature = "parallel")]
+        if filter.predicate.is_none() {
+            use rayon::prelude::*;
+
+            /// The number of rays cast by a single parallel task.
+            const RAYS_PER_TASK: usize = 64;
+
+            let QueryFilter {
+                flags,
+                groups,
+                exclude_collider,
+                exclude_rigid_body,
+                predicate: _,
+            } = filter;
+
+            out.par_chunks_mut(RAYS_PER_TASK)
+                .zip(rays.par_chunks(RAYS_PER_TASK))
+                .for_each(|(out, rays)| {
+                    let filter = QueryFilter {
+                        flags,
+                        groups,
+                        exclude_collider,
+                        exclude_rigid_body,
+                        predicate: None,
+                    };
+                    let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
+                    self.cast_rays_on_shape(&pipeline_shape, rays, max_toi, solid, out);
+                });
+            return;
+        }
+
+        let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
+        self.cast_rays_on_shape(&pipeline_shape, rays, max_toi, solid, out);
+    }
+
+    fn cast_rays_on_shape(
+        &self,
+        pipeline_shape: &QueryPipelineAsCompositeShape,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        out: &mut [Option<(ColliderHandle, Real)>],
+    ) {
+        for (ray, result) in rays.iter().zip(out.iter_mut()) {
+            let mut visitor =
+                RayCompositeShapeToiBestFirstVisitor::new(pipeline_shape, ray, max_toi, solid);
+            *result = self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1);
+        }
+    }
+
     /// Find the closest intersection between a ray and a set of colliders.
     ///
     /// # Parameters
@@ -518,6 +636,32 @@ impl QueryPipeline {
             .map(|h| (h.1 .1, h.1 .0))
     }
 
+    /// Find the projection of a point on the given collider.
+    ///
+    /// Returns `None` if `handle` doesn’t identify a collider of `colliders`. This doesn’t
+    /// use the acceleration structure of this pipeline, so the collider doesn’t need to be part
+    /// of it. See [`Self::project_point`] to find the projection on the closest collider instead.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders containing the collider to project the point on.
+    /// * `handle` - The collider to project the point on.
+    /// * `point` - The point to project, in world-space.
+    /// * `solid` - If this is set to `true` then the collider shape is considered to
+    ///   be plain (if the point is located inside of the shape, its projection is the point
+    ///   itself). If it is set to `false` the collider shape is considered to be hollow
+    ///   (if the point is located inside of the shape, it is projected on the shape's
+    ///   boundary).
+    pub fn project_point_on_collider(
+        &self,
+        colliders: &ColliderSet,
+        handle: ColliderHandle,
+        point: &Point<Real>,
+        solid: bool,
+    ) -> Option<PointProjection> {
+        let co = colliders.get(handle)?;
+        Some(co.shape.project_point(&co.pos, point, solid))
+    }
+
     /// Find all the colliders containing the given point.
     ///
     /// # Parameters
@@ -587,6 +731,41 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 
+    /// Finds all the colliders with an [`Aabb`] intersecting the given [`Aabb`], sorted by
+    /// increasing distance to `reference_point`.
+    ///
+    /// The distance of a collider is the distance between `reference_point` and its world-space
+    /// [`Aabb`], so it is zero if `reference_point` lies inside of it. Unlike
+    /// [`Self::colliders_with_aabb_intersecting_aabb`], the exact [`Aabb`] of each collider is
+    /// tested against `aabb`, so colliders only touching it with the enlarged [`Aabb`] stored by
+    /// this pipeline aren’t returned. Colliders at the same distance are sorted by handle.
+    pub fn colliders_in_aabb_sorted(
+        &self,
+        colliders: &ColliderSet,
+        aabb: &Aabb,
+        reference_point: &Point<Real>,
+    ) -> Vec<(ColliderHandle, Real)> {
+        let mut result = vec![];
+
+        self.colliders_with_aabb_intersecting_aabb(aabb, |handle| {
+            if let Some(co) = colliders.get(*handle) {
+                let co_aabb = co.compute_aabb();
+                if co_aabb.intersects(aabb) {
+                    let dist = co_aabb.distance_to_local_point(reference_point, true);
+                    result.push((*handle, dist));
+                }
+            }
+
+            true
+        });
+
+        result.sort_by(|a, b| {
+            a.1.total_cmp(&b.1)
+                .then_with(|| a.0.into_raw_parts().cmp(&b.0.into_raw_parts()))
+        });
+        result
+    }
+
     /// Casts a shape at a constant linear velocity and retrieve the first collider it hits.
     ///
     /// This is similar to ray-casting except that we are casting a whole shape instead of just a
@@ -626,6 +805,56 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Casts a shape at a constant linear velocity and retrieve the first collider it hits, as
+    /// well as the deepest initial penetration if the shape is already penetrating colliders at
+    /// its starting point.
+    ///
+    /// This is similar to [`Self::cast_shape`], but the initial penetration is only computed
+    /// if the cast stops at its starting point, i.e., if the status of the hit is
+    /// [`ShapeCastStatus::PenetratingOrWithinTargetDist`]. In particular, no initial penetration
+    /// is reported if `options.stop_at_penetration` is `false` and the shape is moving out
+    /// of the penetration.
+    ///
+    /// See [`Self::cast_shape`] for a description of the parameters.
+    pub fn cast_shape_with_initial_overlap(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape_vel: &Vector<Real>,
+        shape: &dyn Shape,
+        options: ShapeCastOptions,
+        filter: QueryFilter,
+    ) -> Option<ShapeCastHitWithOverlap> {
+        let (collider, hit) = self.cast_shape(
+            bodies, colliders, shape_pos, shape_vel, shape, options, filter,
+        )?;
+        let mut initial_overlap: Option<(Real, UnitVector<Real>)> = None;
+
+        if hit.status == ShapeCastStatus::PenetratingOrWithinTargetDist {
+            let dispatcher = &*self.query_dispatcher;
+            self.intersections_with_shape(bodies, colliders, shape_pos, shape, filter, |handle| {
+                let co = &colliders[handle];
+                let pos12 = shape_pos.inv_mul(co.pos.as_ref());
+
+                if let Ok(Some(contact)) = dispatcher.contact(&pos12, shape, &*co.shape, 0.0) {
+                    let depth = -contact.dist;
+                    if depth > 0.0 && initial_overlap.map(|o| depth > o.0).unwrap_or(true) {
+                        initial_overlap = Some((depth, shape_pos * -contact.normal1));
+                    }
+                }
+
+                true
+            });
+        }
+
+        Some(ShapeCastHitWithOverlap {
+            collider,
+            hit,
+            initial_overlap,
+        })
+    }
+
     /// Casts a shape with an arbitrary continuous motion and retrieve the first collider it hits.
     ///
     /// In the resulting `TOI`, witness and normal 1 refer to the world collider, and are in world
@@ -671,6 +900,125 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
+    /// Casts a shape with an arbitrary continuous motion and retrieve all the colliders it hits,
+    /// in increasing order of time of impact.
+    ///
+    /// This is similar to [`Self::nonlinear_cast_shape`], but instead of stopping at the first
+    /// impact, the shape is assumed to pass through the colliders it hits. This reports at most
+    /// one hit per collider: the first time the shape touches it during `[start_time, end_time]`.
+    /// As for [`Self::nonlinear_cast_shape`], witness and normal 1 of each hit refer to the world
+    /// collider, and are in world space.
+    ///
+    /// All the hits are computed before `callback` is called, so the cost of this query doesn’t
+    /// depend on how early the enumeration is interrupted.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_motion` - The motion of the shape.
+    /// * `shape` - The shape to cast.
+    /// * `start_time` - The starting time of the interval where the motion takes place.
+    /// * `end_time` - The end time of the interval where the motion takes place.
+    /// * `stop_at_penetration` - See [`Self::nonlinear_cast_shape`].
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    /// * `callback` - A function called with each collider hit by the shape and the corresponding
+    ///   hit, by increasing time of impact. The enumeration stops as soon as it returns `false`.
+    pub fn cast_shape_nonfirst(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_motion: &NonlinearRigidMotion,
+        shape: &dyn Shape,
+        start_time: Real,
+        end_time: Real,
+        stop_at_penetration: bool,
+        filter: QueryFilter,
+        mut callback: impl FnMut(ColliderHandle, ShapeCastHit) -> bool,
+    ) {
+        // A conservative bound of the volume swept by the shape: its bounding sphere stays at a
+        // constant distance of the rotation center, which moves along a straight line.
+        let sphere = shape.compute_local_bounding_sphere();
+        let radius = sphere.radius() + na::distance(&sphere.center, &shape_motion.local_center);
+        let center_at =
+            |t: Real| shape_motion.start * shape_motion.local_center + shape_motion.linvel * t;
+        let mut swept_aabb = Aabb::from_half_extents(center_at(start_time), Vector::repeat(radius));
+        swept_aabb.merge(&Aabb::from_half_extents(
+            center_at(end_time),
+            Vector::repeat(radius),
+        ));
+
+        let dispatcher = &*self.query_dispatcher;
+        let mut hits = vec![];
+
+        self.colliders_with_aabb_intersecting_aabb(&swept_aabb, |handle| {
+            if let Some(co) = colliders.get(*handle) {
+                if filter.test(bodies, *handle, co) {
+                    let co_pos = co.pos.as_ref();
+                    let hit = dispatcher
+                        .cast_shapes_nonlinear(
+                            &NonlinearRigidMotion::constant_position(*co_pos),
+                            &*co.shape,
+                            shape_motion,
+                            shape,
+                            start_time,
+                            end_time,
+                            stop_at_penetration,
+                        )
+                        .unwrap_or(None);
+
+                    if let Some(hit) = hit {
+                        hits.push((*handle, hit.transform1_by(co_pos)));
+                    }
+                }
+            }
+
+            true
+        });
+
+        hits.sort_by(|a, b| a.1.time_of_impact.total_cmp(&b.1.time_of_impact));
+
+        for (handle, hit) in hits {
+            if !callback(handle, hit) {
+                break;
+            }
+        }
+    }
+
+    /// Computes the first time of impact between two shapes that are both moving.
+    ///
+    /// This is the same computation the CCD solver performs between specific pairs of colliders,
+    /// exposed as a standalone query so it can be run speculatively (e.g. to check if two moving
+    /// objects will collide during the next timestep). It doesn’t involve the colliders of this
+    /// pipeline.
+    ///
+    /// For a purely linear motion, set the angular velocity of the motion to zero.
+    ///
+    /// # Parameters
+    /// * `motion1` - The motion of the first shape, starting at time `0.0`.
+    /// * `shape1` - The first shape.
+    /// * `motion2` - The motion of the second shape, starting at time `0.0`.
+    /// * `shape2` - The second shape.
+    /// * `dt` - The end of the time interval where the motions take place.
+    ///
+    /// Returns `None` if the shapes don’t touch during `[0, dt]`. Otherwise, returns the time of
+    /// impact, as well as the world-space contact point on the first shape and contact normal
+    /// (pointing outward the first shape) at that time. If the shapes are already penetrating at
+    /// time `0.0`, the time of impact is `0.0`.
+    pub fn cast_shapes_pairwise(
+        &self,
+        motion1: &NonlinearRigidMotion,
+        shape1: &dyn Shape,
+        motion2: &NonlinearRigidMotion,
+        shape2: &dyn Shape,
+        dt: Real,
+    ) -> Option<(Real, Point<Real>, UnitVector<Real>)> {
+        let hit = self
+            .query_dispatcher
+            .cast_shapes_nonlinear(motion1, shape1, motion2, shape2, 0.0, dt, true)
+            .ok()??;
+        let pos1 = motion1.position_at_time(hit.time_of_impact);
+        Some((hit.time_of_impact, pos1 * hit.witness1, pos1 * hit.normal1))
+    }
+
     /// Retrieve all the colliders intersecting the given shape.
     ///
     /// # Parameters
@@ -680,6 +1028,8 @@ impl QueryPipeline {
     /// * `shape` - The shape to test.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
     /// * `callback` - A function called with the handles of each collider intersecting the `shape`.
+    ///   The traversal stops as soon as it returns `false`, so no allocation is needed and the
+    ///   query can be interrupted, e.g., after the first hit.
     pub fn intersections_with_shape(
         &self,
         bodies: &RigidBodySet,
@@ -712,3 +1062,423 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{QueryFilter, QueryPipeline};
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{
+        Aabb, Ball, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid, Ray,
+    };
+    use crate::math::{Isometry, Point, Real, Vector};
+    use parry::query::details::ShapeCastOptions;
+    use parry::query::NonlinearRigidMotion;
+
+    #[test]
+    fn cast_shape_reports_deepest_initial_overlap() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        let co1 = ColliderBuilder::ball(1.0);
+        let co2 = ColliderBuilder::ball(1.0).translation(Vector::x() * 2.0);
+        colliders.insert(co1);
+        let handle2 = colliders.insert(co2);
+        query_pipeline.update(&colliders);
+
+        let shape = Ball::new(0.5);
+        let options = ShapeCastOptions::default();
+
+        // The shape overlaps both colliders, more deeply the second one.
+        let pos = Isometry::new(Vector::x() * 1.2, na::zero());
+        let result = query_pipeline
+            .cast_shape_with_initial_overlap(
+                &bodies,
+                &colliders,
+                &pos,
+                &-Vector::x(),
+                &shape,
+                options,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert_eq!(result.hit.time_of_impact, 0.0);
+        let (depth, normal) = result.initial_overlap.unwrap();
+        assert!((depth - 0.7).abs() < 1.0e-5);
+        assert!((*normal + Vector::x()).norm() < 1.0e-5);
+
+        // The shape starts outside of the colliders.
+        let pos = Isometry::new(Vector::x() * 5.0, na::zero());
+        let result = query_pipeline
+            .cast_shape_with_initial_overlap(
+                &bodies,
+                &colliders,
+                &pos,
+                &-Vector::x(),
+                &shape,
+                options,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert_eq!(result.collider, handle2);
+        assert!((result.hit.time_of_impact - 1.5).abs() < 1.0e-5);
+        assert!(result.initial_overlap.is_none());
+    }
+
+    #[test]
+    fn cast_shapes_pairwise_with_two_moving_shapes() {
+        let query_pipeline = QueryPipeline::new();
+        let ball = Ball::new(0.5);
+
+        // Two balls moving toward each other.
+        let motion = |x: Real, vx: Real| {
+            NonlinearRigidMotion::new(
+                Isometry::new(Vector::x() * x, na::zero()),
+                Point::origin(),
+                Vector::x() * vx,
+                na::zero(),
+            )
+        };
+        let motion1 = motion(-2.0, 1.0);
+        let motion2 = motion(2.0, -1.0);
+        let (toi, point, normal) = query_pipeline
+            .cast_shapes_pairwise(&motion1, &ball, &motion2, &ball, 2.0)
+            .unwrap();
+        assert!((toi - 1.5).abs() < 1.0e-3);
+        assert!(point.coords.norm() < 1.0e-3);
+        assert!((*normal - Vector::x()).norm() < 1.0e-3);
+
+        // They don’t touch if the interval is too short.
+        assert!(query_pipeline
+            .cast_shapes_pairwise(&motion1, &ball, &motion2, &ball, 1.0)
+            .is_none());
+
+        // A rotating bar hits a fixed ball it would miss with a linear motion only.
+        let bar = Cuboid::new(Vector::repeat(0.1) + Vector::x() * 1.9);
+        let ball_motion =
+            NonlinearRigidMotion::constant_position(Isometry::new(Vector::y() * 1.5, na::zero()));
+        let mut bar_motion = NonlinearRigidMotion::identity();
+        assert!(query_pipeline
+            .cast_shapes_pairwise(&bar_motion, &bar, &ball_motion, &ball, 2.0)
+            .is_none());
+
+        #[cfg(feature = "dim2")]
+        {
+            bar_motion.angvel = 1.0;
+        }
+        #[cfg(feature = "dim3")]
+        {
+            bar_motion.angvel = Vector::z();
+        }
+        let (toi, _, _) = query_pipeline
+            .cast_shapes_pairwise(&bar_motion, &bar, &ball_motion, &ball, 2.0)
+            .unwrap();
+        // The bar touches the ball when it is at a distance of 0.6 from its center.
+        let expected_toi = Real::acos(0.6 / 1.5);
+        assert!((toi - expected_toi).abs() < 1.0e-2);
+    }
+
+    #[test]
+    fn cast_shape_nonfirst_reports_all_hits_in_order() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        let cuboid = || ColliderBuilder::cuboid(0.25, 0.25);
+        #[cfg(feature = "dim3")]
+        let cuboid = || ColliderBuilder::cuboid(0.25, 0.25, 0.25);
+
+        // A row of boxes along the x axis, inserted out of order, and one box off the path.
+        let mut handles = [ColliderHandle::invalid(); 5];
+        for i in [3, 0, 4, 2, 1] {
+            let co = cuboid().translation(Vector::x() * (2.0 + i as Real * 2.0));
+            handles[i] = colliders.insert(co);
+        }
+        colliders.insert(cuboid().translation(Vector::y() * 3.0));
+        query_pipeline.update(&colliders);
+
+        let ball = Ball::new(0.5);
+        let motion = NonlinearRigidMotion::new(
+            Isometry::identity(),
+            Point::origin(),
+            Vector::x(),
+            na::zero(),
+        );
+        let sweep = |end_time: Real, max_hits: usize| {
+            let mut hits = vec![];
+            query_pipeline.cast_shape_nonfirst(
+                &bodies,
+                &colliders,
+                &motion,
+                &ball,
+                0.0,
+                end_time,
+                true,
+                QueryFilter::default(),
+                |handle, hit| {
+                    hits.push((handle, hit.time_of_impact));
+                    hits.len() < max_hits
+                },
+            );
+            hits
+        };
+
+        let hits = sweep(20.0, usize::MAX);
+        assert_eq!(hits.len(), handles.len());
+        for (i, (handle, toi)) in hits.iter().enumerate() {
+            assert_eq!(*handle, handles[i]);
+            assert!((toi - (1.25 + i as Real * 2.0)).abs() < 1.0e-3);
+        }
+
+        // The sweep only reports the boxes reached before the end time.
+        let hits = sweep(6.0, usize::MAX);
+        assert_eq!(hits.len(), 3);
+
+        // The enumeration stops as soon as the callback returns `false`.
+        let hits = sweep(20.0, 2);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[1].0, handles[1]);
+    }
+
+    #[test]
+    fn colliders_in_aabb_sorted_by_distance() {
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        // Scattered balls, inserted in an order unrelated to their distance to the origin.
+        let positions = [
+            Vector::x() * 4.0,
+            -Vector::y() * 1.5,
+            Vector::x() * 2.0 + Vector::y() * 2.0,
+            -Vector::x() * 3.0,
+            Vector::y() * 0.2,
+            // Outside of the query `Aabb`.
+            Vector::x() * 20.0,
+        ];
+        let handles: Vec<_> = positions
+            .iter()
+            .map(|pos| colliders.insert(ColliderBuilder::ball(0.5).translation(*pos)))
+            .collect();
+        query_pipeline.update(&colliders);
+
+        let aabb = Aabb::new(
+            Point::from(-Vector::repeat(5.0)),
+            Point::from(Vector::repeat(5.0)),
+        );
+        let result = query_pipeline.colliders_in_aabb_sorted(&colliders, &aabb, &Point::origin());
+
+        let sorted_handles: Vec<_> = result.iter().map(|(handle, _)| *handle).collect();
+        assert_eq!(
+            sorted_handles,
+            vec![handles[4], handles[1], handles[2], handles[3], handles[0]]
+        );
+        // The origin lies inside of the `Aabb` of the closest ball.
+        assert_eq!(result[0].1, 0.0);
+        assert!((result[1].1 - 1.0).abs() < 1.0e-5);
+        assert!((result[4].1 - 3.5).abs() < 1.0e-5);
+        assert!(result.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn exclude_sleeping_bodies() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        let sleeping = bodies.insert(RigidBodyBuilder::dynamic().sleeping(true));
+        let awake = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0));
+        let co_sleeping =
+            colliders.insert_with_parent(ColliderBuilder::ball(0.5), sleeping, &mut bodies);
+        let co_awake = colliders.insert_with_parent(ColliderBuilder::ball(0.5), awake, &mut bodies);
+        query_pipeline.update(&colliders);
+
+        let ray = Ray::new(Point::from(-Vector::x() * 2.0), Vector::x());
+        let hit = |filter| {
+            query_pipeline
+                .cast_ray(&bodies, &colliders, &ray, Real::MAX, true, filter)
+                .map(|(handle, _)| handle)
+        };
+
+        assert_eq!(hit(QueryFilter::default()), Some(co_sleeping));
+        assert_eq!(
+            hit(QueryFilter::default().exclude_sleeping()),
+            Some(co_awake)
+        );
+    }
+
+    #[test]
+    fn cast_rays_matches_cast_ray() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        for i in 0..5 {
+            for j in 0..5 {
+                let co = ColliderBuilder::ball(0.4)
+                    .translation(Vector::x() * i as Real + Vector::y() * j as Real);
+                colliders.insert(co);
+            }
+        }
+        query_pipeline.update(&colliders);
+
+        // Enough rays to be split into several batches, many of them hitting a collider.
+        let rays: Vec<_> = (0..200)
+            .map(|i| {
+                let angle = i as Real * 0.1;
+                let origin = Vector::x() * (i % 7) as Real - Vector::y() * 2.0;
+                let dir = Vector::x() * angle.cos() + Vector::y() * angle.sin().abs();
+                Ray::new(Point::from(origin), dir)
+            })
+            .collect();
+
+        let predicate = |handle: ColliderHandle, _: &Collider| handle.into_raw_parts().0 < 12;
+        let filters = [
+            QueryFilter::default(),
+            QueryFilter::default().exclude_collider(colliders.iter().next().unwrap().0),
+            QueryFilter::default().predicate(&predicate),
+        ];
+
+        for filter in filters {
+            let mut batched = vec![None; rays.len()];
+            query_pipeline.cast_rays(&bodies, &colliders, &rays, 10.0, true, filter, &mut batched);
+
+            assert!(batched.iter().filter(|hit| hit.is_some()).count() > 50);
+
+            for (ray, hit) in rays.iter().zip(batched.iter()) {
+                let expected =
+                    query_pipeline.cast_ray(&bodies, &colliders, ray, 10.0, true, filter);
+                assert_eq!(*hit, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn project_point_on_rotated_cuboid() {
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        // A cuboid rotated by 90 degrees, so its long side is along the y axis.
+        #[cfg(feature = "dim2")]
+        let (co, angle) = (
+            ColliderBuilder::cuboid(2.0, 0.5),
+            <Real as na::RealField>::frac_pi_2(),
+        );
+        #[cfg(feature = "dim3")]
+        let (co, angle) = (
+            ColliderBuilder::cuboid(2.0, 0.5, 0.5),
+            Vector::z() * <Real as na::RealField>::frac_pi_2(),
+        );
+        let cuboid = colliders.insert(co.rotation(angle));
+        let ball = colliders.insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 4.0));
+        query_pipeline.update(&colliders);
+
+        #[cfg(feature = "dim2")]
+        let pt = |x: Real, y: Real| Point::new(x, y);
+        #[cfg(feature = "dim3")]
+        let pt = |x: Real, y: Real| Point::new(x, y, 0.0);
+
+        // The point is projected on the face pointing toward +x, which is a long face.
+        let point = pt(1.0, 1.5);
+        let proj = query_pipeline
+            .project_point_on_collider(&colliders, cuboid, &point, true)
+            .unwrap();
+        assert!(!proj.is_inside);
+        assert!((proj.point - pt(0.5, 1.5)).norm() < 1.0e-5);
+
+        // Points inside are kept as-is, unless the cuboid is hollow.
+        let point = pt(0.0, 1.8);
+        let proj = query_pipeline
+            .project_point_on_collider(&colliders, cuboid, &point, true)
+            .unwrap();
+        assert!(proj.is_inside);
+        assert!((proj.point - point).norm() < 1.0e-5);
+        let proj = query_pipeline
+            .project_point_on_collider(&colliders, cuboid, &point, false)
+            .unwrap();
+        assert!(proj.is_inside);
+        assert!((proj.point - pt(0.0, 2.0)).norm() < 1.0e-5);
+
+        // The ball is closer, but only the requested collider is considered.
+        let point = pt(3.0, 0.0);
+        let proj = query_pipeline
+            .project_point_on_collider(&colliders, cuboid, &point, true)
+            .unwrap();
+        assert!((proj.point - pt(0.5, 0.0)).norm() < 1.0e-5);
+        let (closest, _) = query_pipeline
+            .project_point(&bodies, &colliders, &point, true, QueryFilter::default())
+            .unwrap();
+        assert_eq!(closest, ball);
+
+        colliders.remove(
+            ball,
+            &mut crate::dynamics::IslandManager::new(),
+            &mut RigidBodySet::new(),
+            false,
+        );
+        assert!(query_pipeline
+            .project_point_on_collider(&colliders, ball, &point, true)
+            .is_none());
+    }
+
+    #[test]
+    fn intersections_with_shape_stops_early() {
+        use std::cell::Cell;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        for i in 0..10 {
+            for j in 0..10 {
+                let co = ColliderBuilder::ball(0.4)
+                    .translation(Vector::x() * i as Real + Vector::y() * j as Real);
+                colliders.insert(co);
+            }
+        }
+        query_pipeline.update(&colliders);
+
+        // A shape overlapping all the colliders. The predicate counts the colliders visited.
+        let shape = Cuboid::new(Vector::repeat(20.0));
+        let num_visited = Cell::new(0);
+        let predicate = |_: ColliderHandle, _: &Collider| {
+            num_visited.set(num_visited.get() + 1);
+            true
+        };
+        let filter = QueryFilter::default().predicate(&predicate);
+
+        let mut num_hits = 0;
+        query_pipeline.intersections_with_shape(
+            &bodies,
+            &colliders,
+            &Isometry::identity(),
+            &shape,
+            filter,
+            |_| {
+                num_hits += 1;
+                true
+            },
+        );
+        assert_eq!(num_hits, 100);
+        assert_eq!(num_visited.get(), 100);
+
+        // Stop after the first hit.
+        num_visited.set(0);
+        let mut first_hit = None;
+        query_pipeline.intersections_with_shape(
+            &bodies,
+            &colliders,
+            &Isometry::identity(),
+            &shape,
+            filter,
+            |handle| {
+                assert!(first_hit.is_none());
+                first_hit = Some(handle);
+                false
+            },
+        );
+        assert!(first_hit.is_some());
+        assert_eq!(num_visited.get(), 1);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::dynamics::{IslandManager, NonFiniteBodyHandling, RigidBody, RigidBodySet};
+use crate::math::{AngVector, Isometry, Real, Rotation, Vector};
+
+/// Detects the rigid-bodies with a non-finite position, velocity, or force, and handles them
+/// according to `mode`.
+///
+/// Only the active rigid-bodies and the rigid-bodies modified by the user since the last step
+/// are checked since the state of the other rigid-bodies cannot change.
+pub(crate) fn sanitize_non_finite_bodies(
+    mode: NonFiniteBodyHandling,
+    islands: &IslandManager,
+    bodies: &mut RigidBodySet,
+) {
+    let invalid_bodies: Vec<_> = islands
+        .iter_active_bodies()
+        .chain(bodies.modified_bodies.iter().copied())
+        .filter(|handle| {
+            bodies
+                .get(*handle)
+                .map(|rb| !has_finite_state(rb))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    for handle in invalid_bodies {
+        // NOTE: this also marks the rigid-body as modified so that the changes
+        //       are taken into account by the user-changes handling of this step.
+        let Some(rb) = bodies.get_mut_internal_with_modification_tracking(handle) else {
+            continue;
+        };
+
+        if has_finite_state(rb) {
+            // Already handled (the handle was in both the active and modified sets).
+            continue;
+        }
+
+        log::warn!(
+            "Rigid-body {:?} has a non-finite position, velocity, or force.",
+            handle
+        );
+
+        match mode {
+            NonFiniteBodyHandling::ZeroComponents => {
+                let pos = rb.pos.position;
+                if !is_finite_isometry(&pos) {
+                    let mut translation = pos.translation.vector;
+                    zero_non_finite(&mut translation);
+                    let rotation = if is_finite_rotation(&pos.rotation) {
+                        pos.rotation
+                    } else {
+                        Rotation::identity()
+                    };
+                    rb.set_position(Isometry::from_parts(translation.into(), rotation), false);
+                }
+
+                zero_non_finite(&mut rb.vels.linvel);
+                zero_non_finite_ang(&mut rb.vels.angvel);
+                zero_non_finite(&mut rb.forces.force);
+                zero_non_finite_ang(&mut rb.forces.torque);
+                zero_non_finite(&mut rb.forces.user_force);
+                zero_non_finite_ang(&mut rb.forces.user_torque);
+            }
+            NonFiniteBodyHandling::Disable => rb.set_enabled(false),
+        }
+    }
+}
+
+fn has_finite_state(rb: &RigidBody) -> bool {
+    is_finite_isometry(&rb.pos.position)
+        && is_finite_vector(&rb.vels.linvel)
+        && is_finite_ang(&rb.vels.angvel)
+        && is_finite_vector(&rb.forces.force)
+        && is_finite_ang(&rb.forces.torque)
+        && is_finite_vector(&rb.forces.user_force)
+        && is_finite_ang(&rb.forces.user_torque)
+}
+
+fn is_finite_isometry(pos: &Isometry<Real>) -> bool {
+    is_finite_vector(&pos.translation.vector) && is_finite_rotation(&pos.rotation)
+}
+
+fn is_finite_vector(v: &Vector<Real>) -> bool {
+    v.iter().all(|e| e.is_finite())
+}
+
+fn zero_non_finite(v: &mut Vector<Real>) {
+    v.apply(|e| {
+        if !e.is_finite() {
+            *e = 0.0;
+        }
+    });
+}
+
+#[cfg(feature = "dim2")]
+fn is_finite_rotation(rot: &Rotation<Real>) -> bool {
+    rot.re.is_finite() && rot.im.is_finite()
+}
+
+#[cfg(feature = "dim3")]
+fn is_finite_rotation(rot: &Rotation<Real>) -> bool {
+    rot.coords.iter().all(|e| e.is_finite())
+}
+
+#[cfg(feature = "dim2")]
+fn is_finite_ang(v: &AngVector<Real>) -> bool {
+    v.is_finite()
+}
+
+#[cfg(feature = "dim3")]
+fn is_finite_ang(v: &AngVector<Real>) -> bool {
+    is_finite_vector(v)
+}
+
+#[cfg(feature = "dim2")]
+fn zero_non_finite_ang(v: &mut AngVector<Real>) {
+    if !v.is_finite() {
+        *v = 0.0;
+    }
+}
+
+#[cfg(feature = "dim3")]
+fn zero_non_finite_ang(v: &mut AngVector<Real>) {
+    zero_non_finite(v)
+}
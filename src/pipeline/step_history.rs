@@ -0,0 +1,421 @@
+use crate::dynamics::{CCDSolver, ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodySet};
+use crate::geometry::{BroadPhase, BroadPhaseState, ColliderSet, NarrowPhase};
+use std::collections::VecDeque;
+
+#[cfg(doc)]
+use crate::pipeline::PhysicsPipeline;
+
+/// A full copy of the physics state, taken with [`PhysicsPipeline::snapshot`].
+///
+/// This contains the island manager, broad-phase state, narrow-phase, rigid-body set, collider
+/// set, joint sets, and CCD solver. The narrow-phase includes the contact impulses of the last
+/// timestep, so a simulation restored with [`PhysicsPipeline::restore_snapshot`] warmstarts its
+/// contacts exactly like the original one and gives identical results.
+///
+/// When serialized, the broad-phase state isn’t included since it is type-erased. A
+/// deserialized snapshot thus only restores the broad-phase if it was serialized separately
+/// (e.g. by serializing the [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap)
+/// itself).
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PhysicsSnapshot {
+    islands: IslandManager,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    broad_phase: Option<BroadPhaseState>,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd_solver: CCDSolver,
+}
+
+impl PhysicsSnapshot {
+    pub(crate) fn new(
+        islands: &IslandManager,
+        broad_phase: &dyn BroadPhase,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        ccd_solver: &CCDSolver,
+    ) -> Self {
+        Self {
+            islands: islands.clone(),
+            broad_phase: broad_phase.save_state(),
+            narrow_phase: narrow_phase.clone(),
+            bodies: bodies.clone(),
+            colliders: colliders.clone(),
+            impulse_joints: impulse_joints.clone(),
+            multibody_joints: multibody_joints.clone(),
+            ccd_solver: ccd_solver.clone(),
+        }
+    }
+
+    /// Restores this state, leaving the snapshot unchanged so it can be restored again.
+    pub(crate) fn restore_cloned(
+        &self,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+    ) {
+        *islands = self.islands.clone();
+        if let Some(state) = &self.broad_phase {
+            if let Err(err) = broad_phase.restore_state(state) {
+                log::warn!("The broad-phase wasn’t restored: {err}.");
+            }
+        }
+        *narrow_phase = self.narrow_phase.clone();
+        *bodies = self.bodies.clone();
+        *colliders = self.colliders.clone();
+        *impulse_joints = self.impulse_joints.clone();
+        *multibody_joints = self.multibody_joints.clone();
+        *ccd_solver = self.ccd_solver.clone();
+    }
+
+    pub(crate) fn restore(
+        self,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+    ) {
+        *islands = self.islands;
+        if let Some(state) = &self.broad_phase {
+            if let Err(err) = broad_phase.restore_state(state) {
+                log::warn!("The broad-phase wasn’t restored: {err}.");
+            }
+        }
+        *narrow_phase = self.narrow_phase;
+        *bodies = self.bodies;
+        *colliders = self.colliders;
+        *impulse_joints = self.impulse_joints;
+        *multibody_joints = self.multibody_joints;
+        *ccd_solver = self.ccd_solver;
+    }
+}
+
+/// A ring buffer of the physics states at the beginning of the last timesteps.
+#[derive(Default)]
+pub(crate) struct StepHistory {
+    capacity: usize,
+    snapshots: VecDeque<PhysicsSnapshot>,
+}
+
+impl StepHistory {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.snapshots.len() > self.capacity {
+            let _ = self.snapshots.pop_front();
+        }
+
+        if self.capacity == 0 {
+            // Release the memory since it won’t be needed anymore.
+            self.snapshots = VecDeque::new();
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        islands: &IslandManager,
+        broad_phase: &dyn BroadPhase,
+        narrow_phase: &NarrowPhase,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        ccd_solver: &CCDSolver,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.snapshots.len() == self.capacity {
+            let _ = self.snapshots.pop_front();
+        }
+
+        self.snapshots.push_back(PhysicsSnapshot::new(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        ));
+    }
+
+    pub fn pop(
+        &mut self,
+        islands: &mut IslandManager,
+        broad_phase: &mut dyn BroadPhase,
+        narrow_phase: &mut NarrowPhase,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        multibody_joints: &mut MultibodyJointSet,
+        ccd_solver: &mut CCDSolver,
+    ) -> bool {
+        let Some(snapshot) = self.snapshots.pop_back() else {
+            return false;
+        };
+
+        snapshot.restore(
+            islands,
+            broad_phase,
+            narrow_phase,
+            bodies,
+            colliders,
+            impulse_joints,
+            multibody_joints,
+            ccd_solver,
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{
+        BroadPhase, BroadPhaseMultiSap, BroadPhasePairEvent, BroadPhaseStateError, ColliderBuilder,
+        ColliderHandle, ColliderSet,
+    };
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+    use crate::pipeline::PhysicsPipeline;
+
+    #[test]
+    fn snapshot_restore_is_deterministic() {
+        let mut world = TestWorld::with_gravity();
+
+        let co = cuboid(10.0, 0.5);
+        world.insert_body(RigidBodyBuilder::fixed(), co);
+
+        let stack: Vec<_> = (0..5)
+            .map(|i| {
+                let rb = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (1.0 + i as Real) + Vector::x() * 0.1 * i as Real)
+                    .can_sleep(false);
+                let co = cuboid(0.5, 0.5);
+                let (handle, _) = world.insert_body(rb, co);
+                handle
+            })
+            .collect();
+
+        let run = |world: &mut TestWorld, n: usize| {
+            world.steps(n);
+            stack
+                .iter()
+                .map(|h| (*world.bodies[*h].position(), *world.bodies[*h].linvel()))
+                .collect::<Vec<_>>()
+        };
+
+        run(&mut world, 20);
+        assert!(world.narrow_phase.contact_pairs().any(|pair| pair
+            .manifolds
+            .iter()
+            .any(|m| m.points.iter().any(|pt| pt.data.impulse != 0.0))));
+
+        let snapshot = PhysicsPipeline::new().snapshot(
+            &world.islands,
+            &world.broad_phase,
+            &world.narrow_phase,
+            &world.bodies,
+            &world.colliders,
+            &world.impulse_joints,
+            &world.multibody_joints,
+            &world.ccd_solver,
+        );
+        #[cfg(feature = "serde-serialize")]
+        let serialized = (
+            bincode::serialize(&snapshot).unwrap(),
+            bincode::serialize(&world.broad_phase).unwrap(),
+        );
+
+        let expected = run(&mut world, 10);
+
+        // The snapshot can be restored several times.
+        for _ in 0..2 {
+            PhysicsPipeline::new().restore_snapshot(
+                &snapshot,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+            );
+            let restored = run(&mut world, 10);
+            assert_eq!(restored, expected);
+        }
+
+        // A deserialized snapshot gives the same results, provided the broad-phase was
+        // serialized too.
+        #[cfg(feature = "serde-serialize")]
+        {
+            let snapshot: crate::pipeline::PhysicsSnapshot =
+                bincode::deserialize(&serialized.0).unwrap();
+            world.broad_phase = bincode::deserialize(&serialized.1).unwrap();
+            PhysicsPipeline::new().restore_snapshot(
+                &snapshot,
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+            );
+            let restored = run(&mut world, 10);
+            assert_eq!(restored, expected);
+        }
+    }
+
+    /// A broad-phase relying on the default `save_state` and `restore_state`.
+    struct StatelessBroadPhase(BroadPhaseMultiSap);
+
+    impl BroadPhase for StatelessBroadPhase {
+        fn update(
+            &mut self,
+            dt: Real,
+            prediction_distance: Real,
+            colliders: &mut ColliderSet,
+            bodies: &RigidBodySet,
+            modified_colliders: &[ColliderHandle],
+            removed_colliders: &[ColliderHandle],
+            events: &mut Vec<BroadPhasePairEvent>,
+        ) {
+            self.0.update(
+                dt,
+                prediction_distance,
+                colliders,
+                bodies,
+                modified_colliders,
+                removed_colliders,
+                events,
+            )
+        }
+    }
+
+    #[test]
+    fn snapshot_restore_without_broad_phase_state() {
+        let mut world = TestWorld::with_gravity();
+        let (handle, _) =
+            world.insert_body(RigidBodyBuilder::dynamic(), ColliderBuilder::ball(0.5));
+        let mut broad_phase = StatelessBroadPhase(BroadPhaseMultiSap::new());
+        assert!(broad_phase.save_state().is_none());
+        assert_eq!(
+            broad_phase.restore_state(&BroadPhaseMultiSap::new().save_state().unwrap()),
+            Err(BroadPhaseStateError::Unsupported)
+        );
+
+        let snapshot = PhysicsPipeline::new().snapshot(
+            &world.islands,
+            &broad_phase,
+            &world.narrow_phase,
+            &world.bodies,
+            &world.colliders,
+            &world.impulse_joints,
+            &world.multibody_joints,
+            &world.ccd_solver,
+        );
+        let position = *world.bodies[handle].position();
+        world.steps(10);
+        assert_ne!(*world.bodies[handle].position(), position);
+
+        // Everything but the broad-phase is restored.
+        PhysicsPipeline::new().restore_snapshot(
+            &snapshot,
+            &mut world.islands,
+            &mut broad_phase,
+            &mut world.narrow_phase,
+            &mut world.bodies,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            &mut world.ccd_solver,
+        );
+        assert_eq!(*world.bodies[handle].position(), position);
+    }
+
+    #[test]
+    fn step_back_restores_previous_states() {
+        let mut world = TestWorld::with_gravity();
+
+        world.insert_body(
+            RigidBodyBuilder::fixed().translation(Vector::y() * -100.0),
+            ColliderBuilder::ball(100.0),
+        );
+        let (ball, _) = world.insert_ball(Vector::y() * 0.6, 0.5);
+
+        world.pipeline.enable_history(usize::MAX);
+        assert_eq!(
+            world.pipeline.history_capacity(),
+            PhysicsPipeline::MAX_HISTORY_LENGTH
+        );
+        world.pipeline.enable_history(2);
+
+        let mut positions = vec![*world.bodies[ball].translation()];
+        for _ in 0..3 {
+            world.step();
+            positions.push(*world.bodies[ball].translation());
+        }
+        assert_eq!(world.pipeline.history_len(), 2);
+
+        for expected in [positions[2], positions[1]] {
+            assert!(world.pipeline.step_back(
+                &mut world.islands,
+                &mut world.broad_phase,
+                &mut world.narrow_phase,
+                &mut world.bodies,
+                &mut world.colliders,
+                &mut world.impulse_joints,
+                &mut world.multibody_joints,
+                &mut world.ccd_solver,
+            ));
+            assert_eq!(*world.bodies[ball].translation(), expected);
+        }
+
+        // Only the last two steps were kept.
+        assert!(!world.pipeline.step_back(
+            &mut world.islands,
+            &mut world.broad_phase,
+            &mut world.narrow_phase,
+            &mut world.bodies,
+            &mut world.colliders,
+            &mut world.impulse_joints,
+            &mut world.multibody_joints,
+            &mut world.ccd_solver,
+        ));
+
+        // Stepping again from the restored state gives the same results.
+        world.step();
+        assert_eq!(*world.bodies[ball].translation(), positions[2]);
+
+        world.pipeline.enable_history(0);
+        assert_eq!(world.pipeline.history_len(), 0);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::math::Real;
+use std::ops::AddAssign;
+
+#[cfg(doc)]
+use crate::dynamics::IntegrationParameters;
+
+/// Statistics about the work performed by the constraints solver during the last timestep.
+///
+/// Unlike the [`Counters`](crate::counters::Counters), these statistics are always
+/// computed (except the solver residuals) and cheap to gather, which makes them suitable for adapting the simulation
+/// settings (e.g. the number of solver iterations) at runtime.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StepStats {
+    /// Number of contact constraints assembled for the solver.
+    ///
+    /// A SIMD constraint handling multiple contact manifolds at once counts as a single constraint.
+    pub num_contact_constraints: usize,
+    /// Number of joint constraints assembled for the solver.
+    ///
+    /// A SIMD constraint handling multiple joints at once counts as a single constraint.
+    pub num_joint_constraints: usize,
+    /// Estimate of the solver’s workload: the sum, for every island, of the number of
+    /// contact and joint constraints multiplied by the number of solver iterations.
+    pub solver_work: usize,
+    /// The sum, for every island, of the number of PGS iterations run by the solver.
+    ///
+    /// This is the number of solver iterations multiplied by the
+    /// [`IntegrationParameters::num_internal_pgs_iterations`], unless
+    /// [`IntegrationParameters::adaptive_iterations`] is enabled and some of them were skipped,
+    /// including the solver iterations that only applied the impulses of a converged one.
+    pub num_pgs_iterations: usize,
+    /// The largest velocity change, over all the islands, caused by the resolution of the
+    /// non-penetration constraints during the last PGS iteration.
+    ///
+    /// This measures how far the solver is from convergence: it is zero if one more
+    /// iteration wouldn’t change the velocities of the bodies. The linear and angular velocity
+    /// changes are both taken into account.
+    ///
+    /// This is only measured if [`IntegrationParameters::measure_solver_residuals`] is enabled,
+    /// and is zero otherwise.
+    pub max_normal_residual: Real,
+    /// The largest velocity change, over all the islands, caused by the last resolution of
+    /// the friction constraints.
+    ///
+    /// See [`Self::max_normal_residual`] for details.
+    pub max_friction_residual: Real,
+    /// The number of CCD substeps the timestep was split into.
+    ///
+    /// This is at most [`IntegrationParameters::max_ccd_substeps`] (and at least `1`). If it
+    /// is equal to that maximum, the CCD is saturated: some fast contacts didn’t get their own
+    /// substep, and the motion of the bodies involved was clamped instead.
+    pub ccd_substeps_used: u32,
+}
+
+impl AddAssign for StepStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.num_contact_constraints += rhs.num_contact_constraints;
+        self.num_joint_constraints += rhs.num_joint_constraints;
+        self.solver_work += rhs.solver_work;
+        self.num_pgs_iterations += rhs.num_pgs_iterations;
+        self.max_normal_residual = self.max_normal_residual.max(rhs.max_normal_residual);
+        self.max_friction_residual = self.max_friction_residual.max(rhs.max_friction_residual);
+        self.ccd_substeps_used += rhs.ccd_substeps_used;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::test_world::{cuboid, TestWorld};
+
+    #[test]
+    fn solver_residuals_decrease_with_more_iterations() {
+        let run = |num_internal_pgs_iterations: usize, measure_solver_residuals: bool| {
+            let mut world = TestWorld::with_gravity();
+
+            world.insert_body(RigidBodyBuilder::fixed(), cuboid(50.0, 0.5));
+
+            // A stack of boxes getting heavier towards the top. The gravity is slightly tilted,
+            // as if the stack was on a slope, so the friction constraints have some work to do.
+            world.gravity = Vector::y() * -9.81 + Vector::x() * 1.0;
+            for i in 0..10 {
+                let rb = RigidBodyBuilder::dynamic().translation(Vector::y() * (1.0 + i as Real));
+                world.insert_body(rb, cuboid(0.5, 0.5).density(1.0 + 10.0 * i as Real));
+            }
+
+            // Only the number of PGS iterations of a single step from the same initial state
+            // changes between runs, so the solver converges further with more iterations
+            // independently of the order in which the constraints are solved.
+            world.params = IntegrationParameters {
+                num_internal_pgs_iterations,
+                measure_solver_residuals,
+                ..IntegrationParameters::default()
+            };
+            world.step();
+
+            let stats = world.pipeline.step_stats;
+            (stats.max_normal_residual, stats.max_friction_residual)
+        };
+
+        let residuals: Vec<_> = [1, 8, 64]
+            .into_iter()
+            .map(|num_iterations| run(num_iterations, true))
+            .collect();
+        assert!(residuals[0].0 > 0.0);
+        assert!(residuals[1].0 < residuals[0].0);
+        assert!(residuals[2].0 < residuals[1].0);
+        assert!(residuals[1].1 < residuals[0].1);
+        assert!(residuals[2].1 < residuals[1].1);
+
+        // The residuals are not measured unless requested.
+        assert_eq!(run(4, false), (0.0, 0.0));
+    }
+
+    #[test]
+    fn ccd_substeps_are_bounded_and_reported() {
+        const NUM_BALLS: usize = 8;
+
+        let run = |max_ccd_substeps: usize| {
+            let mut world = TestWorld::new();
+
+            // A thin wall, and fast balls at various distances from it, so they all hit it
+            // at different times during the second timestep.
+            #[cfg(feature = "dim2")]
+            let wall = ColliderBuilder::cuboid(0.05, 100.0);
+            #[cfg(feature = "dim3")]
+            let wall = ColliderBuilder::cuboid(0.05, 100.0, 100.0);
+            world.insert_body(RigidBodyBuilder::fixed(), wall);
+
+            let mut handles = vec![];
+            for i in 0..NUM_BALLS {
+                let pos = Vector::x() * -(4.0 + i as Real * 0.3) + Vector::y() * i as Real;
+                let (ball, _) = world.insert_body(
+                    RigidBodyBuilder::dynamic()
+                        .translation(pos)
+                        .linvel(Vector::x() * 200.0)
+                        .ccd_enabled(true),
+                    ColliderBuilder::ball(0.1),
+                );
+                handles.push(ball);
+            }
+
+            world.params = IntegrationParameters {
+                max_ccd_substeps,
+                ..IntegrationParameters::default()
+            };
+            world.steps(2);
+
+            // Even when the CCD is saturated, no ball tunneled through the wall.
+            for handle in &handles {
+                assert!(world.bodies[*handle].translation().x < 0.0);
+            }
+
+            world.pipeline.step_stats.ccd_substeps_used
+        };
+
+        assert_eq!(run(1), 1);
+        assert_eq!(run(3), 3);
+        let substeps = run(100);
+        assert!(substeps > 3 && substeps < 100);
+    }
+
+    #[test]
+    fn step_stats_count_constraints() {
+        let mut world = TestWorld::new();
+
+        world.insert_body(RigidBodyBuilder::fixed(), ColliderBuilder::ball(1.0));
+        world.insert_ball(Vector::y() * 1.5, 1.0);
+
+        world.steps(2);
+
+        let stats = world.pipeline.step_stats;
+        assert_eq!(stats.num_contact_constraints, 1);
+        assert_eq!(stats.num_joint_constraints, 0);
+        assert_eq!(stats.solver_work, world.params.num_solver_iterations.get());
+    }
+}
@@ -0,0 +1,128 @@
+//! A minimal physics world shared by the unit tests of the crate.
+
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet, RigidBody,
+    RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{
+    BroadPhaseMultiSap, Collider, ColliderBuilder, ColliderHandle, ColliderSet, NarrowPhase,
+};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline};
+
+/// A box with the given half-extents along `x` and `y`, as deep as it is wide in 3D.
+pub(crate) fn cuboid(half_width: Real, half_height: Real) -> ColliderBuilder {
+    ColliderBuilder::cuboid(
+        half_width,
+        half_height,
+        #[cfg(feature = "dim3")]
+        half_width,
+    )
+}
+
+/// A `200`-wide box whose top face lies at `y = 0`.
+pub(crate) fn floor() -> ColliderBuilder {
+    cuboid(100.0, 0.5).translation(Vector::y() * -0.5)
+}
+
+/// All the sets and structures needed to step a [`PhysicsPipeline`].
+pub(crate) struct TestWorld {
+    pub gravity: Vector<Real>,
+    pub params: IntegrationParameters,
+    pub pipeline: PhysicsPipeline,
+    pub islands: IslandManager,
+    pub broad_phase: BroadPhaseMultiSap,
+    pub narrow_phase: NarrowPhase,
+    pub bodies: RigidBodySet,
+    pub colliders: ColliderSet,
+    pub impulse_joints: ImpulseJointSet,
+    pub multibody_joints: MultibodyJointSet,
+    pub ccd_solver: CCDSolver,
+}
+
+impl TestWorld {
+    /// An empty world without gravity, using the default integration parameters.
+    pub fn new() -> Self {
+        Self {
+            gravity: Vector::zeros(),
+            params: IntegrationParameters::default(),
+            pipeline: PhysicsPipeline::new(),
+            islands: IslandManager::new(),
+            broad_phase: BroadPhaseMultiSap::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+        }
+    }
+
+    /// An empty world with the standard `-9.81` gravity along the `y` axis.
+    pub fn with_gravity() -> Self {
+        let mut world = Self::new();
+        world.gravity = Vector::y() * -9.81;
+        world
+    }
+
+    /// Inserts a body with a single collider attached to it.
+    pub fn insert_body(
+        &mut self,
+        body: impl Into<RigidBody>,
+        collider: impl Into<Collider>,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let body = self.bodies.insert(body);
+        let collider = self
+            .colliders
+            .insert_with_parent(collider, body, &mut self.bodies);
+        (body, collider)
+    }
+
+    /// Inserts a fixed body with the default [`floor`] attached to it.
+    pub fn insert_floor(&mut self) -> (RigidBodyHandle, ColliderHandle) {
+        self.insert_body(RigidBodyBuilder::fixed(), floor())
+    }
+
+    /// Inserts a dynamic ball of the given radius.
+    pub fn insert_ball(
+        &mut self,
+        translation: Vector<Real>,
+        radius: Real,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        self.insert_body(
+            RigidBodyBuilder::dynamic().translation(translation),
+            ColliderBuilder::ball(radius),
+        )
+    }
+
+    /// Runs one timestep without hooks nor events.
+    pub fn step(&mut self) {
+        self.step_with(&(), &());
+    }
+
+    /// Runs `n` timesteps without hooks nor events.
+    pub fn steps(&mut self, n: usize) {
+        for _ in 0..n {
+            self.step();
+        }
+    }
+
+    /// Runs one timestep with the given hooks and event handler.
+    pub fn step_with(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        self.pipeline.step(
+            &self.gravity,
+            &self.params,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            None,
+            hooks,
+            events,
+        );
+    }
+}
@@ -14,9 +14,9 @@ use crate::{graphics::GraphicsManager, harness::RunState};
 use crate::{mouse, ui};
 
 use na::{self, Point2, Point3, Vector3};
-#[cfg(feature = "dim3")]
-use rapier::control::DynamicRayCastVehicleController;
 use rapier::control::KinematicCharacterController;
+#[cfg(feature = "dim3")]
+use rapier::control::{DynamicRayCastVehicleController, VehicleInputs};
 use rapier::dynamics::{
     ImpulseJointSet, IntegrationParameters, MultibodyJointSet, RigidBodyActivation,
     RigidBodyHandle, RigidBodySet,
@@ -736,13 +736,20 @@ impl<'a, 'b, 'c, 'd, 'e, 'f> Testbed<'a, 'b, 'c, 'd, 'e, 'f> {
             }
 
             let wheels = vehicle.wheels_mut();
-            wheels[0].engine_force = engine_force;
-            wheels[0].steering = steering_angle;
-            wheels[1].engine_force = engine_force;
-            wheels[1].steering = steering_angle;
+            wheels[0].is_driven = true;
+            wheels[0].is_steered = true;
+            wheels[1].is_driven = true;
+            wheels[1].is_steered = true;
+
+            let inputs = VehicleInputs {
+                engine_force,
+                steering: steering_angle,
+                brake: 0.0,
+            };
 
-            vehicle.update_vehicle(
+            vehicle.update(
                 self.harness.physics.integration_parameters.dt,
+                &inputs,
                 &mut self.harness.physics.bodies,
                 &self.harness.physics.colliders,
                 &self.harness.physics.query_pipeline,